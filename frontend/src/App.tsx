@@ -1,10 +1,14 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { ApiPromise, WsProvider } from '@polkadot/api';
+import { blake2AsU8a } from '@polkadot/util-crypto';
 import { web3Accounts, web3Enable } from '@polkadot/extension-dapp';
 import type { InjectedAccountWithMeta } from '@polkadot/extension-inject/types';
 import './App.css';
 
+// Community asset created at genesis (the main NST token)
+const NST_ASSET_ID = 0;
+
 interface TokenBatch {
   amount: string;
   expiresAtBlock: number;
@@ -62,6 +66,7 @@ function App() {
   
   const [burnRecipient, setBurnRecipient] = useState('');
   const [burnAmount, setBurnAmount] = useState('');
+  const [burnCategory, setBurnCategory] = useState('');
   
   const [status, setStatus] = useState('');
   const [loading, setLoading] = useState(false);
@@ -201,7 +206,7 @@ function App() {
           }
           
           try {
-            const totalSupply = await api.query.ubiToken.totalSupply();
+            const totalSupply = await api.query.ubiToken.totalSupply(NST_ASSET_ID);
             const finalizedHead = await api.rpc.chain.getFinalizedHead();
             const finalizedHeader = await api.rpc.chain.getHeader(finalizedHead);
             
@@ -250,7 +255,7 @@ function App() {
     try {
       console.log('Fetching data for account:', selectedAccount, 'at block:', blockNumber);
       
-      const balances = await api.query.ubiToken.balances(selectedAccount);
+      const balances = await api.query.ubiToken.balances(NST_ASSET_ID, selectedAccount);
       const batchesRaw = balances.toJSON() as unknown[];
       console.log('Raw balances:', JSON.stringify(batchesRaw, null, 2));
       const batches: TokenBatch[] = (batchesRaw as Array<{ amount?: string; 0?: string; expiresAt?: number; expires_at?: number; 1?: number }>)?.map((b) => {
@@ -268,7 +273,7 @@ function App() {
         .reduce((sum, b) => sum + BigInt(b.amount), BigInt(0));
       setTotalBalance(total.toString());
       
-      const lastClaimBlock = await api.query.ubiToken.lastClaim(selectedAccount);
+      const lastClaimBlock = await api.query.ubiToken.lastClaim(NST_ASSET_ID, selectedAccount);
       const lastClaim = lastClaimBlock.toJSON() as number | null;
       console.log('Last claim block:', lastClaim);
       
@@ -288,7 +293,7 @@ function App() {
         setClaimableAmount((periods * 100 * 10**9).toString());
       }
       
-      const rep = await api.query.ubiToken.reputationStore(selectedAccount);
+      const rep = await api.query.ubiToken.reputationStore(NST_ASSET_ID, selectedAccount);
       const repJson = rep.toJSON() as {
         score?: number;
         weightedReceived?: number;
@@ -337,6 +342,22 @@ function App() {
     fetchAccountData();
   }, [fetchAccountData]);
 
+  // Find a nonce whose claim hash has `difficulty` leading zero bits
+  const findClaimNonce = (period: number, difficulty: number): bigint => {
+    if (!api || difficulty === 0) return BigInt(0);
+    for (let nonce = BigInt(0); ; nonce++) {
+      const hash = blake2AsU8a(
+        api.createType('(u32, AccountId, u64, u64)', [NST_ASSET_ID, selectedAccount, period, nonce]).toU8a()
+      );
+      let bits = 0;
+      for (const byte of hash) {
+        bits += byte === 0 ? 8 : Math.clz32(byte) - 24;
+        if (byte !== 0) break;
+      }
+      if (bits >= difficulty) return nonce;
+    }
+  };
+
   const claimUBI = async () => {
     if (!api || !selectedAccount) return;
     
@@ -344,7 +365,12 @@ function App() {
     setStatus('Claiming UBI...');
     
     try {
-      const tx = api.tx.ubiToken.claim(selectedAccount);
+      // The claim is included in the next block at the earliest
+      const claimPeriod = 10;
+      const period = Math.floor((blockNumber + 1) / claimPeriod);
+      const difficulty = (api.consts.ubiToken.claimPowDifficulty as unknown as { toNumber(): number }).toNumber();
+      const nonce = findClaimNonce(period, difficulty);
+      const tx = api.tx.ubiToken.claim(NST_ASSET_ID, selectedAccount, nonce);
       console.log('TX hex:', tx.toHex());
       console.log('TX method:', tx.method.toHex());
       
@@ -374,8 +400,17 @@ function App() {
     
     try {
       const amount = BigInt(burnAmount) * BigInt(10 ** 9);
+      const nonce = await api.query.ubiToken.burnNonces(NST_ASSET_ID, selectedAccount);
       
-      const tx = api.tx.ubiToken.burn(selectedAccount, burnRecipient, amount.toString());
+      const tx = api.tx.ubiToken.burn(
+        NST_ASSET_ID,
+        selectedAccount,
+        burnRecipient,
+        amount.toString(),
+        burnCategory || null,
+        null,
+        nonce
+      );
       const extrinsic = api.createType('Extrinsic', tx);
       
       const hash = await api.rpc.author.submitExtrinsic(extrinsic);
@@ -385,6 +420,7 @@ function App() {
         fetchAccountData();
         setBurnRecipient('');
         setBurnAmount('');
+        setBurnCategory('');
         setLoading(false);
         setStatus('Burn processed!');
       }, 6000);
@@ -570,6 +606,21 @@ function App() {
                   min="1"
                 />
               </div>
+              <div className="form-group">
+                <label className="form-label">Category (optional)</label>
+                <select
+                  className="form-input"
+                  value={burnCategory}
+                  onChange={(e) => setBurnCategory(e.target.value)}
+                >
+                  <option value="">None</option>
+                  <option value="Food">Food</option>
+                  <option value="Housing">Housing</option>
+                  <option value="Services">Services</option>
+                  <option value="Donation">Donation</option>
+                  <option value="Other">Other</option>
+                </select>
+              </div>
               <button 
                 onClick={burnTokens} 
                 disabled={!burnRecipient || !burnAmount || loading}
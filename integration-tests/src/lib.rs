@@ -0,0 +1,243 @@
+//! # NST Integration Tests
+//!
+//! Harness for end-to-end tests against a local network of real nodes.
+//!
+//! [`Network::start`] builds two validators of the `local` chain (Alice and
+//! Bob) in-process with `sc_service`, each with its own temporary database,
+//! and links Bob to Alice as bootnode. The genesis shortens the NST claim
+//! period and expiry to [`CLAIM_PERIOD_BLOCKS`] and [`EXPIRATION_BLOCKS`], so
+//! the flows finish within minutes without a `fast-runtime` build. Tests talk
+//! to the nodes over JSON-RPC: extrinsics go through `author_submitExtrinsic`
+//! and the pallet is read through the `UbiTokenApi` runtime API.
+//!
+//! The nodes need the wasm runtime. Built with `SKIP_WASM_BUILD` there is
+//! none, and the tests return early instead of failing:
+//!
+//! ```bash
+//! cargo test -p nst-integration-tests
+//! ```
+
+use std::{
+    net::TcpListener,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use jsonrpsee::{
+    core::client::ClientT,
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use nst_node::{
+    chain_spec::{local_testnet_config, UbiGenesisParams},
+    cli::Cli,
+};
+use nst_runtime::{BlockNumber, RuntimeCall, UncheckedExtrinsic};
+use parity_scale_codec::{Decode, Encode};
+use sc_cli::CliConfiguration;
+use sc_service::TaskManager;
+use sp_core::{Bytes, H256};
+
+/// Claim period of the NST asset in the test genesis
+pub const CLAIM_PERIOD_BLOCKS: BlockNumber = 10;
+
+/// Expiry of claimed NST in the test genesis
+pub const EXPIRATION_BLOCKS: BlockNumber = 20;
+
+/// Node key of the bootnode (Alice)
+const BOOTNODE_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+/// Peer id derived from `BOOTNODE_KEY`
+const BOOTNODE_PEER_ID: &str = "12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp";
+
+/// How long to wait for a node to come up or a block to be reached
+const TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Polling interval while waiting
+const POLL: Duration = Duration::from_millis(500);
+
+/// Result type of the harness
+pub type Result<T> = std::result::Result<T, String>;
+
+/// A node running in this process with its RPC client
+///
+/// The node's tasks are stopped and its database removed on drop.
+pub struct Node {
+    rpc: HttpClient,
+    _task_manager: TaskManager,
+    _base_path: tempfile::TempDir,
+}
+
+/// Two linked validators of the `local` chain
+pub struct Network {
+    pub alice: Node,
+    pub bob: Node,
+}
+
+impl Network {
+    /// Whether the runtime was built to wasm, which the nodes need
+    pub fn available() -> bool {
+        nst_runtime::WASM_BINARY.is_some()
+    }
+
+    /// Start Alice and Bob and wait until they are connected
+    pub async fn start() -> Result<Self> {
+        let alice_port = free_port()?;
+        let alice = Node::start("alice", alice_port, &["--node-key", BOOTNODE_KEY])?;
+        let bootnode = format!("/ip4/127.0.0.1/tcp/{alice_port}/p2p/{BOOTNODE_PEER_ID}");
+        let bob = Node::start("bob", free_port()?, &["--bootnodes", &bootnode])?;
+
+        alice.wait_for_peers(1).await?;
+        bob.wait_for_peers(1).await?;
+        Ok(Network { alice, bob })
+    }
+}
+
+impl Node {
+    /// Build a validator with the given dev key (`alice`, `bob`, ...)
+    fn start(key: &str, port: u16, extra_args: &[&str]) -> Result<Self> {
+        let base_path = tempfile::tempdir().map_err(|e| format!("Cannot create base path: {e}"))?;
+        let rpc_port = free_port()?;
+
+        let (port, rpc_port) = (port.to_string(), rpc_port.to_string());
+        let args = [
+            "nst-node",
+            "--chain=local",
+            &format!("--{key}"),
+            "--base-path",
+            &base_path.path().to_string_lossy(),
+            "--port",
+            &port,
+            "--rpc-port",
+            &rpc_port,
+            "--network-backend=litep2p",
+            "--no-mdns",
+            "--no-prometheus",
+            "--no-telemetry",
+        ];
+        let cli = Cli::try_parse_from(args.iter().chain(extra_args)).map_err(|e| format!("Invalid arguments: {e}"))?;
+
+        let mut config = cli
+            .run
+            .create_configuration(&cli, tokio::runtime::Handle::current())
+            .map_err(|e| format!("Invalid configuration: {e}"))?;
+        config.chain_spec = Box::new(local_testnet_config(&UbiGenesisParams {
+            claim_period_blocks: Some(CLAIM_PERIOD_BLOCKS),
+            expiration_blocks: Some(EXPIRATION_BLOCKS),
+            ..Default::default()
+        })?);
+
+        let task_manager = nst_node::service::new_full::<sc_network::Litep2pNetworkBackend>(
+            config,
+            cli.auto_claim,
+            cli.ubi_archive,
+            cli.rpc_limit,
+        )
+        .map_err(|e| format!("Cannot start {key}: {e}"))?;
+
+        let rpc = HttpClientBuilder::default()
+            .build(format!("http://127.0.0.1:{rpc_port}"))
+            .map_err(|e| format!("Cannot build RPC client: {e}"))?;
+
+        Ok(Node { rpc, _task_manager: task_manager, _base_path: base_path })
+    }
+
+    /// Wait until the node is connected to at least `peers` peers
+    pub async fn wait_for_peers(&self, peers: u64) -> Result<()> {
+        #[derive(serde::Deserialize)]
+        struct Health {
+            peers: u64,
+        }
+
+        wait_until("peers", || async move {
+            let health: Health = self.rpc.request("system_health", rpc_params![]).await.ok()?;
+            (health.peers >= peers).then_some(())
+        })
+        .await
+    }
+
+    /// Number of the node's best block
+    pub async fn best_block(&self) -> Result<BlockNumber> {
+        #[derive(serde::Deserialize)]
+        struct Header {
+            number: String,
+        }
+
+        let header: Header = self
+            .rpc
+            .request("chain_getHeader", rpc_params![])
+            .await
+            .map_err(|e| format!("chain_getHeader failed: {e}"))?;
+        BlockNumber::from_str_radix(header.number.trim_start_matches("0x"), 16)
+            .map_err(|e| format!("Invalid block number {}: {e}", header.number))
+    }
+
+    /// Wait until the best block is at least `block`
+    pub async fn wait_for_block(&self, block: BlockNumber) -> Result<()> {
+        wait_until("block", || async move { (self.best_block().await.ok()? >= block).then_some(()) }).await
+    }
+
+    /// Wait until a runtime API call at the best block satisfies `done`
+    pub async fn wait_for<R: Decode>(
+        &self,
+        method: &str,
+        args: impl Encode,
+        done: impl Fn(&R) -> bool,
+    ) -> Result<R> {
+        let (args, done) = (&args.encode(), &done);
+        wait_until(method, || async move {
+            let result: R = self.state_call_raw(method, args.clone()).await.ok()?;
+            done(&result).then_some(result)
+        })
+        .await
+    }
+
+    /// Call a runtime API function at the best block and decode its result
+    pub async fn state_call<R: Decode>(&self, method: &str, args: impl Encode) -> Result<R> {
+        self.state_call_raw(method, args.encode()).await
+    }
+
+    async fn state_call_raw<R: Decode>(&self, method: &str, args: Vec<u8>) -> Result<R> {
+        let result: Bytes = self
+            .rpc
+            .request("state_call", rpc_params![method, Bytes(args)])
+            .await
+            .map_err(|e| format!("{method} failed: {e}"))?;
+        R::decode(&mut &result[..]).map_err(|e| format!("Cannot decode the result of {method}: {e}"))
+    }
+
+    /// Submit a call as unsigned extrinsic, returning the transaction hash
+    pub async fn submit(&self, call: RuntimeCall) -> Result<H256> {
+        let xt = UncheckedExtrinsic::new_bare(call);
+        self.rpc
+            .request("author_submitExtrinsic", rpc_params![Bytes(xt.encode())])
+            .await
+            .map_err(|e| format!("Transaction rejected: {e}"))
+    }
+}
+
+/// A TCP port that is free right now
+fn free_port() -> Result<u16> {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("No free port: {e}"))
+}
+
+/// Poll `check` until it returns `Some`, failing after `TIMEOUT`
+async fn wait_until<T, F, Fut>(what: &str, check: F) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Option<T>>,
+{
+    let start = Instant::now();
+    loop {
+        if let Some(value) = check().await {
+            return Ok(value);
+        }
+        if start.elapsed() > TIMEOUT {
+            return Err(format!("Timed out waiting for {what}"));
+        }
+        tokio::time::sleep(POLL).await;
+    }
+}
@@ -0,0 +1,93 @@
+//! Claim, burn and expiry across a two-node network
+//!
+//! Every transaction is submitted to one node and its effect checked on the
+//! other, so the flow also covers gossip, block production and import.
+
+use nst_integration_tests::{Network, Result};
+use nst_runtime::{AccountId, Balance, BlockNumber, RuntimeCall, UbiAmount, NST_ASSET_ID};
+use pallet_ubi_token::BurnCategory;
+use pallet_ubi_token_runtime_api::{Reputation, TokenBatch, UbiParameters};
+use sp_keyring::Sr25519Keyring;
+
+type Batches = Vec<TokenBatch<Balance, BlockNumber>>;
+
+fn total(batches: &Batches) -> Balance {
+    batches.iter().map(|batch| batch.amount).sum()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn claim_burn_and_expiry_end_to_end() -> Result<()> {
+    if !Network::available() {
+        eprintln!("Skipped: the runtime was built without wasm (SKIP_WASM_BUILD)");
+        return Ok(());
+    }
+    let network = Network::start().await?;
+    let alice: AccountId = Sr25519Keyring::Alice.to_account_id();
+    let bob: AccountId = Sr25519Keyring::Bob.to_account_id();
+
+    // Claim through Alice's node, observe on Bob's
+    let params: Option<UbiParameters<Balance, BlockNumber>> =
+        network.alice.state_call("UbiTokenApi_ubi_parameters", NST_ASSET_ID).await?;
+    let params = params.ok_or("NST asset missing")?;
+    let period = u64::from(network.alice.best_block().await? + 1) / u64::from(params.asset.claim_period_blocks);
+    let nonce = pallet_ubi_token::find_claim_nonce(&NST_ASSET_ID, &alice, period, params.claim_pow_difficulty)
+        .ok_or("No claim proof found")?;
+    network
+        .alice
+        .submit(RuntimeCall::UbiToken(pallet_ubi_token::Call::claim {
+            asset_id: NST_ASSET_ID,
+            account: alice.clone(),
+            nonce,
+        }))
+        .await?;
+    let batches: Batches = network
+        .bob
+        .wait_for("UbiTokenApi_token_batches", (NST_ASSET_ID, &alice), |batches: &Batches| !batches.is_empty())
+        .await?;
+    assert_eq!(total(&batches), UbiAmount::get());
+    let expires_at = batches[0].expires_at;
+
+    // Burn through Bob's node, observe on Alice's
+    let amount = UbiAmount::get() / 4;
+    let burn_nonce: u64 = network.bob.state_call("UbiTokenApi_burn_nonce", (NST_ASSET_ID, &alice)).await?;
+    network
+        .bob
+        .submit(RuntimeCall::UbiToken(pallet_ubi_token::Call::burn {
+            asset_id: NST_ASSET_ID,
+            from: alice.clone(),
+            to: bob.clone(),
+            amount,
+            category: Some(BurnCategory::Food),
+            reference: None,
+            nonce: burn_nonce,
+            valid_until: None,
+            idempotency_key: None,
+        }))
+        .await?;
+    let received: Reputation<Balance, BlockNumber> = network
+        .alice
+        .wait_for("UbiTokenApi_reputation", (NST_ASSET_ID, &bob), |rep: &Reputation<Balance, BlockNumber>| {
+            rep.burns_received_count == 1
+        })
+        .await?;
+    assert_eq!(received.burns_received_volume, amount);
+
+    let sent: Reputation<Balance, BlockNumber> =
+        network.alice.state_call("UbiTokenApi_reputation", (NST_ASSET_ID, &alice)).await?;
+    assert_eq!(sent.burns_sent_count, 1);
+    let batches: Batches = network.alice.state_call("UbiTokenApi_token_batches", (NST_ASSET_ID, &alice)).await?;
+    assert_eq!(total(&batches), UbiAmount::get() - amount);
+
+    // The rest expires on both nodes
+    for node in [&network.alice, &network.bob] {
+        node.wait_for_block(expires_at).await?;
+        let batches: Batches = node.state_call("UbiTokenApi_token_batches", (NST_ASSET_ID, &alice)).await?;
+        assert!(batches.is_empty());
+        let best = node.best_block().await?;
+        let spendable: Balance =
+            node.state_call("UbiTokenApi_spendable_balance_at", (NST_ASSET_ID, &alice, best)).await?;
+        assert_eq!(spendable, 0);
+    }
+
+    Ok(())
+}
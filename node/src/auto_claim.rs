@@ -0,0 +1,220 @@
+//! Auto-claim relayer
+//!
+//! Optional background task (`--auto-claim`) that submits the unsigned UBI
+//! `claim` of a set of accounts as soon as they can claim again, so a
+//! community can run a claim relayer without external scripts.
+//!
+//! The accounts are the ones given with `--auto-claim-account` plus every
+//! sr25519 key of type `ubic` in the node's keystore. The keystore is read on
+//! every new best block, so keys added with `author_insertKey` are picked up
+//! without a restart. Claims are unsigned, the relayer pays no fees, but it
+//! solves the claim proof-of-work if the runtime requires one. Proofs are
+//! solved on a blocking thread, once per account and claim period, and
+//! accounts whose claim is still in the pool are skipped.
+//!
+//! Each account claims in its own window, anchored to its first claim.
+//! Shortly before an account's next window opens (within the runtime's claim
+//! lookahead) the relayer pre-submits its claim. The pool holds it until the
+//! window's first block, so it lands right at rollover.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::Arc,
+};
+
+use futures::StreamExt;
+use nst_runtime::{opaque::Block, AccountId, Balance, BlockNumber, RuntimeCall, UncheckedExtrinsic};
+use pallet_ubi_token_runtime_api::UbiTokenApi;
+use sc_client_api::BlockchainEvents;
+use sc_service::TaskManager;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TransactionSource};
+use sp_api::ProvideRuntimeApi;
+use sp_core::crypto::KeyTypeId;
+use sp_keystore::KeystorePtr;
+
+/// Key type of the keystore accounts the relayer claims for
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"ubic");
+
+const LOG_TARGET: &str = "ubi-auto-claim";
+
+/// Command line options of the auto-claim relayer
+#[derive(Debug, Clone, clap::Args)]
+pub struct AutoClaimParams {
+    /// Submit the UBI claims of the configured accounts every claim period
+    #[arg(long)]
+    pub auto_claim: bool,
+
+    /// Account to claim for (SS58 or hex), can be repeated
+    ///
+    /// Keys of type `ubic` in the keystore are claimed for as well.
+    #[arg(long = "auto-claim-account", value_name = "ADDRESS", requires = "auto_claim")]
+    pub accounts: Vec<AccountId>,
+
+    /// Community asset to claim
+    #[arg(
+        long = "auto-claim-asset",
+        value_name = "ID",
+        default_value_t = nst_runtime::NST_ASSET_ID,
+        requires = "auto_claim"
+    )]
+    pub asset_id: u32,
+}
+
+/// Spawn the relayer if `--auto-claim` is set
+pub fn spawn<C, P>(
+    params: &AutoClaimParams,
+    task_manager: &TaskManager,
+    client: Arc<C>,
+    pool: Arc<P>,
+    keystore: KeystorePtr,
+) where
+    C: ProvideRuntimeApi<Block> + BlockchainEvents<Block> + Send + Sync + 'static,
+    C::Api: UbiTokenApi<Block, u32, AccountId, Balance, BlockNumber>,
+    P: TransactionPool<Block = Block> + 'static,
+{
+    if !params.auto_claim {
+        return;
+    }
+
+    log::info!(
+        target: LOG_TARGET,
+        "Auto-claim enabled for asset {} and {} configured account(s)",
+        params.asset_id,
+        params.accounts.len(),
+    );
+    task_manager.spawn_handle().spawn(
+        "ubi-auto-claim",
+        None,
+        run(params.asset_id, params.accounts.clone(), client, pool, keystore),
+    );
+}
+
+/// Submit the claims of every account that can claim at each new best block
+async fn run<C, P>(
+    asset_id: u32,
+    configured: Vec<AccountId>,
+    client: Arc<C>,
+    pool: Arc<P>,
+    keystore: KeystorePtr,
+) where
+    C: ProvideRuntimeApi<Block> + BlockchainEvents<Block> + Send + Sync + 'static,
+    C::Api: UbiTokenApi<Block, u32, AccountId, Balance, BlockNumber>,
+    P: TransactionPool<Block = Block> + 'static,
+{
+    let mut blocks = client.import_notification_stream();
+    // Claim proofs solved (difficulty and nonce) and claims submitted, by account and claim period
+    let mut proofs: BTreeMap<(AccountId, u64), (u32, u64)> = BTreeMap::new();
+    let mut submitted: BTreeMap<(AccountId, u64), P::Hash> = BTreeMap::new();
+
+    while let Some(notification) = blocks.next().await {
+        if !notification.is_new_best {
+            continue;
+        }
+        let at = notification.hash;
+
+        let params = match client.runtime_api().ubi_parameters(at, asset_id) {
+            Ok(Some(params)) => params,
+            Ok(None) => {
+                log::warn!(target: LOG_TARGET, "Asset {asset_id} does not exist");
+                continue;
+            }
+            Err(e) => {
+                log::warn!(target: LOG_TARGET, "Cannot query parameters of asset {asset_id}: {e}");
+                continue;
+            }
+        };
+        // Claims are included in the next block at the earliest
+        let next_block = notification.header.number + 1;
+        let period_blocks = params.asset.claim_period_blocks.max(1);
+
+        // Proofs and claims of past periods are of no use anymore
+        let current_period = u64::from(next_block / period_blocks);
+        proofs.retain(|(_, period), _| *period >= current_period);
+        submitted.retain(|(_, period), _| *period >= current_period);
+        let future: HashSet<P::Hash> = pool.futures().iter().map(|tx| tx.hash().clone()).collect();
+
+        let accounts: BTreeSet<AccountId> = configured
+            .iter()
+            .cloned()
+            .chain(keystore.sr25519_public_keys(KEY_TYPE).into_iter().map(AccountId::from))
+            .collect();
+
+        for account in accounts {
+            let claimable = match client.runtime_api().claimable_amount(at, asset_id, account.clone()) {
+                Ok(amount) => amount,
+                Err(e) => {
+                    log::warn!(target: LOG_TARGET, "Cannot query claimable amount of {account}: {e}");
+                    continue;
+                }
+            };
+            // Block the claim is for: the next one, or the start of the account's next
+            // window if that is within the lookahead, the pool rejects the claim otherwise
+            let claim_block = if claimable > 0 {
+                next_block
+            } else {
+                match client.runtime_api().next_claim_block(at, asset_id, account.clone()) {
+                    Ok(Some(block)) if block.saturating_sub(next_block) <= params.claim_lookahead_blocks => {
+                        block.max(next_block)
+                    }
+                    Ok(_) => continue,
+                    Err(e) => {
+                        log::warn!(target: LOG_TARGET, "Cannot query next claim block of {account}: {e}");
+                        continue;
+                    }
+                }
+            };
+            let period = u64::from(claim_block / period_blocks);
+            let key = (account.clone(), period);
+
+            // The claim is still waiting in the pool
+            if submitted.get(&key).is_some_and(|hash| pool.ready_transaction(hash).is_some() || future.contains(hash)) {
+                continue;
+            }
+
+            // A proof of a higher difficulty is valid for a lower one
+            let difficulty = params.claim_pow_difficulty;
+            let nonce = match proofs.get(&key) {
+                Some((solved, nonce)) if *solved >= difficulty => *nonce,
+                _ => {
+                    // About 2^difficulty hashes, off the async executor
+                    let who = account.clone();
+                    let solved = tokio::task::spawn_blocking(move || {
+                        pallet_ubi_token::find_claim_nonce(&asset_id, &who, period, difficulty)
+                    })
+                    .await;
+                    let nonce = match solved {
+                        Ok(Some(nonce)) => nonce,
+                        Ok(None) => {
+                            log::warn!(target: LOG_TARGET, "No claim proof of difficulty {difficulty} for {account}");
+                            continue;
+                        }
+                        Err(e) => {
+                            log::warn!(target: LOG_TARGET, "Claim proof for {account} not solved: {e}");
+                            continue;
+                        }
+                    };
+                    proofs.insert(key.clone(), (difficulty, nonce));
+                    nonce
+                }
+            };
+            let call = RuntimeCall::UbiToken(pallet_ubi_token::Call::claim {
+                asset_id,
+                account: account.clone(),
+                nonce,
+            });
+            let xt = UncheckedExtrinsic::new_bare(call).into();
+
+            match pool.submit_one(at, TransactionSource::Local, xt).await {
+                Ok(hash) => {
+                    submitted.insert(key, hash);
+                    if claimable == 0 {
+                        log::info!(target: LOG_TARGET, "Pre-submitted next claim for {account}");
+                    } else {
+                        log::info!(target: LOG_TARGET, "Submitted claim of {claimable} for {account}");
+                    }
+                }
+                Err(e) => log::debug!(target: LOG_TARGET, "Claim for {account} not submitted: {e}"),
+            }
+        }
+    }
+}
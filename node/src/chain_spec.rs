@@ -1,13 +1,18 @@
 //! Chain specification for the NST (Non Speculative Tokens) blockchain
 
-use nst_runtime::{AccountId, Signature, WASM_BINARY};
-use sc_service::ChainType;
+use nst_runtime::{
+    AccountId, Balance, BlockNumber, ClaimPeriodBlocks, ExpirationBlocks, MaxBacklogPeriods, SessionKeys, Signature,
+    UbiAmount, NST_ASSET_ID, SS58_PREFIX, TOKEN_DECIMALS, TOKEN_SYMBOL, WASM_BINARY,
+};
+use pallet_ubi_token::AssetParameters;
+use sc_service::{ChainType, Properties};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
 /// Specialized chain spec
+#[cfg(not(feature = "parachain"))]
 pub type ChainSpec = sc_service::GenericChainSpec;
 
 /// Generate a crypto pair from seed
@@ -27,16 +32,86 @@ where
     AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate authority keys (Aura and Grandpa)
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
+/// Generate authority keys (validator account, Aura and Grandpa)
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId) {
     (
+        get_account_id_from_seed::<sr25519::Public>(s),
         get_from_seed::<AuraId>(s),
         get_from_seed::<GrandpaId>(s),
     )
 }
 
+/// Chain properties read by wallets to render NST addresses and amounts
+pub fn chain_properties() -> Properties {
+    let mut properties = Properties::new();
+    properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+    properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+    properties.insert("ss58Format".into(), SS58_PREFIX.into());
+    properties
+}
+
+/// Overrides of the NST asset's economics in the built-in chain specs
+///
+/// Unset values keep the runtime's defaults.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct UbiGenesisParams {
+    /// Tokens distributed per claim period, in the smallest unit
+    #[arg(long, value_name = "AMOUNT")]
+    pub ubi_amount: Option<Balance>,
+
+    /// Blocks in one claim period
+    #[arg(long, value_name = "BLOCKS")]
+    pub claim_period_blocks: Option<BlockNumber>,
+
+    /// Blocks until claimed tokens expire
+    #[arg(long, value_name = "BLOCKS")]
+    pub expiration_blocks: Option<BlockNumber>,
+
+    /// Claim periods that can be claimed as backlog
+    #[arg(long, value_name = "PERIODS")]
+    pub max_backlog_periods: Option<u32>,
+}
+
+impl UbiGenesisParams {
+    /// Parameters of the NST asset, `None` if nothing is overridden
+    fn asset_parameters(&self) -> Result<Option<AssetParameters<Balance, BlockNumber>>, String> {
+        if self.is_unset() {
+            return Ok(None);
+        }
+        let params = AssetParameters {
+            ubi_amount: self.ubi_amount.unwrap_or(UbiAmount::get()),
+            claim_period_blocks: self.claim_period_blocks.unwrap_or(ClaimPeriodBlocks::get()),
+            expiration_blocks: self.expiration_blocks.unwrap_or(ExpirationBlocks::get()),
+            max_backlog_periods: self.max_backlog_periods.unwrap_or(MaxBacklogPeriods::get()),
+        };
+        // Checked again by the genesis build, which would only panic
+        if params.ubi_amount == 0 || params.claim_period_blocks == 0 || params.expiration_blocks == 0 {
+            return Err("--ubi-amount, --claim-period-blocks and --expiration-blocks must not be zero".into());
+        }
+        Ok(Some(params))
+    }
+
+    /// Whether no value is overridden
+    fn is_unset(&self) -> bool {
+        self.ubi_amount.is_none()
+            && self.claim_period_blocks.is_none()
+            && self.expiration_blocks.is_none()
+            && self.max_backlog_periods.is_none()
+    }
+
+    /// Reject overrides for chain specs read from a file
+    pub fn ensure_unset(&self) -> Result<(), String> {
+        if self.is_unset() {
+            Ok(())
+        } else {
+            Err("UBI parameter overrides only apply to the built-in `dev` and `local` chain specs".into())
+        }
+    }
+}
+
 /// Development chain config
-pub fn development_config() -> Result<ChainSpec, String> {
+#[cfg(not(feature = "parachain"))]
+pub fn development_config(ubi: &UbiGenesisParams) -> Result<ChainSpec, String> {
     Ok(ChainSpec::builder(
         WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
         None,
@@ -44,6 +119,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
     .with_name("NST Development")
     .with_id("nst_dev")
     .with_chain_type(ChainType::Development)
+    .with_properties(chain_properties())
     .with_genesis_config_patch(testnet_genesis(
         // Initial authorities
         vec![authority_keys_from_seed("Alice")],
@@ -58,12 +134,15 @@ pub fn development_config() -> Result<ChainSpec, String> {
             get_account_id_from_seed::<sr25519::Public>("Eve"),
             get_account_id_from_seed::<sr25519::Public>("Ferdie"),
         ],
+        // NST asset parameters
+        ubi.asset_parameters()?,
     ))
     .build())
 }
 
 /// Local testnet config
-pub fn local_testnet_config() -> Result<ChainSpec, String> {
+#[cfg(not(feature = "parachain"))]
+pub fn local_testnet_config(ubi: &UbiGenesisParams) -> Result<ChainSpec, String> {
     Ok(ChainSpec::builder(
         WASM_BINARY.ok_or_else(|| "Testnet wasm not available".to_string())?,
         None,
@@ -71,6 +150,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
     .with_name("NST Local Testnet")
     .with_id("nst_local")
     .with_chain_type(ChainType::Local)
+    .with_properties(chain_properties())
     .with_genesis_config_patch(testnet_genesis(
         // Initial authorities
         vec![
@@ -86,34 +166,135 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
             get_account_id_from_seed::<sr25519::Public>("Charlie"),
             get_account_id_from_seed::<sr25519::Public>("Dave"),
         ],
+        // NST asset parameters
+        ubi.asset_parameters()?,
     ))
     .build())
 }
 
+/// Parachain development config, collating for a local relay chain
+#[cfg(feature = "parachain")]
+pub fn parachain_development_config(ubi: &UbiGenesisParams) -> Result<crate::parachain::ChainSpec, String> {
+    Ok(crate::parachain::ChainSpec::builder(
+        WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
+        crate::parachain::Extensions {
+            relay_chain: "rococo-local".into(),
+            para_id: crate::parachain::DEFAULT_PARA_ID,
+        },
+    )
+    .with_name("NST Parachain Development")
+    .with_id("nst_parachain_dev")
+    .with_chain_type(ChainType::Development)
+    .with_properties(chain_properties())
+    .with_genesis_config_patch(parachain_genesis(
+        testnet_genesis(
+            vec![authority_keys_from_seed("Alice")],
+            get_account_id_from_seed::<sr25519::Public>("Alice"),
+            vec![
+                get_account_id_from_seed::<sr25519::Public>("Alice"),
+                get_account_id_from_seed::<sr25519::Public>("Bob"),
+            ],
+            ubi.asset_parameters()?,
+        ),
+        crate::parachain::DEFAULT_PARA_ID,
+    ))
+    .build())
+}
+
+/// Parachain local testnet config
+#[cfg(feature = "parachain")]
+pub fn parachain_local_testnet_config(ubi: &UbiGenesisParams) -> Result<crate::parachain::ChainSpec, String> {
+    Ok(crate::parachain::ChainSpec::builder(
+        WASM_BINARY.ok_or_else(|| "Testnet wasm not available".to_string())?,
+        crate::parachain::Extensions {
+            relay_chain: "rococo-local".into(),
+            para_id: crate::parachain::DEFAULT_PARA_ID,
+        },
+    )
+    .with_name("NST Parachain Local Testnet")
+    .with_id("nst_parachain_local")
+    .with_chain_type(ChainType::Local)
+    .with_properties(chain_properties())
+    .with_genesis_config_patch(parachain_genesis(
+        testnet_genesis(
+            vec![
+                authority_keys_from_seed("Alice"),
+                authority_keys_from_seed("Bob"),
+            ],
+            get_account_id_from_seed::<sr25519::Public>("Alice"),
+            vec![
+                get_account_id_from_seed::<sr25519::Public>("Alice"),
+                get_account_id_from_seed::<sr25519::Public>("Bob"),
+                get_account_id_from_seed::<sr25519::Public>("Charlie"),
+                get_account_id_from_seed::<sr25519::Public>("Dave"),
+            ],
+            ubi.asset_parameters()?,
+        ),
+        crate::parachain::DEFAULT_PARA_ID,
+    ))
+    .build())
+}
+
+/// Extend a solochain genesis patch with the parachain id.
+///
+/// Finality comes from the relay chain; the GRANDPA keys in the session
+/// genesis are simply never used by the collators.
+#[cfg(feature = "parachain")]
+fn parachain_genesis(mut genesis: serde_json::Value, para_id: u32) -> serde_json::Value {
+    genesis["parachainInfo"] = serde_json::json!({ "parachainId": para_id });
+    genesis
+}
+
+/// Session keys of a genesis authority
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+    SessionKeys { aura, grandpa }
+}
+
 /// Configure initial storage state for genesis
 /// 
 /// Note: UBI tokens are NOT pre-allocated. Every account must call claim() to receive
 /// their daily UBI allocation. The balances here are for native token (gas fees only).
 fn testnet_genesis(
-    initial_authorities: Vec<(AuraId, GrandpaId)>,
+    initial_authorities: Vec<(AccountId, AuraId, GrandpaId)>,
     root_key: AccountId,
     endowed_accounts: Vec<AccountId>,
+    asset_parameters: Option<AssetParameters<Balance, BlockNumber>>,
 ) -> serde_json::Value {
     serde_json::json!({
         "balances": {
             // Native token for gas fees (not UBI tokens)
             "balances": endowed_accounts.iter().cloned().map(|k| (k, 1_000_000_000_000_000u128)).collect::<Vec<_>>(),
         },
+        // Aura and Grandpa authorities are set by the session pallet from the keys below
         "aura": {
-            "authorities": initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
+            "authorities": Vec::<AuraId>::new(),
         },
         "grandpa": {
-            "authorities": initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
+            "authorities": Vec::<(GrandpaId, u64)>::new(),
+        },
+        "session": {
+            "keys": initial_authorities
+                .iter()
+                .map(|x| (x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone())))
+                .collect::<Vec<_>>(),
+        },
+        // Same authorities, so that governance can later add or remove validators
+        "validatorSet": {
+            "validators": initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
         },
         "sudo": {
-            "key": Some(root_key),
+            "key": Some(root_key.clone()),
+        },
+        // The initial validators also form the technical committee
+        "technicalCommittee": {
+            "members": initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
+        },
+        // Only the main NST asset exists at genesis (owned by the sudo key).
+        // Everyone starts with 0 tokens and must claim() to receive their daily UBI
+        "ubiToken": {
+            "assets": vec![(NST_ASSET_ID, root_key)],
+            // Economics given to `build-spec`, the runtime's defaults otherwise
+            "assetParameters": asset_parameters.map(|params| (NST_ASSET_ID, params)).into_iter().collect::<Vec<_>>(),
         },
-        // UbiToken pallet has no genesis config - everyone starts with 0 tokens
-        // and must claim() to receive their daily UBI
     })
 }
@@ -1,6 +1,9 @@
 //! CLI definition for the node
 
+#[cfg(not(feature = "parachain"))]
 use sc_cli::RunCmd;
+#[cfg(feature = "parachain")]
+use cumulus_client_cli::RunCmd;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -9,12 +12,26 @@ pub struct Cli {
 
     #[clap(flatten)]
     pub run: RunCmd,
+
+    #[clap(flatten)]
+    pub auto_claim: crate::auto_claim::AutoClaimParams,
+
+    #[clap(flatten)]
+    pub ubi_archive: crate::ubi_archive::UbiArchiveParams,
+
+    #[clap(flatten)]
+    pub rpc_limit: crate::rpc_limit::RpcLimitParams,
+
+    /// Relay chain arguments, passed after `--`
+    #[cfg(feature = "parachain")]
+    #[arg(raw = true)]
+    pub relay_chain_args: Vec<String>,
 }
 
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
     /// Build a chain specification
-    BuildSpec(sc_cli::BuildSpecCmd),
+    BuildSpec(BuildSpecCmd),
 
     /// Validate blocks
     CheckBlock(sc_cli::CheckBlockCmd),
@@ -25,6 +42,9 @@ pub enum Subcommand {
     /// Export the state of a given block into a chain spec
     ExportState(sc_cli::ExportStateCmd),
 
+    /// Export the UBI balances, reputations and stats of a given block to JSON or CSV
+    ExportUbiState(crate::export_ubi_state::ExportUbiStateCmd),
+
     /// Import blocks
     ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -34,6 +54,14 @@ pub enum Subcommand {
     /// Revert the chain to a previous state
     Revert(sc_cli::RevertCmd),
 
+    /// Export the genesis head data of the parachain
+    #[cfg(feature = "parachain")]
+    ExportGenesisHead(cumulus_client_cli::ExportGenesisHeadCommand),
+
+    /// Export the genesis wasm of the parachain
+    #[cfg(feature = "parachain")]
+    ExportGenesisWasm(cumulus_client_cli::ExportGenesisWasmCommand),
+
     /// Sub-commands concerned with benchmarking
     #[cfg(feature = "runtime-benchmarks")]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
@@ -44,4 +72,18 @@ pub enum Subcommand {
 
     /// Db meta columns information
     ChainInfo(sc_cli::ChainInfoCmd),
+
+    /// Inspect the UBI token through a running node
+    #[command(subcommand)]
+    Ubi(crate::ubi::UbiCmd),
+}
+
+/// `build-spec` with overrides of the UBI economics of the built-in specs
+#[derive(Debug, clap::Parser)]
+pub struct BuildSpecCmd {
+    #[clap(flatten)]
+    pub base: sc_cli::BuildSpecCmd,
+
+    #[clap(flatten)]
+    pub ubi: crate::chain_spec::UbiGenesisParams,
 }
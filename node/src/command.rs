@@ -9,7 +9,7 @@ use clap::Parser;
 use nst_runtime::opaque::Block;
 use sc_cli::SubstrateCli;
 use sc_service::PartialComponents;
-use sp_runtime::traits::Block as BlockT;
+use sp_core::crypto::{set_default_ss58_version, Ss58AddressFormat};
 
 impl SubstrateCli for Cli {
     fn impl_name() -> String {
@@ -36,26 +36,55 @@ impl SubstrateCli for Cli {
         2024
     }
 
+    #[cfg(feature = "parachain")]
     fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+        let ubi = self.ubi_genesis_params();
         Ok(match id {
-            "dev" => Box::new(chain_spec::development_config()?),
-            "" | "local" => Box::new(chain_spec::local_testnet_config()?),
-            path => Box::new(chain_spec::ChainSpec::from_json_file(
-                std::path::PathBuf::from(path),
-            )?),
+            "dev" => Box::new(chain_spec::parachain_development_config(&ubi)?),
+            "" | "local" => Box::new(chain_spec::parachain_local_testnet_config(&ubi)?),
+            path => {
+                ubi.ensure_unset()?;
+                Box::new(crate::parachain::ChainSpec::from_json_file(std::path::PathBuf::from(path))?)
+            }
         })
     }
+
+    #[cfg(not(feature = "parachain"))]
+    fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+        let ubi = self.ubi_genesis_params();
+        Ok(match id {
+            "dev" => Box::new(chain_spec::development_config(&ubi)?),
+            "" | "local" => Box::new(chain_spec::local_testnet_config(&ubi)?),
+            path => {
+                ubi.ensure_unset()?;
+                Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?)
+            }
+        })
+    }
+}
+
+impl Cli {
+    /// UBI overrides given to `build-spec`, none for every other command
+    fn ubi_genesis_params(&self) -> chain_spec::UbiGenesisParams {
+        match &self.subcommand {
+            Some(Subcommand::BuildSpec(cmd)) => cmd.ubi.clone(),
+            _ => Default::default(),
+        }
+    }
 }
 
 /// Parse and run command line arguments
 pub fn run() -> sc_cli::Result<()> {
     let cli = Cli::parse();
+    // Print and parse addresses in the NST format
+    set_default_ss58_version(Ss58AddressFormat::custom(nst_runtime::SS58_PREFIX));
 
     match &cli.subcommand {
         Some(Subcommand::Key(cmd)) => cmd.run(&cli),
+        Some(Subcommand::Ubi(cmd)) => cmd.run(),
         Some(Subcommand::BuildSpec(cmd)) => {
-            let runner = cli.create_runner(cmd)?;
-            runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+            let runner = cli.create_runner(&cmd.base)?;
+            runner.sync_run(|config| cmd.base.run(config.chain_spec, config.network))
         }
         Some(Subcommand::CheckBlock(cmd)) => {
             let runner = cli.create_runner(cmd)?;
@@ -91,6 +120,17 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok((cmd.run(client, config.chain_spec), task_manager))
             })
         }
+        Some(Subcommand::ExportUbiState(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    ..
+                } = service::new_partial(&config)?;
+                Ok((cmd.run(client), task_manager))
+            })
+        }
         Some(Subcommand::ImportBlocks(cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.async_run(|config| {
@@ -119,6 +159,19 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok((cmd.run(client, backend, None), task_manager))
             })
         }
+        #[cfg(feature = "parachain")]
+        Some(Subcommand::ExportGenesisHead(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| {
+                let PartialComponents { client, .. } = service::new_partial(&config)?;
+                cmd.run(client)
+            })
+        }
+        #[cfg(feature = "parachain")]
+        Some(Subcommand::ExportGenesisWasm(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| cmd.run(&*config.chain_spec))
+        }
         Some(Subcommand::ChainInfo(cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.sync_run(|config| cmd.run::<Block>(&config))
@@ -127,6 +180,43 @@ pub fn run() -> sc_cli::Result<()> {
         Some(Subcommand::Benchmark(_cmd)) => {
             Err("Benchmarking not implemented".into())
         }
+        #[cfg(feature = "parachain")]
+        None => {
+            use crate::parachain::{start_parachain_node, Extensions, RelayChainCli};
+
+            let runner = cli.create_runner(&cli.run.normalize())?;
+            let collator_options = cli.run.collator_options();
+
+            runner.run_node_until_exit(|config| async move {
+                let para_id = Extensions::try_get(&*config.chain_spec)
+                    .map(|e| e.para_id)
+                    .ok_or("Could not find parachain ID in chain spec")?;
+
+                let polkadot_cli = RelayChainCli::new(
+                    &config,
+                    [RelayChainCli::executable_name()]
+                        .iter()
+                        .chain(cli.relay_chain_args.iter()),
+                );
+                let tokio_handle = config.tokio_handle.clone();
+                let polkadot_config =
+                    SubstrateCli::create_configuration(&polkadot_cli, &polkadot_cli, tokio_handle)
+                        .map_err(|err| format!("Relay chain argument error: {}", err))?;
+
+                start_parachain_node(
+                    config,
+                    polkadot_config,
+                    collator_options,
+                    para_id.into(),
+                    cli.auto_claim.clone(),
+                    cli.ubi_archive.clone(),
+                    cli.rpc_limit.clone(),
+                )
+                .await
+                    .map_err(sc_cli::Error::Service)
+            })
+        }
+        #[cfg(not(feature = "parachain"))]
         None => {
             let runner = cli.create_runner(&cli.run)?;
             runner.run_node_until_exit(|config| async move {
@@ -136,11 +226,16 @@ pub fn run() -> sc_cli::Result<()> {
                             nst_runtime::opaque::Block,
                             <nst_runtime::opaque::Block as sp_runtime::traits::Block>::Hash,
                         >,
-                    >(config)
+                    >(config, cli.auto_claim.clone(), cli.ubi_archive.clone(), cli.rpc_limit.clone())
                     .map_err(sc_cli::Error::Service),
                     sc_network::config::NetworkBackendType::Litep2p =>
-                        service::new_full::<sc_network::Litep2pNetworkBackend>(config)
-                            .map_err(sc_cli::Error::Service),
+                        service::new_full::<sc_network::Litep2pNetworkBackend>(
+                            config,
+                            cli.auto_claim.clone(),
+                            cli.ubi_archive.clone(),
+                            cli.rpc_limit.clone(),
+                        )
+                        .map_err(sc_cli::Error::Service),
                 }
             })
         }
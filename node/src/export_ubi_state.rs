@@ -0,0 +1,342 @@
+//! `export-ubi-state` subcommand
+//!
+//! Dumps the UBI token state of a block from the node's database: per asset
+//! the total supply, every account's unexpired batches and reputation, and
+//! the burn category statistics. It reads the raw storage like
+//! `export-state`, so the node must be stopped (or a copy of its database
+//! used) and the state of the block must not be pruned.
+//!
+//! The JSON output ends with a `genesis` section that can be pasted as the
+//! `ubiToken` genesis config of a new chain. Batch expiries are rebased so
+//! that each batch keeps its remaining lifetime, and the block and period
+//! fields of reputations are reset to the new chain's start.
+//!
+//! Reputations are exported as stored, decay and streak lapses since the
+//! accounts' last activity are not applied.
+
+use std::{collections::BTreeMap, fs::File, io::Write, path::PathBuf, sync::Arc};
+
+use frame_support::storage::StoragePrefixedMap;
+use nst_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Runtime};
+use pallet_ubi_token::{AssetDetailsOf, BurnCategory, CategoryStatsOf, ReputationOf, TokenBatchOf};
+use parity_scale_codec::Decode;
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::StorageProvider;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+
+use crate::service::FullClient;
+
+/// Length of a `Blake2_128Concat` hash before the encoded key
+const BLAKE2_128_LEN: usize = 16;
+
+/// Length of a `Twox64Concat` hash before the encoded key
+const TWOX_64_LEN: usize = 8;
+
+/// Output format of `export-ubi-state`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Full state including batches, category stats and the genesis section
+    Json,
+    /// One row per account with its balance and reputation
+    Csv,
+}
+
+/// `export-ubi-state [BLOCK]`
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportUbiStateCmd {
+    /// Block hash or number to export, the best block if omitted
+    #[arg(value_name = "HASH or NUMBER")]
+    pub input: Option<BlockNumberOrHash>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+    pub format: ExportFormat,
+
+    /// File to write to, stdout if omitted
+    #[arg(long, short, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+/// State of one account in one asset
+#[derive(Default)]
+struct AccountState {
+    batches: Vec<TokenBatchOf<Runtime>>,
+    reputation: ReputationOf<Runtime>,
+}
+
+/// State of one asset
+struct AssetState {
+    owner: AccountId,
+    total_supply: Balance,
+    accounts: BTreeMap<AccountId, AccountState>,
+    category_stats: Vec<(u64, BurnCategory, CategoryStatsOf<Runtime>)>,
+}
+
+impl ExportUbiStateCmd {
+    /// Run the export against the node's database
+    pub async fn run(&self, client: Arc<FullClient>) -> sc_cli::Result<()> {
+        let hash = match self.input.as_ref().map(|b| b.parse::<Block>()).transpose()? {
+            Some(id) => client.expect_block_hash_from_id(&id)?,
+            None => client.info().best_hash,
+        };
+        let block: BlockNumber = client
+            .number(hash)?
+            .ok_or_else(|| format!("Unknown block {hash:?}"))?;
+        log::info!("Exporting UBI state of block #{block} ({hash:?})...");
+
+        let assets = read_assets(&client, hash, block)?;
+
+        let mut out: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(std::io::stdout()),
+        };
+        match self.format {
+            ExportFormat::Json => {
+                let json = to_json(block, hash, &assets);
+                serde_json::to_writer_pretty(&mut out, &json).map_err(|e| format!("Cannot write JSON: {e}"))?;
+                writeln!(out)?;
+            }
+            ExportFormat::Csv => write_csv(&mut out, &assets)?,
+        }
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ExportUbiStateCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+/// Read the UBI storage of a block, grouped by asset
+fn read_assets(
+    client: &FullClient,
+    hash: Hash,
+    block: BlockNumber,
+) -> sc_cli::Result<BTreeMap<u32, AssetState>> {
+    let mut assets = BTreeMap::new();
+
+    for (key, value) in storage_pairs::<AssetDetailsOf<Runtime>>(
+        client,
+        hash,
+        pallet_ubi_token::Assets::<Runtime>::final_prefix(),
+    )? {
+        let asset_id: u32 = concat_key(&mut &key[..], BLAKE2_128_LEN)?;
+        assets.insert(
+            asset_id,
+            AssetState {
+                owner: value.owner,
+                total_supply: 0,
+                accounts: BTreeMap::new(),
+                category_stats: Vec::new(),
+            },
+        );
+    }
+
+    for (key, value) in
+        storage_pairs::<Balance>(client, hash, pallet_ubi_token::TotalSupply::<Runtime>::final_prefix())?
+    {
+        let asset_id: u32 = concat_key(&mut &key[..], BLAKE2_128_LEN)?;
+        if let Some(asset) = assets.get_mut(&asset_id) {
+            asset.total_supply = value;
+        }
+    }
+
+    for (key, value) in storage_pairs::<Vec<TokenBatchOf<Runtime>>>(
+        client,
+        hash,
+        pallet_ubi_token::Balances::<Runtime>::final_prefix(),
+    )? {
+        let input = &mut &key[..];
+        let asset_id: u32 = concat_key(input, BLAKE2_128_LEN)?;
+        let who: AccountId = concat_key(input, BLAKE2_128_LEN)?;
+        let batches: Vec<_> = value.into_iter().filter(|batch| batch.expires_at > block).collect();
+        if let (Some(asset), false) = (assets.get_mut(&asset_id), batches.is_empty()) {
+            asset.accounts.entry(who).or_default().batches = batches;
+        }
+    }
+
+    for (key, value) in storage_pairs::<ReputationOf<Runtime>>(
+        client,
+        hash,
+        pallet_ubi_token::ReputationStore::<Runtime>::final_prefix(),
+    )? {
+        let input = &mut &key[..];
+        let asset_id: u32 = concat_key(input, BLAKE2_128_LEN)?;
+        let who: AccountId = concat_key(input, BLAKE2_128_LEN)?;
+        if let Some(asset) = assets.get_mut(&asset_id) {
+            asset.accounts.entry(who).or_default().reputation = value;
+        }
+    }
+
+    for (key, value) in storage_pairs::<CategoryStatsOf<Runtime>>(
+        client,
+        hash,
+        pallet_ubi_token::BurnCategoryStats::<Runtime>::final_prefix(),
+    )? {
+        let input = &mut &key[..];
+        let asset_id: u32 = concat_key(input, BLAKE2_128_LEN)?;
+        let period: u64 = concat_key(input, TWOX_64_LEN)?;
+        let category: BurnCategory = concat_key(input, TWOX_64_LEN)?;
+        if let Some(asset) = assets.get_mut(&asset_id) {
+            asset.category_stats.push((period, category, value));
+        }
+    }
+
+    Ok(assets)
+}
+
+/// Storage entries under `prefix` with the prefix stripped from the keys
+fn storage_pairs<V: Decode>(
+    client: &FullClient,
+    hash: Hash,
+    prefix: [u8; 32],
+) -> sc_cli::Result<Vec<(Vec<u8>, V)>> {
+    client
+        .storage_pairs(hash, Some(&StorageKey(prefix.to_vec())), None)?
+        .map(|(key, value)| Ok::<_, sc_cli::Error>((key.0[prefix.len()..].to_vec(), V::decode(&mut &value.0[..])?)))
+        .collect()
+}
+
+/// Decode the next part of a storage key hashed with a `*Concat` hasher
+fn concat_key<K: Decode>(input: &mut &[u8], hash_len: usize) -> sc_cli::Result<K> {
+    if input.len() < hash_len {
+        return Err("Storage key too short".into());
+    }
+    *input = &input[hash_len..];
+    Ok(K::decode(input)?)
+}
+
+/// JSON document of the exported state
+fn to_json(
+    block: BlockNumber,
+    hash: Hash,
+    assets: &BTreeMap<u32, AssetState>,
+) -> serde_json::Value {
+    let mut genesis_assets = Vec::new();
+    let mut genesis_balances = Vec::new();
+    let mut genesis_reputations = Vec::new();
+
+    let assets_json: Vec<_> = assets
+        .iter()
+        .map(|(asset_id, asset)| {
+            genesis_assets.push(serde_json::json!([asset_id, asset.owner]));
+
+            let accounts: Vec<_> = asset
+                .accounts
+                .iter()
+                .map(|(who, state)| {
+                    if !state.batches.is_empty() {
+                        let rebased: Vec<_> = state
+                            .batches
+                            .iter()
+                            .map(|batch| pallet_ubi_token::TokenBatch {
+                                amount: batch.amount,
+                                expires_at: batch.expires_at - block,
+                            })
+                            .collect();
+                        genesis_balances.push(serde_json::json!([asset_id, who, rebased]));
+                    }
+                    let reputation = pallet_ubi_token::Reputation {
+                        first_activity: 0,
+                        last_activity: 0,
+                        last_claim_period: 0,
+                        last_decay_period: 0,
+                        // Boosts are not exported, so their stakes are released
+                        boost_stake: 0,
+                        boost_received: 0,
+                        ..state.reputation.clone()
+                    };
+                    genesis_reputations.push(serde_json::json!([asset_id, who, reputation]));
+
+                    serde_json::json!({
+                        "account": who,
+                        "balance": balance(state),
+                        "batches": state.batches,
+                        "reputation": state.reputation,
+                    })
+                })
+                .collect();
+
+            let category_stats: Vec<_> = asset
+                .category_stats
+                .iter()
+                .map(|(period, category, stats)| {
+                    serde_json::json!({ "period": period, "category": category, "stats": stats })
+                })
+                .collect();
+
+            serde_json::json!({
+                "assetId": asset_id,
+                "owner": asset.owner,
+                "totalSupply": asset.total_supply,
+                "accounts": accounts,
+                "categoryStats": category_stats,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "block": block,
+        "hash": hash,
+        "assets": assets_json,
+        "genesis": {
+            "assets": genesis_assets,
+            "balances": genesis_balances,
+            "reputations": genesis_reputations,
+        },
+    })
+}
+
+/// Write one CSV row per account and asset
+fn write_csv(out: &mut dyn Write, assets: &BTreeMap<u32, AssetState>) -> sc_cli::Result<()> {
+    writeln!(
+        out,
+        "asset_id,account,balance,score,claim_streak,unique_recipients,\
+         burns_sent_count,burns_sent_volume,burns_received_count,burns_received_volume"
+    )?;
+    for (asset_id, asset) in assets {
+        for (who, state) in &asset.accounts {
+            let rep = &state.reputation;
+            writeln!(
+                out,
+                "{asset_id},{who},{},{},{},{},{},{},{},{}",
+                balance(state),
+                rep.score,
+                rep.claim_streak,
+                rep.unique_recipients_count,
+                rep.burns_sent_count,
+                rep.burns_sent_volume,
+                rep.burns_received_count,
+                rep.burns_received_volume,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Unexpired balance of an account
+fn balance(state: &AccountState) -> Balance {
+    state.batches.iter().map(|batch| batch.amount).sum()
+}
@@ -0,0 +1,25 @@
+//! NST Node
+//!
+//! A substrate-based blockchain node for Non Speculative Tokens (NST).
+//! NST is a burn-only UBI cryptocurrency where tokens cannot be transferred,
+//! only burned to signal value to recipients.
+//!
+//! The node is also a library so the integration tests can run it in-process.
+
+// `sc_cli::Error` and `sc_service::Error` are what the Substrate client APIs return
+#![allow(clippy::result_large_err)]
+
+pub mod auto_claim;
+pub mod chain_spec;
+pub mod cli;
+pub mod command;
+pub mod export_ubi_state;
+#[cfg(feature = "parachain")]
+pub mod parachain;
+pub mod rpc;
+pub mod rpc_limit;
+pub mod service;
+pub mod ubi;
+pub mod ubi_archive;
+pub mod ubi_rpc;
+pub mod ubi_telemetry;
@@ -1,15 +1,8 @@
-//! NST Node
-//!
-//! A substrate-based blockchain node for Non Speculative Tokens (NST).
-//! NST is a burn-only UBI cryptocurrency where tokens cannot be transferred,
-//! only burned to signal value to recipients.
+//! NST Node binary, see the `nst_node` library
 
-mod chain_spec;
-mod cli;
-mod command;
-mod rpc;
-mod service;
+// `sc_cli::Error` is what the Substrate CLI returns
+#![allow(clippy::result_large_err)]
 
 fn main() -> sc_cli::Result<()> {
-    command::run()
+    nst_node::command::run()
 }
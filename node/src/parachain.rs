@@ -0,0 +1,551 @@
+//! Parachain (Cumulus) flavor of the NST node
+//!
+//! Only compiled with the `parachain` feature. The node runs as a collator
+//! using Aura (lookahead) for block authoring and relies on the relay chain
+//! for finality instead of GRANDPA.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use cumulus_client_cli::CollatorOptions;
+use cumulus_client_collator::service::CollatorService;
+use cumulus_client_consensus_aura::collators::lookahead::{self as aura, Params as AuraParams};
+use cumulus_client_consensus_common::ParachainBlockImport as TParachainBlockImport;
+use cumulus_client_service::{
+    build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
+    BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, ParachainTracingExecuteBlock,
+    StartRelayChainTasksParams,
+};
+use cumulus_primitives_core::{relay_chain::CollatorPair, ParaId};
+use cumulus_relay_chain_interface::{OverseerHandle, RelayChainInterface};
+use futures::FutureExt;
+use nst_runtime::{opaque::Block, Hash};
+use polkadot_primitives::ValidationCode;
+use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
+use sc_cli::{
+    CliConfiguration, DefaultConfigurationValues, ImportParams, KeystoreParams, NetworkParams, Result as CliResult,
+    SharedParams, SubstrateCli,
+};
+use sc_client_api::Backend;
+use sc_consensus::ImportQueue;
+use sc_network::{NetworkBackend, NetworkBlock};
+use sc_service::{
+    config::{BasePath, PrometheusConfig},
+    error::Error as ServiceError,
+    Configuration, TaskManager,
+};
+use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker, TelemetryWorkerHandle};
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use serde::{Deserialize, Serialize};
+use sp_keystore::KeystorePtr;
+
+use crate::service::FullClient;
+
+/// The default parachain id used by the bundled chain specs
+pub const DEFAULT_PARA_ID: u32 = 2000;
+
+/// Relay chain slot duration, matching the runtime's consensus hook
+const RELAY_CHAIN_SLOT_DURATION: Duration = Duration::from_secs(6);
+
+/// Chain spec extensions required by a parachain node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ChainSpecGroup, ChainSpecExtension)]
+#[serde(deny_unknown_fields)]
+pub struct Extensions {
+    /// The relay chain of the parachain
+    pub relay_chain: String,
+    /// The id of the parachain
+    pub para_id: u32,
+}
+
+impl Extensions {
+    /// Try to get the extension from the given `ChainSpec`
+    pub fn try_get(chain_spec: &dyn sc_service::ChainSpec) -> Option<&Self> {
+        sc_chain_spec::get_extension(chain_spec.extensions())
+    }
+}
+
+/// Specialized chain spec for the parachain flavor
+pub type ChainSpec = sc_service::GenericChainSpec<Extensions>;
+
+type ParachainBackend = sc_service::TFullBackend<Block>;
+type ParachainBlockImport = TParachainBlockImport<Block, Arc<FullClient>, ParachainBackend>;
+
+pub type Service = sc_service::PartialComponents<
+    FullClient,
+    ParachainBackend,
+    (),
+    sc_consensus::DefaultImportQueue<Block>,
+    sc_transaction_pool::TransactionPoolHandle<Block, FullClient>,
+    (ParachainBlockImport, Option<Telemetry>, Option<TelemetryWorkerHandle>),
+>;
+
+// ============================================================================
+// RELAY CHAIN CLI
+// ============================================================================
+
+/// Command line arguments of the embedded relay chain node
+#[derive(Debug)]
+pub struct RelayChainCli {
+    /// The actual relay chain cli object
+    pub base: polkadot_cli::RunCmd,
+    /// Optional chain id that should be passed to the relay chain
+    pub chain_id: Option<String>,
+    /// The base path that should be used by the relay chain
+    pub base_path: Option<PathBuf>,
+}
+
+impl RelayChainCli {
+    /// Parse the relay chain CLI parameters using the parachain `Configuration`
+    pub fn new<'a>(
+        para_config: &Configuration,
+        relay_chain_args: impl Iterator<Item = &'a String>,
+    ) -> Self {
+        let extension = Extensions::try_get(&*para_config.chain_spec);
+        let chain_id = extension.map(|e| e.relay_chain.clone());
+        let base_path = para_config.base_path.path().join("polkadot");
+
+        Self {
+            base: clap::Parser::parse_from(relay_chain_args),
+            chain_id,
+            base_path: Some(base_path),
+        }
+    }
+}
+
+impl SubstrateCli for RelayChainCli {
+    fn impl_name() -> String {
+        "NST Parachain Collator".into()
+    }
+
+    fn impl_version() -> String {
+        env!("SUBSTRATE_CLI_IMPL_VERSION").into()
+    }
+
+    fn description() -> String {
+        env!("CARGO_PKG_DESCRIPTION").into()
+    }
+
+    fn author() -> String {
+        env!("CARGO_PKG_AUTHORS").into()
+    }
+
+    fn support_url() -> String {
+        "https://github.com/michaljach/nst/issues".into()
+    }
+
+    fn copyright_start_year() -> i32 {
+        2024
+    }
+
+    fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+        polkadot_cli::Cli::from_iter([RelayChainCli::executable_name()].iter()).load_spec(id)
+    }
+}
+
+impl DefaultConfigurationValues for RelayChainCli {
+    fn p2p_listen_port() -> u16 {
+        30334
+    }
+
+    fn rpc_listen_port() -> u16 {
+        9945
+    }
+
+    fn prometheus_listen_port() -> u16 {
+        9616
+    }
+}
+
+impl CliConfiguration<Self> for RelayChainCli {
+    fn shared_params(&self) -> &SharedParams {
+        self.base.base.shared_params()
+    }
+
+    fn import_params(&self) -> Option<&ImportParams> {
+        self.base.base.import_params()
+    }
+
+    fn network_params(&self) -> Option<&NetworkParams> {
+        self.base.base.network_params()
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        self.base.base.keystore_params()
+    }
+
+    fn base_path(&self) -> CliResult<Option<BasePath>> {
+        Ok(self
+            .shared_params()
+            .base_path()?
+            .or_else(|| self.base_path.clone().map(Into::into)))
+    }
+
+    fn rpc_addr(
+        &self,
+        default_listen_port: u16,
+    ) -> CliResult<Option<Vec<sc_cli::RpcEndpoint>>> {
+        self.base.base.rpc_addr(default_listen_port)
+    }
+
+    fn prometheus_config(
+        &self,
+        default_listen_port: u16,
+        chain_spec: &Box<dyn sc_service::ChainSpec>,
+    ) -> CliResult<Option<PrometheusConfig>> {
+        self.base.base.prometheus_config(default_listen_port, chain_spec)
+    }
+
+    fn chain_id(&self, is_dev: bool) -> CliResult<String> {
+        let chain_id = self.base.base.chain_id(is_dev)?;
+
+        Ok(if chain_id.is_empty() {
+            self.chain_id.clone().unwrap_or_default()
+        } else {
+            chain_id
+        })
+    }
+
+    fn role(&self, is_dev: bool) -> CliResult<sc_service::Role> {
+        self.base.base.role(is_dev)
+    }
+
+    fn transaction_pool(
+        &self,
+        is_dev: bool,
+    ) -> CliResult<sc_service::config::TransactionPoolOptions> {
+        self.base.base.transaction_pool(is_dev)
+    }
+
+    fn rpc_methods(&self) -> CliResult<sc_service::config::RpcMethods> {
+        self.base.base.rpc_methods()
+    }
+
+    fn rpc_cors(&self, is_dev: bool) -> CliResult<Option<Vec<String>>> {
+        self.base.base.rpc_cors(is_dev)
+    }
+
+    fn node_name(&self) -> CliResult<String> {
+        self.base.base.node_name()
+    }
+}
+
+// ============================================================================
+// SERVICE
+// ============================================================================
+
+/// Creates a new partial parachain node
+pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
+    let telemetry = config
+        .telemetry_endpoints
+        .clone()
+        .filter(|x| !x.is_empty())
+        .map(|endpoints| -> Result<_, sc_telemetry::Error> {
+            let worker = TelemetryWorker::new(16)?;
+            let telemetry = worker.handle().new_telemetry(endpoints);
+            Ok((worker, telemetry))
+        })
+        .transpose()?;
+
+    let executor = sc_service::new_wasm_executor(&config.executor);
+    let (client, backend, keystore_container, task_manager) =
+        sc_service::new_full_parts_record_import::<Block, nst_runtime::RuntimeApi, _>(
+            config,
+            telemetry.as_ref().map(|(_, telemetry)| telemetry.handle()),
+            executor,
+            true,
+        )?;
+    let client = Arc::new(client);
+
+    let telemetry_worker_handle = telemetry.as_ref().map(|(worker, _)| worker.handle());
+
+    let telemetry = telemetry.map(|(worker, telemetry)| {
+        task_manager.spawn_handle().spawn("telemetry", None, worker.run());
+        telemetry
+    });
+
+    let transaction_pool = Arc::from(
+        sc_transaction_pool::Builder::new(
+            task_manager.spawn_essential_handle(),
+            client.clone(),
+            config.role.is_authority().into(),
+        )
+        .with_options(config.transaction_pool.clone())
+        .with_prometheus(config.prometheus_registry())
+        .build(),
+    );
+
+    let block_import = ParachainBlockImport::new(client.clone(), backend.clone());
+
+    let import_queue =
+        cumulus_client_consensus_aura::equivocation_import_queue::fully_verifying_import_queue::<
+            sp_consensus_aura::sr25519::AuthorityPair,
+            _,
+            _,
+            _,
+            _,
+        >(
+            client.clone(),
+            block_import.clone(),
+            move |_, _| async move {
+                let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+                Ok(timestamp)
+            },
+            &task_manager.spawn_essential_handle(),
+            config.prometheus_registry(),
+            telemetry.as_ref().map(|x| x.handle()),
+        );
+
+    Ok(sc_service::PartialComponents {
+        client,
+        backend,
+        task_manager,
+        import_queue,
+        keystore_container,
+        select_chain: (),
+        transaction_pool,
+        other: (block_import, telemetry, telemetry_worker_handle),
+    })
+}
+
+/// Start a parachain node (collator or full node)
+pub async fn start_parachain_node(
+    parachain_config: Configuration,
+    polkadot_config: Configuration,
+    collator_options: CollatorOptions,
+    para_id: ParaId,
+    auto_claim: crate::auto_claim::AutoClaimParams,
+    ubi_archive: crate::ubi_archive::UbiArchiveParams,
+    rpc_limit: crate::rpc_limit::RpcLimitParams,
+) -> Result<TaskManager, ServiceError> {
+    let parachain_config = prepare_node_config(parachain_config);
+
+    let params = new_partial(&parachain_config)?;
+    let (block_import, mut telemetry, telemetry_worker_handle) = params.other;
+
+    let prometheus_registry = parachain_config.prometheus_registry().cloned();
+    let net_config = sc_network::config::FullNetworkConfiguration::<
+        _,
+        _,
+        sc_network::NetworkWorker<Block, Hash>,
+    >::new(&parachain_config.network, prometheus_registry.clone());
+
+    let client = params.client.clone();
+    let backend = params.backend.clone();
+    let mut task_manager = params.task_manager;
+
+    let (relay_chain_interface, collator_key, _, _) = build_relay_chain_interface(
+        polkadot_config,
+        &parachain_config,
+        telemetry_worker_handle,
+        &mut task_manager,
+        collator_options,
+        None,
+    )
+    .await
+    .map_err(|e| ServiceError::Application(Box::new(e) as Box<_>))?;
+
+    let validator = parachain_config.role.is_authority();
+    let transaction_pool = params.transaction_pool.clone();
+    let import_queue_service = params.import_queue.service();
+
+    let (network, system_rpc_tx, tx_handler_controller, sync_service) =
+        build_network(BuildNetworkParams {
+            parachain_config: &parachain_config,
+            net_config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            para_id,
+            spawn_handle: task_manager.spawn_handle(),
+            relay_chain_interface: relay_chain_interface.clone(),
+            import_queue: params.import_queue,
+            sybil_resistance_level: CollatorSybilResistance::Resistant,
+            metrics: sc_network::NetworkWorker::<Block, Hash>::register_notification_metrics(
+                parachain_config.prometheus_registry(),
+            ),
+        })
+        .await?;
+
+    if parachain_config.offchain_worker.enabled {
+        let offchain_workers =
+            sc_offchain::OffchainWorkers::new(sc_offchain::OffchainWorkerOptions {
+                runtime_api_provider: client.clone(),
+                is_validator: validator,
+                keystore: Some(params.keystore_container.keystore()),
+                offchain_db: backend.offchain_storage(),
+                transaction_pool: Some(OffchainTransactionPoolFactory::new(
+                    transaction_pool.clone(),
+                )),
+                network_provider: Arc::new(network.clone()),
+                enable_http_requests: true,
+                custom_extensions: |_| vec![],
+            })?;
+        task_manager.spawn_handle().spawn(
+            "offchain-workers-runner",
+            "offchain-worker",
+            offchain_workers.run(client.clone(), task_manager.spawn_handle()).boxed(),
+        );
+    }
+
+    // Only RPC submissions go through the limit
+    let rpc_pool = Arc::new(crate::rpc_limit::RateLimitedPool::new(transaction_pool.clone(), &rpc_limit));
+
+    let rpc_extensions_builder = {
+        let client = client.clone();
+        let pool = rpc_pool.clone();
+        let connection_limit = Arc::new(crate::rpc_limit::ConnectionLimit::new(&rpc_limit));
+        let ubi_archive = ubi_archive.ubi_archive;
+
+        Box::new(move |_| {
+            let deps = crate::rpc::FullDeps {
+                client: client.clone(),
+                pool: pool.clone(),
+                connection_limit: connection_limit.clone(),
+                ubi_archive,
+            };
+            crate::rpc::create_full(deps).map_err(Into::into)
+        })
+    };
+
+    sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        network: network.clone(),
+        client: client.clone(),
+        keystore: params.keystore_container.keystore(),
+        task_manager: &mut task_manager,
+        transaction_pool: rpc_pool,
+        rpc_builder: rpc_extensions_builder,
+        backend: backend.clone(),
+        system_rpc_tx,
+        tx_handler_controller,
+        sync_service: sync_service.clone(),
+        config: parachain_config,
+        telemetry: telemetry.as_mut(),
+        tracing_execute_block: Some(Arc::new(ParachainTracingExecuteBlock::new(client.clone()))),
+    })?;
+
+    crate::auto_claim::spawn(
+        &auto_claim,
+        &task_manager,
+        client.clone(),
+        transaction_pool.clone(),
+        params.keystore_container.keystore(),
+    );
+
+    crate::ubi_archive::spawn(&ubi_archive, &task_manager, client.clone());
+
+    crate::ubi_telemetry::spawn(&task_manager, client.clone(), telemetry.as_ref().map(|x| x.handle()));
+
+    let announce_block = {
+        let sync_service = sync_service.clone();
+        Arc::new(move |hash, data| sync_service.announce_block(hash, data))
+    };
+
+    let overseer_handle = relay_chain_interface
+        .overseer_handle()
+        .map_err(|e| ServiceError::Application(Box::new(e)))?;
+
+    start_relay_chain_tasks(StartRelayChainTasksParams {
+        client: client.clone(),
+        announce_block: announce_block.clone(),
+        para_id,
+        relay_chain_interface: relay_chain_interface.clone(),
+        task_manager: &mut task_manager,
+        da_recovery_profile: if validator {
+            DARecoveryProfile::Collator
+        } else {
+            DARecoveryProfile::FullNode
+        },
+        import_queue: import_queue_service,
+        relay_chain_slot_duration: RELAY_CHAIN_SLOT_DURATION,
+        recovery_handle: Box::new(overseer_handle.clone()),
+        sync_service,
+        prometheus_registry: prometheus_registry.as_ref(),
+    })?;
+
+    if validator {
+        start_consensus(
+            client,
+            backend,
+            block_import,
+            prometheus_registry.as_ref(),
+            telemetry.as_ref().map(|t| t.handle()),
+            &task_manager,
+            relay_chain_interface,
+            transaction_pool,
+            params.keystore_container.keystore(),
+            para_id,
+            collator_key.expect("Command line arguments do not allow this; qed"),
+            network.local_peer_id(),
+            overseer_handle,
+            announce_block,
+        )?;
+    }
+
+    Ok(task_manager)
+}
+
+/// Spawn the Aura lookahead collator task
+#[allow(clippy::too_many_arguments)]
+fn start_consensus(
+    client: Arc<FullClient>,
+    backend: Arc<ParachainBackend>,
+    block_import: ParachainBlockImport,
+    prometheus_registry: Option<&substrate_prometheus_endpoint::Registry>,
+    telemetry: Option<TelemetryHandle>,
+    task_manager: &TaskManager,
+    relay_chain_interface: Arc<dyn RelayChainInterface>,
+    transaction_pool: Arc<sc_transaction_pool::TransactionPoolHandle<Block, FullClient>>,
+    keystore: KeystorePtr,
+    para_id: ParaId,
+    collator_key: CollatorPair,
+    collator_peer_id: sc_network::PeerId,
+    overseer_handle: OverseerHandle,
+    announce_block: Arc<dyn Fn(Hash, Option<Vec<u8>>) + Send + Sync>,
+) -> Result<(), ServiceError> {
+    let proposer = sc_basic_authorship::ProposerFactory::with_proof_recording(
+        task_manager.spawn_handle(),
+        client.clone(),
+        transaction_pool,
+        prometheus_registry,
+        telemetry,
+    );
+
+    let collator_service = CollatorService::new(
+        client.clone(),
+        Arc::new(task_manager.spawn_handle()),
+        announce_block,
+        client.clone(),
+    );
+
+    let params = AuraParams {
+        create_inherent_data_providers: move |_, ()| async move { Ok(()) },
+        block_import,
+        para_client: client.clone(),
+        para_backend: backend,
+        relay_client: relay_chain_interface,
+        code_hash_provider: move |block_hash| {
+            client
+                .code_at(block_hash)
+                .ok()
+                .map(|c| ValidationCode::from(c).hash())
+        },
+        keystore,
+        collator_key,
+        collator_peer_id,
+        para_id,
+        overseer_handle,
+        relay_chain_slot_duration: RELAY_CHAIN_SLOT_DURATION,
+        proposer,
+        collator_service,
+        authoring_duration: Duration::from_millis(2000),
+        reinitialize: false,
+        max_pov_percentage: None,
+    };
+
+    let fut = aura::run::<Block, sp_consensus_aura::sr25519::AuthorityPair, _, _, _, _, _, _, _, _>(
+        params,
+    );
+    task_manager
+        .spawn_essential_handle()
+        .spawn("aura", None, fut);
+
+    Ok(())
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
-use nst_runtime::{opaque::Block, AccountId, Balance, Nonce};
+use nst_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Nonce};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
@@ -13,8 +13,12 @@ use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 pub struct FullDeps<C, P> {
     /// The client instance to use
     pub client: Arc<C>,
-    /// Transaction pool instance
+    /// Transaction pool instance, rate limited for unsigned UBI calls
     pub pool: Arc<P>,
+    /// Budget per connection of the UBI submission methods
+    pub connection_limit: Arc<crate::rpc_limit::ConnectionLimit>,
+    /// Serve the UBI event archive (`--ubi-archive`)
+    pub ubi_archive: bool,
 }
 
 /// Instantiate all full RPC extensions
@@ -24,20 +28,25 @@ pub fn create_full<C, P>(
 where
     C: ProvideRuntimeApi<Block>,
     C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
-    C: Send + Sync + 'static,
+    C: sc_client_api::AuxStore + Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: BlockBuilder<Block>,
-    P: TransactionPool + 'static,
+    C::Api: pallet_ubi_token_runtime_api::UbiTokenApi<Block, u32, AccountId, Balance, BlockNumber>,
+    P: TransactionPool<Block = Block> + 'static,
 {
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
     use substrate_frame_rpc_system::{System, SystemApiServer};
 
     let mut module = RpcModule::new(());
-    let FullDeps { client, pool } = deps;
+    let FullDeps { client, pool, connection_limit, ubi_archive } = deps;
 
-    module.merge(System::new(client.clone(), pool).into_rpc())?;
-    module.merge(TransactionPayment::new(client).into_rpc())?;
+    module.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
+    module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+    module.merge(crate::ubi_rpc::rpc(client.clone(), pool, connection_limit)?)?;
+    if ubi_archive {
+        module.merge(crate::ubi_archive::rpc(client)?)?;
+    }
 
     Ok(module)
 }
@@ -0,0 +1,322 @@
+//! Rate limit of unsigned UBI submissions over RPC
+//!
+//! Unsigned UBI calls (claims, burns, service payments) pay no fees, so a
+//! public RPC endpoint would let anyone fill the transaction pool for free.
+//! Connections and IP addresses are only known to the RPC server, which caps
+//! the calls of each connection with `--rpc-rate-limit` and exempts trusted
+//! addresses with `--rpc-rate-limit-whitelisted-ips` (behind a reverse proxy,
+//! add `--rpc-rate-limit-trust-proxy-headers`).
+//!
+//! On top of that, the transaction pool handed to the RPC layer is wrapped by
+//! [`RateLimitedPool`], which rejects unsigned UBI extrinsics submitted over
+//! RPC beyond a budget per minute, in total and per account they act for.
+//! Only extrinsics the pool accepts count against the budgets, so invalid
+//! submissions naming someone else's account cannot use up their budget.
+//! Transactions from peers, offchain workers and the node's own relayer do not
+//! go through it and are not limited.
+//!
+//! The node's own submission methods (`nst_submitClaim`, `nst_submitBurn`)
+//! also see the RPC connection and charge [`ConnectionLimit`], a budget per
+//! connection, so one client cannot use them to exhaust the budgets of others.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    num::NonZeroU32,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use jsonrpsee::{ConnectionId, Extensions};
+use nst_runtime::{opaque::Block, AccountId, Runtime, RuntimeCall, UncheckedExtrinsic};
+use parity_scale_codec::{Decode, Encode};
+use sc_transaction_pool_api::{
+    error::Error as PoolError, ChainEvent, ImportNotificationStream, MaintainedTransactionPool, PoolStatus,
+    ReadyTransactions, TransactionFor, TransactionPool, TransactionSource, TransactionStatusStreamFor, TxHash,
+    TxInvalidityReportMap,
+};
+use sp_runtime::{generic::Preamble, traits::Block as BlockT, transaction_validity::InvalidTransaction};
+
+const LOG_TARGET: &str = "ubi-rpc-limit";
+
+/// Length of a rate limit window
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Command line options of the unsigned submission limit
+#[derive(Debug, Clone, clap::Args)]
+pub struct RpcLimitParams {
+    /// Unsigned UBI extrinsics accepted over RPC per minute, in total (0 disables the limit)
+    #[arg(long, value_name = "COUNT", default_value_t = 600)]
+    pub ubi_rpc_unsigned_limit: u32,
+
+    /// Unsigned UBI extrinsics accepted over RPC per minute for the same account
+    /// (0 disables the limit)
+    #[arg(long, value_name = "COUNT", default_value_t = 10)]
+    pub ubi_rpc_unsigned_limit_per_account: u32,
+
+    /// Extrinsics accepted per minute through `nst_submitClaim` and `nst_submitBurn`
+    /// from the same RPC connection (0 disables the limit)
+    #[arg(long, value_name = "COUNT", default_value_t = 10)]
+    pub ubi_rpc_unsigned_limit_per_connection: u32,
+}
+
+/// Submissions counted in the current window
+struct Window<K> {
+    started: Instant,
+    total: u32,
+    per_key: HashMap<K, u32>,
+}
+
+impl<K> Window<K> {
+    fn new() -> Self {
+        Self { started: Instant::now(), total: 0, per_key: HashMap::new() }
+    }
+}
+
+/// Fixed-window counters of the accepted submissions, in total and per key
+struct Limiter<K> {
+    total: Option<NonZeroU32>,
+    per_key: Option<NonZeroU32>,
+    window: Mutex<Window<K>>,
+}
+
+impl<K: Hash + Eq + Clone> Limiter<K> {
+    fn new(total: u32, per_key: u32) -> Self {
+        Self { total: NonZeroU32::new(total), per_key: NonZeroU32::new(per_key), window: Mutex::new(Window::new()) }
+    }
+
+    fn with_window<R>(&self, f: impl FnOnce(&mut Window<K>) -> R) -> R {
+        let mut window = self.window.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if window.started.elapsed() >= WINDOW {
+            *window = Window::new();
+        }
+        f(&mut window)
+    }
+
+    /// Whether a submission for `key` is within the budgets
+    fn check(&self, key: Option<&K>) -> bool {
+        self.with_window(|window| {
+            let total = self.total.is_none_or(|limit| window.total < limit.get());
+            let per_key = match (self.per_key, key) {
+                (Some(limit), Some(key)) => window.per_key.get(key).is_none_or(|count| *count < limit.get()),
+                _ => true,
+            };
+            total && per_key
+        })
+    }
+
+    /// Count an accepted submission for `key`
+    fn charge(&self, key: Option<&K>) {
+        self.with_window(|window| {
+            window.total = window.total.saturating_add(1);
+            if let (Some(_), Some(key)) = (self.per_key, key) {
+                let count = window.per_key.entry(key.clone()).or_default();
+                *count = count.saturating_add(1);
+            }
+        })
+    }
+}
+
+/// Budget per RPC connection of the node's own submission methods
+pub struct ConnectionLimit(Limiter<ConnectionId>);
+
+impl ConnectionLimit {
+    pub fn new(params: &RpcLimitParams) -> Self {
+        Self(Limiter::new(0, params.ubi_rpc_unsigned_limit_per_connection))
+    }
+
+    /// Whether the connection of a call is within its budget
+    ///
+    /// Calls without a connection (made in-process) are not limited.
+    pub fn check(&self, extensions: &Extensions) -> bool {
+        self.0.check(extensions.get::<ConnectionId>())
+    }
+
+    /// Count a submission of the connection of a call the pool accepted
+    pub fn charge(&self, extensions: &Extensions) {
+        self.0.charge(extensions.get::<ConnectionId>())
+    }
+}
+
+/// Whether an extrinsic is an unsigned UBI call, and the account it acts for
+fn unsigned_ubi_call(xt: &<Block as BlockT>::Extrinsic) -> Option<Option<AccountId>> {
+    let uxt = UncheckedExtrinsic::decode(&mut &xt.encode()[..]).ok()?;
+    if !matches!(uxt.preamble, Preamble::Bare(_)) {
+        return None;
+    }
+    let RuntimeCall::UbiToken(call) = uxt.function else {
+        return None;
+    };
+    let who = match call {
+        pallet_ubi_token::Call::<Runtime>::claim { account, .. } |
+        pallet_ubi_token::Call::<Runtime>::claim_short_expiry { account, .. } |
+        pallet_ubi_token::Call::<Runtime>::claim_with_referral { account, .. } => Some(account),
+        pallet_ubi_token::Call::<Runtime>::burn { from, .. } |
+        pallet_ubi_token::Call::<Runtime>::burn_ticket { from, .. } |
+        pallet_ubi_token::Call::<Runtime>::pay_service { from, .. } |
+        pallet_ubi_token::Call::<Runtime>::pay_invoice { from, .. } => Some(from),
+        pallet_ubi_token::Call::<Runtime>::burn_authorized { authorization, .. } => Some(authorization.from),
+        _ => None,
+    };
+    Some(who)
+}
+
+/// Transaction pool limiting the unsigned UBI extrinsics submitted over RPC
+pub struct RateLimitedPool<P> {
+    inner: Arc<P>,
+    limiter: Limiter<AccountId>,
+}
+
+/// Budget an extrinsic is charged to once accepted: `None` if it is not limited,
+/// the account it acts for otherwise
+type Charge = Option<Option<AccountId>>;
+
+impl<P> RateLimitedPool<P> {
+    pub fn new(inner: Arc<P>, params: &RpcLimitParams) -> Self {
+        Self {
+            inner,
+            limiter: Limiter::new(params.ubi_rpc_unsigned_limit, params.ubi_rpc_unsigned_limit_per_account),
+        }
+    }
+
+    /// Budget of an extrinsic submitted from `source`, `Err` if it is exhausted
+    fn admit(&self, source: TransactionSource, xt: &<Block as BlockT>::Extrinsic) -> Result<Charge, PoolError> {
+        if source != TransactionSource::External {
+            return Ok(None);
+        }
+        let Some(who) = unsigned_ubi_call(xt) else {
+            return Ok(None);
+        };
+        if !self.limiter.check(who.as_ref()) {
+            log::debug!(target: LOG_TARGET, "Unsigned UBI submission over the rate limit rejected");
+            return Err(PoolError::InvalidTransaction(InvalidTransaction::ExhaustsResources));
+        }
+        Ok(Some(who))
+    }
+
+    /// Count an extrinsic the pool accepted
+    fn charge(&self, charge: Charge) {
+        if let Some(who) = charge {
+            self.limiter.charge(who.as_ref());
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<P> TransactionPool for RateLimitedPool<P>
+where
+    P: TransactionPool<Block = Block>,
+{
+    type Block = Block;
+    type Hash = P::Hash;
+    type InPoolTransaction = P::InPoolTransaction;
+    type Error = P::Error;
+
+    async fn submit_at(
+        &self,
+        at: <Block as BlockT>::Hash,
+        source: TransactionSource,
+        xts: Vec<TransactionFor<Self>>,
+    ) -> Result<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+        let admitted: Vec<_> = xts.iter().map(|xt| self.admit(source, xt)).collect();
+        let submitted = xts.into_iter().zip(&admitted).filter(|(_, admit)| admit.is_ok()).map(|(xt, _)| xt).collect();
+        let mut results = self.inner.submit_at(at, source, submitted).await?.into_iter();
+        Ok(admitted
+            .into_iter()
+            .map(|admit| {
+                let charge = admit?;
+                let result = results.next().unwrap_or_else(|| Err(PoolError::ImmediatelyDropped.into()));
+                if result.is_ok() {
+                    self.charge(charge);
+                }
+                result
+            })
+            .collect())
+    }
+
+    async fn submit_one(
+        &self,
+        at: <Block as BlockT>::Hash,
+        source: TransactionSource,
+        xt: TransactionFor<Self>,
+    ) -> Result<TxHash<Self>, Self::Error> {
+        let charge = self.admit(source, &xt)?;
+        let hash = self.inner.submit_one(at, source, xt).await?;
+        self.charge(charge);
+        Ok(hash)
+    }
+
+    async fn submit_and_watch(
+        &self,
+        at: <Block as BlockT>::Hash,
+        source: TransactionSource,
+        xt: TransactionFor<Self>,
+    ) -> Result<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+        let charge = self.admit(source, &xt)?;
+        let watcher = self.inner.submit_and_watch(at, source, xt).await?;
+        self.charge(charge);
+        Ok(watcher)
+    }
+
+    async fn ready_at(
+        &self,
+        at: <Block as BlockT>::Hash,
+    ) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send> {
+        self.inner.ready_at(at).await
+    }
+
+    fn ready(&self) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send> {
+        self.inner.ready()
+    }
+
+    async fn report_invalid(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+        invalid_tx_errors: TxInvalidityReportMap<TxHash<Self>>,
+    ) -> Vec<Arc<Self::InPoolTransaction>> {
+        self.inner.report_invalid(at, invalid_tx_errors).await
+    }
+
+    fn futures(&self) -> Vec<Self::InPoolTransaction> {
+        self.inner.futures()
+    }
+
+    fn status(&self) -> PoolStatus {
+        self.inner.status()
+    }
+
+    fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+        self.inner.import_notification_stream()
+    }
+
+    fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+        self.inner.on_broadcasted(propagations)
+    }
+
+    fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+        self.inner.hash_of(xt)
+    }
+
+    fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+        self.inner.ready_transaction(hash)
+    }
+
+    async fn ready_at_with_timeout(
+        &self,
+        at: <Block as BlockT>::Hash,
+        timeout: Duration,
+    ) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send> {
+        self.inner.ready_at_with_timeout(at, timeout).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<P> MaintainedTransactionPool for RateLimitedPool<P>
+where
+    P: MaintainedTransactionPool<Block = Block>,
+{
+    async fn maintain(&self, event: ChainEvent<Block>) {
+        self.inner.maintain(event).await
+    }
+}
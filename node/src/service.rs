@@ -3,24 +3,35 @@
 //! Uses Aura for block production and GRANDPA for finality
 
 use std::sync::Arc;
-use std::time::Duration;
 
-use futures::FutureExt;
 use nst_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::{Backend, BlockBackend};
-use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
-use sc_consensus_grandpa::SharedVoterState;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncConfig};
+use sc_consensus_aura::ImportQueueParams;
+use sc_service::{error::Error as ServiceError, Configuration};
 use sc_telemetry::{Telemetry, TelemetryWorker};
-use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 
+// Only used by `new_full`, the parachain flavor starts its node in `crate::parachain`
+#[cfg(not(feature = "parachain"))]
+use {
+    futures::FutureExt,
+    sc_client_api::{Backend, BlockBackend},
+    sc_consensus_aura::{SlotProportion, StartAuraParams},
+    sc_consensus_grandpa::SharedVoterState,
+    sc_service::{TaskManager, WarpSyncConfig},
+    sc_transaction_pool_api::OffchainTransactionPoolFactory,
+    std::time::Duration,
+};
+
+/// Host functions available to the runtime. The parachain flavor additionally
+/// exposes the PoV-reclaim host function required by Cumulus.
+#[cfg(not(feature = "parachain"))]
+type HostFunctions = sp_io::SubstrateHostFunctions;
+#[cfg(feature = "parachain")]
+type HostFunctions = cumulus_client_service::ParachainHostFunctions;
+
 /// The full client type
-pub type FullClient = sc_service::TFullClient<
-    Block,
-    RuntimeApi,
-    sc_executor::WasmExecutor<sp_io::SubstrateHostFunctions>,
->;
+pub type FullClient =
+    sc_service::TFullClient<Block, RuntimeApi, sc_executor::WasmExecutor<HostFunctions>>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
@@ -54,7 +65,7 @@ pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
         })
         .transpose()?;
 
-    let executor = sc_service::new_wasm_executor::<sp_io::SubstrateHostFunctions>(&config.executor);
+    let executor = sc_service::new_wasm_executor::<HostFunctions>(&config.executor);
     let (client, backend, keystore_container, task_manager) =
         sc_service::new_full_parts::<Block, RuntimeApi, _>(
             config,
@@ -133,10 +144,14 @@ pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
 }
 
 /// Build a full node
+#[cfg(not(feature = "parachain"))]
 pub fn new_full<
     N: sc_network::NetworkBackend<Block, <Block as sp_runtime::traits::Block>::Hash>,
 >(
     config: Configuration,
+    auto_claim: crate::auto_claim::AutoClaimParams,
+    ubi_archive: crate::ubi_archive::UbiArchiveParams,
+    rpc_limit: crate::rpc_limit::RpcLimitParams,
 ) -> Result<TaskManager, ServiceError> {
     let sc_service::PartialComponents {
         client,
@@ -169,6 +184,8 @@ pub fn new_full<
         );
     net_config.add_notification_protocol(grandpa_protocol_config);
 
+    // Serve GRANDPA warp proofs so new nodes can join with `--sync warp`. The
+    // list of authority set hard forks stays empty until the chain needs one.
     let warp_sync = Arc::new(sc_consensus_grandpa::warp_proof::NetworkProvider::new(
         backend.clone(),
         grandpa_link.shared_authority_set().clone(),
@@ -217,14 +234,21 @@ pub fn new_full<
     let enable_grandpa = !config.disable_grandpa;
     let prometheus_registry = config.prometheus_registry().cloned();
 
+    // Only RPC submissions go through the limit
+    let rpc_pool = Arc::new(crate::rpc_limit::RateLimitedPool::new(transaction_pool.clone(), &rpc_limit));
+
     let rpc_extensions_builder = {
         let client = client.clone();
-        let pool = transaction_pool.clone();
+        let pool = rpc_pool.clone();
+        let connection_limit = Arc::new(crate::rpc_limit::ConnectionLimit::new(&rpc_limit));
+        let ubi_archive = ubi_archive.ubi_archive;
 
         Box::new(move |_| {
             let deps = crate::rpc::FullDeps {
                 client: client.clone(),
                 pool: pool.clone(),
+                connection_limit: connection_limit.clone(),
+                ubi_archive,
             };
             crate::rpc::create_full(deps).map_err(Into::into)
         })
@@ -235,7 +259,7 @@ pub fn new_full<
         client: client.clone(),
         keystore: keystore_container.keystore(),
         task_manager: &mut task_manager,
-        transaction_pool: transaction_pool.clone(),
+        transaction_pool: rpc_pool,
         rpc_builder: rpc_extensions_builder,
         backend,
         system_rpc_tx,
@@ -246,6 +270,18 @@ pub fn new_full<
         tracing_execute_block: None,
     })?;
 
+    crate::auto_claim::spawn(
+        &auto_claim,
+        &task_manager,
+        client.clone(),
+        transaction_pool.clone(),
+        keystore_container.keystore(),
+    );
+
+    crate::ubi_archive::spawn(&ubi_archive, &task_manager, client.clone());
+
+    crate::ubi_telemetry::spawn(&task_manager, client.clone(), telemetry.as_ref().map(|x| x.handle()));
+
     if role.is_authority() {
         let proposer_factory = sc_basic_authorship::ProposerFactory::new(
             task_manager.spawn_handle(),
@@ -0,0 +1,354 @@
+//! `ubi` subcommands: UBI diagnostics and transactions against a running node
+//!
+//! The commands talk to the node over JSON-RPC and read the pallet through
+//! the `UbiTokenApi` runtime API (`state_call`), so they work while the node
+//! is running and need neither polkadot-js nor access to its database.
+//! `claim` and `burn` submit the pallet's unsigned extrinsics with
+//! `author_submitExtrinsic` and print the transaction hash. `receipt` prints
+//! a burn's receipt with a storage proof that can be checked offline.
+
+use jsonrpsee::{
+    core::client::ClientT,
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use nst_runtime::{AccountId, Balance, BlockNumber, RuntimeCall, UncheckedExtrinsic, NST_ASSET_ID};
+use pallet_ubi_token::BurnCategory;
+use pallet_ubi_token_receipt_verifier::BurnProof;
+use pallet_ubi_token_runtime_api::{BurnReceiptWithKey, Reputation, TokenBatch, UbiParameters};
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{crypto::Ss58Codec, Bytes, H256};
+
+/// Number of decimals of the UBI tokens
+const DECIMALS: u32 = 9;
+
+/// UBI token subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum UbiCmd {
+    /// Print the balance, batches, claimable amount and reputation of an account
+    Status(StatusCmd),
+
+    /// Submit the UBI claim of an account
+    Claim(ClaimCmd),
+
+    /// Burn tokens of an account with a named recipient
+    Burn(BurnCmd),
+
+    /// Print the receipt of a burn with a proof checkable against the block header
+    Receipt(ReceiptCmd),
+}
+
+/// Connection options shared by the `ubi` subcommands
+#[derive(Debug, Clone, clap::Args)]
+pub struct ConnectionParams {
+    /// HTTP RPC endpoint of the node
+    #[arg(long, value_name = "URL", default_value = "http://127.0.0.1:9944")]
+    pub url: String,
+
+    /// Community asset
+    #[arg(long, value_name = "ID", default_value_t = NST_ASSET_ID)]
+    pub asset: u32,
+}
+
+/// `ubi status <ADDRESS>`
+#[derive(Debug, clap::Args)]
+pub struct StatusCmd {
+    /// Account to inspect (SS58 or hex)
+    #[arg(value_name = "ADDRESS")]
+    pub address: AccountId,
+
+    #[clap(flatten)]
+    pub connection: ConnectionParams,
+}
+
+/// `ubi claim <ADDRESS>`
+#[derive(Debug, clap::Args)]
+pub struct ClaimCmd {
+    /// Account to claim for (SS58 or hex)
+    #[arg(value_name = "ADDRESS")]
+    pub address: AccountId,
+
+    #[clap(flatten)]
+    pub connection: ConnectionParams,
+}
+
+/// Burn category as given on the command line
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum CategoryArg {
+    Food,
+    Housing,
+    Services,
+    Donation,
+    Other,
+}
+
+impl From<CategoryArg> for BurnCategory {
+    fn from(category: CategoryArg) -> Self {
+        match category {
+            CategoryArg::Food => BurnCategory::Food,
+            CategoryArg::Housing => BurnCategory::Housing,
+            CategoryArg::Services => BurnCategory::Services,
+            CategoryArg::Donation => BurnCategory::Donation,
+            CategoryArg::Other => BurnCategory::Other,
+        }
+    }
+}
+
+/// `ubi burn <FROM> <TO> <AMOUNT>`
+#[derive(Debug, clap::Args)]
+pub struct BurnCmd {
+    /// Account whose tokens are burned (SS58 or hex)
+    #[arg(value_name = "FROM")]
+    pub from: AccountId,
+
+    /// Named recipient of the burn (SS58 or hex)
+    #[arg(value_name = "TO")]
+    pub to: AccountId,
+
+    /// Amount in tokens, e.g. `12.5`
+    #[arg(value_name = "AMOUNT", value_parser = parse_tokens)]
+    pub amount: Balance,
+
+    /// What the burn paid for
+    #[arg(long, value_enum)]
+    pub category: Option<CategoryArg>,
+
+    /// Reference of the payment, e.g. an invoice hash (32 bytes hex)
+    #[arg(long, value_name = "HASH")]
+    pub reference: Option<H256>,
+
+    /// Last block the burn may be included in; it is dropped after that
+    #[arg(long, value_name = "BLOCK")]
+    pub valid_until: Option<BlockNumber>,
+
+    /// Key rejecting repeated submissions of the same payment (32 bytes hex)
+    #[arg(long, value_name = "HASH")]
+    pub idempotency_key: Option<H256>,
+
+    #[clap(flatten)]
+    pub connection: ConnectionParams,
+}
+
+/// `ubi receipt <BURN_ID>`
+#[derive(Debug, clap::Args)]
+pub struct ReceiptCmd {
+    /// Id of the burn, from its `Burned` event
+    #[arg(value_name = "BURN_ID")]
+    pub burn_id: u64,
+
+    /// Block to prove the receipt at, the finalized head by default
+    #[arg(long, value_name = "HASH")]
+    pub at: Option<H256>,
+
+    #[clap(flatten)]
+    pub connection: ConnectionParams,
+}
+
+impl UbiCmd {
+    /// Run the subcommand to completion
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Cannot start async runtime: {e}"))?;
+
+        match self {
+            UbiCmd::Status(cmd) => runtime.block_on(cmd.run()),
+            UbiCmd::Claim(cmd) => runtime.block_on(cmd.run()),
+            UbiCmd::Burn(cmd) => runtime.block_on(cmd.run()),
+            UbiCmd::Receipt(cmd) => runtime.block_on(cmd.run()),
+        }
+    }
+}
+
+impl StatusCmd {
+    async fn run(&self) -> sc_cli::Result<()> {
+        let client = connect(&self.connection)?;
+        let asset = self.connection.asset;
+        let who = &self.address;
+
+        let spendable: Balance =
+            state_call(&client, "UbiTokenApi_spendable_balance_at", (asset, who, best_block(&client).await?)).await?;
+        let claimable: Balance = state_call(&client, "UbiTokenApi_claimable_amount", (asset, who)).await?;
+        let batches: Vec<TokenBatch<Balance, BlockNumber>> =
+            state_call(&client, "UbiTokenApi_token_batches", (asset, who)).await?;
+        let reputation: Reputation<Balance, BlockNumber> =
+            state_call(&client, "UbiTokenApi_reputation", (asset, who)).await?;
+
+        println!("Account:     {}", who.to_ss58check());
+        println!("Asset:       {asset}");
+        println!("Spendable:   {}", format_tokens(spendable));
+        println!("Claimable:   {}", format_tokens(claimable));
+        println!("Batches:");
+        if batches.is_empty() {
+            println!("  none");
+        }
+        for batch in batches {
+            println!("  {} expiring at block {}", format_tokens(batch.amount), batch.expires_at);
+        }
+        println!("Reputation:");
+        println!("  score              {}", reputation.score);
+        println!("  claim streak       {}", reputation.claim_streak);
+        println!("  unique recipients  {}", reputation.unique_recipients_count);
+        println!("  burns sent         {} ({})", reputation.burns_sent_count, format_tokens(reputation.burns_sent_volume));
+        println!(
+            "  burns received     {} ({})",
+            reputation.burns_received_count,
+            format_tokens(reputation.burns_received_volume)
+        );
+        Ok(())
+    }
+}
+
+impl ClaimCmd {
+    async fn run(&self) -> sc_cli::Result<()> {
+        let client = connect(&self.connection)?;
+        let asset = self.connection.asset;
+        let params: Option<UbiParameters<Balance, BlockNumber>> =
+            state_call(&client, "UbiTokenApi_ubi_parameters", asset).await?;
+        let params = params.ok_or_else(|| format!("Asset {asset} does not exist"))?;
+
+        // The claim is included in the next block at the earliest
+        let period = u64::from(best_block(&client).await? + 1) / u64::from(params.asset.claim_period_blocks.max(1));
+        let nonce = pallet_ubi_token::find_claim_nonce(&asset, &self.address, period, params.claim_pow_difficulty)
+            .ok_or("No claim proof found")?;
+        let call = pallet_ubi_token::Call::claim { asset_id: asset, account: self.address.clone(), nonce };
+        let hash = submit(&client, RuntimeCall::UbiToken(call)).await?;
+        println!("{hash:?}");
+        Ok(())
+    }
+}
+
+impl BurnCmd {
+    async fn run(&self) -> sc_cli::Result<()> {
+        let client = connect(&self.connection)?;
+        let asset = self.connection.asset;
+        let nonce: u64 = state_call(&client, "UbiTokenApi_burn_nonce", (asset, &self.from)).await?;
+        let call = pallet_ubi_token::Call::burn {
+            asset_id: asset,
+            from: self.from.clone(),
+            to: self.to.clone(),
+            amount: self.amount,
+            category: self.category.map(Into::into),
+            reference: self.reference,
+            nonce,
+            valid_until: self.valid_until,
+            idempotency_key: self.idempotency_key,
+        };
+        let hash = submit(&client, RuntimeCall::UbiToken(call)).await?;
+        println!("{hash:?}");
+        Ok(())
+    }
+}
+
+impl ReceiptCmd {
+    async fn run(&self) -> sc_cli::Result<()> {
+        #[derive(serde::Deserialize)]
+        struct ReadProof {
+            proof: Vec<Bytes>,
+        }
+
+        let client = connect(&self.connection)?;
+        let block_hash = match self.at {
+            Some(hash) => hash,
+            None => client
+                .request("chain_getFinalizedHead", rpc_params![])
+                .await
+                .map_err(|e| format!("chain_getFinalizedHead failed: {e}"))?,
+        };
+
+        let receipt: Option<BurnReceiptWithKey<u32, AccountId, Balance, BlockNumber>> =
+            state_call_at(&client, "UbiTokenApi_burn_receipt", self.burn_id, Some(block_hash)).await?;
+        let (receipt, key) = receipt.ok_or_else(|| format!("Burn {} does not exist at {block_hash:?}", self.burn_id))?;
+        let read_proof: ReadProof = client
+            .request("state_getReadProof", rpc_params![vec![Bytes(key)], block_hash])
+            .await
+            .map_err(|e| format!("state_getReadProof failed: {e}"))?;
+
+        let proof = BurnProof {
+            burn_id: self.burn_id,
+            receipt,
+            block_hash,
+            proof: read_proof.proof.into_iter().map(|node| node.0).collect(),
+        };
+        println!("{}", sp_core::bytes::to_hex(&proof.encode(), false));
+        Ok(())
+    }
+}
+
+/// Build an RPC client for the node
+pub fn connect(params: &ConnectionParams) -> sc_cli::Result<HttpClient> {
+    HttpClientBuilder::default()
+        .build(&params.url)
+        .map_err(|e| format!("Cannot connect to {}: {e}", params.url).into())
+}
+
+/// Number of the node's best block
+pub async fn best_block(client: &HttpClient) -> sc_cli::Result<BlockNumber> {
+    #[derive(serde::Deserialize)]
+    struct Header {
+        number: String,
+    }
+
+    let header: Header = client
+        .request("chain_getHeader", rpc_params![])
+        .await
+        .map_err(|e| format!("chain_getHeader failed: {e}"))?;
+    BlockNumber::from_str_radix(header.number.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Invalid block number {}: {e}", header.number).into())
+}
+
+/// Call a runtime API function at the best block and decode its result
+pub async fn state_call<R: Decode>(client: &HttpClient, method: &str, args: impl Encode) -> sc_cli::Result<R> {
+    state_call_at(client, method, args, None).await
+}
+
+/// Call a runtime API function at block `at` (the best block if `None`) and decode its result
+pub async fn state_call_at<R: Decode>(
+    client: &HttpClient,
+    method: &str,
+    args: impl Encode,
+    at: Option<H256>,
+) -> sc_cli::Result<R> {
+    let result: Bytes = client
+        .request("state_call", rpc_params![method, Bytes(args.encode()), at])
+        .await
+        .map_err(|e| format!("{method} failed: {e}"))?;
+    R::decode(&mut &result[..]).map_err(|e| format!("Cannot decode the result of {method}: {e}").into())
+}
+
+/// Submit a call as unsigned extrinsic, returning the transaction hash
+pub async fn submit(client: &HttpClient, call: RuntimeCall) -> sc_cli::Result<H256> {
+    let xt = UncheckedExtrinsic::new_bare(call);
+    client
+        .request("author_submitExtrinsic", rpc_params![Bytes(xt.encode())])
+        .await
+        .map_err(|e| format!("Transaction rejected: {e}").into())
+}
+
+/// Parse an amount in tokens with up to `DECIMALS` decimals
+fn parse_tokens(value: &str) -> Result<Balance, String> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > DECIMALS as usize {
+        return Err(format!("At most {DECIMALS} decimals are supported"));
+    }
+    let whole: Balance = whole.parse().map_err(|e| format!("Invalid amount {value}: {e}"))?;
+    let fraction: Balance = format!("{fraction:0<width$}", width = DECIMALS as usize)
+        .parse()
+        .map_err(|e| format!("Invalid amount {value}: {e}"))?;
+    whole
+        .checked_mul(10u128.pow(DECIMALS))
+        .and_then(|amount| amount.checked_add(fraction))
+        .ok_or_else(|| format!("Amount {value} is too large"))
+}
+
+/// Format an amount with the token decimals, e.g. `100.5`
+fn format_tokens(amount: Balance) -> String {
+    let unit = 10u128.pow(DECIMALS);
+    let fraction = amount % unit;
+    if fraction == 0 {
+        return format!("{}", amount / unit);
+    }
+    let fraction = format!("{fraction:0width$}", width = DECIMALS as usize);
+    format!("{}.{}", amount / unit, fraction.trim_end_matches('0'))
+}
@@ -0,0 +1,260 @@
+//! UBI event archive
+//!
+//! Optional background task (`--ubi-archive`) that writes every `Claimed`,
+//! `Burned` and `Expired` event of the finalized chain into the node's
+//! auxiliary database, indexed by account, and exposes them over RPC. It is a
+//! built-in lightweight indexer for deployments that cannot run SubQuery.
+//!
+//! Blocks are archived once they are finalized. After a restart the task
+//! catches up from the last archived block. Events of blocks whose state has
+//! been pruned cannot be read, so an archive of an existing chain only covers
+//! its full history on a node run with `--state-pruning archive`.
+//!
+//! RPC methods (available when the archive is enabled):
+//!
+//! - `ubiArchive_accountEvents(account, skip?, limit?)`: events of an account,
+//!   newest first. Burns are listed for both the sender and the recipient.
+//! - `ubiArchive_lastBlock()`: last archived block, `null` if none.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use frame_support::storage::storage_prefix;
+use futures::StreamExt;
+use jsonrpsee::{types::ErrorObjectOwned, RpcModule};
+use nst_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, RuntimeEvent};
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::{AuxStore, Backend, BlockchainEvents, StorageProvider};
+use sc_service::TaskManager;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+
+const LOG_TARGET: &str = "ubi-archive";
+
+/// Prefix of every archive key in the auxiliary database
+const PREFIX: &[u8] = b"ubi_archive/";
+
+/// Default and maximum number of events returned by one RPC call
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+type EventRecord = frame_system::EventRecord<RuntimeEvent, Hash>;
+
+/// Position of an event in the chain: block number and index in the block
+type EventId = (BlockNumber, u32);
+
+/// Command line options of the event archive
+#[derive(Debug, Clone, clap::Args)]
+pub struct UbiArchiveParams {
+    /// Archive the UBI claims, burns and expirations in the node's database
+    /// and serve them over RPC (`ubiArchive_*`)
+    #[arg(long)]
+    pub ubi_archive: bool,
+}
+
+/// Kind of an archived event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventKind {
+    Claimed,
+    Burned,
+    Expired,
+}
+
+/// A decoded UBI event as stored in the archive
+#[derive(Debug, Clone, Encode, Decode, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedEvent {
+    pub block: BlockNumber,
+    /// Index of the event in the block
+    pub index: u32,
+    pub kind: EventKind,
+    pub asset_id: u32,
+    /// Claiming or expiring account, sender of a burn
+    pub account: AccountId,
+    /// Recipient of a burn
+    pub counterparty: Option<AccountId>,
+    /// Smallest unit, sent as a string since balances do not fit JSON numbers
+    #[serde(serialize_with = "balance_as_string")]
+    pub amount: Balance,
+    pub burn_id: Option<u64>,
+}
+
+fn balance_as_string<S: serde::Serializer>(amount: &Balance, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&amount.to_string())
+}
+
+impl ArchivedEvent {
+    /// Decode the archived events out of a block's event record
+    fn from_event(block: BlockNumber, index: u32, event: RuntimeEvent) -> Option<Self> {
+        let RuntimeEvent::UbiToken(event) = event else {
+            return None;
+        };
+        let (kind, asset_id, account, counterparty, amount, burn_id) = match event {
+            pallet_ubi_token::Event::Claimed { asset_id, who, amount, .. } =>
+                (EventKind::Claimed, asset_id, who, None, amount, None),
+            pallet_ubi_token::Event::Burned { burn_id, asset_id, from, to, amount, .. } =>
+                (EventKind::Burned, asset_id, from, Some(to), amount, Some(burn_id)),
+            pallet_ubi_token::Event::Expired { asset_id, who, amount, .. } =>
+                (EventKind::Expired, asset_id, who, None, amount, None),
+            _ => return None,
+        };
+        Some(Self { block, index, kind, asset_id, account, counterparty, amount, burn_id })
+    }
+}
+
+fn last_block_key() -> Vec<u8> {
+    [PREFIX, b"last"].concat()
+}
+
+fn event_key((block, index): EventId) -> Vec<u8> {
+    [PREFIX, b"event/", &block.to_be_bytes()[..], &index.to_be_bytes()[..]].concat()
+}
+
+fn account_key(who: &AccountId) -> Vec<u8> {
+    [PREFIX, b"account/", who.as_ref()].concat()
+}
+
+fn read_aux<C: AuxStore, V: Decode>(client: &C, key: &[u8]) -> Option<V> {
+    match client.get_aux(key) {
+        Ok(Some(data)) => V::decode(&mut &data[..])
+            .inspect_err(|e| log::warn!(target: LOG_TARGET, "Cannot decode archive entry: {e}"))
+            .ok(),
+        Ok(None) => None,
+        Err(e) => {
+            log::warn!(target: LOG_TARGET, "Cannot read the archive: {e}");
+            None
+        }
+    }
+}
+
+/// Spawn the archiver if `--ubi-archive` is set
+pub fn spawn<C, B>(params: &UbiArchiveParams, task_manager: &TaskManager, client: Arc<C>)
+where
+    C: AuxStore + BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+    C: Send + Sync + 'static,
+    B: Backend<Block> + 'static,
+{
+    if !params.ubi_archive {
+        return;
+    }
+    log::info!(target: LOG_TARGET, "UBI event archive enabled");
+    task_manager.spawn_handle().spawn("ubi-archive", None, run(client));
+}
+
+/// Catch up with the finalized chain, then archive every newly finalized block
+async fn run<C, B>(client: Arc<C>)
+where
+    C: AuxStore + BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+    B: Backend<Block>,
+{
+    // Subscribe first so no block finalized during the catch-up is missed
+    let mut finality = client.finality_notification_stream();
+    let mut last: BlockNumber = read_aux(&*client, &last_block_key()).unwrap_or_default();
+
+    let finalized = client.info().finalized_number;
+    if last < finalized {
+        log::info!(target: LOG_TARGET, "Archiving blocks #{} to #{finalized}", last + 1);
+    }
+    while last < finalized {
+        let number = last + 1;
+        match client.hash(number) {
+            Ok(Some(hash)) => archive_block(&*client, hash, number),
+            _ => log::warn!(target: LOG_TARGET, "Block #{number} not found, skipped"),
+        }
+        last = number;
+    }
+
+    while let Some(notification) = finality.next().await {
+        // Blocks finalized implicitly come first
+        for hash in notification.tree_route.iter().chain(std::iter::once(&notification.hash)) {
+            let number: BlockNumber = match client.number(*hash) {
+                Ok(Some(number)) => number,
+                _ => continue,
+            };
+            if number <= last {
+                continue;
+            }
+            archive_block(&*client, *hash, number);
+            last = number;
+        }
+    }
+}
+
+/// Write the events of a block and the last archived block in one batch
+fn archive_block<C, B>(client: &C, hash: Hash, number: BlockNumber)
+where
+    C: AuxStore + StorageProvider<Block, B>,
+    B: Backend<Block>,
+{
+    // `frame_system::Events` is private to its pallet
+    let key = StorageKey(storage_prefix(b"System", b"Events").to_vec());
+    let records: Vec<EventRecord> = match client.storage(hash, &key) {
+        Ok(Some(data)) => Decode::decode(&mut &data.0[..])
+            .inspect_err(|e| log::warn!(target: LOG_TARGET, "Cannot decode the events of #{number}: {e}"))
+            .unwrap_or_default(),
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            log::warn!(target: LOG_TARGET, "Cannot read the events of #{number}, skipped: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut inserts: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+    let mut indices: BTreeMap<AccountId, Vec<EventId>> = BTreeMap::new();
+    for (index, record) in records.into_iter().enumerate() {
+        let Some(event) = ArchivedEvent::from_event(number, index as u32, record.event) else {
+            continue;
+        };
+        let id = (number, event.index);
+        let accounts = std::iter::once(event.account.clone()).chain(event.counterparty.clone());
+        for who in accounts {
+            let index = indices
+                .entry(who.clone())
+                .or_insert_with(|| read_aux(client, &account_key(&who)).unwrap_or_default());
+            if index.last() != Some(&id) {
+                index.push(id);
+            }
+        }
+        inserts.push((event_key(id), event.encode()));
+    }
+    for (who, index) in indices {
+        inserts.push((account_key(&who), index.encode()));
+    }
+    inserts.push((last_block_key(), number.encode()));
+
+    let batch: Vec<(&[u8], &[u8])> = inserts.iter().map(|(k, v)| (&k[..], &v[..])).collect();
+    if let Err(e) = client.insert_aux(&batch, &[]) {
+        log::error!(target: LOG_TARGET, "Cannot archive block #{number}: {e}");
+    }
+}
+
+/// RPC methods serving the archive
+pub fn rpc<C>(client: Arc<C>) -> Result<RpcModule<Arc<C>>, Box<dyn std::error::Error + Send + Sync>>
+where
+    C: AuxStore + Send + Sync + 'static,
+{
+    let mut module = RpcModule::new(client);
+
+    module.register_method("ubiArchive_accountEvents", |params, client, _| {
+        let mut params = params.sequence();
+        let who: AccountId = params.next()?;
+        let skip: u32 = params.optional_next()?.unwrap_or_default();
+        let limit: u32 = params.optional_next()?.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+        let index: Vec<EventId> = read_aux(&**client, &account_key(&who)).unwrap_or_default();
+        let events: Vec<ArchivedEvent> = index
+            .into_iter()
+            .rev()
+            .skip(skip as usize)
+            .take(limit as usize)
+            .filter_map(|id| read_aux(&**client, &event_key(id)))
+            .collect();
+        Ok::<_, ErrorObjectOwned>(events)
+    })?;
+
+    module.register_method("ubiArchive_lastBlock", |_, client, _| {
+        read_aux::<_, BlockNumber>(&**client, &last_block_key())
+    })?;
+
+    Ok(module)
+}
@@ -0,0 +1,200 @@
+//! Convenience RPCs building UBI extrinsics on the node
+//!
+//! Thin clients (mobile, web) can claim and burn without SCALE encoding or
+//! extrinsic construction: the node builds the unsigned extrinsics and
+//! submits them to the pool. Every method takes an optional asset id, the
+//! main NST asset by default, and returns the transaction hash.
+//!
+//! - `nst_submitClaim(address, assetId?, nonce?)`: claim for `address` with
+//!   the claim proof-of-work `nonce` (see `pallet_ubi_token::find_claim_nonce`).
+//!   Without a nonce the node solves the proof itself, but only for callers
+//!   allowed unsafe RPC methods and up to `MAX_RPC_CLAIM_POW_DIFFICULTY`, so
+//!   a public endpoint cannot be made to hash for anonymous callers. No nonce
+//!   is needed if the runtime requires no proof-of-work.
+//! - `nst_burnPayload(from, to, amount, assetId?)`: bytes `from` must sign to
+//!   authorize a burn, hex encoded. `amount` is a decimal string in the
+//!   smallest unit. Returns the payload instead of a hash.
+//! - `nst_submitBurn(from, to, amount, signature, assetId?)`: submit that burn
+//!   as `burn_authorized`. The signature is the hex of a SCALE-encoded
+//!   `MultiSignature`, e.g. `0x01` followed by the 64 bytes of an sr25519
+//!   signature.
+//!
+//! Burn payloads include `from`'s next burn nonce and the runtime version, so
+//! a payload must be signed and submitted before another burn of `from` or a
+//! runtime upgrade. Submissions count against the unsigned RPC limit like
+//! `author_submitExtrinsic`, and against the budget of the RPC connection.
+
+use std::sync::Arc;
+
+use jsonrpsee::{types::ErrorObjectOwned, Extensions, RpcModule};
+use nst_runtime::{
+    opaque::Block, AccountId, Balance, BlockNumber, Hash, RuntimeCall, Signature, UncheckedExtrinsic, NST_ASSET_ID,
+};
+use pallet_ubi_token_runtime_api::{BurnAuthorization, UbiTokenApi};
+use parity_scale_codec::{Decode, Encode};
+use sc_rpc_api::DenyUnsafe;
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+
+use crate::rpc_limit::ConnectionLimit;
+
+/// Error code of every failure of these methods
+const ERROR_CODE: i32 = 4000;
+
+/// Highest claim proof-of-work difficulty `nst_submitClaim` solves
+///
+/// About a million hashes. Harder proofs are left to the caller, so one RPC
+/// call cannot keep the node busy for long. Only trusted callers (allowed
+/// unsafe RPC methods) have the node solve proofs at all.
+const MAX_RPC_CLAIM_POW_DIFFICULTY: u32 = 20;
+
+/// Burn authorization of the runtime API, with the block it was built at
+type AuthorizationAt = (Hash, BurnAuthorization<u32, AccountId, Balance, Hash>);
+
+fn error(message: impl ToString) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(ERROR_CODE, message.to_string(), None::<()>)
+}
+
+/// Client and pool the methods work with
+pub struct UbiRpc<C, P> {
+    client: Arc<C>,
+    pool: Arc<P>,
+    limit: Arc<ConnectionLimit>,
+}
+
+impl<C, P> UbiRpc<C, P>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: UbiTokenApi<Block, u32, AccountId, Balance, BlockNumber>,
+    P: TransactionPool<Block = Block> + 'static,
+{
+    /// Fail if the connection of a call used up its submission budget
+    fn check_limit(&self, extensions: &Extensions) -> Result<(), ErrorObjectOwned> {
+        if !self.limit.check(extensions) {
+            return Err(error("Too many submissions from this connection, try again later"));
+        }
+        Ok(())
+    }
+
+    /// Submit an unsigned call on top of the best block
+    async fn submit(&self, at: Hash, call: RuntimeCall, extensions: &Extensions) -> Result<P::Hash, ErrorObjectOwned> {
+        self.check_limit(extensions)?;
+        let xt = UncheckedExtrinsic::new_bare(call).into();
+        let hash = self.pool.submit_one(at, TransactionSource::External, xt).await.map_err(error)?;
+        self.limit.charge(extensions);
+        Ok(hash)
+    }
+
+    async fn submit_claim(
+        &self,
+        asset_id: u32,
+        account: AccountId,
+        nonce: Option<u64>,
+        extensions: &Extensions,
+    ) -> Result<P::Hash, ErrorObjectOwned> {
+        self.check_limit(extensions)?;
+        let info = self.client.info();
+        let api = self.client.runtime_api();
+        let at = info.best_hash;
+
+        let params = api
+            .ubi_parameters(at, asset_id)
+            .map_err(error)?
+            .ok_or_else(|| error(format!("Asset {asset_id} does not exist")))?;
+        if api.claimable_amount(at, asset_id, account.clone()).map_err(error)? == 0 {
+            return Err(error("Nothing to claim"));
+        }
+
+        let difficulty = params.claim_pow_difficulty;
+        let nonce = match nonce {
+            Some(nonce) => nonce,
+            // Every nonce meets no difficulty
+            None if difficulty == 0 => 0,
+            None => {
+                // Unsafe methods are denied to the public, as is work on their behalf
+                let trusted = extensions.get::<DenyUnsafe>().is_some_and(|deny| deny.check_if_safe().is_ok());
+                if !trusted || difficulty > MAX_RPC_CLAIM_POW_DIFFICULTY {
+                    return Err(error(format!(
+                        "Claim proof-of-work of difficulty {difficulty} must be solved by the caller"
+                    )));
+                }
+                // Claims are included in the next block at the earliest
+                let period = u64::from((info.best_number + 1) / params.asset.claim_period_blocks.max(1));
+                // Off the async executor, other RPC calls go on while the proof is solved
+                let who = account.clone();
+                tokio::task::spawn_blocking(move || {
+                    pallet_ubi_token::find_claim_nonce(&asset_id, &who, period, difficulty)
+                })
+                .await
+                .map_err(error)?
+                .ok_or_else(|| error("No claim proof found"))?
+            }
+        };
+        self.submit(at, RuntimeCall::UbiToken(pallet_ubi_token::Call::claim { asset_id, account, nonce }), extensions)
+            .await
+    }
+
+    fn burn_authorization(
+        &self,
+        asset_id: u32,
+        from: AccountId,
+        to: AccountId,
+        amount: String,
+    ) -> Result<AuthorizationAt, ErrorObjectOwned> {
+        let amount: Balance = amount.parse().map_err(|_| error("Amount must be a decimal integer"))?;
+        let at = self.client.info().best_hash;
+        let authorization =
+            self.client.runtime_api().burn_authorization(at, asset_id, from, to, amount).map_err(error)?;
+        Ok((at, authorization))
+    }
+}
+
+/// RPC methods building and submitting UBI extrinsics
+pub fn rpc<C, P>(
+    client: Arc<C>,
+    pool: Arc<P>,
+    limit: Arc<ConnectionLimit>,
+) -> Result<RpcModule<UbiRpc<C, P>>, Box<dyn std::error::Error + Send + Sync>>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: UbiTokenApi<Block, u32, AccountId, Balance, BlockNumber>,
+    P: TransactionPool<Block = Block> + 'static,
+{
+    let mut module = RpcModule::new(UbiRpc { client, pool, limit });
+
+    module.register_async_method("nst_submitClaim", |params, rpc, extensions| async move {
+        let mut params = params.sequence();
+        let account: AccountId = params.next()?;
+        let asset_id: u32 = params.optional_next()?.unwrap_or(NST_ASSET_ID);
+        let nonce: Option<u64> = params.optional_next()?;
+        rpc.submit_claim(asset_id, account, nonce, &extensions).await
+    })?;
+
+    module.register_method("nst_burnPayload", |params, rpc, _| {
+        let mut params = params.sequence();
+        let from: AccountId = params.next()?;
+        let to: AccountId = params.next()?;
+        let amount: String = params.next()?;
+        let asset_id: u32 = params.optional_next()?.unwrap_or(NST_ASSET_ID);
+        let (_, authorization) = rpc.burn_authorization(asset_id, from, to, amount)?;
+        Ok::<_, ErrorObjectOwned>(Bytes(authorization.encode()))
+    })?;
+
+    module.register_async_method("nst_submitBurn", |params, rpc, extensions| async move {
+        let mut params = params.sequence();
+        let from: AccountId = params.next()?;
+        let to: AccountId = params.next()?;
+        let amount: String = params.next()?;
+        let signature: Bytes = params.next()?;
+        let asset_id: u32 = params.optional_next()?.unwrap_or(NST_ASSET_ID);
+
+        let signature = Signature::decode(&mut &signature[..]).map_err(|_| error("Invalid signature encoding"))?;
+        let (at, authorization) = rpc.burn_authorization(asset_id, from, to, amount)?;
+        let call = RuntimeCall::UbiToken(pallet_ubi_token::Call::burn_authorized { authorization, signature });
+        rpc.submit(at, call, &extensions).await
+    })?;
+
+    Ok(module)
+}
@@ -0,0 +1,139 @@
+//! UBI telemetry
+//!
+//! Sends a `ubi.session` record to the node's telemetry endpoints at the end
+//! of every session, so the project dashboard can show network health next to
+//! the usual Substrate records. A record carries the total supply of the main
+//! NST asset at the session's last block and the claims and burns of the
+//! session (count and volume).
+//!
+//! Blocks are counted once they are finalized, so forks do not inflate the
+//! numbers. The first record after a restart only covers the blocks finalized
+//! since then.
+
+use std::sync::Arc;
+
+use frame_support::storage::storage_prefix;
+use futures::StreamExt;
+use nst_runtime::{Balance, BlockNumber, Hash, Runtime, RuntimeEvent, NST_ASSET_ID};
+use parity_scale_codec::Decode;
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sc_service::TaskManager;
+use sc_telemetry::{telemetry, TelemetryHandle, SUBSTRATE_INFO};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+
+use crate::service::FullClient;
+
+const LOG_TARGET: &str = "ubi-telemetry";
+
+type EventRecord = frame_system::EventRecord<RuntimeEvent, Hash>;
+
+/// Claims and burns of the main asset since the last record
+#[derive(Default)]
+struct SessionStats {
+    claims: u32,
+    claimed: Balance,
+    burns: u32,
+    burned: Balance,
+}
+
+/// Spawn the reporter if the node has telemetry enabled
+pub fn spawn(task_manager: &TaskManager, client: Arc<FullClient>, telemetry: Option<TelemetryHandle>) {
+    let Some(telemetry) = telemetry else {
+        return;
+    };
+    task_manager.spawn_handle().spawn("ubi-telemetry", None, run(client, telemetry));
+}
+
+/// Count the events of every finalized block and report at session ends
+async fn run(client: Arc<FullClient>, telemetry: TelemetryHandle) {
+    let session_period = nst_runtime::SessionPeriod::get().max(1);
+    let mut finality = client.finality_notification_stream();
+    let mut stats = SessionStats::default();
+
+    while let Some(notification) = finality.next().await {
+        // Blocks finalized implicitly come first
+        for hash in notification.tree_route.iter().chain(std::iter::once(&notification.hash)) {
+            let number: BlockNumber = match client.number(*hash) {
+                Ok(Some(number)) => number,
+                _ => continue,
+            };
+            for record in block_events(&client, *hash) {
+                stats.count(record.event);
+            }
+            if number.is_multiple_of(session_period) {
+                report(&client, &telemetry, *hash, number, &stats);
+                stats = SessionStats::default();
+            }
+        }
+    }
+}
+
+impl SessionStats {
+    fn count(&mut self, event: RuntimeEvent) {
+        match event {
+            RuntimeEvent::UbiToken(pallet_ubi_token::Event::Claimed { asset_id, amount, .. })
+                if asset_id == NST_ASSET_ID =>
+            {
+                self.claims = self.claims.saturating_add(1);
+                self.claimed = self.claimed.saturating_add(amount);
+            }
+            RuntimeEvent::UbiToken(pallet_ubi_token::Event::Burned { asset_id, amount, .. })
+                if asset_id == NST_ASSET_ID =>
+            {
+                self.burns = self.burns.saturating_add(1);
+                self.burned = self.burned.saturating_add(amount);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Send the record of the session ending at `number`
+fn report(client: &FullClient, telemetry: &TelemetryHandle, hash: Hash, number: BlockNumber, stats: &SessionStats) {
+    let total_supply: Balance = read_storage(
+        client,
+        hash,
+        pallet_ubi_token::TotalSupply::<Runtime>::hashed_key_for(NST_ASSET_ID),
+    )
+    .unwrap_or_default();
+
+    log::debug!(
+        target: LOG_TARGET,
+        "Session ending at #{number}: supply {total_supply}, {} claims, {} burns",
+        stats.claims,
+        stats.burns,
+    );
+    // Balances do not fit JSON numbers, they are sent as strings
+    telemetry!(
+        Some(telemetry.clone());
+        SUBSTRATE_INFO;
+        "ubi.session";
+        "block" => number,
+        "total_supply" => total_supply.to_string(),
+        "claims" => stats.claims,
+        "claimed" => stats.claimed.to_string(),
+        "burns" => stats.burns,
+        "burned" => stats.burned.to_string(),
+    );
+}
+
+/// Events deposited in a block
+fn block_events(client: &FullClient, hash: Hash) -> Vec<EventRecord> {
+    // `frame_system::Events` is private to its pallet
+    read_storage(client, hash, storage_prefix(b"System", b"Events").to_vec()).unwrap_or_default()
+}
+
+/// Read and decode a storage value at a block
+fn read_storage<V: Decode>(client: &FullClient, hash: Hash, key: Vec<u8>) -> Option<V> {
+    match client.storage(hash, &StorageKey(key)) {
+        Ok(Some(data)) => V::decode(&mut &data.0[..])
+            .inspect_err(|e| log::warn!(target: LOG_TARGET, "Cannot decode storage at {hash:?}: {e}"))
+            .ok(),
+        Ok(None) => None,
+        Err(e) => {
+            log::warn!(target: LOG_TARGET, "Cannot read storage at {hash:?}: {e}");
+            None
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! # Call Pause Pallet
+//!
+//! Governance-controlled suspension of individual calls.
+//!
+//! During an incident it is often enough to stop one call (e.g. burns) while
+//! claims, transfers and everything else keep working. `T::PauseOrigin` can
+//! pause any call by its pallet and call name, as listed in the metadata
+//! (e.g. `("UbiToken", "burn")`), and `T::UnpauseOrigin` lifts the pause.
+//!
+//! ## How Calls Are Filtered
+//!
+//! The pallet implements `Contains<RuntimeCall>` and is meant to be the
+//! runtime's `BaseCallFilter`. Every dispatch from a signed or unsigned
+//! origin, including calls nested in a batch or multisig, fails with
+//! `CallFiltered` while the call is paused. Root dispatches (sudo) bypass the
+//! filter, so governance can still act on behalf of the chain.
+//!
+//! Calls matched by `T::WhitelistedCalls` cannot be paused. The runtime uses
+//! it for inherents and for the calls needed to undo a pause.
+//!
+//! Unsigned calls are filtered at dispatch, not when they enter the
+//! transaction pool.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::pallet_prelude::*;
+use frame_support::traits::{CallMetadata, Contains, GetCallMetadata};
+use frame_system::pallet_prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    /// Name of a pallet or of a call, as listed in the metadata
+    pub type NameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+
+    /// Pallet and call name identifying a call
+    pub type RuntimeCallNameOf<T> = (NameOf<T>, NameOf<T>);
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configuration trait for the call pause pallet
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// The overarching call type, whose pallet and call names are paused
+        type RuntimeCall: Parameter + GetCallMetadata;
+
+        /// Origin allowed to pause calls (e.g. root or governance)
+        type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origin allowed to unpause calls
+        type UnpauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Calls that can never be paused
+        type WhitelistedCalls: Contains<RuntimeCallNameOf<Self>>;
+
+        /// Maximum length of a pallet or call name
+        #[pallet::constant]
+        type MaxNameLen: Get<u32>;
+    }
+
+    /// Calls currently paused
+    #[pallet::storage]
+    pub type PausedCalls<T: Config> = StorageMap<_, Blake2_128Concat, RuntimeCallNameOf<T>, (), OptionQuery>;
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A call was paused
+        CallPaused { full_name: RuntimeCallNameOf<T> },
+        /// A call was unpaused
+        CallUnpaused { full_name: RuntimeCallNameOf<T> },
+    }
+
+    /// Errors that can occur in this pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The call is already paused
+        IsPaused,
+        /// The call is not paused
+        IsUnpaused,
+        /// The call is whitelisted and cannot be paused
+        Unpausable,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Pause a call
+        ///
+        /// Takes effect from the next dispatch of the call, in the same block.
+        ///
+        /// # Errors
+        /// - `Unpausable` if the call is whitelisted
+        /// - `IsPaused` if the call is already paused
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn pause(origin: OriginFor<T>, full_name: RuntimeCallNameOf<T>) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            ensure!(!T::WhitelistedCalls::contains(&full_name), Error::<T>::Unpausable);
+            ensure!(!PausedCalls::<T>::contains_key(&full_name), Error::<T>::IsPaused);
+            PausedCalls::<T>::insert(&full_name, ());
+
+            Self::deposit_event(Event::CallPaused { full_name });
+            Ok(())
+        }
+
+        /// Unpause a call
+        ///
+        /// # Errors
+        /// - `IsUnpaused` if the call is not paused
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn unpause(origin: OriginFor<T>, full_name: RuntimeCallNameOf<T>) -> DispatchResult {
+            T::UnpauseOrigin::ensure_origin(origin)?;
+
+            ensure!(PausedCalls::<T>::contains_key(&full_name), Error::<T>::IsUnpaused);
+            PausedCalls::<T>::remove(&full_name);
+
+            Self::deposit_event(Event::CallUnpaused { full_name });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether the call with the given pallet and call name is paused
+        ///
+        /// Names longer than `MaxNameLen` can never be paused.
+        pub fn is_paused(pallet_name: &str, call_name: &str) -> bool {
+            let (Ok(pallet_name), Ok(call_name)) = (
+                NameOf::<T>::try_from(pallet_name.as_bytes().to_vec()),
+                NameOf::<T>::try_from(call_name.as_bytes().to_vec()),
+            ) else {
+                return false;
+            };
+            PausedCalls::<T>::contains_key((pallet_name, call_name))
+        }
+    }
+}
+
+/// Lets through every call that is not paused
+impl<T: Config> Contains<<T as Config>::RuntimeCall> for Pallet<T> {
+    fn contains(call: &<T as Config>::RuntimeCall) -> bool {
+        let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+        !Self::is_paused(pallet_name, function_name)
+    }
+}
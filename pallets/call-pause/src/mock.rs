@@ -0,0 +1,83 @@
+use crate as pallet_call_pause;
+use frame_support::{
+    derive_impl,
+    traits::{ConstU16, ConstU32, ConstU64, Contains},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        CallPause: pallet_call_pause,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = CallPause;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+/// The pallet's own calls can never be paused
+pub struct Whitelist;
+impl Contains<pallet_call_pause::RuntimeCallNameOf<Test>> for Whitelist {
+    fn contains((pallet_name, _): &pallet_call_pause::RuntimeCallNameOf<Test>) -> bool {
+        &pallet_name[..] == b"CallPause"
+    }
+}
+
+impl pallet_call_pause::Config for Test {
+    type RuntimeCall = RuntimeCall;
+    type PauseOrigin = EnsureRoot<u64>;
+    type UnpauseOrigin = EnsureRoot<u64>;
+    type WhitelistedCalls = Whitelist;
+    type MaxNameLen = ConstU32<32>;
+}
+
+// Test accounts
+pub const ALICE: u64 = 1;
+
+/// Pallet and call name as passed to `pause`
+pub fn full_name(pallet_name: &str, call_name: &str) -> pallet_call_pause::RuntimeCallNameOf<Test> {
+    (
+        pallet_name.as_bytes().to_vec().try_into().unwrap(),
+        call_name.as_bytes().to_vec().try_into().unwrap(),
+    )
+}
+
+/// Build empty genesis storage
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
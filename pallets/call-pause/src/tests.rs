@@ -0,0 +1,74 @@
+use crate::{mock::*, Error, Event, PausedCalls};
+use frame_support::{assert_noop, assert_ok};
+use frame_system::Call as SystemCall;
+use sp_runtime::{traits::Dispatchable, DispatchError};
+
+fn remark() -> RuntimeCall {
+    RuntimeCall::System(SystemCall::remark { remark: vec![] })
+}
+
+#[test]
+fn pause_and_unpause_work() {
+    new_test_ext().execute_with(|| {
+        let name = full_name("System", "remark");
+
+        assert_ok!(CallPause::pause(RuntimeOrigin::root(), name.clone()));
+        assert!(PausedCalls::<Test>::contains_key(&name));
+        assert!(CallPause::is_paused("System", "remark"));
+        assert!(!CallPause::is_paused("System", "remark_with_event"));
+        System::assert_last_event(Event::CallPaused { full_name: name.clone() }.into());
+
+        assert_ok!(CallPause::unpause(RuntimeOrigin::root(), name.clone()));
+        assert!(!CallPause::is_paused("System", "remark"));
+        System::assert_last_event(Event::CallUnpaused { full_name: name }.into());
+    });
+}
+
+#[test]
+fn paused_calls_are_filtered_except_for_root() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(remark().dispatch(RuntimeOrigin::signed(ALICE)));
+        assert_ok!(CallPause::pause(RuntimeOrigin::root(), full_name("System", "remark")));
+
+        let call = remark();
+        assert_eq!(
+            call.clone().dispatch(RuntimeOrigin::signed(ALICE)).unwrap_err().error,
+            frame_system::Error::<Test>::CallFiltered.into()
+        );
+        assert_eq!(
+            call.clone().dispatch(RuntimeOrigin::none()).unwrap_err().error,
+            frame_system::Error::<Test>::CallFiltered.into()
+        );
+        assert_ok!(call.dispatch(RuntimeOrigin::root()));
+
+        // Other calls of the same pallet keep working
+        let other = RuntimeCall::System(SystemCall::remark_with_event { remark: vec![1] });
+        assert_ok!(other.dispatch(RuntimeOrigin::signed(ALICE)));
+    });
+}
+
+#[test]
+fn only_pause_origins_can_pause() {
+    new_test_ext().execute_with(|| {
+        let name = full_name("System", "remark");
+        assert_noop!(CallPause::pause(RuntimeOrigin::signed(ALICE), name.clone()), DispatchError::BadOrigin);
+
+        assert_ok!(CallPause::pause(RuntimeOrigin::root(), name.clone()));
+        assert_noop!(CallPause::unpause(RuntimeOrigin::signed(ALICE), name), DispatchError::BadOrigin);
+    });
+}
+
+#[test]
+fn pause_rejects_whitelisted_and_repeated_changes() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            CallPause::pause(RuntimeOrigin::root(), full_name("CallPause", "unpause")),
+            Error::<Test>::Unpausable
+        );
+
+        let name = full_name("System", "remark");
+        assert_noop!(CallPause::unpause(RuntimeOrigin::root(), name.clone()), Error::<Test>::IsUnpaused);
+        assert_ok!(CallPause::pause(RuntimeOrigin::root(), name.clone()));
+        assert_noop!(CallPause::pause(RuntimeOrigin::root(), name), Error::<Test>::IsPaused);
+    });
+}
@@ -0,0 +1,361 @@
+//! # Committee Elections Pallet
+//!
+//! Periodic elections of a small committee, weighted by reputation.
+//!
+//! Accounts with at least `T::MinCandidacyReputation` nominate themselves
+//! with `nominate`. Voters with at least `T::MinVoterReputation` approve up
+//! to `T::MaxVotesPerVoter` candidates with `vote`, and each approval counts
+//! with the voter's reputation score at the time of the election. Votes and
+//! candidacies stay in place across terms until they are withdrawn, so the
+//! committee follows reputation as it changes. Every election removes the
+//! votes of voters whose reputation fell below `T::MinVoterReputation`,
+//! freeing their `T::MaxVoters` slots.
+//!
+//! ## Terms
+//!
+//! Every `T::TermDuration` blocks, `on_initialize` tallies the votes and
+//! the `T::DesiredMembers` candidates with the most approval weight win.
+//! The elected set is handed to `T::ChangeMembers`, e.g. the technical
+//! committee, so membership follows the result without a referendum.
+//!
+//! ```text
+//! nominate ─┐
+//! vote ─────┴→ every TermDuration blocks: tally → top DesiredMembers → ChangeMembers
+//! ```
+//!
+//! Ties are broken by account id. Candidates without any approval weight are
+//! never elected, and a term in which nobody is elected keeps the previous
+//! members.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use frame_support::{pallet_prelude::*, traits::ChangeMembers};
+use frame_system::pallet_prelude::*;
+use sp_runtime::traits::{Saturating, Zero};
+
+/// Source of the reputation that weighs votes
+pub trait ReputationProvider<AccountId> {
+    /// Current reputation score of an account
+    fn reputation(who: &AccountId) -> u128;
+}
+
+impl<AccountId> ReputationProvider<AccountId> for () {
+    fn reputation(_who: &AccountId) -> u128 {
+        0
+    }
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    /// Candidates approved by a voter
+    pub type VotesOf<T> = BoundedVec<<T as frame_system::Config>::AccountId, <T as Config>::MaxVotesPerVoter>;
+
+    /// Approval weight of each eligible candidate
+    pub type TallyOf<T> = Vec<(<T as frame_system::Config>::AccountId, u128)>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configuration trait for the committee elections pallet
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// Reputation weighing votes and admitting candidates
+        type Reputation: ReputationProvider<Self::AccountId>;
+
+        /// Receives the elected members after every term
+        type ChangeMembers: ChangeMembers<Self::AccountId>;
+
+        /// Blocks between elections
+        #[pallet::constant]
+        type TermDuration: Get<BlockNumberFor<Self>>;
+
+        /// Number of members elected every term
+        #[pallet::constant]
+        type DesiredMembers: Get<u32>;
+
+        /// Reputation needed to stand and stay a candidate
+        #[pallet::constant]
+        type MinCandidacyReputation: Get<u128>;
+
+        /// Reputation needed to vote and keep the votes in place
+        #[pallet::constant]
+        type MinVoterReputation: Get<u128>;
+
+        /// Maximum number of candidates
+        #[pallet::constant]
+        type MaxCandidates: Get<u32>;
+
+        /// Maximum number of voters
+        #[pallet::constant]
+        type MaxVoters: Get<u32>;
+
+        /// Maximum number of candidates a voter approves
+        #[pallet::constant]
+        type MaxVotesPerVoter: Get<u32>;
+    }
+
+    /// Accounts standing for election, sorted
+    #[pallet::storage]
+    #[pallet::getter(fn candidates)]
+    pub type Candidates<T: Config> = StorageValue<_, BoundedVec<T::AccountId, T::MaxCandidates>, ValueQuery>;
+
+    /// Candidates approved by each voter
+    #[pallet::storage]
+    #[pallet::getter(fn votes_of)]
+    pub type Votes<T: Config> = CountedStorageMap<_, Blake2_128Concat, T::AccountId, VotesOf<T>, OptionQuery>;
+
+    /// Members elected in the last election, sorted
+    #[pallet::storage]
+    #[pallet::getter(fn members)]
+    pub type Members<T: Config> = StorageValue<_, BoundedVec<T::AccountId, T::DesiredMembers>, ValueQuery>;
+
+    /// Number of elections held so far
+    #[pallet::storage]
+    #[pallet::getter(fn election_rounds)]
+    pub type ElectionRounds<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// An account nominated itself
+        CandidateNominated { who: T::AccountId },
+        /// A candidate withdrew
+        CandidacyWithdrawn { who: T::AccountId },
+        /// A voter set the candidates they approve
+        Voted { who: T::AccountId, votes: u32 },
+        /// A voter removed their votes
+        VoteRemoved { who: T::AccountId },
+        /// The votes of a voter below `MinVoterReputation` were removed at an election
+        VoterEvicted { who: T::AccountId },
+        /// An election elected new members
+        NewTerm { round: u32, members: Vec<(T::AccountId, u128)> },
+        /// An election elected nobody; the previous members stay
+        EmptyTerm { round: u32 },
+    }
+
+    /// Errors that can occur in this pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account's reputation is below `MinCandidacyReputation`
+        InsufficientReputation,
+        /// The account is already a candidate
+        AlreadyCandidate,
+        /// The account is not a candidate
+        NotCandidate,
+        /// `MaxCandidates` accounts are already standing
+        TooManyCandidates,
+        /// The voter's reputation is below `MinVoterReputation`
+        InsufficientVoterReputation,
+        /// A vote must approve at least one candidate
+        NoVotes,
+        /// A candidate is approved twice
+        DuplicateVote,
+        /// `MaxVoters` accounts are already voting
+        TooManyVoters,
+        /// The account has no votes
+        NotVoter,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            if !(now % T::TermDuration::get()).is_zero() {
+                return Weight::zero();
+            }
+            Self::elect()
+        }
+
+        fn integrity_test() {
+            assert!(!T::TermDuration::get().is_zero(), "TermDuration must not be zero");
+            assert!(T::DesiredMembers::get() > 0, "DesiredMembers must not be zero");
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Stand for election
+        ///
+        /// # Errors
+        /// - `InsufficientReputation` below `MinCandidacyReputation`
+        /// - `AlreadyCandidate` if the account is already standing
+        /// - `TooManyCandidates` if `MaxCandidates` accounts are standing
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn nominate(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::Reputation::reputation(&who) >= T::MinCandidacyReputation::get(),
+                Error::<T>::InsufficientReputation
+            );
+
+            Candidates::<T>::try_mutate(|candidates| {
+                let index = candidates.binary_search(&who).err().ok_or(Error::<T>::AlreadyCandidate)?;
+                candidates.try_insert(index, who.clone()).map_err(|_| Error::<T>::TooManyCandidates)
+            })?;
+
+            Self::deposit_event(Event::CandidateNominated { who });
+            Ok(())
+        }
+
+        /// Stop standing for election
+        ///
+        /// Votes for the account stay in place but no longer count. An elected
+        /// member keeps their seat until the next election.
+        ///
+        /// # Errors
+        /// - `NotCandidate` if the account is not standing
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn withdraw_candidacy(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Candidates::<T>::try_mutate(|candidates| -> DispatchResult {
+                let index = candidates.binary_search(&who).map_err(|_| Error::<T>::NotCandidate)?;
+                candidates.remove(index);
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::CandidacyWithdrawn { who });
+            Ok(())
+        }
+
+        /// Approve candidates, replacing any previous votes
+        ///
+        /// The votes count with the voter's reputation at every following
+        /// election until they are removed.
+        ///
+        /// # Errors
+        /// - `InsufficientVoterReputation` if the voter's reputation is below `MinVoterReputation`
+        /// - `NoVotes` if no candidate is approved
+        /// - `NotCandidate` if an approved account is not standing
+        /// - `DuplicateVote` if a candidate is approved twice
+        /// - `TooManyVoters` if `MaxVoters` other accounts are voting
+        #[pallet::call_index(2)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 2))]
+        pub fn vote(origin: OriginFor<T>, votes: VotesOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let reputation = T::Reputation::reputation(&who);
+            ensure!(
+                !reputation.is_zero() && reputation >= T::MinVoterReputation::get(),
+                Error::<T>::InsufficientVoterReputation
+            );
+            ensure!(!votes.is_empty(), Error::<T>::NoVotes);
+
+            let candidates = Candidates::<T>::get();
+            for (i, candidate) in votes.iter().enumerate() {
+                ensure!(candidates.binary_search(candidate).is_ok(), Error::<T>::NotCandidate);
+                ensure!(!votes[..i].contains(candidate), Error::<T>::DuplicateVote);
+            }
+            ensure!(
+                Votes::<T>::contains_key(&who) || Votes::<T>::count() < T::MaxVoters::get(),
+                Error::<T>::TooManyVoters
+            );
+
+            let count = votes.len() as u32;
+            Votes::<T>::insert(&who, votes);
+
+            Self::deposit_event(Event::Voted { who, votes: count });
+            Ok(())
+        }
+
+        /// Remove all votes of the caller
+        ///
+        /// # Errors
+        /// - `NotVoter` if the account has no votes
+        #[pallet::call_index(3)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn remove_vote(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Votes::<T>::take(&who).ok_or(Error::<T>::NotVoter)?;
+
+            Self::deposit_event(Event::VoteRemoved { who });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Approval weight of every eligible candidate, in candidate order
+        ///
+        /// Voters below `MinVoterReputation` do not count.
+        pub fn tally() -> TallyOf<T> {
+            Self::tally_and_find_stale_voters().0
+        }
+
+        /// `tally`, and the voters below `MinVoterReputation`
+        fn tally_and_find_stale_voters() -> (TallyOf<T>, Vec<T::AccountId>) {
+            let min_reputation = T::MinCandidacyReputation::get();
+            let mut tally: TallyOf<T> = Candidates::<T>::get()
+                .into_iter()
+                .filter(|candidate| T::Reputation::reputation(candidate) >= min_reputation)
+                .map(|candidate| (candidate, 0))
+                .collect();
+
+            let min_voter_reputation = T::MinVoterReputation::get();
+            let mut stale = Vec::new();
+            for (voter, votes) in Votes::<T>::iter() {
+                let weight = T::Reputation::reputation(&voter);
+                if weight.is_zero() || weight < min_voter_reputation {
+                    stale.push(voter);
+                    continue;
+                }
+                for candidate in votes {
+                    if let Ok(index) = tally.binary_search_by(|(c, _)| c.cmp(&candidate)) {
+                        tally[index].1.saturating_accrue(weight);
+                    }
+                }
+            }
+            (tally, stale)
+        }
+
+        /// Elect the candidates with the most approval weight and hand them to `T::ChangeMembers`
+        fn elect() -> Weight {
+            let round = ElectionRounds::<T>::mutate(|round| {
+                round.saturating_inc();
+                *round
+            });
+            let candidates = Candidates::<T>::decode_len().unwrap_or(0) as u64;
+            let voters = Votes::<T>::count() as u64;
+
+            let (mut tally, stale) = Self::tally_and_find_stale_voters();
+            let weight = T::DbWeight::get().reads_writes(4 + 2 * candidates + 2 * voters, 3 + 2 * stale.len() as u64);
+            for who in stale {
+                Votes::<T>::remove(&who);
+                Self::deposit_event(Event::VoterEvicted { who });
+            }
+
+            tally.retain(|(_, weight)| !weight.is_zero());
+            // Most weight first, ties broken by account id
+            tally.sort_by(|(a, a_weight), (b, b_weight)| b_weight.cmp(a_weight).then_with(|| a.cmp(b)));
+            tally.truncate(T::DesiredMembers::get() as usize);
+            if tally.is_empty() {
+                Self::deposit_event(Event::EmptyTerm { round });
+                return weight;
+            }
+
+            let mut elected: Vec<T::AccountId> = tally.iter().map(|(who, _)| who.clone()).collect();
+            elected.sort();
+            let previous = Members::<T>::get();
+            let incoming: Vec<_> = elected.iter().filter(|who| !previous.contains(who)).cloned().collect();
+            let outgoing: Vec<_> = previous.iter().filter(|who| !elected.contains(who)).cloned().collect();
+            T::ChangeMembers::change_members_sorted(&incoming, &outgoing, &elected);
+            Members::<T>::put(BoundedVec::truncate_from(elected));
+
+            Self::deposit_event(Event::NewTerm { round, members: tally });
+            weight
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate as pallet_committee_elections;
+use frame_support::{
+    derive_impl,
+    parameter_types,
+    traits::{ChangeMembers, ConstU16, ConstU32, ConstU64, ConstU128, Hooks},
+};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Elections: pallet_committee_elections,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    /// Reputation of each account, as reported by the (fake) UBI pallet
+    pub storage Reputations: Vec<(u64, u128)> = Vec::new();
+    /// Members of the (fake) committee the elections feed
+    pub storage Committee: Vec<u64> = vec![DAVE];
+}
+
+/// Set the reputation returned for an account
+pub fn set_reputation(who: u64, reputation: u128) {
+    let mut reputations = Reputations::get();
+    reputations.retain(|(account, _)| *account != who);
+    reputations.push((who, reputation));
+    Reputations::set(&reputations);
+}
+
+/// Reads reputation from the `Reputations` test storage
+pub struct TestReputation;
+impl crate::ReputationProvider<u64> for TestReputation {
+    fn reputation(who: &u64) -> u128 {
+        Reputations::get()
+            .into_iter()
+            .find(|(account, _)| account == who)
+            .map(|(_, reputation)| reputation)
+            .unwrap_or(0)
+    }
+}
+
+/// Writes the elected members to the `Committee` test storage
+pub struct TestCommittee;
+impl ChangeMembers<u64> for TestCommittee {
+    fn change_members_sorted(_incoming: &[u64], _outgoing: &[u64], sorted_new: &[u64]) {
+        Committee::set(&sorted_new.to_vec());
+    }
+}
+
+impl pallet_committee_elections::Config for Test {
+    type Reputation = TestReputation;
+    type ChangeMembers = TestCommittee;
+    type TermDuration = ConstU64<10>;
+    type DesiredMembers = ConstU32<2>;
+    type MinCandidacyReputation = ConstU128<100>;
+    type MinVoterReputation = ConstU128<50>;
+    type MaxCandidates = ConstU32<3>;
+    type MaxVoters = ConstU32<3>;
+    type MaxVotesPerVoter = ConstU32<2>;
+}
+
+// Test accounts
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+pub const EVE: u64 = 5;
+
+/// Build genesis storage with ALICE, BOB and CHARLIE holding reputation 300, 200 and 100
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        set_reputation(ALICE, 300);
+        set_reputation(BOB, 200);
+        set_reputation(CHARLIE, 100);
+    });
+    ext
+}
+
+/// Run `on_initialize` of the blocks up to `n`
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Elections::on_initialize(System::block_number());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{mock::*, Error, Event, VotesOf};
+use frame_support::{assert_noop, assert_ok};
+
+fn votes(candidates: &[u64]) -> VotesOf<Test> {
+    VotesOf::<Test>::truncate_from(candidates.to_vec())
+}
+
+#[test]
+fn accounts_with_reputation_nominate_themselves() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(BOB)));
+        System::assert_last_event(Event::CandidateNominated { who: BOB }.into());
+        assert_noop!(Elections::nominate(RuntimeOrigin::signed(BOB)), Error::<Test>::AlreadyCandidate);
+        assert_noop!(Elections::nominate(RuntimeOrigin::signed(DAVE)), Error::<Test>::InsufficientReputation);
+
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(CHARLIE)));
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(ALICE)));
+        set_reputation(DAVE, 100);
+        assert_noop!(Elections::nominate(RuntimeOrigin::signed(DAVE)), Error::<Test>::TooManyCandidates);
+        assert_eq!(Elections::candidates().to_vec(), vec![ALICE, BOB, CHARLIE]);
+
+        assert_ok!(Elections::withdraw_candidacy(RuntimeOrigin::signed(BOB)));
+        System::assert_last_event(Event::CandidacyWithdrawn { who: BOB }.into());
+        assert_noop!(Elections::withdraw_candidacy(RuntimeOrigin::signed(BOB)), Error::<Test>::NotCandidate);
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(DAVE)));
+        assert_eq!(Elections::candidates().to_vec(), vec![ALICE, CHARLIE, DAVE]);
+    });
+}
+
+#[test]
+fn votes_approve_standing_candidates() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(ALICE)));
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(BOB)));
+
+        assert_noop!(
+            Elections::vote(RuntimeOrigin::signed(DAVE), votes(&[ALICE])),
+            Error::<Test>::InsufficientVoterReputation
+        );
+        set_reputation(DAVE, 49);
+        assert_noop!(
+            Elections::vote(RuntimeOrigin::signed(DAVE), votes(&[ALICE])),
+            Error::<Test>::InsufficientVoterReputation
+        );
+        assert_noop!(Elections::vote(RuntimeOrigin::signed(CHARLIE), votes(&[])), Error::<Test>::NoVotes);
+        assert_noop!(Elections::vote(RuntimeOrigin::signed(CHARLIE), votes(&[CHARLIE])), Error::<Test>::NotCandidate);
+        assert_noop!(Elections::vote(RuntimeOrigin::signed(CHARLIE), votes(&[BOB, BOB])), Error::<Test>::DuplicateVote);
+
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(CHARLIE), votes(&[BOB, ALICE])));
+        System::assert_last_event(Event::Voted { who: CHARLIE, votes: 2 }.into());
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(ALICE), votes(&[ALICE])));
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(BOB), votes(&[BOB])));
+        set_reputation(EVE, 50);
+        assert_noop!(Elections::vote(RuntimeOrigin::signed(EVE), votes(&[ALICE])), Error::<Test>::TooManyVoters);
+        // Replacing votes does not take another voter slot
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(CHARLIE), votes(&[BOB])));
+        assert_eq!(Elections::votes_of(CHARLIE), Some(votes(&[BOB])));
+
+        assert_ok!(Elections::remove_vote(RuntimeOrigin::signed(CHARLIE)));
+        System::assert_last_event(Event::VoteRemoved { who: CHARLIE }.into());
+        assert_noop!(Elections::remove_vote(RuntimeOrigin::signed(CHARLIE)), Error::<Test>::NotVoter);
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(EVE), votes(&[ALICE])));
+    });
+}
+
+#[test]
+fn elections_weigh_votes_by_reputation_every_term() {
+    new_test_ext().execute_with(|| {
+        set_reputation(DAVE, 150);
+        for who in [ALICE, BOB, CHARLIE] {
+            assert_ok!(Elections::nominate(RuntimeOrigin::signed(who)));
+        }
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(ALICE), votes(&[CHARLIE])));
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(BOB), votes(&[BOB, CHARLIE])));
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(DAVE), votes(&[ALICE, BOB])));
+
+        run_to_block(9);
+        assert_eq!(Committee::get(), vec![DAVE]);
+        run_to_block(10);
+        System::assert_last_event(Event::NewTerm { round: 1, members: vec![(CHARLIE, 500), (BOB, 350)] }.into());
+        assert_eq!(Elections::members().to_vec(), vec![BOB, CHARLIE]);
+        assert_eq!(Committee::get(), vec![BOB, CHARLIE]);
+
+        // Votes count with the reputation voters hold at the next election
+        set_reputation(ALICE, 100);
+        set_reputation(DAVE, 400);
+        run_to_block(20);
+        System::assert_last_event(Event::NewTerm { round: 2, members: vec![(BOB, 600), (ALICE, 400)] }.into());
+        assert_eq!(Committee::get(), vec![ALICE, BOB]);
+    });
+}
+
+#[test]
+fn terms_without_approvals_keep_the_committee() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(ALICE)));
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(BOB), votes(&[ALICE])));
+        assert_ok!(Elections::withdraw_candidacy(RuntimeOrigin::signed(ALICE)));
+
+        run_to_block(10);
+        System::assert_last_event(Event::EmptyTerm { round: 1 }.into());
+        assert_eq!(Committee::get(), vec![DAVE]);
+
+        // Candidates whose reputation fell below the minimum are skipped
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(ALICE)));
+        set_reputation(ALICE, 99);
+        run_to_block(20);
+        System::assert_last_event(Event::EmptyTerm { round: 2 }.into());
+        assert_eq!(Elections::election_rounds(), 2);
+    });
+}
+
+#[test]
+fn elections_evict_voters_below_the_minimum() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Elections::nominate(RuntimeOrigin::signed(ALICE)));
+        for who in [ALICE, BOB, CHARLIE] {
+            assert_ok!(Elections::vote(RuntimeOrigin::signed(who), votes(&[ALICE])));
+        }
+        set_reputation(EVE, 50);
+        assert_noop!(Elections::vote(RuntimeOrigin::signed(EVE), votes(&[ALICE])), Error::<Test>::TooManyVoters);
+
+        // A voter whose reputation fell below the minimum is not counted and gives up their slot
+        set_reputation(CHARLIE, 49);
+        run_to_block(10);
+        System::assert_has_event(Event::VoterEvicted { who: CHARLIE }.into());
+        System::assert_last_event(Event::NewTerm { round: 1, members: vec![(ALICE, 500)] }.into());
+        assert_eq!(Elections::votes_of(CHARLIE), None);
+        assert_ok!(Elections::vote(RuntimeOrigin::signed(EVE), votes(&[ALICE])));
+    });
+}
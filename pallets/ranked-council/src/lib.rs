@@ -0,0 +1,501 @@
+//! # Ranked Council Pallet
+//!
+//! A collective whose member ranks follow UBI reputation.
+//!
+//! Any account whose reputation reaches the first of `T::RankThresholds`
+//! can `join`. A member's rank is the number of thresholds its reputation
+//! reaches, so governance rights directly reflect contribution history
+//! instead of being handed out. Ranks are synced from reputation every
+//! `T::SyncPeriod` blocks, and anyone can `sync_rank` a single member in
+//! between. A member whose reputation falls below the first threshold is
+//! removed.
+//!
+//! ## Rights
+//!
+//! - Rank 1 (`VOTE_RANK`): vote on motions, with the rank as vote weight
+//! - Rank 2 (`PROPOSE_RANK`): propose motions
+//! - Rank 3 (`VETO_RANK`): veto open motions
+//!
+//! ## Motions
+//!
+//! ```text
+//! propose → vote (VotingPeriod blocks) → on_initialize at the deadline
+//!        │                                 ├─ approved → dispatched as root
+//!        │                                 └─ rejected → dropped
+//!        └─ veto by a rank 3 member → dropped
+//! ```
+//!
+//! Only calls matched by `T::AllowedCalls` can be proposed. A motion is
+//! approved when the aye weight exceeds the nay weight and reaches
+//! `T::MinApprovalWeight`. A vote can be changed until the deadline; its
+//! weight is the voter's rank at the time of voting.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{boxed::Box, vec::Vec};
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    pallet_prelude::*,
+    traits::Contains,
+};
+use frame_system::pallet_prelude::*;
+use parity_scale_codec::DecodeLimit;
+use sp_runtime::traits::{Dispatchable, Saturating, Zero};
+
+/// Source of the reputation ranks are derived from
+pub trait ReputationProvider<AccountId> {
+    /// Current reputation score of an account
+    fn reputation(who: &AccountId) -> u128;
+}
+
+impl<AccountId> ReputationProvider<AccountId> for () {
+    fn reputation(_who: &AccountId) -> u128 {
+        0
+    }
+}
+
+/// Rank of a member, the number of `RankThresholds` its reputation reaches
+pub type Rank = u16;
+
+/// Index of a motion
+pub type MotionIndex = u32;
+
+/// Rank needed to vote on motions
+pub const VOTE_RANK: Rank = 1;
+
+/// Rank needed to propose motions
+pub const PROPOSE_RANK: Rank = 2;
+
+/// Rank needed to veto motions
+pub const VETO_RANK: Rank = 3;
+
+/// Number of ranks, and of `RankThresholds`
+pub const RANKS: usize = 3;
+
+/// Nesting depth allowed when decoding a stored motion
+const MOTION_DECODE_DEPTH: u32 = 256;
+
+/// A motion in its voting period
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxCallLen))]
+pub struct Motion<AccountId, BlockNumber, MaxCallLen: Get<u32>> {
+    /// Member that proposed the motion
+    pub proposer: AccountId,
+    /// SCALE-encoded call dispatched as root if approved
+    pub call: BoundedVec<u8, MaxCallLen>,
+    /// Block at which the votes are counted
+    pub end: BlockNumber,
+    /// Total rank voting aye
+    pub ayes: u32,
+    /// Total rank voting nay
+    pub nays: u32,
+    /// Number of members that voted
+    pub voters: u32,
+}
+
+/// A vote on a motion
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Vote {
+    /// Whether the vote is in favor
+    pub aye: bool,
+    /// Rank of the voter when voting
+    pub weight: Rank,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    /// Motion type of a runtime
+    pub type MotionOf<T> =
+        Motion<<T as frame_system::Config>::AccountId, BlockNumberFor<T>, <T as Config>::MaxCallLen>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configuration trait for the ranked council pallet
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// The overarching call type motions are made of
+        type RuntimeCall: Parameter
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo;
+
+        /// Reputation the ranks are derived from
+        type Reputation: ReputationProvider<Self::AccountId>;
+
+        /// Calls the council may dispatch
+        type AllowedCalls: Contains<<Self as Config>::RuntimeCall>;
+
+        /// Reputation needed for ranks 1 to 3, in increasing order
+        #[pallet::constant]
+        type RankThresholds: Get<[u128; RANKS]>;
+
+        /// Blocks between two syncs of all ranks
+        #[pallet::constant]
+        type SyncPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Blocks between the proposal of a motion and the vote count
+        #[pallet::constant]
+        type VotingPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Total rank that must vote aye for a motion to be approved
+        #[pallet::constant]
+        type MinApprovalWeight: Get<u32>;
+
+        /// Maximum number of members
+        #[pallet::constant]
+        type MaxMembers: Get<u32>;
+
+        /// Maximum number of motions in their voting period
+        #[pallet::constant]
+        type MaxMotions: Get<u32>;
+
+        /// Maximum length of an encoded motion call
+        #[pallet::constant]
+        type MaxCallLen: Get<u32>;
+
+        /// Maximum weight of a motion call, charged to the block counting its votes
+        #[pallet::constant]
+        type MaxCallWeight: Get<Weight>;
+    }
+
+    /// Members of the council and their ranks
+    #[pallet::storage]
+    #[pallet::getter(fn rank_of)]
+    pub type Members<T: Config> = CountedStorageMap<_, Blake2_128Concat, T::AccountId, Rank, OptionQuery>;
+
+    /// Number of motions proposed so far, the index of the next one
+    #[pallet::storage]
+    pub type MotionCount<T: Config> = StorageValue<_, MotionIndex, ValueQuery>;
+
+    /// Motions in their voting period
+    #[pallet::storage]
+    #[pallet::getter(fn motion)]
+    pub type Motions<T: Config> = CountedStorageMap<_, Twox64Concat, MotionIndex, MotionOf<T>, OptionQuery>;
+
+    /// Votes on the open motions
+    #[pallet::storage]
+    #[pallet::getter(fn vote_of)]
+    pub type Votes<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, MotionIndex, Blake2_128Concat, T::AccountId, Vote, OptionQuery>;
+
+    /// Motions counted at each block
+    #[pallet::storage]
+    pub type MotionDeadlines<T: Config> =
+        StorageMap<_, Twox64Concat, BlockNumberFor<T>, BoundedVec<MotionIndex, T::MaxMotions>, ValueQuery>;
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// An account joined the council
+        MemberAdded { who: T::AccountId, rank: Rank },
+        /// A member's rank followed its reputation
+        RankChanged { who: T::AccountId, from: Rank, to: Rank },
+        /// A member left or its reputation fell below the first threshold
+        MemberRemoved { who: T::AccountId },
+        /// A member proposed a motion
+        Proposed { index: MotionIndex, proposer: T::AccountId, end: BlockNumberFor<T> },
+        /// A member voted on a motion
+        Voted { index: MotionIndex, who: T::AccountId, aye: bool, weight: Rank },
+        /// A member vetoed a motion
+        Vetoed { index: MotionIndex, who: T::AccountId },
+        /// A motion was approved and its call dispatched
+        Executed { index: MotionIndex, result: DispatchResult },
+        /// A motion was rejected
+        Rejected { index: MotionIndex, ayes: u32, nays: u32 },
+    }
+
+    /// Errors that can occur in this pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account is already a member
+        AlreadyMember,
+        /// The account is not a member
+        NotMember,
+        /// The account's reputation is below the first rank threshold
+        InsufficientReputation,
+        /// `MaxMembers` accounts are already members
+        TooManyMembers,
+        /// The member's rank does not grant this right
+        RankTooLow,
+        /// The call is not in `AllowedCalls`
+        CallNotAllowed,
+        /// The encoded call is longer than `MaxCallLen`
+        CallTooLong,
+        /// The call may weigh more than `MaxCallWeight`
+        CallTooHeavy,
+        /// `MaxMotions` motions are already in their voting period
+        TooManyMotions,
+        /// No open motion has this index
+        UnknownMotion,
+        /// The motion's call can no longer be decoded
+        BadCall,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            let period = T::SyncPeriod::get();
+            if !period.is_zero() && (now % period).is_zero() {
+                weight.saturating_accrue(Self::sync_all());
+            }
+            for index in MotionDeadlines::<T>::take(now) {
+                weight.saturating_accrue(Self::conclude(index));
+            }
+            weight
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::RankThresholds::get().windows(2).all(|pair| pair[0] < pair[1]),
+                "RankThresholds must increase"
+            );
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Join the council with the rank of the caller's reputation
+        ///
+        /// # Errors
+        /// - `AlreadyMember` if the caller is already a member
+        /// - `InsufficientReputation` if the reputation is below the first rank threshold
+        /// - `TooManyMembers` if `MaxMembers` accounts are members
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn join(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(!Members::<T>::contains_key(&who), Error::<T>::AlreadyMember);
+            let rank = Self::rank_for(&who);
+            ensure!(rank >= VOTE_RANK, Error::<T>::InsufficientReputation);
+            ensure!(Members::<T>::count() < T::MaxMembers::get(), Error::<T>::TooManyMembers);
+            Members::<T>::insert(&who, rank);
+
+            Self::deposit_event(Event::MemberAdded { who, rank });
+            Ok(())
+        }
+
+        /// Leave the council
+        ///
+        /// Votes already cast keep counting.
+        ///
+        /// # Errors
+        /// - `NotMember` if the caller is not a member
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn leave(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(Members::<T>::take(&who).is_some(), Error::<T>::NotMember);
+
+            Self::deposit_event(Event::MemberRemoved { who });
+            Ok(())
+        }
+
+        /// Sync the rank of a member with its current reputation
+        ///
+        /// Anyone can call it, e.g. a member that just earned a rank, or
+        /// anyone noticing a member's reputation dropped.
+        ///
+        /// # Errors
+        /// - `NotMember` if the account is not a member
+        #[pallet::call_index(2)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn sync_rank(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let rank = Members::<T>::get(&who).ok_or(Error::<T>::NotMember)?;
+            Self::sync(who, rank);
+            Ok(())
+        }
+
+        /// Propose a call to the council
+        ///
+        /// The votes are counted `VotingPeriod` blocks from now.
+        ///
+        /// # Errors
+        /// - `NotMember` if the caller is not a member
+        /// - `RankTooLow` if the caller's rank is below `PROPOSE_RANK`
+        /// - `CallNotAllowed` if the call is not in `AllowedCalls`
+        /// - `CallTooHeavy` / `CallTooLong` if the call exceeds `MaxCallWeight` / `MaxCallLen`
+        /// - `TooManyMotions` if `MaxMotions` motions are open
+        #[pallet::call_index(3)]
+        #[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(4, 4))]
+        pub fn propose(origin: OriginFor<T>, call: Box<<T as Config>::RuntimeCall>) -> DispatchResult {
+            let proposer = ensure_signed(origin)?;
+
+            Self::ensure_rank(&proposer, PROPOSE_RANK)?;
+            ensure!(T::AllowedCalls::contains(&call), Error::<T>::CallNotAllowed);
+            ensure!(
+                call.get_dispatch_info().total_weight().all_lte(T::MaxCallWeight::get()),
+                Error::<T>::CallTooHeavy
+            );
+            ensure!(Motions::<T>::count() < T::MaxMotions::get(), Error::<T>::TooManyMotions);
+            let call = BoundedVec::try_from(call.encode()).map_err(|_| Error::<T>::CallTooLong)?;
+
+            let end = frame_system::Pallet::<T>::block_number().saturating_add(T::VotingPeriod::get());
+            let index = MotionCount::<T>::get();
+            MotionDeadlines::<T>::try_mutate(end, |indices| indices.try_push(index))
+                .map_err(|_| Error::<T>::TooManyMotions)?;
+            MotionCount::<T>::put(index.saturating_add(1));
+            Motions::<T>::insert(
+                index,
+                Motion { proposer: proposer.clone(), call, end, ayes: 0, nays: 0, voters: 0 },
+            );
+
+            Self::deposit_event(Event::Proposed { index, proposer, end });
+            Ok(())
+        }
+
+        /// Vote on an open motion with the caller's rank
+        ///
+        /// Voting again replaces the previous vote, with the current rank.
+        ///
+        /// # Errors
+        /// - `NotMember` if the caller is not a member
+        /// - `UnknownMotion` if the motion is not open
+        #[pallet::call_index(4)]
+        #[pallet::weight(Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(3, 2))]
+        pub fn vote(origin: OriginFor<T>, index: MotionIndex, aye: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let weight = Self::ensure_rank(&who, VOTE_RANK)?;
+            Motions::<T>::try_mutate(index, |motion| -> DispatchResult {
+                let motion = motion.as_mut().ok_or(Error::<T>::UnknownMotion)?;
+                match Votes::<T>::get(index, &who) {
+                    Some(previous) if previous.aye => motion.ayes.saturating_reduce(previous.weight.into()),
+                    Some(previous) => motion.nays.saturating_reduce(previous.weight.into()),
+                    None => motion.voters.saturating_inc(),
+                }
+                if aye {
+                    motion.ayes.saturating_accrue(weight.into());
+                } else {
+                    motion.nays.saturating_accrue(weight.into());
+                }
+                Ok(())
+            })?;
+            Votes::<T>::insert(index, &who, Vote { aye, weight });
+
+            Self::deposit_event(Event::Voted { index, who, aye, weight });
+            Ok(())
+        }
+
+        /// Drop an open motion without counting the votes
+        ///
+        /// # Errors
+        /// - `NotMember` if the caller is not a member
+        /// - `RankTooLow` if the caller's rank is below `VETO_RANK`
+        /// - `UnknownMotion` if the motion is not open
+        #[pallet::call_index(5)]
+        #[pallet::weight(
+            Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(3, 3 + T::MaxMembers::get() as u64)
+        )]
+        pub fn veto(origin: OriginFor<T>, index: MotionIndex) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_rank(&who, VETO_RANK)?;
+            let motion = Motions::<T>::get(index).ok_or(Error::<T>::UnknownMotion)?;
+            MotionDeadlines::<T>::mutate(motion.end, |indices| indices.retain(|i| *i != index));
+            Self::remove(index, &motion);
+
+            Self::deposit_event(Event::Vetoed { index, who });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Rank the current reputation of an account earns, zero below the first threshold
+        pub fn rank_for(who: &T::AccountId) -> Rank {
+            let reputation = T::Reputation::reputation(who);
+            T::RankThresholds::get().iter().filter(|threshold| reputation >= **threshold).count() as Rank
+        }
+
+        /// Members with their ranks, in storage order
+        pub fn members() -> Vec<(T::AccountId, Rank)> {
+            Members::<T>::iter().collect()
+        }
+
+        /// Ensure `who` is a member of at least rank `min`, returning its rank
+        fn ensure_rank(who: &T::AccountId, min: Rank) -> Result<Rank, DispatchError> {
+            let rank = Members::<T>::get(who).ok_or(Error::<T>::NotMember)?;
+            ensure!(rank >= min, Error::<T>::RankTooLow);
+            Ok(rank)
+        }
+
+        /// Set a member's rank from its reputation, removing it below the first threshold
+        fn sync(who: T::AccountId, rank: Rank) {
+            let new_rank = Self::rank_for(&who);
+            if new_rank == rank {
+                return;
+            }
+            if new_rank < VOTE_RANK {
+                Members::<T>::remove(&who);
+                Self::deposit_event(Event::MemberRemoved { who });
+            } else {
+                Members::<T>::insert(&who, new_rank);
+                Self::deposit_event(Event::RankChanged { who, from: rank, to: new_rank });
+            }
+        }
+
+        /// Sync the ranks of all members
+        pub(crate) fn sync_all() -> Weight {
+            let members = Self::members();
+            let count = members.len() as u64;
+            for (who, rank) in members {
+                Self::sync(who, rank);
+            }
+            T::DbWeight::get().reads_writes(count.saturating_mul(2), count)
+        }
+
+        /// Count the votes of a motion and dispatch its call if approved
+        pub(crate) fn conclude(index: MotionIndex) -> Weight {
+            let Some(motion) = Motions::<T>::get(index) else {
+                return T::DbWeight::get().reads(1);
+            };
+            Self::remove(index, &motion);
+            let mut weight = T::DbWeight::get()
+                .reads_writes(1, 2)
+                .saturating_add(T::DbWeight::get().writes(motion.voters.into()));
+
+            let approved = motion.ayes > motion.nays && motion.ayes >= T::MinApprovalWeight::get();
+            if !approved {
+                Self::deposit_event(Event::Rejected { index, ayes: motion.ayes, nays: motion.nays });
+                return weight;
+            }
+
+            let call =
+                <T as Config>::RuntimeCall::decode_all_with_depth_limit(MOTION_DECODE_DEPTH, &mut &motion.call[..]);
+            let result = match call {
+                Ok(call) => {
+                    let info = call.get_dispatch_info();
+                    let result = call.dispatch(frame_system::RawOrigin::Root.into());
+                    weight.saturating_accrue(frame_support::dispatch::extract_actual_weight(&result, &info));
+                    result.map(|_| ()).map_err(|e| e.error)
+                },
+                Err(_) => Err(Error::<T>::BadCall.into()),
+            };
+            Self::deposit_event(Event::Executed { index, result });
+            weight
+        }
+
+        /// Drop a motion and its votes
+        fn remove(index: MotionIndex, motion: &MotionOf<T>) {
+            Motions::<T>::remove(index);
+            let _ = Votes::<T>::clear_prefix(index, motion.voters, None);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate as pallet_ranked_council;
+use frame_support::{
+    derive_impl,
+    parameter_types,
+    traits::{ConstU16, ConstU32, ConstU64, Contains, Hooks},
+    weights::Weight,
+};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        RankedCouncil: pallet_ranked_council,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    /// Reputation of each account, as reported by the (fake) UBI pallet
+    pub storage Reputations: Vec<(u64, u128)> = Vec::new();
+    pub const RankThresholds: [u128; crate::RANKS] = [100, 200, 300];
+    pub MaxCallWeight: Weight = Weight::from_parts(1_000_000_000, 0);
+}
+
+/// Set the reputation returned for an account
+pub fn set_reputation(who: u64, reputation: u128) {
+    let mut reputations = Reputations::get();
+    reputations.retain(|(account, _)| *account != who);
+    reputations.push((who, reputation));
+    Reputations::set(&reputations);
+}
+
+/// Reads reputation from the `Reputations` test storage
+pub struct TestReputation;
+impl crate::ReputationProvider<u64> for TestReputation {
+    fn reputation(who: &u64) -> u128 {
+        Reputations::get()
+            .into_iter()
+            .find(|(account, _)| account == who)
+            .map(|(_, reputation)| reputation)
+            .unwrap_or(0)
+    }
+}
+
+/// The council may only write storage directly, a root-only call
+pub struct SetStorageOnly;
+impl Contains<RuntimeCall> for SetStorageOnly {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(call, RuntimeCall::System(frame_system::Call::set_storage { .. }))
+    }
+}
+
+impl pallet_ranked_council::Config for Test {
+    type RuntimeCall = RuntimeCall;
+    type Reputation = TestReputation;
+    type AllowedCalls = SetStorageOnly;
+    type RankThresholds = RankThresholds;
+    type SyncPeriod = ConstU64<10>;
+    type VotingPeriod = ConstU64<5>;
+    type MinApprovalWeight = ConstU32<4>;
+    type MaxMembers = ConstU32<4>;
+    type MaxMotions = ConstU32<2>;
+    type MaxCallLen = ConstU32<128>;
+    type MaxCallWeight = MaxCallWeight;
+}
+
+// Test accounts
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+pub const EVE: u64 = 5;
+
+/// Build genesis storage with ALICE, BOB and CHARLIE holding reputation for ranks 3, 2 and 1
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        set_reputation(ALICE, 300);
+        set_reputation(BOB, 200);
+        set_reputation(CHARLIE, 100);
+    });
+    ext
+}
+
+/// Run `on_initialize` of the blocks up to `n`
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        RankedCouncil::on_initialize(System::block_number());
+    }
+}
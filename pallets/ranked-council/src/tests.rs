@@ -0,0 +1,118 @@
+use crate::{mock::*, Error, Event, MotionDeadlines, Vote, Votes};
+use frame_support::{assert_noop, assert_ok};
+use frame_system::Call as SystemCall;
+
+/// Root-only call writing a known storage item
+fn call() -> Box<RuntimeCall> {
+    Box::new(RuntimeCall::System(SystemCall::set_storage { items: vec![(b"key".to_vec(), b"value".to_vec())] }))
+}
+
+fn executed() -> bool {
+    frame_support::storage::unhashed::get_raw(b"key") == Some(b"value".to_vec())
+}
+
+/// ALICE, BOB and CHARLIE join with ranks 3, 2 and 1
+fn join_all() {
+    for who in [ALICE, BOB, CHARLIE] {
+        assert_ok!(RankedCouncil::join(RuntimeOrigin::signed(who)));
+    }
+}
+
+#[test]
+fn accounts_join_with_the_rank_of_their_reputation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RankedCouncil::join(RuntimeOrigin::signed(BOB)));
+        System::assert_last_event(Event::MemberAdded { who: BOB, rank: 2 }.into());
+        assert_noop!(RankedCouncil::join(RuntimeOrigin::signed(BOB)), Error::<Test>::AlreadyMember);
+        assert_noop!(RankedCouncil::join(RuntimeOrigin::signed(DAVE)), Error::<Test>::InsufficientReputation);
+
+        assert_ok!(RankedCouncil::join(RuntimeOrigin::signed(ALICE)));
+        assert_ok!(RankedCouncil::join(RuntimeOrigin::signed(CHARLIE)));
+        set_reputation(DAVE, 150);
+        assert_ok!(RankedCouncil::join(RuntimeOrigin::signed(DAVE)));
+        set_reputation(EVE, 1_000);
+        assert_noop!(RankedCouncil::join(RuntimeOrigin::signed(EVE)), Error::<Test>::TooManyMembers);
+
+        assert_eq!(RankedCouncil::rank_of(ALICE), Some(3));
+        assert_eq!(RankedCouncil::rank_of(CHARLIE), Some(1));
+        assert_ok!(RankedCouncil::leave(RuntimeOrigin::signed(DAVE)));
+        System::assert_last_event(Event::MemberRemoved { who: DAVE }.into());
+        assert_noop!(RankedCouncil::leave(RuntimeOrigin::signed(DAVE)), Error::<Test>::NotMember);
+        assert_ok!(RankedCouncil::join(RuntimeOrigin::signed(EVE)));
+        assert_eq!(RankedCouncil::rank_of(EVE), Some(3));
+    });
+}
+
+#[test]
+fn ranks_follow_reputation() {
+    new_test_ext().execute_with(|| {
+        join_all();
+        set_reputation(CHARLIE, 250);
+        assert_ok!(RankedCouncil::sync_rank(RuntimeOrigin::signed(DAVE), CHARLIE));
+        System::assert_last_event(Event::RankChanged { who: CHARLIE, from: 1, to: 2 }.into());
+        assert_noop!(RankedCouncil::sync_rank(RuntimeOrigin::signed(DAVE), DAVE), Error::<Test>::NotMember);
+
+        // All ranks are synced every `SyncPeriod` blocks
+        set_reputation(ALICE, 150);
+        set_reputation(BOB, 50);
+        run_to_block(9);
+        assert_eq!(RankedCouncil::rank_of(ALICE), Some(3));
+        run_to_block(10);
+        System::assert_has_event(Event::RankChanged { who: ALICE, from: 3, to: 1 }.into());
+        System::assert_has_event(Event::MemberRemoved { who: BOB }.into());
+        assert_eq!(RankedCouncil::rank_of(ALICE), Some(1));
+        assert_eq!(RankedCouncil::rank_of(BOB), None);
+        assert_eq!(RankedCouncil::members().len(), 2);
+    });
+}
+
+#[test]
+fn ranks_grant_proposing_voting_and_vetoing() {
+    new_test_ext().execute_with(|| {
+        join_all();
+        assert_noop!(RankedCouncil::propose(RuntimeOrigin::signed(CHARLIE), call()), Error::<Test>::RankTooLow);
+        assert_noop!(RankedCouncil::propose(RuntimeOrigin::signed(DAVE), call()), Error::<Test>::NotMember);
+        let remark = Box::new(RuntimeCall::System(SystemCall::remark { remark: vec![] }));
+        assert_noop!(RankedCouncil::propose(RuntimeOrigin::signed(BOB), remark), Error::<Test>::CallNotAllowed);
+
+        assert_ok!(RankedCouncil::propose(RuntimeOrigin::signed(BOB), call()));
+        System::assert_last_event(Event::Proposed { index: 0, proposer: BOB, end: 6 }.into());
+        assert_noop!(RankedCouncil::vote(RuntimeOrigin::signed(DAVE), 0, true), Error::<Test>::NotMember);
+        assert_noop!(RankedCouncil::veto(RuntimeOrigin::signed(BOB), 0), Error::<Test>::RankTooLow);
+
+        assert_ok!(RankedCouncil::veto(RuntimeOrigin::signed(ALICE), 0));
+        System::assert_last_event(Event::Vetoed { index: 0, who: ALICE }.into());
+        assert!(RankedCouncil::motion(0).is_none());
+        assert!(MotionDeadlines::<Test>::get(6).is_empty());
+        assert_noop!(RankedCouncil::vote(RuntimeOrigin::signed(CHARLIE), 0, true), Error::<Test>::UnknownMotion);
+    });
+}
+
+#[test]
+fn motions_pass_with_enough_rank_weight() {
+    new_test_ext().execute_with(|| {
+        join_all();
+        assert_ok!(RankedCouncil::propose(RuntimeOrigin::signed(BOB), call()));
+        assert_ok!(RankedCouncil::vote(RuntimeOrigin::signed(BOB), 0, true));
+        assert_ok!(RankedCouncil::vote(RuntimeOrigin::signed(CHARLIE), 0, false));
+        assert_ok!(RankedCouncil::vote(RuntimeOrigin::signed(CHARLIE), 0, true));
+        System::assert_last_event(Event::Voted { index: 0, who: CHARLIE, aye: true, weight: 1 }.into());
+        assert_eq!(Votes::<Test>::get(0, CHARLIE), Some(Vote { aye: true, weight: 1 }));
+        let motion = RankedCouncil::motion(0).unwrap();
+        assert_eq!((motion.ayes, motion.nays, motion.voters), (3, 0, 2));
+
+        // Three aye weight is below `MinApprovalWeight`
+        run_to_block(6);
+        System::assert_last_event(Event::Rejected { index: 0, ayes: 3, nays: 0 }.into());
+        assert!(!executed());
+        assert!(Votes::<Test>::get(0, CHARLIE).is_none());
+
+        assert_ok!(RankedCouncil::propose(RuntimeOrigin::signed(BOB), call()));
+        assert_ok!(RankedCouncil::vote(RuntimeOrigin::signed(BOB), 1, true));
+        assert_ok!(RankedCouncil::vote(RuntimeOrigin::signed(ALICE), 1, true));
+        assert_ok!(RankedCouncil::vote(RuntimeOrigin::signed(CHARLIE), 1, false));
+        run_to_block(11);
+        System::assert_has_event(Event::Executed { index: 1, result: Ok(()) }.into());
+        assert!(executed());
+    });
+}
@@ -0,0 +1,438 @@
+//! # Reputation Referenda Pallet
+//!
+//! On-chain governance for the NST chain, weighted by UBI reputation.
+//!
+//! Any account with at least `T::MinProposerReputation` can put a call to a
+//! referendum, as long as its weight is within `T::MaxProposalWeight`.
+//! Accounts with at least `T::MinVoterReputation` vote aye or nay with their
+//! current reputation as weight, so influence comes from sustained honest
+//! activity rather than from holdings, which NST tokens cannot accumulate
+//! anyway.
+//!
+//! ## Lifecycle
+//!
+//! ```text
+//! submit → vote (VotingPeriod blocks) → on_initialize at the deadline
+//!                                         ├─ approved → dispatched as root
+//!                                         └─ rejected → dropped
+//! ```
+//!
+//! A referendum is approved when the aye weight exceeds the nay weight and
+//! the total weight reaches `T::MinTurnout`. The proposal is then dispatched
+//! with the root origin, so it can do anything sudo can: runtime upgrades
+//! (`System.authorize_upgrade`, after which anyone submits the code with
+//! `System.apply_authorized_upgrade`), parameter changes, validator changes.
+//!
+//! A vote can be changed until the deadline. Its weight is the voter's
+//! reputation at the time of voting, and it counts at the deadline with no
+//! more than the voter's reputation then, so reputation given away after
+//! voting (e.g. staked on a boost for another voter) is not counted twice.
+//!
+//! A referendum keeps the `T::MaxVoters` heaviest votes. Once they are taken,
+//! a new voter replaces the lightest vote if it is heavier, so cheap accounts
+//! cannot fill the electorate and lock everyone else out.
+//!
+//! ## Retiring Sudo
+//!
+//! Sudo accepts root as its own key, so a referendum proposing
+//! `Sudo.remove_key` permanently removes the sudo key. From then on only
+//! referenda can act as root.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::boxed::Box;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    pallet_prelude::*,
+};
+use frame_system::pallet_prelude::*;
+use parity_scale_codec::DecodeLimit;
+use sp_runtime::traits::{Dispatchable, Saturating};
+
+/// Source of the reputation used as voting weight
+pub trait ReputationProvider<AccountId> {
+    /// Current reputation score of an account
+    fn reputation(who: &AccountId) -> u128;
+}
+
+impl<AccountId> ReputationProvider<AccountId> for () {
+    fn reputation(_who: &AccountId) -> u128 {
+        0
+    }
+}
+
+/// Index of a referendum
+pub type ReferendumIndex = u32;
+
+/// Nesting depth allowed when decoding a stored proposal
+const PROPOSAL_DECODE_DEPTH: u32 = 256;
+
+/// A referendum in its voting period
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxProposalLen))]
+pub struct Referendum<AccountId, BlockNumber, MaxProposalLen: Get<u32>> {
+    /// Account that submitted the referendum
+    pub proposer: AccountId,
+    /// SCALE-encoded call dispatched as root if approved
+    pub proposal: BoundedVec<u8, MaxProposalLen>,
+    /// Block at which the votes are counted
+    pub end: BlockNumber,
+    /// Total reputation voting aye
+    pub ayes: u128,
+    /// Total reputation voting nay
+    pub nays: u128,
+    /// Number of accounts that voted
+    pub voters: u32,
+}
+
+/// A vote on a referendum
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Vote {
+    /// Whether the vote is in favor
+    pub aye: bool,
+    /// Reputation of the voter when voting
+    pub weight: u128,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    /// Referendum type of a runtime
+    pub type ReferendumOf<T> =
+        Referendum<<T as frame_system::Config>::AccountId, BlockNumberFor<T>, <T as Config>::MaxProposalLen>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configuration trait for the referenda pallet
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// The overarching call type proposals are made of
+        type RuntimeCall: Parameter
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo;
+
+        /// Reputation used as voting weight
+        type Reputation: ReputationProvider<Self::AccountId>;
+
+        /// Origin allowed to cancel an ongoing referendum (e.g. root)
+        type CancelOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Blocks between the submission of a referendum and the vote count
+        #[pallet::constant]
+        type VotingPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Reputation needed to submit a referendum
+        #[pallet::constant]
+        type MinProposerReputation: Get<u128>;
+
+        /// Reputation needed to vote
+        #[pallet::constant]
+        type MinVoterReputation: Get<u128>;
+
+        /// Total reputation that must vote for a referendum to be approved
+        #[pallet::constant]
+        type MinTurnout: Get<u128>;
+
+        /// Maximum number of referenda in their voting period
+        #[pallet::constant]
+        type MaxOngoing: Get<u32>;
+
+        /// Maximum number of voters of a referendum; the heaviest votes are kept
+        #[pallet::constant]
+        type MaxVoters: Get<u32>;
+
+        /// Maximum length of an encoded proposal
+        #[pallet::constant]
+        type MaxProposalLen: Get<u32>;
+
+        /// Maximum weight of a proposal, dispatched in `on_initialize` if approved
+        #[pallet::constant]
+        type MaxProposalWeight: Get<Weight>;
+    }
+
+    /// Number of referenda submitted so far, the index of the next one
+    #[pallet::storage]
+    #[pallet::getter(fn referendum_count)]
+    pub type ReferendumCount<T: Config> = StorageValue<_, ReferendumIndex, ValueQuery>;
+
+    /// Referenda in their voting period
+    #[pallet::storage]
+    #[pallet::getter(fn referendum)]
+    pub type Referenda<T: Config> = StorageMap<_, Twox64Concat, ReferendumIndex, ReferendumOf<T>, OptionQuery>;
+
+    /// Votes on the ongoing referenda
+    #[pallet::storage]
+    #[pallet::getter(fn vote_of)]
+    pub type Votes<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, ReferendumIndex, Blake2_128Concat, T::AccountId, Vote, OptionQuery>;
+
+    /// Voters of each ongoing referendum with the weight of their vote, heaviest first
+    #[pallet::storage]
+    pub type Electorate<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        ReferendumIndex,
+        BoundedVec<(T::AccountId, u128), T::MaxVoters>,
+        ValueQuery,
+    >;
+
+    /// Ongoing referendum of each proposer, one at a time
+    #[pallet::storage]
+    pub type Proposers<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ReferendumIndex, OptionQuery>;
+
+    /// Referenda counted at each block
+    #[pallet::storage]
+    pub type Deadlines<T: Config> =
+        StorageMap<_, Twox64Concat, BlockNumberFor<T>, BoundedVec<ReferendumIndex, T::MaxOngoing>, ValueQuery>;
+
+    /// Number of referenda in their voting period
+    #[pallet::storage]
+    pub type OngoingCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A referendum was submitted
+        Submitted { index: ReferendumIndex, proposer: T::AccountId, end: BlockNumberFor<T> },
+        /// An account voted on a referendum
+        Voted { index: ReferendumIndex, who: T::AccountId, aye: bool, weight: u128 },
+        /// The lightest vote of a full referendum was dropped for a heavier one
+        VoteReplaced { index: ReferendumIndex, who: T::AccountId, weight: u128 },
+        /// A referendum was approved and its proposal dispatched
+        Executed { index: ReferendumIndex, result: DispatchResult },
+        /// A referendum was rejected
+        Rejected { index: ReferendumIndex, ayes: u128, nays: u128 },
+        /// A referendum was cancelled before its deadline
+        Cancelled { index: ReferendumIndex },
+    }
+
+    /// Errors that can occur in this pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The proposer's reputation is below `MinProposerReputation`
+        InsufficientReputation,
+        /// The proposer already has a referendum in its voting period
+        AlreadyProposing,
+        /// `MaxOngoing` referenda are already in their voting period
+        TooManyReferenda,
+        /// The encoded proposal is longer than `MaxProposalLen`
+        ProposalTooLong,
+        /// The proposal can no longer be decoded
+        BadProposal,
+        /// The proposal weighs more than `MaxProposalWeight`
+        ProposalTooHeavy,
+        /// No ongoing referendum has this index
+        UnknownReferendum,
+        /// The voter's reputation is below `MinVoterReputation`
+        InsufficientVoterReputation,
+        /// `MaxVoters` accounts with at least the caller's reputation already voted
+        TooManyVoters,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            for index in Deadlines::<T>::take(now) {
+                weight.saturating_accrue(Self::conclude(index));
+            }
+            weight
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Put a call to a referendum
+        ///
+        /// The votes are counted `VotingPeriod` blocks from now.
+        ///
+        /// # Errors
+        /// - `InsufficientReputation` if the proposer's reputation is below `MinProposerReputation`
+        /// - `AlreadyProposing` if the proposer has an ongoing referendum
+        /// - `TooManyReferenda` if `MaxOngoing` referenda are ongoing
+        /// - `ProposalTooLong` if the encoded call exceeds `MaxProposalLen`
+        /// - `ProposalTooHeavy` if the call weighs more than `MaxProposalWeight`
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(4, 5))]
+        pub fn submit(origin: OriginFor<T>, proposal: Box<<T as Config>::RuntimeCall>) -> DispatchResult {
+            let proposer = ensure_signed(origin)?;
+
+            ensure!(
+                T::Reputation::reputation(&proposer) >= T::MinProposerReputation::get(),
+                Error::<T>::InsufficientReputation
+            );
+            ensure!(!Proposers::<T>::contains_key(&proposer), Error::<T>::AlreadyProposing);
+            ensure!(OngoingCount::<T>::get() < T::MaxOngoing::get(), Error::<T>::TooManyReferenda);
+            ensure!(
+                proposal.get_dispatch_info().call_weight.all_lte(T::MaxProposalWeight::get()),
+                Error::<T>::ProposalTooHeavy
+            );
+            let proposal = BoundedVec::try_from(proposal.encode()).map_err(|_| Error::<T>::ProposalTooLong)?;
+
+            let end = frame_system::Pallet::<T>::block_number().saturating_add(T::VotingPeriod::get());
+            let index = ReferendumCount::<T>::get();
+            Deadlines::<T>::try_mutate(end, |indices| indices.try_push(index))
+                .map_err(|_| Error::<T>::TooManyReferenda)?;
+            ReferendumCount::<T>::put(index.saturating_add(1));
+            OngoingCount::<T>::mutate(|count| *count = count.saturating_add(1));
+            Proposers::<T>::insert(&proposer, index);
+            Referenda::<T>::insert(
+                index,
+                Referendum { proposer: proposer.clone(), proposal, end, ayes: 0, nays: 0, voters: 0 },
+            );
+
+            Self::deposit_event(Event::Submitted { index, proposer, end });
+            Ok(())
+        }
+
+        /// Vote on an ongoing referendum with the caller's reputation
+        ///
+        /// Voting again replaces the previous vote, with the current reputation.
+        /// If `MaxVoters` accounts voted already, the lightest of their votes is
+        /// dropped for a heavier one.
+        ///
+        /// # Errors
+        /// - `UnknownReferendum` if the referendum is not ongoing
+        /// - `InsufficientVoterReputation` if the caller's reputation is below `MinVoterReputation`
+        /// - `TooManyVoters` if `MaxVoters` accounts with at least the caller's reputation already voted
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(4, 5))]
+        pub fn vote(origin: OriginFor<T>, index: ReferendumIndex, aye: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let weight = T::Reputation::reputation(&who);
+            ensure!(weight > 0 && weight >= T::MinVoterReputation::get(), Error::<T>::InsufficientVoterReputation);
+            let mut referendum = Referenda::<T>::get(index).ok_or(Error::<T>::UnknownReferendum)?;
+            let mut electorate = Electorate::<T>::get(index);
+
+            if let Some(previous) = Votes::<T>::get(index, &who) {
+                Self::retract(&mut referendum, previous);
+                electorate.retain(|(voter, _)| *voter != who);
+            } else if electorate.is_full() {
+                let lightest = electorate.last().filter(|(_, lightest)| *lightest < weight).cloned();
+                let (dropped, dropped_weight) = lightest.ok_or(Error::<T>::TooManyVoters)?;
+                electorate.pop();
+                if let Some(vote) = Votes::<T>::take(index, &dropped) {
+                    Self::retract(&mut referendum, vote);
+                }
+                Self::deposit_event(Event::VoteReplaced { index, who: dropped, weight: dropped_weight });
+            }
+
+            let position = electorate.partition_point(|(_, heavier)| *heavier >= weight);
+            electorate.try_insert(position, (who.clone(), weight)).map_err(|_| Error::<T>::TooManyVoters)?;
+            if aye {
+                referendum.ayes.saturating_accrue(weight);
+            } else {
+                referendum.nays.saturating_accrue(weight);
+            }
+            referendum.voters = electorate.len() as u32;
+            Referenda::<T>::insert(index, referendum);
+            Electorate::<T>::insert(index, electorate);
+            Votes::<T>::insert(index, &who, Vote { aye, weight });
+
+            Self::deposit_event(Event::Voted { index, who, aye, weight });
+            Ok(())
+        }
+
+        /// Cancel an ongoing referendum without counting the votes
+        ///
+        /// # Errors
+        /// - `UnknownReferendum` if the referendum is not ongoing
+        #[pallet::call_index(2)]
+        #[pallet::weight(
+            Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(3, 5 + T::MaxVoters::get() as u64)
+        )]
+        pub fn cancel(origin: OriginFor<T>, index: ReferendumIndex) -> DispatchResult {
+            T::CancelOrigin::ensure_origin(origin)?;
+
+            let referendum = Referenda::<T>::get(index).ok_or(Error::<T>::UnknownReferendum)?;
+            Deadlines::<T>::mutate(referendum.end, |indices| indices.retain(|i| *i != index));
+            Self::remove(index, &referendum);
+
+            Self::deposit_event(Event::Cancelled { index });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Take a vote out of the running totals of a referendum
+        fn retract(referendum: &mut ReferendumOf<T>, vote: Vote) {
+            if vote.aye {
+                referendum.ayes.saturating_reduce(vote.weight);
+            } else {
+                referendum.nays.saturating_reduce(vote.weight);
+            }
+        }
+
+        /// Count the votes of a referendum and dispatch its proposal if approved
+        ///
+        /// Every vote counts with no more than the voter's reputation now.
+        pub(crate) fn conclude(index: ReferendumIndex) -> Weight {
+            let Some(referendum) = Referenda::<T>::get(index) else {
+                return T::DbWeight::get().reads(1);
+            };
+            let (mut ayes, mut nays) = (0u128, 0u128);
+            for (voter, vote) in Votes::<T>::iter_prefix(index) {
+                let weight = vote.weight.min(T::Reputation::reputation(&voter));
+                if vote.aye {
+                    ayes.saturating_accrue(weight);
+                } else {
+                    nays.saturating_accrue(weight);
+                }
+            }
+            Self::remove(index, &referendum);
+            // A vote and the voter's reputation record read, the vote removed
+            let mut weight = T::DbWeight::get()
+                .reads_writes(2, 4)
+                .saturating_add(T::DbWeight::get().reads_writes(2, 1).saturating_mul(referendum.voters.into()));
+
+            let approved = ayes > nays && ayes.saturating_add(nays) >= T::MinTurnout::get();
+            if !approved {
+                Self::deposit_event(Event::Rejected { index, ayes, nays });
+                return weight;
+            }
+
+            let proposal = <T as Config>::RuntimeCall::decode_all_with_depth_limit(
+                PROPOSAL_DECODE_DEPTH,
+                &mut &referendum.proposal[..],
+            );
+            let result = match proposal {
+                // The weight of a call may have grown with a runtime upgrade since `submit`
+                Ok(call) if !call.get_dispatch_info().call_weight.all_lte(T::MaxProposalWeight::get()) => {
+                    Err(Error::<T>::ProposalTooHeavy.into())
+                },
+                Ok(call) => {
+                    let info = call.get_dispatch_info();
+                    let result = call.dispatch(frame_system::RawOrigin::Root.into());
+                    weight.saturating_accrue(frame_support::dispatch::extract_actual_weight(&result, &info));
+                    result.map(|_| ()).map_err(|e| e.error)
+                },
+                Err(_) => Err(Error::<T>::BadProposal.into()),
+            };
+            Self::deposit_event(Event::Executed { index, result });
+            weight
+        }
+
+        /// Drop a referendum and its votes
+        fn remove(index: ReferendumIndex, referendum: &ReferendumOf<T>) {
+            Referenda::<T>::remove(index);
+            Proposers::<T>::remove(&referendum.proposer);
+            Electorate::<T>::remove(index);
+            OngoingCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+            let _ = Votes::<T>::clear_prefix(index, referendum.voters, None);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate as pallet_reputation_referenda;
+use frame_support::{
+    derive_impl,
+    parameter_types,
+    traits::{ConstU128, ConstU16, ConstU32, ConstU64, Hooks},
+    weights::Weight,
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Referenda: pallet_reputation_referenda,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    /// Reputation of each account, as reported by the (fake) UBI pallet
+    pub storage Reputations: Vec<(u64, u128)> = Vec::new();
+    pub static MaxProposalWeight: Weight = Weight::MAX;
+}
+
+/// Set the reputation returned for an account
+pub fn set_reputation(who: u64, reputation: u128) {
+    let mut reputations = Reputations::get();
+    reputations.retain(|(account, _)| *account != who);
+    reputations.push((who, reputation));
+    Reputations::set(&reputations);
+}
+
+/// Reads reputation from the `Reputations` test storage
+pub struct TestReputation;
+impl crate::ReputationProvider<u64> for TestReputation {
+    fn reputation(who: &u64) -> u128 {
+        Reputations::get()
+            .into_iter()
+            .find(|(account, _)| account == who)
+            .map(|(_, reputation)| reputation)
+            .unwrap_or(0)
+    }
+}
+
+impl pallet_reputation_referenda::Config for Test {
+    type RuntimeCall = RuntimeCall;
+    type Reputation = TestReputation;
+    type CancelOrigin = EnsureRoot<u64>;
+    type VotingPeriod = ConstU64<10>;
+    type MinProposerReputation = ConstU128<100>;
+    type MinVoterReputation = ConstU128<50>;
+    type MinTurnout = ConstU128<500>;
+    type MaxOngoing = ConstU32<2>;
+    type MaxVoters = ConstU32<3>;
+    type MaxProposalLen = ConstU32<128>;
+    type MaxProposalWeight = MaxProposalWeight;
+}
+
+// Test accounts
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+pub const EVE: u64 = 5;
+
+/// Build genesis storage with ALICE, BOB and CHARLIE holding reputation
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        set_reputation(ALICE, 300);
+        set_reputation(BOB, 200);
+        set_reputation(CHARLIE, 100);
+    });
+    ext
+}
+
+/// Run `on_initialize` of the blocks up to `n`
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Referenda::on_initialize(System::block_number());
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{mock::*, Deadlines, Electorate, Error, Event, OngoingCount, Proposers, Votes};
+use frame_support::{assert_noop, assert_ok, weights::Weight};
+use frame_system::Call as SystemCall;
+use sp_runtime::DispatchError;
+
+/// Root-only call writing a known storage item
+fn proposal() -> Box<RuntimeCall> {
+    Box::new(RuntimeCall::System(SystemCall::set_storage { items: vec![(b"key".to_vec(), b"value".to_vec())] }))
+}
+
+fn executed() -> bool {
+    frame_support::storage::unhashed::get_raw(b"key") == Some(b"value".to_vec())
+}
+
+#[test]
+fn approved_referendum_is_dispatched_as_root() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        System::assert_last_event(Event::Submitted { index: 0, proposer: ALICE, end: 11 }.into());
+
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(BOB), 0, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(CHARLIE), 0, false));
+        assert_eq!(Referenda::referendum(0).map(|r| (r.ayes, r.nays, r.voters)), Some((500, 100, 3)));
+
+        run_to_block(10);
+        assert!(!executed());
+
+        run_to_block(11);
+        assert!(executed());
+        System::assert_last_event(Event::Executed { index: 0, result: Ok(()) }.into());
+        assert!(Referenda::referendum(0).is_none());
+        assert!(Votes::<Test>::get(0, ALICE).is_none());
+        assert!(Proposers::<Test>::get(ALICE).is_none());
+        assert_eq!(OngoingCount::<Test>::get(), 0);
+    });
+}
+
+#[test]
+fn referendum_without_majority_or_turnout_is_rejected() {
+    new_test_ext().execute_with(|| {
+        // Majority against
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, false));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(BOB), 0, true));
+        run_to_block(11);
+        System::assert_last_event(Event::Rejected { index: 0, ayes: 200, nays: 300 }.into());
+
+        // Majority in favor, but turnout below 500
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 1, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(CHARLIE), 1, true));
+        run_to_block(21);
+        System::assert_last_event(Event::Rejected { index: 1, ayes: 400, nays: 0 }.into());
+        assert!(!executed());
+    });
+}
+
+#[test]
+fn changing_a_vote_moves_its_weight() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(BOB), 0, false));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(BOB), 0, true));
+
+        let referendum = Referenda::referendum(0).unwrap();
+        assert_eq!((referendum.ayes, referendum.nays, referendum.voters), (200, 0, 1));
+        assert_eq!(Referenda::vote_of(0, BOB).map(|v| v.aye), Some(true));
+    });
+}
+
+#[test]
+fn submit_requires_reputation_and_free_slots() {
+    new_test_ext().execute_with(|| {
+        set_reputation(DAVE, 99);
+        assert_noop!(
+            Referenda::submit(RuntimeOrigin::signed(DAVE), proposal()),
+            Error::<Test>::InsufficientReputation
+        );
+
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_noop!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()), Error::<Test>::AlreadyProposing);
+
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(BOB), proposal()));
+        assert_noop!(
+            Referenda::submit(RuntimeOrigin::signed(CHARLIE), proposal()),
+            Error::<Test>::TooManyReferenda
+        );
+
+        let long = Box::new(RuntimeCall::System(SystemCall::remark { remark: vec![0; 200] }));
+        assert_ok!(Referenda::cancel(RuntimeOrigin::root(), 1));
+        assert_noop!(Referenda::submit(RuntimeOrigin::signed(CHARLIE), long), Error::<Test>::ProposalTooLong);
+    });
+}
+
+#[test]
+fn vote_requires_reputation_and_voter_slots() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, true), Error::<Test>::UnknownReferendum);
+
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_noop!(
+            Referenda::vote(RuntimeOrigin::signed(DAVE), 0, true),
+            Error::<Test>::InsufficientVoterReputation
+        );
+        set_reputation(DAVE, 49);
+        assert_noop!(
+            Referenda::vote(RuntimeOrigin::signed(DAVE), 0, true),
+            Error::<Test>::InsufficientVoterReputation
+        );
+
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(BOB), 0, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(CHARLIE), 0, true));
+        set_reputation(EVE, 100);
+        assert_noop!(Referenda::vote(RuntimeOrigin::signed(EVE), 0, true), Error::<Test>::TooManyVoters);
+    });
+}
+
+#[test]
+fn heavier_voter_replaces_the_lightest_vote_when_full() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(BOB), 0, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(CHARLIE), 0, false));
+
+        set_reputation(EVE, 150);
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(EVE), 0, true));
+        System::assert_has_event(Event::VoteReplaced { index: 0, who: CHARLIE, weight: 100 }.into());
+        assert!(Votes::<Test>::get(0, CHARLIE).is_none());
+        assert_eq!(Referenda::referendum(0).map(|r| (r.ayes, r.nays, r.voters)), Some((650, 0, 3)));
+        let electorate: Vec<_> = Electorate::<Test>::get(0).into_iter().map(|(voter, _)| voter).collect();
+        assert_eq!(electorate, [ALICE, BOB, EVE]);
+
+        // Changing a vote keeps its slot
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(EVE), 0, false));
+        assert_eq!(Referenda::referendum(0).map(|r| (r.ayes, r.nays, r.voters)), Some((500, 150, 3)));
+    });
+}
+
+#[test]
+fn reputation_given_away_after_voting_is_not_counted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, true));
+
+        // Alice hands 200 of her reputation to Dave, who votes with it
+        set_reputation(ALICE, 100);
+        set_reputation(DAVE, 200);
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(DAVE), 0, true));
+        assert_eq!(Referenda::referendum(0).map(|r| r.ayes), Some(500));
+
+        // Alice's vote counts with 100, short of the turnout of 500
+        run_to_block(11);
+        System::assert_last_event(Event::Rejected { index: 0, ayes: 300, nays: 0 }.into());
+        assert!(!executed());
+    });
+}
+
+#[test]
+fn proposals_heavier_than_max_proposal_weight_are_not_dispatched() {
+    new_test_ext().execute_with(|| {
+        MaxProposalWeight::set(Weight::zero());
+        assert_noop!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()), Error::<Test>::ProposalTooHeavy);
+
+        // The limit is checked again when the proposal is dispatched
+        MaxProposalWeight::set(Weight::MAX);
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, true));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(BOB), 0, true));
+        MaxProposalWeight::set(Weight::zero());
+        run_to_block(11);
+        System::assert_last_event(
+            Event::Executed { index: 0, result: Err(Error::<Test>::ProposalTooHeavy.into()) }.into(),
+        );
+        assert!(!executed());
+    });
+}
+
+#[test]
+fn cancel_drops_the_referendum_and_its_votes() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+        assert_ok!(Referenda::vote(RuntimeOrigin::signed(ALICE), 0, true));
+        assert_noop!(Referenda::cancel(RuntimeOrigin::signed(ALICE), 0), DispatchError::BadOrigin);
+
+        assert_ok!(Referenda::cancel(RuntimeOrigin::root(), 0));
+        System::assert_last_event(Event::Cancelled { index: 0 }.into());
+        assert!(Referenda::referendum(0).is_none());
+        assert!(Votes::<Test>::get(0, ALICE).is_none());
+        assert!(Deadlines::<Test>::get(11).is_empty());
+        assert_noop!(Referenda::cancel(RuntimeOrigin::root(), 0), Error::<Test>::UnknownReferendum);
+
+        // The proposer can submit again
+        assert_ok!(Referenda::submit(RuntimeOrigin::signed(ALICE), proposal()));
+    });
+}
@@ -0,0 +1,256 @@
+//! # Stepped Migrations Pallet
+//!
+//! Runs storage migrations across as many blocks as they need.
+//!
+//! A migration that rewrites every reputation record cannot run in one
+//! `on_runtime_upgrade` once the chain has many accounts: the block would be
+//! over weight and could never be produced. This pallet is the runtime's
+//! `MultiBlockMigrator` instead. After a runtime upgrade it steps through
+//! `T::Migrations` in order, spending up to `T::MaxServiceWeight` per block,
+//! and stores the cursor of the running migration between blocks.
+//!
+//! Migrations implement `frame_support`'s `SteppedMigration`, the interface
+//! of the upstream `pallet-migrations`, so they can move to it unchanged.
+//!
+//! ## Progress
+//!
+//! While migrations are ongoing, `frame_executive` only applies inherents, so
+//! no transaction sees half-migrated storage. `Cursor` holds the index of the
+//! running migration, its own cursor and the number of steps it took, and an
+//! event marks every completed migration and the end of the upgrade.
+//!
+//! ## Failures
+//!
+//! A migration fails when it returns an error, exceeds its `max_steps`,
+//! needs more weight than a block can give or returns a cursor longer than
+//! `T::CursorMaxLen`. `MigrationFailed` is emitted and
+//! `T::FailedMigrationHandler` decides whether the chain stays stuck or
+//! resumes transactions. `T::ControlOrigin` can overwrite the cursor to retry
+//! or skip a migration.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::migrations::{
+    FailedMigrationHandler, FailedMigrationHandling, MultiStepMigrator, SteppedMigrationError, SteppedMigrations,
+};
+use frame_support::pallet_prelude::*;
+use frame_support::weights::WeightMeter;
+use frame_system::pallet_prelude::*;
+use sp_runtime::Saturating;
+
+/// Progress of the migrations of a runtime upgrade
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum MigrationCursor<Cursor, BlockNumber> {
+    /// Migrations are running
+    Active(ActiveCursor<Cursor, BlockNumber>),
+    /// A migration failed and the chain waits for `T::ControlOrigin`
+    Stuck,
+}
+
+/// Position within the running migration
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ActiveCursor<Cursor, BlockNumber> {
+    /// Index of the migration in `T::Migrations`
+    pub index: u32,
+    /// Cursor the migration returned from its last step
+    pub inner_cursor: Option<Cursor>,
+    /// Steps the migration took so far
+    pub steps: u32,
+    /// Block in which the migration started
+    pub started_at: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    /// Encoded cursor of a single migration
+    pub type RawCursorOf<T> = BoundedVec<u8, <T as Config>::CursorMaxLen>;
+
+    /// Cursor type of the pallet
+    pub type CursorOf<T> = MigrationCursor<RawCursorOf<T>, BlockNumberFor<T>>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configuration trait for the stepped migrations pallet
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// Migrations run after every runtime upgrade, in order
+        ///
+        /// Each one must skip itself once it has been applied.
+        type Migrations: SteppedMigrations;
+
+        /// Origin allowed to overwrite the cursor (e.g. root)
+        type ControlOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Decides how the chain continues after a migration failed
+        type FailedMigrationHandler: FailedMigrationHandler;
+
+        /// Maximum encoded length of a migration's cursor
+        #[pallet::constant]
+        type CursorMaxLen: Get<u32>;
+
+        /// Weight migrations may use per block
+        #[pallet::constant]
+        type MaxServiceWeight: Get<Weight>;
+    }
+
+    /// Progress of the ongoing upgrade, if any
+    #[pallet::storage]
+    pub type Cursor<T: Config> = StorageValue<_, CursorOf<T>, OptionQuery>;
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A runtime upgrade started running its migrations
+        UpgradeStarted { migrations: u32 },
+        /// A migration finished
+        MigrationCompleted { index: u32, steps: u32 },
+        /// A migration failed
+        MigrationFailed { index: u32 },
+        /// All migrations of the upgrade finished
+        UpgradeCompleted,
+        /// `ControlOrigin` overwrote the cursor
+        CursorSet { cursor: Option<CursorOf<T>> },
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            // An upgrade while migrations are still running restarts them.
+            // The new runtime may list different migrations, so the old index
+            // means nothing; the migrations skip themselves once applied.
+            let migrations = T::Migrations::len();
+            if migrations == 0 {
+                Cursor::<T>::kill();
+                return T::DbWeight::get().writes(1);
+            }
+
+            Cursor::<T>::put(MigrationCursor::Active(ActiveCursor {
+                index: 0,
+                inner_cursor: None,
+                steps: 0,
+                started_at: frame_system::Pallet::<T>::block_number(),
+            }));
+            Self::deposit_event(Event::UpgradeStarted { migrations });
+            T::DbWeight::get().reads_writes(1, 2)
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::Migrations::cursor_max_encoded_len() <= T::CursorMaxLen::get() as usize,
+                "CursorMaxLen is shorter than the cursor of a migration"
+            );
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Overwrite the cursor
+        ///
+        /// Sets a stuck upgrade active again to retry a migration, moves the
+        /// index past one that cannot succeed, or clears the cursor to resume
+        /// transactions.
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+        pub fn force_set_cursor(origin: OriginFor<T>, cursor: Option<CursorOf<T>>) -> DispatchResult {
+            T::ControlOrigin::ensure_origin(origin)?;
+
+            Cursor::<T>::set(cursor.clone());
+
+            Self::deposit_event(Event::CursorSet { cursor });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Report a failed migration and let `T::FailedMigrationHandler` handle it
+        pub(crate) fn migration_failed(index: u32) {
+            Self::deposit_event(Event::MigrationFailed { index });
+
+            match T::FailedMigrationHandler::failed(Some(index)) {
+                FailedMigrationHandling::KeepStuck => Cursor::<T>::put(MigrationCursor::Stuck),
+                FailedMigrationHandling::ForceUnstuck => Cursor::<T>::kill(),
+                FailedMigrationHandling::Ignore => {}
+            }
+        }
+    }
+}
+
+impl<T: Config> MultiStepMigrator for Pallet<T> {
+    fn ongoing() -> bool {
+        Cursor::<T>::exists()
+    }
+
+    /// Run migrations until this block's `T::MaxServiceWeight` is used up
+    fn step() -> Weight {
+        let mut meter = WeightMeter::with_limit(T::MaxServiceWeight::get());
+        meter.consume(T::DbWeight::get().reads_writes(1, 1));
+
+        let Some(MigrationCursor::Active(mut active)) = Cursor::<T>::get() else {
+            return meter.consumed();
+        };
+
+        loop {
+            if active.index >= T::Migrations::len() {
+                Cursor::<T>::kill();
+                Self::deposit_event(Event::UpgradeCompleted);
+                break;
+            }
+            if let Some(Some(max_steps)) = T::Migrations::nth_max_steps(active.index) {
+                if active.steps >= max_steps {
+                    Self::migration_failed(active.index);
+                    break;
+                }
+            }
+
+            let inner_cursor = active.inner_cursor.clone().map(BoundedVec::into_inner);
+            match T::Migrations::nth_transactional_step(active.index, inner_cursor, &mut meter) {
+                Some(Ok(Some(inner_cursor))) => {
+                    let Ok(inner_cursor) = RawCursorOf::<T>::try_from(inner_cursor) else {
+                        Self::migration_failed(active.index);
+                        break;
+                    };
+                    active.inner_cursor = Some(inner_cursor);
+                    active.steps.saturating_inc();
+                }
+                Some(Ok(None)) => {
+                    Self::deposit_event(Event::MigrationCompleted {
+                        index: active.index,
+                        steps: active.steps.saturating_add(1),
+                    });
+                    active = ActiveCursor {
+                        index: active.index.saturating_add(1),
+                        inner_cursor: None,
+                        steps: 0,
+                        started_at: frame_system::Pallet::<T>::block_number(),
+                    };
+                }
+                Some(Err(SteppedMigrationError::InsufficientWeight { required })) => {
+                    // A step that no block can fit would stall the chain forever
+                    if T::MaxServiceWeight::get().any_lt(required) {
+                        Self::migration_failed(active.index);
+                    } else {
+                        Cursor::<T>::put(MigrationCursor::Active(active));
+                    }
+                    break;
+                }
+                Some(Err(_)) | None => {
+                    Self::migration_failed(active.index);
+                    break;
+                }
+            }
+        }
+        meter.consumed()
+    }
+}
@@ -0,0 +1,163 @@
+use crate as pallet_stepped_migrations;
+use frame_support::{
+    derive_impl,
+    migrations::{FailedMigrationHandler, FailedMigrationHandling, SteppedMigration, SteppedMigrationError},
+    parameter_types,
+    traits::{ConstU16, ConstU32, ConstU64, Hooks},
+    weights::{Weight, WeightMeter},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        MultiBlockMigrations: pallet_stepped_migrations,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+    type MultiBlockMigrator = MultiBlockMigrations;
+}
+
+/// Weight of one step of a `CountingMigration`
+pub const STEP_WEIGHT: Weight = Weight::from_parts(10, 0);
+
+/// What `FinalMigration` does when stepped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Succeed,
+    Fail,
+    NeedMoreThanABlock,
+}
+
+parameter_types! {
+    pub const MaxServiceWeight: Weight = Weight::from_parts(100, 0);
+    pub static FinalOutcome: Outcome = Outcome::Succeed;
+    pub static FailureHandling: FailedMigrationHandling = FailedMigrationHandling::KeepStuck;
+}
+
+/// Migration taking `STEPS` steps of `STEP_WEIGHT`, counted under its `ID`
+pub struct CountingMigration<const ID: u8, const STEPS: u32>;
+
+impl<const ID: u8, const STEPS: u32> SteppedMigration for CountingMigration<ID, STEPS> {
+    type Cursor = u32;
+    type Identifier = u8;
+
+    fn id() -> u8 {
+        ID
+    }
+
+    fn step(cursor: Option<u32>, meter: &mut WeightMeter) -> Result<Option<u32>, SteppedMigrationError> {
+        let done = cursor.unwrap_or_default();
+        if done == STEPS {
+            return Ok(None);
+        }
+        if meter.try_consume(STEP_WEIGHT).is_err() {
+            return Err(SteppedMigrationError::InsufficientWeight { required: STEP_WEIGHT });
+        }
+        frame_support::storage::unhashed::put(&[ID], &(done + 1));
+        Ok(Some(done + 1))
+    }
+}
+
+/// Single-step migration behaving as `FinalOutcome` says
+pub struct FinalMigration;
+
+impl SteppedMigration for FinalMigration {
+    type Cursor = ();
+    type Identifier = u8;
+
+    fn id() -> u8 {
+        u8::MAX
+    }
+
+    fn max_steps() -> Option<u32> {
+        Some(1)
+    }
+
+    fn step(_cursor: Option<()>, _meter: &mut WeightMeter) -> Result<Option<()>, SteppedMigrationError> {
+        match FinalOutcome::get() {
+            Outcome::Succeed => Ok(None),
+            Outcome::Fail => Err(SteppedMigrationError::Failed),
+            Outcome::NeedMoreThanABlock => {
+                Err(SteppedMigrationError::InsufficientWeight { required: MaxServiceWeight::get() * 2 })
+            }
+        }
+    }
+}
+
+/// Handles failures as `FailureHandling` says
+pub struct MockFailedMigrationHandler;
+
+impl FailedMigrationHandler for MockFailedMigrationHandler {
+    fn failed(_migration: Option<u32>) -> FailedMigrationHandling {
+        FailureHandling::get()
+    }
+}
+
+impl pallet_stepped_migrations::Config for Test {
+    type Migrations = (CountingMigration<0, 25>, CountingMigration<1, 5>, FinalMigration);
+    type ControlOrigin = EnsureRoot<u64>;
+    type FailedMigrationHandler = MockFailedMigrationHandler;
+    type CursorMaxLen = ConstU32<16>;
+    type MaxServiceWeight = MaxServiceWeight;
+}
+
+// Test accounts
+pub const ALICE: u64 = 1;
+
+/// Steps `CountingMigration` `ID` has taken
+pub fn steps_taken(id: u8) -> u32 {
+    frame_support::storage::unhashed::get_or_default(&[id])
+}
+
+/// Start the migrations as a runtime upgrade would
+pub fn upgrade() {
+    MultiBlockMigrations::on_runtime_upgrade();
+}
+
+/// Step the migrations as `frame_executive` does in the next block
+pub fn next_block() -> Weight {
+    System::set_block_number(System::block_number() + 1);
+    <MultiBlockMigrations as frame_support::migrations::MultiStepMigrator>::step()
+}
+
+/// Build empty genesis storage
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,127 @@
+use crate::{mock::*, ActiveCursor, Cursor, Event, MigrationCursor};
+use frame_support::{assert_noop, assert_ok, migrations::FailedMigrationHandling, migrations::MultiStepMigrator};
+use sp_runtime::DispatchError;
+
+fn active_cursor() -> ActiveCursor<crate::RawCursorOf<Test>, u64> {
+    match Cursor::<Test>::get() {
+        Some(MigrationCursor::Active(active)) => active,
+        other => panic!("expected an active cursor, got {other:?}"),
+    }
+}
+
+#[test]
+fn migrations_run_across_blocks() {
+    new_test_ext().execute_with(|| {
+        assert!(!MultiBlockMigrations::ongoing());
+        upgrade();
+        assert!(MultiBlockMigrations::ongoing());
+        System::assert_last_event(Event::UpgradeStarted { migrations: 3 }.into());
+
+        // Ten steps fit into a block
+        assert_eq!(next_block(), MaxServiceWeight::get());
+        assert_eq!(steps_taken(0), 10);
+        let active = active_cursor();
+        assert_eq!((active.index, active.steps, active.started_at), (0, 10, 1));
+
+        next_block();
+        assert_eq!(steps_taken(0), 20);
+        assert!(MultiBlockMigrations::ongoing());
+
+        // The rest of the first migration leaves room for the others
+        next_block();
+        assert_eq!((steps_taken(0), steps_taken(1)), (25, 5));
+        System::assert_has_event(Event::MigrationCompleted { index: 0, steps: 26 }.into());
+        System::assert_has_event(Event::MigrationCompleted { index: 1, steps: 6 }.into());
+        System::assert_has_event(Event::MigrationCompleted { index: 2, steps: 1 }.into());
+        System::assert_last_event(Event::UpgradeCompleted.into());
+        assert!(!MultiBlockMigrations::ongoing());
+
+        // Nothing is left to do
+        next_block();
+        assert_eq!(steps_taken(0), 25);
+    });
+}
+
+#[test]
+fn upgrade_during_migrations_restarts_them() {
+    new_test_ext().execute_with(|| {
+        upgrade();
+        next_block();
+        assert_eq!(active_cursor().steps, 10);
+
+        upgrade();
+        let active = active_cursor();
+        assert_eq!((active.index, active.inner_cursor, active.steps), (0, None, 0));
+    });
+}
+
+#[test]
+fn failed_migration_is_handed_to_the_handler() {
+    new_test_ext().execute_with(|| {
+        FinalOutcome::set(Outcome::Fail);
+        upgrade();
+        (0..3).for_each(|_| {
+            next_block();
+        });
+
+        System::assert_last_event(Event::MigrationFailed { index: 2 }.into());
+        assert_eq!(Cursor::<Test>::get(), Some(MigrationCursor::Stuck));
+        assert!(MultiBlockMigrations::ongoing());
+
+        // A stuck upgrade makes no progress
+        next_block();
+        assert_eq!(Cursor::<Test>::get(), Some(MigrationCursor::Stuck));
+    });
+
+    new_test_ext().execute_with(|| {
+        FinalOutcome::set(Outcome::Fail);
+        FailureHandling::set(FailedMigrationHandling::ForceUnstuck);
+        upgrade();
+        (0..3).for_each(|_| {
+            next_block();
+        });
+
+        System::assert_last_event(Event::MigrationFailed { index: 2 }.into());
+        assert!(!MultiBlockMigrations::ongoing());
+    });
+}
+
+#[test]
+fn migration_needing_more_than_a_block_fails() {
+    new_test_ext().execute_with(|| {
+        FinalOutcome::set(Outcome::NeedMoreThanABlock);
+        upgrade();
+        (0..3).for_each(|_| {
+            next_block();
+        });
+
+        System::assert_last_event(Event::MigrationFailed { index: 2 }.into());
+        assert_eq!(Cursor::<Test>::get(), Some(MigrationCursor::Stuck));
+    });
+}
+
+#[test]
+fn control_origin_can_overwrite_the_cursor() {
+    new_test_ext().execute_with(|| {
+        FinalOutcome::set(Outcome::Fail);
+        upgrade();
+        (0..3).for_each(|_| {
+            next_block();
+        });
+        assert_eq!(Cursor::<Test>::get(), Some(MigrationCursor::Stuck));
+
+        assert_noop!(
+            MultiBlockMigrations::force_set_cursor(RuntimeOrigin::signed(ALICE), None),
+            DispatchError::BadOrigin
+        );
+
+        // Skip the failing migration
+        let skip = MigrationCursor::Active(ActiveCursor { index: 3, inner_cursor: None, steps: 0, started_at: 4 });
+        assert_ok!(MultiBlockMigrations::force_set_cursor(RuntimeOrigin::root(), Some(skip.clone())));
+        System::assert_last_event(Event::CursorSet { cursor: Some(skip) }.into());
+
+        next_block();
+        System::assert_last_event(Event::UpgradeCompleted.into());
+        assert!(!MultiBlockMigrations::ongoing());
+    });
+}
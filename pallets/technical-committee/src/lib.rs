@@ -0,0 +1,373 @@
+//! # Technical Committee Pallet
+//!
+//! A small committee that can fast-track emergency actions.
+//!
+//! Referenda take days. During an incident, a handful of trusted members
+//! can act in minutes instead, but only jointly and only within a narrow
+//! mandate. Members are managed by `T::MembershipOrigin` (root, hence a
+//! referendum), or replaced as a whole through `ChangeMembers` by an
+//! election pallet.
+//!
+//! ## Motions
+//!
+//! A member proposes a call with `propose`, which counts as their approval.
+//! Other members approve it with `approve`. Once `T::MinApprovals` current
+//! members approved, the call is dispatched with the root origin. Motions not
+//! approved within `T::MotionDuration` blocks are dropped.
+//!
+//! ```text
+//! propose → approve … (MinApprovals members) → dispatched as root
+//!        └─ MotionDuration blocks without enough approvals → expired
+//! ```
+//!
+//! Only calls matched by `T::AllowedCalls` can be proposed, e.g. pausing a
+//! call or rolling a parameter back to its default. Everything else stays
+//! with the referenda.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{boxed::Box, vec::Vec};
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    pallet_prelude::*,
+    traits::{ChangeMembers, Contains},
+};
+use frame_system::pallet_prelude::*;
+use parity_scale_codec::DecodeLimit;
+use sp_runtime::traits::{Dispatchable, Saturating};
+
+/// Index of a motion
+pub type MotionIndex = u32;
+
+/// Nesting depth allowed when decoding a stored motion
+const MOTION_DECODE_DEPTH: u32 = 256;
+
+/// A motion waiting for approvals
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxMembers, MaxCallLen))]
+pub struct Motion<AccountId, BlockNumber, MaxMembers: Get<u32>, MaxCallLen: Get<u32>> {
+    /// SCALE-encoded call dispatched as root once approved
+    pub call: BoundedVec<u8, MaxCallLen>,
+    /// Members that approved the motion, the proposer first
+    pub approvals: BoundedVec<AccountId, MaxMembers>,
+    /// Block at which the motion expires
+    pub end: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    /// Motion type of a runtime
+    pub type MotionOf<T> = Motion<
+        <T as frame_system::Config>::AccountId,
+        BlockNumberFor<T>,
+        <T as Config>::MaxMembers,
+        <T as Config>::MaxCallLen,
+    >;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configuration trait for the technical committee pallet
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// The overarching call type motions are made of
+        type RuntimeCall: Parameter
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo;
+
+        /// Origin allowed to add and remove members (e.g. root)
+        type MembershipOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Calls the committee may dispatch
+        type AllowedCalls: Contains<<Self as Config>::RuntimeCall>;
+
+        /// Approvals of current members needed to dispatch a motion
+        #[pallet::constant]
+        type MinApprovals: Get<u32>;
+
+        /// Blocks a motion stays open
+        #[pallet::constant]
+        type MotionDuration: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of members
+        #[pallet::constant]
+        type MaxMembers: Get<u32>;
+
+        /// Maximum number of open motions
+        #[pallet::constant]
+        type MaxMotions: Get<u32>;
+
+        /// Maximum length of an encoded motion call
+        #[pallet::constant]
+        type MaxCallLen: Get<u32>;
+
+        /// Maximum weight of a motion call, charged to the approval dispatching it
+        #[pallet::constant]
+        type MaxCallWeight: Get<Weight>;
+    }
+
+    /// Members of the committee
+    #[pallet::storage]
+    #[pallet::getter(fn members)]
+    pub type Members<T: Config> = StorageValue<_, BoundedVec<T::AccountId, T::MaxMembers>, ValueQuery>;
+
+    /// Number of motions proposed so far, the index of the next one
+    #[pallet::storage]
+    pub type MotionCount<T: Config> = StorageValue<_, MotionIndex, ValueQuery>;
+
+    /// Open motions
+    #[pallet::storage]
+    #[pallet::getter(fn motion)]
+    pub type Motions<T: Config> = CountedStorageMap<_, Twox64Concat, MotionIndex, MotionOf<T>, OptionQuery>;
+
+    /// Motions expiring at each block
+    #[pallet::storage]
+    pub type MotionDeadlines<T: Config> =
+        StorageMap<_, Twox64Concat, BlockNumberFor<T>, BoundedVec<MotionIndex, T::MaxMotions>, ValueQuery>;
+
+    /// Genesis configuration: members from block zero
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Initial members
+        pub members: Vec<T::AccountId>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            let mut members = BoundedVec::<T::AccountId, T::MaxMembers>::default();
+            for who in &self.members {
+                assert!(!members.contains(who), "Duplicate committee member in genesis");
+                members.try_push(who.clone()).expect("Too many genesis committee members");
+            }
+            Members::<T>::put(members);
+        }
+    }
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A member joined the committee
+        MemberAdded { who: T::AccountId },
+        /// A member left the committee
+        MemberRemoved { who: T::AccountId },
+        /// A member proposed a motion
+        Proposed { index: MotionIndex, proposer: T::AccountId, end: BlockNumberFor<T> },
+        /// A member approved a motion
+        Approved { index: MotionIndex, who: T::AccountId, approvals: u32 },
+        /// A motion reached `MinApprovals` and its call was dispatched
+        Executed { index: MotionIndex, result: DispatchResult },
+        /// A motion expired without enough approvals
+        Expired { index: MotionIndex },
+    }
+
+    /// Errors that can occur in this pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account is already a member
+        AlreadyMember,
+        /// The account is not a member
+        NotMember,
+        /// The committee is full
+        TooManyMembers,
+        /// The call is not in `AllowedCalls`
+        CallNotAllowed,
+        /// The encoded call is longer than `MaxCallLen`
+        CallTooLong,
+        /// The call may weigh more than `MaxCallWeight`
+        CallTooHeavy,
+        /// `MaxMotions` motions are already open
+        TooManyMotions,
+        /// No open motion has this index
+        UnknownMotion,
+        /// The member already approved the motion
+        AlreadyApproved,
+        /// The motion's call can no longer be decoded
+        BadCall,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let expiring = MotionDeadlines::<T>::take(now);
+            let count = expiring.len() as u64;
+            for index in expiring {
+                if Motions::<T>::take(index).is_some() {
+                    Self::deposit_event(Event::Expired { index });
+                }
+            }
+            T::DbWeight::get().reads_writes(1 + count, 1 + count)
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Add a member to the committee
+        ///
+        /// # Errors
+        /// - `AlreadyMember` if the account is already a member
+        /// - `TooManyMembers` if the committee is full
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn add_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::MembershipOrigin::ensure_origin(origin)?;
+
+            Members::<T>::try_mutate(|members| {
+                ensure!(!members.contains(&who), Error::<T>::AlreadyMember);
+                members.try_push(who.clone()).map_err(|_| Error::<T>::TooManyMembers)
+            })?;
+
+            Self::deposit_event(Event::MemberAdded { who });
+            Ok(())
+        }
+
+        /// Remove a member from the committee
+        ///
+        /// Their approvals of open motions no longer count.
+        ///
+        /// # Errors
+        /// - `NotMember` if the account is not a member
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn remove_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::MembershipOrigin::ensure_origin(origin)?;
+
+            Members::<T>::try_mutate(|members| -> DispatchResult {
+                let index = members.iter().position(|m| m == &who).ok_or(Error::<T>::NotMember)?;
+                members.remove(index);
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::MemberRemoved { who });
+            Ok(())
+        }
+
+        /// Propose a call, approving it at the same time
+        ///
+        /// With `MinApprovals` of one, the call is dispatched right away.
+        ///
+        /// # Errors
+        /// - `NotMember` if the caller is not a member
+        /// - `CallNotAllowed` if the call is not in `AllowedCalls`
+        /// - `CallTooHeavy` / `CallTooLong` if the call exceeds `MaxCallWeight` / `MaxCallLen`
+        /// - `TooManyMotions` if `MaxMotions` motions are open
+        #[pallet::call_index(2)]
+        #[pallet::weight(
+            Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(4, 4) + T::MaxCallWeight::get()
+        )]
+        pub fn propose(origin: OriginFor<T>, call: Box<<T as Config>::RuntimeCall>) -> DispatchResult {
+            let proposer = ensure_signed(origin)?;
+
+            ensure!(Self::is_member(&proposer), Error::<T>::NotMember);
+            ensure!(T::AllowedCalls::contains(&call), Error::<T>::CallNotAllowed);
+            ensure!(
+                call.get_dispatch_info().total_weight().all_lte(T::MaxCallWeight::get()),
+                Error::<T>::CallTooHeavy
+            );
+            ensure!(Motions::<T>::count() < T::MaxMotions::get(), Error::<T>::TooManyMotions);
+            let encoded = BoundedVec::try_from(call.encode()).map_err(|_| Error::<T>::CallTooLong)?;
+
+            let end = frame_system::Pallet::<T>::block_number().saturating_add(T::MotionDuration::get());
+            let index = MotionCount::<T>::get();
+            MotionDeadlines::<T>::try_mutate(end, |indices| indices.try_push(index))
+                .map_err(|_| Error::<T>::TooManyMotions)?;
+            MotionCount::<T>::put(index.saturating_add(1));
+            let approvals = BoundedVec::truncate_from(alloc::vec![proposer.clone()]);
+            Motions::<T>::insert(index, Motion { call: encoded, approvals, end });
+
+            Self::deposit_event(Event::Proposed { index, proposer, end });
+            Self::execute_if_approved(index);
+            Ok(())
+        }
+
+        /// Approve an open motion
+        ///
+        /// The approval reaching `MinApprovals` dispatches the call.
+        ///
+        /// # Errors
+        /// - `NotMember` if the caller is not a member
+        /// - `UnknownMotion` if the motion is not open
+        /// - `AlreadyApproved` if the caller already approved it
+        #[pallet::call_index(3)]
+        #[pallet::weight(
+            Weight::from_parts(15_000, 0) + T::DbWeight::get().reads_writes(2, 2) + T::MaxCallWeight::get()
+        )]
+        pub fn approve(origin: OriginFor<T>, index: MotionIndex) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(Self::is_member(&who), Error::<T>::NotMember);
+            Motions::<T>::try_mutate(index, |motion| -> DispatchResult {
+                let motion = motion.as_mut().ok_or(Error::<T>::UnknownMotion)?;
+                ensure!(!motion.approvals.contains(&who), Error::<T>::AlreadyApproved);
+                // Removed members free their slot first
+                motion.approvals.retain(|member| Self::is_member(member));
+                motion.approvals.try_push(who.clone()).map_err(|_| Error::<T>::TooManyMembers)?;
+                Ok(())
+            })?;
+
+            Self::execute_if_approved(index);
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether an account is a member of the committee
+        pub fn is_member(who: &T::AccountId) -> bool {
+            Members::<T>::get().contains(who)
+        }
+
+        /// Report the approvals of a motion and dispatch it once `MinApprovals` members approved
+        fn execute_if_approved(index: MotionIndex) {
+            let Some(motion) = Motions::<T>::get(index) else {
+                return;
+            };
+            let members = Members::<T>::get();
+            let approvals = motion.approvals.iter().filter(|who| members.contains(who)).count() as u32;
+            if let Some(who) = motion.approvals.last() {
+                Self::deposit_event(Event::Approved { index, who: who.clone(), approvals });
+            }
+            if approvals < T::MinApprovals::get() {
+                return;
+            }
+
+            Motions::<T>::remove(index);
+            MotionDeadlines::<T>::mutate(motion.end, |indices| indices.retain(|i| *i != index));
+            let call =
+                <T as Config>::RuntimeCall::decode_all_with_depth_limit(MOTION_DECODE_DEPTH, &mut &motion.call[..]);
+            let result = match call {
+                Ok(call) => call.dispatch(frame_system::RawOrigin::Root.into()).map(|_| ()).map_err(|e| e.error),
+                Err(_) => Err(Error::<T>::BadCall.into()),
+            };
+            Self::deposit_event(Event::Executed { index, result });
+        }
+    }
+}
+
+impl<T: Config> ChangeMembers<T::AccountId> for Pallet<T> {
+    /// Replace the committee with the elected members, keeping at most `T::MaxMembers`
+    fn change_members_sorted(_incoming: &[T::AccountId], _outgoing: &[T::AccountId], sorted_new: &[T::AccountId]) {
+        let members = BoundedVec::<T::AccountId, T::MaxMembers>::truncate_from(sorted_new.to_vec());
+        let previous = Members::<T>::get();
+        for who in previous.iter().filter(|who| !members.contains(who)) {
+            Self::deposit_event(Event::MemberRemoved { who: who.clone() });
+        }
+        for who in members.iter().filter(|who| !previous.contains(who)) {
+            Self::deposit_event(Event::MemberAdded { who: who.clone() });
+        }
+        Members::<T>::put(members);
+    }
+}
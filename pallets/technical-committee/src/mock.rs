@@ -0,0 +1,101 @@
+use crate as pallet_technical_committee;
+use frame_support::{
+    derive_impl,
+    parameter_types,
+    traits::{ConstU16, ConstU32, ConstU64, Contains, Hooks},
+    weights::Weight,
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        TechnicalCommittee: pallet_technical_committee,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+/// The committee may only write storage directly, a root-only call
+pub struct SetStorageOnly;
+impl Contains<RuntimeCall> for SetStorageOnly {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(call, RuntimeCall::System(frame_system::Call::set_storage { .. }))
+    }
+}
+
+parameter_types! {
+    pub MaxCallWeight: Weight = Weight::from_parts(1_000_000_000, 0);
+}
+
+impl pallet_technical_committee::Config for Test {
+    type RuntimeCall = RuntimeCall;
+    type MembershipOrigin = EnsureRoot<u64>;
+    type AllowedCalls = SetStorageOnly;
+    type MinApprovals = ConstU32<2>;
+    type MotionDuration = ConstU64<5>;
+    type MaxMembers = ConstU32<3>;
+    type MaxMotions = ConstU32<2>;
+    type MaxCallLen = ConstU32<128>;
+    type MaxCallWeight = MaxCallWeight;
+}
+
+// Test accounts
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+
+/// Build genesis storage with ALICE, BOB and CHARLIE on the committee
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = RuntimeGenesisConfig {
+        system: Default::default(),
+        technical_committee: pallet_technical_committee::GenesisConfig { members: vec![ALICE, BOB, CHARLIE] },
+    }
+    .build_storage()
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Run `on_initialize` of the blocks up to `n`
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        TechnicalCommittee::on_initialize(System::block_number());
+    }
+}
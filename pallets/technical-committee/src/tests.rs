@@ -0,0 +1,122 @@
+use crate::{mock::*, Error, Event, MotionDeadlines, Motions};
+use frame_support::{assert_noop, assert_ok, traits::ChangeMembers};
+use frame_system::Call as SystemCall;
+use sp_runtime::DispatchError;
+
+/// Root-only call writing a known storage item
+fn call() -> Box<RuntimeCall> {
+    Box::new(RuntimeCall::System(SystemCall::set_storage { items: vec![(b"key".to_vec(), b"value".to_vec())] }))
+}
+
+fn executed() -> bool {
+    frame_support::storage::unhashed::get_raw(b"key") == Some(b"value".to_vec())
+}
+
+#[test]
+fn genesis_members_are_set() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(TechnicalCommittee::members().to_vec(), vec![ALICE, BOB, CHARLIE]);
+        assert!(TechnicalCommittee::is_member(&BOB));
+        assert!(!TechnicalCommittee::is_member(&DAVE));
+    });
+}
+
+#[test]
+fn membership_is_managed_by_membership_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(TechnicalCommittee::add_member(RuntimeOrigin::signed(ALICE), DAVE), DispatchError::BadOrigin);
+        assert_noop!(TechnicalCommittee::add_member(RuntimeOrigin::root(), ALICE), Error::<Test>::AlreadyMember);
+        assert_noop!(TechnicalCommittee::add_member(RuntimeOrigin::root(), DAVE), Error::<Test>::TooManyMembers);
+
+        assert_ok!(TechnicalCommittee::remove_member(RuntimeOrigin::root(), CHARLIE));
+        System::assert_last_event(Event::MemberRemoved { who: CHARLIE }.into());
+        assert_noop!(TechnicalCommittee::remove_member(RuntimeOrigin::root(), CHARLIE), Error::<Test>::NotMember);
+
+        assert_ok!(TechnicalCommittee::add_member(RuntimeOrigin::root(), DAVE));
+        System::assert_last_event(Event::MemberAdded { who: DAVE }.into());
+        assert_eq!(TechnicalCommittee::members().to_vec(), vec![ALICE, BOB, DAVE]);
+    });
+}
+
+#[test]
+fn elections_replace_the_whole_committee() {
+    new_test_ext().execute_with(|| {
+        TechnicalCommittee::set_members_sorted(&[BOB, DAVE], &[ALICE, BOB, CHARLIE]);
+        System::assert_has_event(Event::MemberRemoved { who: ALICE }.into());
+        System::assert_has_event(Event::MemberRemoved { who: CHARLIE }.into());
+        System::assert_last_event(Event::MemberAdded { who: DAVE }.into());
+        assert_eq!(TechnicalCommittee::members().to_vec(), vec![BOB, DAVE]);
+
+        // Elected accounts beyond `MaxMembers` are dropped
+        TechnicalCommittee::set_members_sorted(&[ALICE, BOB, CHARLIE, DAVE], &[BOB, DAVE]);
+        System::assert_has_event(Event::MemberRemoved { who: DAVE }.into());
+        assert_eq!(TechnicalCommittee::members().to_vec(), vec![ALICE, BOB, CHARLIE]);
+    });
+}
+
+#[test]
+fn motion_is_dispatched_as_root_once_approved() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TechnicalCommittee::propose(RuntimeOrigin::signed(ALICE), call()));
+        System::assert_last_event(Event::Approved { index: 0, who: ALICE, approvals: 1 }.into());
+        assert!(!executed());
+        assert_noop!(TechnicalCommittee::approve(RuntimeOrigin::signed(ALICE), 0), Error::<Test>::AlreadyApproved);
+
+        assert_ok!(TechnicalCommittee::approve(RuntimeOrigin::signed(BOB), 0));
+        assert!(executed());
+        System::assert_last_event(Event::Executed { index: 0, result: Ok(()) }.into());
+        assert!(TechnicalCommittee::motion(0).is_none());
+        assert!(MotionDeadlines::<Test>::get(6).is_empty());
+        assert_noop!(TechnicalCommittee::approve(RuntimeOrigin::signed(CHARLIE), 0), Error::<Test>::UnknownMotion);
+    });
+}
+
+#[test]
+fn only_members_and_allowed_calls_take_part() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(TechnicalCommittee::propose(RuntimeOrigin::signed(DAVE), call()), Error::<Test>::NotMember);
+
+        let remark = Box::new(RuntimeCall::System(SystemCall::remark { remark: vec![] }));
+        assert_noop!(TechnicalCommittee::propose(RuntimeOrigin::signed(ALICE), remark), Error::<Test>::CallNotAllowed);
+
+        assert_ok!(TechnicalCommittee::propose(RuntimeOrigin::signed(ALICE), call()));
+        assert_noop!(TechnicalCommittee::approve(RuntimeOrigin::signed(DAVE), 0), Error::<Test>::NotMember);
+
+        assert_ok!(TechnicalCommittee::propose(RuntimeOrigin::signed(BOB), call()));
+        assert_noop!(
+            TechnicalCommittee::propose(RuntimeOrigin::signed(CHARLIE), call()),
+            Error::<Test>::TooManyMotions
+        );
+    });
+}
+
+#[test]
+fn approvals_of_removed_members_do_not_count() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TechnicalCommittee::propose(RuntimeOrigin::signed(ALICE), call()));
+        assert_ok!(TechnicalCommittee::remove_member(RuntimeOrigin::root(), ALICE));
+
+        assert_ok!(TechnicalCommittee::approve(RuntimeOrigin::signed(BOB), 0));
+        System::assert_last_event(Event::Approved { index: 0, who: BOB, approvals: 1 }.into());
+        assert!(!executed());
+
+        assert_ok!(TechnicalCommittee::approve(RuntimeOrigin::signed(CHARLIE), 0));
+        assert!(executed());
+    });
+}
+
+#[test]
+fn motions_expire_after_motion_duration() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TechnicalCommittee::propose(RuntimeOrigin::signed(ALICE), call()));
+
+        run_to_block(5);
+        assert!(Motions::<Test>::contains_key(0));
+
+        run_to_block(6);
+        System::assert_last_event(Event::Expired { index: 0 }.into());
+        assert!(!Motions::<Test>::contains_key(0));
+        assert_noop!(TechnicalCommittee::approve(RuntimeOrigin::signed(BOB), 0), Error::<Test>::UnknownMotion);
+        assert!(!executed());
+    });
+}
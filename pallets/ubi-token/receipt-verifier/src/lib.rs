@@ -0,0 +1,98 @@
+//! # UBI Burn Receipt Verifier
+//!
+//! Checks that a burn happened without trusting the node that reports it.
+//!
+//! A `BurnProof` holds a burn's receipt, the hash of the block it was read
+//! at and the trie nodes proving the receipt is stored in that block's
+//! state. Given that block's header from a source the verifier trusts, e.g.
+//! a finalized header of its own light client, `BurnProof::verify` checks the
+//! proof against the header's state root, so "show me you paid" works
+//! offline. `nst-node ubi receipt <BURN_ID>` produces the SCALE encoded
+//! proofs from the `UbiTokenApi::burn_receipt` runtime API and a read proof.
+//!
+//! The proof shows the receipt as of that block: a later rejection or refund
+//! only shows in a proof read at a later block.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{
+    hashing::{twox_128, twox_64},
+    H256,
+};
+use sp_runtime::{
+    traits::{BlakeTwo256, Header},
+    RuntimeDebug,
+};
+use sp_trie::{LayoutV1, StorageProof};
+
+pub use pallet_ubi_token::{BurnId, BurnReceipt};
+
+/// Name of the storage map of the receipts in the pallet
+const RECEIPTS_STORAGE: &[u8] = b"BurnReceipts";
+
+/// A burn receipt with the proof that it is stored in a block's state
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct BurnProof<AssetId, AccountId, Balance, BlockNumber> {
+    /// Id of the burn
+    pub burn_id: BurnId,
+    /// Receipt of the burn as stored in the block
+    pub receipt: BurnReceipt<AssetId, AccountId, Balance, BlockNumber>,
+    /// Hash of the block the receipt was read at
+    pub block_hash: H256,
+    /// Trie nodes proving the receipt under the block's state root
+    pub proof: Vec<Vec<u8>>,
+}
+
+/// Why a `BurnProof` was not accepted
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum VerifyError {
+    /// The proof was read at another block than the header's
+    BlockMismatch,
+    /// The trie nodes are incomplete or not part of the header's state
+    InvalidProof,
+    /// The block's state holds no receipt with the burn id
+    UnknownBurn,
+    /// The block's state holds a different receipt under the burn id
+    ReceiptMismatch,
+}
+
+/// Storage key of the receipt of `burn_id` in the pallet instance `pallet_name`
+///
+/// `pallet_name` is the instance's name in the runtime, `UbiToken` on NST.
+pub fn receipt_storage_key(pallet_name: &str, burn_id: BurnId) -> Vec<u8> {
+    let id = burn_id.encode();
+    [&twox_128(pallet_name.as_bytes())[..], &twox_128(RECEIPTS_STORAGE)[..], &twox_64(&id)[..], &id[..]].concat()
+}
+
+impl<AssetId, AccountId, Balance, BlockNumber> BurnProof<AssetId, AccountId, Balance, BlockNumber>
+where
+    BurnReceipt<AssetId, AccountId, Balance, BlockNumber>: Encode,
+{
+    /// Check that the block of `header` stores the receipt in the pallet instance `pallet_name`
+    ///
+    /// The header must come from a trusted source; the proof only ties the
+    /// receipt to it.
+    pub fn verify<H: Header<Hash = H256>>(&self, header: &H, pallet_name: &str) -> Result<(), VerifyError> {
+        if header.hash() != self.block_hash {
+            return Err(VerifyError::BlockMismatch);
+        }
+
+        let db = StorageProof::new(self.proof.iter().cloned()).into_memory_db::<BlakeTwo256>();
+        let key = receipt_storage_key(pallet_name, self.burn_id);
+        let stored = sp_trie::read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, header.state_root(), &key, None, None)
+            .map_err(|_| VerifyError::InvalidProof)?
+            .ok_or(VerifyError::UnknownBurn)?;
+
+        if stored != self.receipt.encode() {
+            return Err(VerifyError::ReceiptMismatch);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use crate::*;
+use sp_runtime::{generic, StateVersion};
+use sp_state_machine::{prove_read, Backend, InMemoryBackend};
+use std::collections::BTreeMap;
+
+type TestHeader = generic::Header<u32, BlakeTwo256>;
+type TestProof = BurnProof<u32, u64, u128, u32>;
+
+const PALLET: &str = "UbiToken";
+
+fn receipt(amount: u128) -> BurnReceipt<u32, u64, u128, u32> {
+    BurnReceipt {
+        asset_id: 0,
+        from: 1,
+        to: 2,
+        amount,
+        category: None,
+        reference: None,
+        block: 7,
+        weighted_amount: amount,
+        sender_bonus: 0,
+        new_recipient: true,
+        rejected: false,
+        refunded: false,
+    }
+}
+
+/// State holding burns 0 and 1, its header and a proof of `burn_id`
+fn prove(burn_id: BurnId) -> (TestHeader, TestProof) {
+    let state: BTreeMap<_, _> = [
+        (receipt_storage_key(PALLET, 0), receipt(10).encode()),
+        (receipt_storage_key(PALLET, 1), receipt(20).encode()),
+        (b":code".to_vec(), vec![1, 2, 3]),
+    ]
+    .into_iter()
+    .collect();
+    let backend = InMemoryBackend::<BlakeTwo256>::from((state, StateVersion::V1));
+    let state_root = backend.storage_root(core::iter::empty(), StateVersion::V1).0;
+    let proof = prove_read(backend, [receipt_storage_key(PALLET, burn_id)]).unwrap();
+
+    let header = TestHeader::new(7, Default::default(), state_root, Default::default(), Default::default());
+    let proof = TestProof {
+        burn_id,
+        receipt: receipt(10),
+        block_hash: header.hash(),
+        proof: proof.into_iter_nodes().collect(),
+    };
+    (header, proof)
+}
+
+#[test]
+fn stored_receipts_verify_against_their_header() {
+    let (header, proof) = prove(0);
+    assert_eq!(proof.verify(&header, PALLET), Ok(()));
+
+    let decoded = TestProof::decode(&mut &proof.encode()[..]).unwrap();
+    assert_eq!(decoded.verify(&header, PALLET), Ok(()));
+}
+
+#[test]
+fn altered_receipts_are_rejected() {
+    let (header, mut proof) = prove(0);
+    proof.receipt.amount = 11;
+    assert_eq!(proof.verify(&header, PALLET), Err(VerifyError::ReceiptMismatch));
+
+    // The receipt of another burn is not the receipt of this one
+    let (header, mut proof) = prove(1);
+    proof.burn_id = 0;
+    assert_eq!(proof.verify(&header, PALLET), Err(VerifyError::InvalidProof));
+}
+
+#[test]
+fn proofs_are_bound_to_the_header_and_pallet() {
+    let (header, proof) = prove(0);
+    let other = TestHeader::new(8, Default::default(), *header.state_root(), header.hash(), Default::default());
+    assert_eq!(proof.verify(&other, PALLET), Err(VerifyError::BlockMismatch));
+
+    let (_, mut forged) = prove(0);
+    let header = TestHeader::new(7, Default::default(), H256::repeat_byte(1), Default::default(), Default::default());
+    forged.block_hash = header.hash();
+    assert_eq!(forged.verify(&header, PALLET), Err(VerifyError::InvalidProof));
+
+    // Other instances store their receipts under other keys
+    let (header, proof) = prove(0);
+    assert_eq!(proof.verify(&header, "CityToken"), Err(VerifyError::UnknownBurn));
+}
+
+#[test]
+fn missing_burns_are_reported() {
+    let (header, proof) = prove(2);
+    assert_eq!(proof.verify(&header, PALLET), Err(VerifyError::UnknownBurn));
+}
@@ -0,0 +1,126 @@
+//! # UBI Token Runtime API
+//!
+//! Read-only queries of the UBI token pallet for wallets and tooling.
+//!
+//! Wallets should read the asset parameters through this API instead of
+//! hard-coding them, since governance can change them with
+//! `set_asset_parameters`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use parity_scale_codec::{Codec, HasCompact};
+use sp_core::H256;
+use sp_runtime::Perbill;
+
+pub use pallet_ubi_token::{
+    AccountPreferences, AssetParameters, BurnAuthorization, BurnId, BurnReceipt, Reputation, ReputationParameters,
+    TokenBatch, TopSupporter, UbiParameters,
+};
+
+/// Receipt of a burn with the storage key it is stored under
+pub type BurnReceiptWithKey<AssetId, AccountId, Balance, BlockNumber> =
+    (BurnReceipt<AssetId, AccountId, Balance, BlockNumber>, Vec<u8>);
+
+sp_api::decl_runtime_apis! {
+    /// Queries of the UBI token pallet
+    pub trait UbiTokenApi<AssetId, AccountId, Balance, BlockNumber>
+    where
+        AssetId: Codec,
+        AccountId: Codec,
+        // Reputations are encoded compactly
+        Balance: Codec + HasCompact,
+        BlockNumber: Codec + HasCompact,
+    {
+        /// Effective parameters of an asset and the reputation constants
+        ///
+        /// Returns `None` if the asset does not exist.
+        fn ubi_parameters(asset_id: AssetId) -> Option<UbiParameters<Balance, BlockNumber>>;
+
+        /// Reputation of an account with pending decay and streak lapses applied
+        fn reputation(asset_id: AssetId, who: AccountId) -> Reputation<Balance, BlockNumber>;
+
+        /// Period index of the account's last claim, `None` if it never claimed
+        fn claim_anchor(asset_id: AssetId, who: AccountId) -> Option<u64>;
+
+        /// First block from which the account can claim again
+        ///
+        /// Claim windows are anchored to each account's first claim. Returns
+        /// the current block if it can claim now, `None` if the asset does
+        /// not exist.
+        fn next_claim_block(asset_id: AssetId, who: AccountId) -> Option<BlockNumber>;
+
+        /// Amount the account could claim now, zero if it cannot claim
+        fn claimable_amount(asset_id: AssetId, who: AccountId) -> Balance;
+
+        /// Spendable balance of an account with `block` taken as the current block
+        ///
+        /// Called on the state of block N with `block = N` (e.g. on an archive
+        /// node), it tells what the account could spend at that block.
+        fn spendable_balance_at(asset_id: AssetId, who: AccountId, block: BlockNumber) -> Balance;
+
+        /// Spendable balances of several accounts, in the order given
+        ///
+        /// Saves explorers and leaderboards a round-trip per account.
+        fn balances_of(asset_id: AssetId, accounts: Vec<AccountId>) -> Vec<Balance>;
+
+        /// Share of the asset's accounts with a lower reputation score bucket
+        ///
+        /// Read from a histogram of stored scores, zero if the account has no
+        /// reputation record.
+        fn reputation_percentile(asset_id: AssetId, who: AccountId) -> Perbill;
+
+        /// Reputations of several accounts as returned by `reputation`, in the order given
+        fn reputations_of(asset_id: AssetId, accounts: Vec<AccountId>) -> Vec<Reputation<Balance, BlockNumber>>;
+
+        /// Unexpired token batches of an account, oldest expiry first
+        fn token_batches(asset_id: AssetId, who: AccountId) -> Vec<TokenBatch<Balance, BlockNumber>>;
+
+        /// Senders that burned the most to `who`, largest volume first
+        fn top_supporters(asset_id: AssetId, who: AccountId) -> Vec<TopSupporter<AccountId, Balance>>;
+
+        /// Accounts attested `role`, with their number of attesters
+        fn attested_accounts(asset_id: AssetId, role: Vec<u8>) -> Vec<(AccountId, u32)>;
+
+        /// Display name and metadata hash of an account, `None` if it set no profile
+        fn profile(asset_id: AssetId, who: AccountId) -> Option<(Vec<u8>, Option<H256>)>;
+
+        /// Preferences of an account, the defaults if it never set any
+        fn preferences(asset_id: AssetId, who: AccountId) -> AccountPreferences<Balance>;
+
+        /// Nonce the account's next unsigned burn must carry
+        fn burn_nonce(asset_id: AssetId, who: AccountId) -> u64;
+
+        /// Authorization of a burn of `amount` to `to`, for `from` to sign
+        ///
+        /// Bound to this chain's genesis hash, the current spec version and
+        /// `from`'s next burn nonce. Set `category` and `reference` if needed,
+        /// then sign the SCALE encoding and submit it with `burn_authorized`.
+        fn burn_authorization(
+            asset_id: AssetId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> BurnAuthorization<AssetId, AccountId, Balance, H256>;
+
+        /// Page of the recipients `sender` has burned to, in storage order
+        ///
+        /// Pass the last recipient of the previous page as `start_key` (`None`
+        /// for the first page). A page shorter than `limit` is the last one.
+        fn unique_recipients(
+            asset_id: AssetId,
+            sender: AccountId,
+            start_key: Option<AccountId>,
+            limit: u32,
+        ) -> Vec<AccountId>;
+
+        /// Receipt of a burn with the storage key it is stored under, `None` if unknown
+        ///
+        /// Together with a read proof of the key at the same block
+        /// (`state_getReadProof`) it forms a `BurnProof` that
+        /// `pallet-ubi-token-receipt-verifier` checks against the block's header.
+        fn burn_receipt(burn_id: BurnId) -> Option<BurnReceiptWithKey<AssetId, AccountId, Balance, BlockNumber>>;
+    }
+}
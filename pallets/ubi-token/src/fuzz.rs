@@ -0,0 +1,118 @@
+//! Randomized invariant tests
+//!
+//! Every case runs a random sequence of claims, burns and block jumps across
+//! a handful of accounts and checks the pallet's global invariants after each
+//! operation. Cases are generated from fixed seeds, so a failure names the
+//! seed and the operations that led to it and reproduces exactly. Set
+//! `UBI_FUZZ_CASES` to run more cases than the default.
+
+use crate::{mock::*, Balances, Pallet, ReputationStore, ScoreHistogram, TotalSupply, MAX_BATCHES};
+use arbitrary::{Arbitrary, Unstructured};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+/// Cases run by default
+const DEFAULT_CASES: u64 = 24;
+
+/// Accounts the operations pick from
+const ACCOUNTS: u64 = 6;
+
+/// Random input bytes of one case
+const INPUT_LEN: usize = 256;
+
+/// One step of a case
+#[derive(Debug)]
+enum Op {
+    Claim { who: u64 },
+    Burn { from: u64, to: u64, amount: u128 },
+    Jump { blocks: u64 },
+}
+
+impl<'a> Arbitrary<'a> for Op {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let account = |u: &mut Unstructured<'a>| u.int_in_range(1..=ACCOUNTS);
+        Ok(match u.int_in_range(0..=2)? {
+            0 => Op::Claim { who: account(u)? },
+            1 => Op::Burn { from: account(u)?, to: account(u)?, amount: u.int_in_range(1..=250)? },
+            // Mostly short jumps, sometimes past a whole expiry
+            _ => Op::Jump { blocks: *u.choose(&[1, 7, 50, 100, 250])? },
+        })
+    }
+}
+
+impl Op {
+    /// Apply the operation; failing calls are part of the sequence and roll back
+    fn apply(&self) {
+        match *self {
+            Op::Claim { who } => {
+                let _ = UbiToken::claim(RuntimeOrigin::none(), ASSET, who, 0);
+            }
+            Op::Burn { from, to, amount } => {
+                let nonce = UbiToken::burn_nonce(ASSET, from);
+                let _ = UbiToken::burn(RuntimeOrigin::none(), ASSET, from, to, amount, None, None, nonce, None, None);
+            }
+            Op::Jump { blocks } => run_to_block(System::block_number() + blocks),
+        }
+    }
+}
+
+/// Check the global invariants, describing the first violated one
+fn check_invariants() -> Result<(), String> {
+    // Supply accounting: the total supply is the sum of all stored batches
+    let mut stored = 0u128;
+    for (_, who, batches) in Balances::<Test>::iter().filter(|(asset_id, ..)| *asset_id == ASSET) {
+        if batches.len() > MAX_BATCHES as usize {
+            return Err(format!("{who} holds {} batches", batches.len()));
+        }
+        if batches.iter().any(|batch| batch.amount == 0) {
+            return Err(format!("{who} holds an empty batch"));
+        }
+        let held = batches.iter().try_fold(0u128, |total, batch| total.checked_add(batch.amount));
+        let held = held.ok_or_else(|| format!("balance of {who} overflows"))?;
+        if UbiToken::spendable_balance(&ASSET, &who) > held {
+            return Err(format!("{who} can spend more than they hold"));
+        }
+        stored = stored.checked_add(held).ok_or("stored balances overflow")?;
+    }
+    if TotalSupply::<Test>::get(ASSET) != stored {
+        return Err(format!("total supply {} but {stored} stored", TotalSupply::<Test>::get(ASSET)));
+    }
+
+    // Score formula: stored scores match their components, and the histogram counts them
+    let mut histogram = [0u32; crate::SCORE_HISTOGRAM_BUCKETS as usize];
+    for (who, rep) in ReputationStore::<Test>::iter_prefix(ASSET) {
+        if rep.score != Pallet::<Test>::recalculate_score(&rep) {
+            return Err(format!("score of {who} does not match its components: {rep:?}"));
+        }
+        histogram[Pallet::<Test>::score_bucket(rep.score)] += 1;
+    }
+    if ScoreHistogram::<Test>::get(ASSET) != histogram {
+        return Err(format!("histogram {:?} but scores give {histogram:?}", ScoreHistogram::<Test>::get(ASSET)));
+    }
+    Ok(())
+}
+
+/// Run the case generated from `seed`
+fn run_case(seed: u64) {
+    let mut input = [0u8; INPUT_LEN];
+    StdRng::seed_from_u64(seed).fill_bytes(&mut input);
+    let mut u = Unstructured::new(&input);
+
+    new_test_ext().execute_with(|| {
+        let mut applied = Vec::new();
+        // Exhausted input would only yield the same default operation forever
+        while !u.is_empty() {
+            let Ok(op) = Op::arbitrary(&mut u) else { break };
+            op.apply();
+            applied.push(op);
+            if let Err(violation) = check_invariants() {
+                panic!("seed {seed}: {violation}\nafter {applied:#?}");
+            }
+        }
+    });
+}
+
+#[test]
+fn random_claims_burns_and_jumps_keep_invariants() {
+    let cases = std::env::var("UBI_FUZZ_CASES").ok().and_then(|cases| cases.parse().ok()).unwrap_or(DEFAULT_CASES);
+    (0..cases).for_each(run_case);
+}
@@ -46,38 +46,498 @@
 //!
 //! 3. **Claim Streak**: Rewards consistent daily claiming (10 points/day, max 500)
 //!    - 2-day grace period before streak resets
+//!    - `freeze_streak` burns `T::StreakFreezeCost` tokens to cover
+//!      `T::StreakFreezePeriods` more missed periods, e.g. for a vacation
+//!    - `set_vacation` pauses the streak for up to `T::MaxVacationPeriods`,
+//!      at most once per `T::VacationCooldownPeriods`; nobody can claim for
+//!      the account meanwhile, so relayers do not mint tokens that expire unused
 //!    - Encourages regular participation
 //!
-//! 4. **Reputation Decay**: 5% decay per claim period
-//!    - Inactive users' reputation slowly decreases
+//! 4. **Reputation Decay**: 5% decay per elapsed claim period
+//!    - Sent and weighted received volume decay whether or not the account claims
+//!    - Applied lazily on every reputation read and write
 //!    - Must stay active to maintain high reputation
+//!
+//! ### Percentiles
+//!
+//! `ScoreHistogram` counts the reputation records of an asset in
+//! `SCORE_HISTOGRAM_BUCKETS` power-of-two score buckets and is updated on
+//! every reputation write. `reputation_percentile` reads it to tell an
+//! account the share of accounts in lower buckets without iterating
+//! `ReputationStore`. It works on stored scores, so decay still pending on
+//! idle accounts is not reflected until their next update.
+//!
+//! ### Reputation Oracle
+//!
+//! Other pallets, e.g. a bridge, read reputation through the
+//! `ReputationOracle` trait instead of the pallet's storage, so sister chains
+//! and L2s can honor NST reputation without reimplementing the scoring and
+//! keep working when it changes. An account publishes its current score with
+//! `export_reputation`, at most once per claim period; the `ReputationSnapshot`
+//! joins an outbound queue of up to `T::MaxOutboundSnapshots` entries, which
+//! the bridge drains with `ReputationOracle::take_snapshots`. Sequence numbers
+//! increase without gaps, so the receiving side can detect missed snapshots.
+//!
+//! ## Burn Categories
+//!
+//! A burn may be tagged with a `BurnCategory` (food, housing, services,
+//! donation, other). The tag is part of the `Burned` event and tagged burns are
+//! counted per asset and claim period in `BurnCategoryStats`, showing what the
+//! UBI is actually spent on.
+//!
+//! ## Burn Receipts
+//!
+//! Every burn gets a sequential `BurnId` and a `BurnReceipt` in storage. A burn
+//! may carry an optional 32-byte `reference`, the hash of an off-chain document
+//! such as a receipt, contract or invoice PDF, so either party can later prove
+//! which document a payment was for.
+//!
+//! The recipient can `reject_burn` within `T::RejectionWindow` blocks, which
+//! reverses the reputation effects on both sides (the tokens stay destroyed).
+//! Nobody can thus force an unwanted association onto someone's record.
+//!
+//! When a sale falls through after the tokens were burned (goods out of
+//! stock, a cancelled appointment), the recipient can acknowledge it with
+//! `issue_refund` at any time. It reverses the same reputation effects, so
+//! the sender gets back the standing the burn cost and the recipient does
+//! not keep credit for a sale that never happened; the tokens stay destroyed.
+//! Refunded burns are marked in their receipt and cannot be rejected later.
+//!
+//! Each recipient keeps a short list of its most recent supporters in
+//! `RecentSupporters` (up to `MAX_RECENT_SUPPORTERS`), with the number and
+//! volume of the burns each of them sent while on the list. A merchant can
+//! thus read who supports them from storage instead of replaying events.
+//! Rejected burns are taken out of it again.
+//!
+//! Next to it, `TopSupporters` ranks up to `MAX_TOP_SUPPORTERS` senders by
+//! the volume they burned to the recipient, largest first. A sender not on a
+//! full list enters it by burning more in one go than the smallest entry,
+//! which is evicted; volumes count the burns sent while on the list. Creators
+//! and merchants read it with the `top_supporters` runtime API.
+//!
+//! If something went wrong with the deal, either party can `flag_burn` with
+//! the hash of an off-chain explanation. The dispute is stored next to the
+//! receipt in `BurnDisputes` as a minimal recourse trail for governance.
+//!
+//! ## Boosted Burns
+//!
+//! An established sender can vouch strongly for a newcomer with
+//! `boosted_burn`, staking part of its own reputation score on the burn. The
+//! stake is held out of the sender's score in `boost_stake` and added to the
+//! recipient's score in `boost_received`, point for point and on top of the
+//! usual weighted amount. Only reputation of the sender's own can be staked,
+//! not boosts it received itself. After `T::BoostLockBlocks` anyone can
+//! `release_boost`: the recipient's boost ends and the sender gets the stake
+//! back, so a boost lends reputation for the lock period but never creates
+//! any. If the burn was
+//! flagged in the meantime, `T::ReviewOrigin` can `slash_boost` instead: the
+//! recipient loses the boost and the sender the stake, which then decays like
+//! the other reputation costs.
+//!
+//! ## Vouching
+//!
+//! `vouch` makes trust explicit on top of burn-derived reputation. The
+//! voucher pays `VOUCH_COST` reputation points, decayed like the other
+//! costs, and the target gains `VOUCH_BONUS` points of `vouch_credit`, up to
+//! `MAX_VOUCH_CREDIT` in total. An account vouches for another at most once
+//! and for at most `T::MaxVouchesPerPeriod` accounts per claim period. The
+//! resulting web of trust is kept in `Vouches`.
+//!
+//! ## Ring Detection
+//!
+//! Validators run an offchain worker that looks at the last
+//! `RING_SCAN_BURNS` burns for reciprocal pairs (A burns to B, B burns back
+//! to A) and tight cycles of three accounts. Each ring found is submitted as
+//! an unsigned `report_suspicion` naming its burns. The chain checks that the
+//! burns really form the ring before queueing it in `SuspicionReports` (up to
+//! `MAX_SUSPICION_REPORTS`, one report per ring), so reports cannot be forged.
+//! Reports change nothing by themselves; `T::ReviewOrigin` reviews them and
+//! removes them with `dismiss_suspicion`.
+//!
+//! ## Operator Approvals
+//!
+//! An owner can `approve` an operator (a point-of-sale app, a subscription
+//! manager) to burn a limited budget on their behalf until a given block.
+//! The operator spends it with `burn_from`, which behaves like `burn` with the
+//! owner as sender; the owner can `revoke_approval` at any time.
+//!
+//! ## Service Registry
+//!
+//! Providers can `register_service` a named service with a fixed price (e.g.
+//! a haircut for 30 tokens). Customers then `pay_service`, which burns exactly
+//! the listed price to the provider and emits a structured `ServicePaid` event
+//! that merchant integrations can match without parsing amounts.
+//!
+//! ## Invoices
+//!
+//! A payee can `create_invoice` for a total that is due before a given block.
+//! Payers settle it with `pay_invoice`, which burns to the payee like `burn`
+//! and may cover only part of the total; every payment that leaves something
+//! open emits `InvoicePartiallyPaid` with the amount paid so far. The invoice
+//! closes with `InvoicePaid` once the total is met, or with `InvoiceExpired`
+//! in `on_initialize` of its expiry block. Payments already made stay with the
+//! payee when an invoice expires.
+//!
+//! ## Payment Intents
+//!
+//! A merchant can `create_payment_intent` for an exact amount, with the hash of
+//! off-chain metadata such as the order, valid until a given block. The
+//! customer pays it with an ordinary burn to the merchant whose `reference` is
+//! the intent id from `PaymentIntentCreated`. A burn referencing an intent
+//! must pay exactly its amount to its merchant, and marks it fulfilled with
+//! `PaymentIntentFulfilled`; an intent is paid at most once. Intents are
+//! removed at their expiry block, after which their id is an ordinary reference.
+//!
+//! ## Tickets
+//!
+//! `burn_ticket` is a burn that records the sender's commitment, the
+//! `blake2_256` hash of a secret, e.g. printed on a ticket or voucher. The
+//! recipient later `redeem`s the burn with the secret, marking the ticket
+//! consumed, so the proof of payment can be presented exactly once. Rejected
+//! and refunded burns cannot be redeemed.
+//!
+//! ## Attestations
+//!
+//! Accounts with a reputation score of at least `T::MinAttesterReputation`
+//! can `attest` a role tag such as `baker` or `plumber` to another account,
+//! and `revoke_attestation` it later. Tags are lowercase ASCII letters,
+//! digits and dashes of up to `T::MaxRoleLen` bytes, so the same role is
+//! always spelled the same way. `AttestationCounts` is keyed by role first:
+//! wallets find every attested baker and how many accounts vouch for each of
+//! them with the `attested_accounts` runtime API.
+//!
+//! ## Organizations
+//!
+//! Co-ops and teams can take burns as one payee. An account registers itself
+//! as an organization with `set_organization`, listing its members and their
+//! shares. Burns to it still count as received by the organization, but the
+//! recognition they carry (`weighted_received`) is split across the members
+//! by share instead. The split of every burn is kept in `OrganizationSplits`
+//! so a rejection takes each member's part back even if the members changed
+//! in between. Organizations cannot be the recipient of a `boosted_burn`.
+//!
+//! ## Households
+//!
+//! A family sharing one device can let one designated claimer claim for
+//! everyone. Each member consents on-chain by calling `join_household` with
+//! the claimer, up to `T::MaxHouseholdMembers` members, and can
+//! `leave_household` at any time. The claimer's `claim_household` then
+//! claims for itself and every member in one signed call, skipping those
+//! that cannot claim. An account belongs to at most one household, as
+//! claimer or member.
+//!
+//! ## Verified Charities
+//!
+//! `T::CharityOrigin` keeps a registry of verified charities per asset with
+//! `verify_charity` and `revoke_charity`. A burn to a verified charity adds
+//! `T::CharityBonus` of the amount on top of the sender's sent volume, so
+//! public-benefit spending builds reputation faster. The bonus is recorded in
+//! the burn receipt and taken back if the charity rejects the burn.
+//!
+//! ## Short-Expiry Claims
+//!
+//! `claim_short_expiry` is an optional claim mode: the tokens expire after
+//! `T::ShortExpiryRatio` of the asset's expiration (e.g. 3 instead of 7 days)
+//! and `T::ShortExpiryBonus` extra tokens are granted, encouraging velocity.
+//!
+//! ## Commitments
+//!
+//! To save for a larger purchase, a holder can `commit_tokens` for up to
+//! `T::MaxCommitPeriods` claim periods. The tokens are locked (unburnable)
+//! for that time and their expiration moves back by the same amount, at a
+//! small reputation cost per period. Unlocked commitments flow back into the
+//! regular batches during the next cleanup.
+//!
+//! ## Claim Anchoring
+//!
+//! Each account claims in its own rolling window: its first claim fixes a
+//! claim offset (the first claim block modulo `claim_period_blocks`), and
+//! its claim period `n` spans the blocks from `offset + n × claim_period_blocks`
+//! on. Claims are thus spread over the whole period instead of all accounts
+//! claiming at one global boundary. Accounts that claimed before offsets
+//! were introduced keep the global boundary (offset zero).
+//!
+//! Backlog is counted in the account's period indices from its claim anchor
+//! (the period of its last claim), never in blocks since the last claim.
+//! Since anyone may submit a claim, this keeps a third party from hurting the
+//! owner by claiming at the end of a window and delaying the next claim.
+//! Everything else (streaks, statistics, burn limits) uses the global periods.
+//!
+//! ## Extra Backlog
+//!
+//! Claims pay at most the asset's `max_backlog_periods` missed periods. An
+//! account that was away longer can claim up to `T::MaxExtraBacklogPeriods`
+//! more with `claim_extra_backlog`, paying `backlog_cost_per_period`
+//! reputation points per extra period. Its score must cover the cost, so
+//! catching up after a week offline is possible while farming backlog with
+//! fresh accounts is not.
+//!
+//! ## Account Preferences
+//!
+//! Per-account toggles live in one `AccountPreferences` entry per asset, set
+//! with `set_preferences` and stored only while they differ from the
+//! defaults. `allow_third_party_claims` keeps relayer batches from claiming
+//! for the account and `min_incoming_burn` rejects smaller burns to it. The
+//! relayer opt-in and the webhook are read by off-chain services. New
+//! settings are added to this struct rather than as separate storage items.
+//!
+//! ## Profiles
+//!
+//! An account can publish a display name and a hash of off-chain metadata
+//! per asset with `set_profile`, so wallets show burn recipients by name.
+//! Setting one requires a reputation score of `T::MinProfileReputation`.
+//!
+//! ## Claim Proof-of-Work
+//!
+//! A deployment can require a light proof-of-work on unsigned claims with
+//! `T::ClaimPowDifficulty`: `claim` and `claim_short_expiry` then carry a
+//! `nonce` such that `blake2_256((asset_id, account, period, nonce))` starts
+//! with that many zero bits, `period` being the claim period of the block the
+//! claim is included in. Finding a nonce takes a wallet a fraction of a second
+//! once a day, but makes mass sybil claims cost real CPU time. Unsigned
+//! `claim_for_many` cannot carry proofs and is refused while it is required.
+//! A difficulty of 0 disables the check; the nonce is then ignored.
+//!
+//! ## Pre-submitted Claims
+//!
+//! Within `T::ClaimLookaheadBlocks` blocks before a period boundary, an
+//! account that can claim once the next period starts may already submit
+//! that claim, with its proof-of-work computed for the next period. The
+//! transaction pool accepts it as a future transaction requiring a tag that
+//! no transaction provides; once the boundary block is reached the pool
+//! validates it again, the tag is gone and the claim is included right at
+//! rollover. Claim bots thus pre-submit once instead of retrying every block.
+//!
+//! ## Unsigned Burn Limit
+//!
+//! Unsigned burns (`burn`, `pay_service`) pay no fees, so each account may
+//! send at most `T::MaxUnsignedBurnsPerPeriod` of them per claim period of the
+//! asset. Further ones are rejected by `validate_unsigned` before they reach
+//! the pool, keeping a compromised or spammy account from saturating the free
+//! lane. Signed `burn_from` is not limited.
+//!
+//! ## Burn Nonces
+//!
+//! Each unsigned burn (`burn`, `burn_ticket`, `pay_service`, `pay_invoice`)
+//! carries the sender's next burn nonce for the asset (`burn_nonce`, starting
+//! at 0). The pool tags a burn with `(from, nonce)` and makes it require the
+//! tag of the previous nonce, so a sender's pending burns are applied in nonce
+//! order and a resubmitted burn replaces its duplicate instead of being
+//! included twice. Burns with a nonce already used are stale and dropped.
+//!
+//! ## Burn Authorizations
+//!
+//! `burn_authorized` is an unsigned burn carrying the sender's signature of a
+//! `BurnAuthorization` payload, so relayers and merchants can submit burns a
+//! wallet signed off-chain. The payload opens with a fixed context string and
+//! the pallet name and includes the genesis hash, the spec version and the
+//! burn nonce: a signature is only valid for one burn, on one chain, under
+//! one runtime version, and cannot be mistaken for a transaction or a message
+//! of another application. Wallets get the authorization to sign from the
+//! `burn_authorization` runtime API. Burns with a bad signature are rejected
+//! from the pool (`BadProof`).
+//!
+//! ## Bonus Periods
+//!
+//! `T::BonusOrigin` (root or a committee) can declare special windows for an
+//! asset, e.g. holidays, in which claims are multiplied by a percentage and/or
+//! the claim streak grows twice as fast. Community events thus need no
+//! runtime upgrade.
+//!
+//! ## Community Pool
+//!
+//! Expired tokens are not simply lost: every expired amount is added to the
+//! asset's `CommunityPool`, a counter of tokens the community let lapse.
+//! `T::BonusOrigin` spends it on campaigns with `allocate_community_pool`,
+//! paying matching bonuses as extra claims (fresh batches) or as reputation
+//! credited like recognition received. Tokens are destroyed on expiry and
+//! issued again on allocation, so the pool never exceeds what expired.
+//!
+//! ## Emission Schedule
+//!
+//! An asset owner can encode long-term monetary policy with
+//! `set_emission_schedule`: a list of steps, each giving the per-period UBI
+//! amount from a claim period on (e.g. 10% less every 365 periods). Claims
+//! pay the amount of the last step started by the current period, backlog
+//! periods included, and fall back to the asset's `ubi_amount` before the
+//! first step or without a schedule.
+//!
+//! ## Velocity-Responsive Issuance
+//!
+//! The pallet tracks how much of each asset is claimed and burned per claim
+//! period. When a period closes, its velocity (burned per claimed, in
+//! permille) is recorded in `Velocity`. Under a governance-set
+//! `VelocityPolicy` the UBI amount is then scaled one step down when the
+//! velocity stayed below the target, or one step up when it exceeded it,
+//! within the policy's bounds. Issuance thus follows actual spending rather
+//! than letting unspent tokens expire en masse.
+//!
+//! ## Supply Cap
+//!
+//! Deployments that want a hard macro bound give an asset a `SupplyCap` with
+//! `set_supply_cap`. Once the circulating supply passes `throttle_from` of
+//! the ceiling, claims are reduced in proportion to the headroom left below
+//! it and `ClaimThrottled` reports the cut; no claim ever takes the supply
+//! past the ceiling. At the ceiling, claims fail with `SupplyCapReached`
+//! until burns and expiry bring the supply back down.
+//!
+//! ## Cleanup Bounty
+//!
+//! Expired batches are normally purged lazily when their holder claims or
+//! burns again. Anyone may call `sweep_expired` to purge them for a list of
+//! accounts instead, keeping storage and `TotalSupply` accurate; each account
+//! that actually had expired tokens earns the caller a small reputation
+//! credit (`POINTS_PER_SWEPT_ACCOUNT`, capped at `MAX_MAINTENANCE_CREDIT`).
+//!
+//! ## Expiry Sweeps
+//!
+//! Accounts nobody touches would keep their expired batches, and count in
+//! `TotalSupply`, forever. Claims therefore record the account under the
+//! claim period its batch expires in (`ExpiryIndex`). Every block, `on_poll`
+//! works through the index of the periods that have passed, purging expired
+//! batches and dropping the entries until `T::ExpiryPollWeight` is used up.
+//! `ExpiryPollCursor` keeps the first period of each asset not fully swept,
+//! so large expiries are spread smoothly over the following blocks. The
+//! assets take turns: each poll starts at the asset after the last one the
+//! previous poll reached (`ExpiryPollAsset`), so a large expiry in one asset
+//! does not hold up the others.
+//!
+//! As a fallback, once per period the offchain worker of a validator submits
+//! unsigned `sweep_expiry_index` transactions for entries still left, up to
+//! `T::MaxSweepAccounts` accounts each. No reputation credit is paid for
+//! these sweeps.
+//!
+//! Besides the per-account `Expired` events, every block in which batches
+//! expired ends with one `ExpiredBatch` event per asset, carrying the number
+//! of batches and the total amount removed in that block. Indexers tracking
+//! supply only need to follow these summaries.
+//!
+//! ## Dormant Account Pruning
+//!
+//! Abandoned (e.g. sybil) accounts would otherwise keep their reputation and
+//! recipient records forever. An account is dormant in an asset once it has
+//! been inactive for `T::DormantPeriods` claim periods, its score is at most
+//! `T::DormantScoreThreshold` and it holds no spendable tokens. Its
+//! `ReputationStore`, `RecentSupporters` and `UniqueRecipientSets` entries are
+//! then removed, either by anyone through `prune_dormant` or gradually from
+//! `on_idle`.
+//!
+//! ## Account Lifetime
+//!
+//! Fresh wallets have no native balance, so nothing would keep their
+//! `frame_system` account alive. An account's first claim in an asset
+//! therefore adds a provider reference to it, which the pallet holds as long
+//! as the account has a `LastClaim` entry in the asset. Pruning a dormant
+//! account drops its `LastClaim` and the reference with it; the account then
+//! starts over as a new claimant.
+//!
+//! The pallet implements `OnKilledAccount` for the runtime's `frame_system`.
+//! When an account is reaped, its batches and commitments (taken out of the
+//! total supply), `LastClaim`, unsigned burn counter and approvals are removed
+//! in every asset, so a re-created account does not resurrect old tokens.
+//! Visiting every asset is why creating one reserves `T::AssetDeposit` and
+//! there are at most `T::MaxAssets` of them.
+//! Reputation is public history and is left to dormancy pruning, and burn
+//! nonces are kept so old burns cannot be replayed against the new account.
+//!
+//! ## Standard Traits
+//!
+//! For generic tooling and other pallets, the pallet implements the read-only
+//! `fungibles::Inspect` over its assets; a single asset can be exposed as
+//! `fungible::Inspect` with `fungible::ItemOf`. Balances are the unexpired
+//! tokens, the reducible balance is what can be burned now, and deposits are
+//! always refused since tokens only enter through claims. `Mutate`,
+//! `Balanced` and the transfer traits are deliberately not implemented.
+//!
+//! ## Pre-Expiry Warnings
+//!
+//! One claim period before a claimed batch expires, an `ExpiringSoon` event
+//! is emitted for whatever is left of it, so wallets and bots can nudge the
+//! holder to spend the tokens before they are lost. Warnings are scheduled
+//! at claim time in `ExpiryWarnings` and emitted from `on_initialize`.
+//!
+//! ## Tunable Parameters
+//!
+//! The reputation constants and a few issuance knobs (short-expiry ratio and
+//! bonus, commitment cost, sweep credit) live in the `Economics` storage
+//! value instead of being compiled in. `T::ParameterOrigin` changes one of
+//! them with `set_parameter`, passing a typed `UbiParameter` such as
+//! `UbiParameter::DecayFactor(900)`, and `reset_parameter` restores its
+//! default. Both emit an event. The defaults are the constants of this crate
+//! and the runtime's `ShortExpiryRatio`/`ShortExpiryBonus`.
+//!
+//! ## Community Assets
+//!
+//! A single pallet instance can host several independent UBI currencies, each
+//! identified by an `AssetId` (e.g. a neighborhood token next to a co-op token).
+//! Every asset has its own claim amount, period, expiry and backlog, and keeps
+//! its own balances, supply and reputation. Assets are created by
+//! `T::CreateOrigin` and their parameters can be updated by the asset owner.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
 pub use pallet::*;
 
+pub mod migrations;
+
 #[cfg(test)]
 mod mock;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(test)]
+mod fuzz;
+
+use alloc::vec::Vec;
 use frame_support::pallet_prelude::*;
+use frame_support::weights::WeightMeter;
+use frame_support::traits::{Currency, ReservableCurrency};
+use frame_support::traits::tokens::{
+    fungibles, DepositConsequence, Fortitude, Preservation, Provenance, WithdrawConsequence,
+};
 use frame_system::pallet_prelude::*;
-use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use parity_scale_codec::{Compact, Decode, DecodeWithMemTracking, Encode, HasCompact, MaxEncodedLen};
 use scale_info::TypeInfo;
-use sp_runtime::traits::{Saturating, Zero};
+use sp_core::H256;
+use sp_runtime::traits::{
+    AtLeast32BitUnsigned, CheckedSub, IdentifyAccount, One, Saturating, UniqueSaturatedInto, Verify, Zero,
+};
+use sp_runtime::Perbill;
 use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction};
 
 /// A batch of tokens with an expiration block
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
-pub struct TokenBatch<BlockNumber> {
+///
+/// Both fields are SCALE encoded compactly. Amounts never exceed a few
+/// periods' worth of UBI, far below the range of `Balance`, so every holder's
+/// `Balances` entry stays small in state and proofs.
+///
+/// Always serializable since batches can be given in the genesis config.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TokenBatch<Balance, BlockNumber> {
     /// Amount of tokens in this batch
-    pub amount: u128,
+    #[codec(compact)]
+    pub amount: Balance,
     /// Block number when these tokens expire
+    #[codec(compact)]
     pub expires_at: BlockNumber,
 }
 
+// Derived `MaxEncodedLen` cannot bound the compact fields of generic types
+impl<Balance, BlockNumber> MaxEncodedLen for TokenBatch<Balance, BlockNumber>
+where
+    Balance: HasCompact + MaxEncodedLen,
+    BlockNumber: HasCompact + MaxEncodedLen,
+{
+    fn max_encoded_len() -> usize {
+        // A compact encoding is at most one byte longer than the plain one
+        Balance::max_encoded_len().saturating_add(BlockNumber::max_encoded_len()).saturating_add(2)
+    }
+}
+
 /// Reputation data for an account
 /// 
 /// Reputation score is calculated as:
@@ -85,466 +545,6513 @@ pub struct TokenBatch<BlockNumber> {
 /// - burns_sent_volume × 1 (giving to others)
 /// - weighted_received × 2 (recognition from others, weighted by sender reputation)
 /// - claim_streak × 10 (consistency bonus, capped at 500)
+/// - maintenance_credit (earned by sweeping expired batches, capped at 100)
+/// - minus commitment_cost (paid for extending expiry with `commit_tokens`)
+/// - minus backlog_cost (paid for claiming extra backlog with `claim_extra_backlog`)
+/// - vouch_credit (vouched for by others with `vouch`, capped at 250)
+/// - minus boost_stake and slashed_stake (staked on and lost with `boosted_burn`)
+/// - boost_received (staked on this account by others with `boosted_burn`)
+/// - minus vouch_cost (paid for vouching for others)
+/// - bonus_credit (welcome reputation granted with the first claim)
 /// 
-/// On each claim, reputation decays by 5% to encourage continued activity.
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
-pub struct Reputation<BlockNumber> {
+/// The sent and weighted received volumes decay by 5% per elapsed claim period
+/// (applied lazily) to encourage continued activity.
+///
+/// All fields are SCALE encoded compactly: most of them are small or zero for
+/// most accounts, and this is the largest map of the chain.
+///
+/// Always serializable since reputations can be given in the genesis config.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Reputation<Balance, BlockNumber> {
     /// Number of burn transactions sent
+    #[codec(compact)]
     pub burns_sent_count: u64,
-    /// Total volume of tokens burned (sent)
-    pub burns_sent_volume: u128,
+    /// Volume of tokens burned (sent) plus charity bonuses, decayed per elapsed claim period
+    #[codec(compact)]
+    pub burns_sent_volume: Balance,
     /// Number of burn transactions received
+    #[codec(compact)]
     pub burns_received_count: u64,
     /// Total volume of tokens burned to this account (raw, unweighted)
-    pub burns_received_volume: u128,
+    #[codec(compact)]
+    pub burns_received_volume: Balance,
     /// Block number of first activity (claim or burn)
+    #[codec(compact)]
     pub first_activity: BlockNumber,
     
     // === New fields for enhanced reputation ===
     
-    /// Weighted burns received (weighted by sender's reputation at time of burn)
-    pub weighted_received: u128,
+    /// Weighted burns received (weighted by sender's reputation at time of burn),
+    /// decayed per elapsed claim period
+    #[codec(compact)]
+    pub weighted_received: Balance,
     /// Number of unique recipients this account has burned to
+    #[codec(compact)]
     pub unique_recipients_count: u32,
     /// Current claim streak (consecutive periods claimed)
+    #[codec(compact)]
     pub claim_streak: u32,
     /// Last claim period number (for streak tracking)
+    #[codec(compact)]
     pub last_claim_period: u64,
     /// Cached reputation score (updated on claim/burn)
-    pub score: u128,
+    #[codec(compact)]
+    pub score: Balance,
+    /// Credit earned by sweeping other accounts' expired batches
+    #[codec(compact)]
+    pub maintenance_credit: Balance,
+    /// Block number of the latest activity (claim, burn sent or received, sweep)
+    #[codec(compact)]
+    pub last_activity: BlockNumber,
+    /// Claim period up to which decay has been applied
+    #[codec(compact)]
+    pub last_decay_period: u64,
+    /// Reputation paid for committing tokens, subtracted from the score and
+    /// decayed per elapsed claim period
+    #[codec(compact)]
+    pub commitment_cost: Balance,
+    /// Reputation paid for claiming beyond the backlog, subtracted from the
+    /// score and decayed per elapsed claim period
+    #[codec(compact)]
+    pub backlog_cost: Balance,
+    /// Missed claim periods covered by bought streak freezes, used up as
+    /// periods beyond the grace period are missed
+    #[codec(compact)]
+    pub streak_freeze: u32,
+    /// First claim period of the account's latest vacation
+    #[codec(compact)]
+    pub vacation_start: u64,
+    /// First claim period after the account's latest vacation
+    #[codec(compact)]
+    pub vacation_end: u64,
+    /// Reputation staked on boosted burns that were not released yet,
+    /// subtracted from the score
+    #[codec(compact)]
+    pub boost_stake: Balance,
+    /// Reputation lost with slashed boosted burns, subtracted from the score
+    /// and decayed per elapsed claim period
+    #[codec(compact)]
+    pub slashed_stake: Balance,
+    /// Credit granted by accounts vouching for this one
+    #[codec(compact)]
+    pub vouch_credit: Balance,
+    /// Reputation paid for vouching for others, subtracted from the score and
+    /// decayed per elapsed claim period
+    #[codec(compact)]
+    pub vouch_cost: Balance,
+    /// Reputation granted as a bonus, such as the welcome reputation of the
+    /// first claim, decayed per elapsed claim period
+    #[codec(compact)]
+    #[serde(default)]
+    pub bonus_credit: Balance,
+    /// Reputation staked on boosted burns to this account that were not
+    /// released yet, added to the score as is
+    #[codec(compact)]
+    #[serde(default)]
+    pub boost_received: Balance,
 }
 
-#[frame_support::pallet]
-pub mod pallet {
-    use super::*;
+// Derived `MaxEncodedLen` cannot bound the compact fields of generic types
+impl<Balance, BlockNumber> MaxEncodedLen for Reputation<Balance, BlockNumber>
+where
+    Balance: HasCompact + MaxEncodedLen,
+    BlockNumber: HasCompact + MaxEncodedLen,
+{
+    fn max_encoded_len() -> usize {
+        // A compact encoding is at most one byte longer than the plain one
+        Compact::<u64>::max_encoded_len()
+            .saturating_mul(6)
+            .saturating_add(Compact::<u32>::max_encoded_len().saturating_mul(3))
+            .saturating_add(Balance::max_encoded_len().saturating_add(1).saturating_mul(13))
+            .saturating_add(BlockNumber::max_encoded_len().saturating_add(1).saturating_mul(2))
+    }
+}
 
-    /// Maximum number of token batches per account
-    pub const MAX_BATCHES: u32 = 10;
-    
-    /// Maximum unique recipients to track per account
-    pub const MAX_UNIQUE_RECIPIENTS: u32 = 1000;
-    
-    // Reputation calculation constants (using fixed-point math with 1000 = 1.0)
-    /// Minimum sender weight (0.5 = 500/1000)
-    pub const MIN_SENDER_WEIGHT: u128 = 500;
-    /// Maximum sender weight (2.0 = 2000/1000)
-    pub const MAX_SENDER_WEIGHT: u128 = 2000;
-    /// Decay factor per claim (95% = 950/1000, i.e., 5% decay)
-    pub const DECAY_FACTOR: u128 = 950;
-    /// Reputation points per unique recipient
-    pub const POINTS_PER_UNIQUE_RECIPIENT: u128 = 50;
-    /// Reputation points per streak day (capped at 50 days = 500 points)
-    pub const POINTS_PER_STREAK_DAY: u128 = 10;
+/// Tokens locked by their holder in exchange for a later expiration
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Commitment<Balance, BlockNumber> {
+    /// Amount of locked tokens
+    pub amount: Balance,
+    /// Block from which the tokens can be burned again
+    pub unlocks_at: BlockNumber,
+    /// Extended expiration of the tokens
+    pub expires_at: BlockNumber,
+}
+
+/// Economic parameters of a community asset
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssetParameters<Balance, BlockNumber> {
+    /// Amount of tokens distributed per claim period
+    pub ubi_amount: Balance,
+    /// Number of blocks in one claim period
+    pub claim_period_blocks: BlockNumber,
+    /// Number of blocks until claimed tokens expire
+    pub expiration_blocks: BlockNumber,
+    /// Maximum number of claim periods that can be claimed as backlog
+    pub max_backlog_periods: u32,
+}
+
+/// Constants of the reputation score calculation
+///
+/// Weights and factors are fixed-point values with 1000 = 1.0.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReputationParameters {
+    /// Minimum weight of a burn sender
+    pub min_sender_weight: u32,
+    /// Maximum weight of a burn sender
+    pub max_sender_weight: u32,
+    /// Decay factor applied per elapsed claim period
+    pub decay_factor: u32,
+    /// Points per unique recipient
+    pub points_per_unique_recipient: u32,
+    /// Points per claim streak period
+    pub points_per_streak_day: u32,
     /// Maximum streak bonus
-    pub const MAX_STREAK_BONUS: u128 = 500;
-    /// Multiplier for weighted received in score (2x)
-    pub const WEIGHTED_RECEIVED_MULTIPLIER: u128 = 2;
-    /// Grace period for streak (can miss up to 2 periods)
-    pub const STREAK_GRACE_PERIODS: u64 = 2;
+    pub max_streak_bonus: u32,
+    /// Multiplier of the weighted received volume
+    pub weighted_received_multiplier: u32,
+    /// Claim periods that can be missed before the streak resets
+    pub streak_grace_periods: u64,
+}
 
-    #[pallet::pallet]
-    pub struct Pallet<T>(_);
+impl Default for ReputationParameters {
+    fn default() -> Self {
+        Self {
+            min_sender_weight: MIN_SENDER_WEIGHT,
+            max_sender_weight: MAX_SENDER_WEIGHT,
+            decay_factor: DECAY_FACTOR,
+            points_per_unique_recipient: POINTS_PER_UNIQUE_RECIPIENT,
+            points_per_streak_day: POINTS_PER_STREAK_DAY,
+            max_streak_bonus: MAX_STREAK_BONUS,
+            weighted_received_multiplier: WEIGHTED_RECEIVED_MULTIPLIER,
+            streak_grace_periods: STREAK_GRACE_PERIODS,
+        }
+    }
+}
 
-    /// Configuration trait for the UBI token pallet
-    #[pallet::config]
-    pub trait Config: frame_system::Config {
-        /// The overarching event type
-        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+/// Reputation of an account at a block, queued for other chains
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReputationSnapshot<AssetId, AccountId, Balance, BlockNumber> {
+    /// Position in the outbound queue, increasing without gaps
+    pub sequence: u64,
+    /// Asset the reputation was earned in
+    pub asset_id: AssetId,
+    /// Account the reputation belongs to
+    pub who: AccountId,
+    /// Reputation score with the decay up to `block` applied
+    pub score: Balance,
+    /// Block in which the snapshot was taken
+    pub block: BlockNumber,
+}
 
-        /// Amount of tokens distributed per claim period (daily UBI)
-        #[pallet::constant]
-        type UbiAmount: Get<u128>;
+/// Stable interface to NST reputation for bridges and other consumers
+///
+/// Consumers depend on this trait only, so the scoring logic can change
+/// without breaking them.
+pub trait ReputationOracle<AccountId> {
+    /// Identifier of a community asset
+    type AssetId;
+    /// Reputation score
+    type Score;
+    /// Block number of the snapshots
+    type BlockNumber;
 
-        /// Number of blocks in one claim period (e.g., 1 day worth of blocks)
-        #[pallet::constant]
-        type ClaimPeriodBlocks: Get<BlockNumberFor<Self>>;
+    /// Current reputation score of `who`, with pending decay applied
+    fn score(asset_id: &Self::AssetId, who: &AccountId) -> Self::Score;
 
-        /// Number of blocks until tokens expire (e.g., 7 days worth of blocks)
-        #[pallet::constant]
-        type ExpirationBlocks: Get<BlockNumberFor<Self>>;
+    /// Number of snapshots waiting in the outbound queue
+    fn queued_snapshots() -> u32;
 
-        /// Maximum number of claim periods that can be claimed as backlog
-        #[pallet::constant]
-        type MaxBacklogPeriods: Get<u32>;
-    }
+    /// Remove up to `max` snapshots from the outbound queue, oldest first
+    fn take_snapshots(max: u32) -> Vec<OracleSnapshotOf<Self, AccountId>>;
+}
 
-    /// Token balances stored as batches with expiration
-    #[pallet::storage]
-    #[pallet::getter(fn balances)]
-    pub type Balances<T: Config> = StorageMap<
-        _,
-        Blake2_128Concat,
-        T::AccountId,
-        BoundedVec<TokenBatch<BlockNumberFor<T>>, ConstU32<MAX_BATCHES>>,
-        ValueQuery,
-    >;
+/// Snapshot type of a `ReputationOracle`
+pub type OracleSnapshotOf<O, AccountId> = ReputationSnapshot<
+    <O as ReputationOracle<AccountId>>::AssetId,
+    AccountId,
+    <O as ReputationOracle<AccountId>>::Score,
+    <O as ReputationOracle<AccountId>>::BlockNumber,
+>;
 
-    /// Block number of last claim for each account
-    #[pallet::storage]
-    #[pallet::getter(fn last_claim)]
-    pub type LastClaim<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+/// Governance-tunable economics of a pallet instance
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct EconomicParameters {
+    /// Constants of the reputation score calculation
+    pub reputation: ReputationParameters,
+    /// Expiration of short-expiry claims, as a fraction of the asset's expiration
+    pub short_expiry_ratio: Perbill,
+    /// Extra tokens granted on short-expiry claims, as a fraction of the claim
+    pub short_expiry_bonus: Perbill,
+    /// Reputation points a commitment costs per claim period
+    pub commitment_cost_per_period: u32,
+    /// Reputation points credited per account swept with `sweep_expired`
+    pub points_per_swept_account: u32,
+    /// Reputation points each period claimed beyond the backlog costs
+    pub backlog_cost_per_period: u32,
+}
 
-    /// Reputation data for each account
-    #[pallet::storage]
-    #[pallet::getter(fn reputation)]
-    pub type ReputationStore<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::AccountId, Reputation<BlockNumberFor<T>>, ValueQuery>;
+/// Key of a tunable parameter in `EconomicParameters`
+#[derive(Clone, Copy, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum UbiParameterKey {
+    MinSenderWeight,
+    MaxSenderWeight,
+    DecayFactor,
+    PointsPerUniqueRecipient,
+    PointsPerStreakDay,
+    MaxStreakBonus,
+    WeightedReceivedMultiplier,
+    StreakGracePeriods,
+    ShortExpiryRatio,
+    ShortExpiryBonus,
+    CommitmentCostPerPeriod,
+    PointsPerSweptAccount,
+    BacklogCostPerPeriod,
+}
 
-    /// Track unique recipients for each sender (for reputation breadth bonus)
-    /// Uses double map: sender -> recipient -> bool (exists)
-    #[pallet::storage]
-    pub type UniqueRecipients<T: Config> = StorageDoubleMap<
-        _,
-        Blake2_128Concat,
-        T::AccountId,  // sender
-        Blake2_128Concat,
-        T::AccountId,  // recipient
-        bool,
-        ValueQuery,
-    >;
+/// A matching bonus paid out of an asset's community pool
+#[derive(Clone, Copy, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum PoolGrant<Balance> {
+    /// An extra claim: a fresh batch of tokens with the asset's expiration
+    Tokens(Balance),
+    /// Reputation, credited as recognition received with a sender weight of 1.0x
+    Reputation(Balance),
+}
 
-    /// Total tokens currently in circulation (not expired)
-    #[pallet::storage]
-    #[pallet::getter(fn total_supply)]
-    pub type TotalSupply<T: Config> = StorageValue<_, u128, ValueQuery>;
+/// A tunable parameter together with its value
+#[derive(Clone, Copy, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum UbiParameter {
+    /// Weight of the lowest-reputation burn senders (1000 = 1.0x)
+    MinSenderWeight(u32),
+    /// Weight of the highest-reputation burn senders (1000 = 1.0x)
+    MaxSenderWeight(u32),
+    /// Reputation kept per elapsed claim period (1000 = no decay)
+    DecayFactor(u32),
+    PointsPerUniqueRecipient(u32),
+    PointsPerStreakDay(u32),
+    MaxStreakBonus(u32),
+    WeightedReceivedMultiplier(u32),
+    StreakGracePeriods(u64),
+    ShortExpiryRatio(Perbill),
+    ShortExpiryBonus(Perbill),
+    CommitmentCostPerPeriod(u32),
+    PointsPerSweptAccount(u32),
+    BacklogCostPerPeriod(u32),
+}
 
-    /// Events emitted by this pallet
-    #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    pub enum Event<T: Config> {
-        /// Tokens were claimed from UBI
-        Claimed {
-            who: T::AccountId,
-            amount: u128,
-            periods: u32,
-            expires_at: BlockNumberFor<T>,
-        },
-        /// Tokens were burned (payment made)
-        Burned {
-            from: T::AccountId,
-            to: T::AccountId,
-            amount: u128,
-        },
-        /// Tokens expired and were removed
-        Expired {
-            who: T::AccountId,
-            amount: u128,
-        },
+impl UbiParameter {
+    /// Key of the parameter
+    pub fn key(&self) -> UbiParameterKey {
+        match self {
+            Self::MinSenderWeight(_) => UbiParameterKey::MinSenderWeight,
+            Self::MaxSenderWeight(_) => UbiParameterKey::MaxSenderWeight,
+            Self::DecayFactor(_) => UbiParameterKey::DecayFactor,
+            Self::PointsPerUniqueRecipient(_) => UbiParameterKey::PointsPerUniqueRecipient,
+            Self::PointsPerStreakDay(_) => UbiParameterKey::PointsPerStreakDay,
+            Self::MaxStreakBonus(_) => UbiParameterKey::MaxStreakBonus,
+            Self::WeightedReceivedMultiplier(_) => UbiParameterKey::WeightedReceivedMultiplier,
+            Self::StreakGracePeriods(_) => UbiParameterKey::StreakGracePeriods,
+            Self::ShortExpiryRatio(_) => UbiParameterKey::ShortExpiryRatio,
+            Self::ShortExpiryBonus(_) => UbiParameterKey::ShortExpiryBonus,
+            Self::CommitmentCostPerPeriod(_) => UbiParameterKey::CommitmentCostPerPeriod,
+            Self::PointsPerSweptAccount(_) => UbiParameterKey::PointsPerSweptAccount,
+            Self::BacklogCostPerPeriod(_) => UbiParameterKey::BacklogCostPerPeriod,
+        }
     }
+}
 
-    /// Errors that can occur in this pallet
-    #[pallet::error]
-    pub enum Error<T> {
-        /// No claimable periods available (must wait for next period)
-        NothingToClaim,
-        /// Insufficient balance for burn operation
-        InsufficientBalance,
-        /// Cannot burn to yourself
-        CannotBurnToSelf,
-        /// Amount must be greater than zero
-        AmountMustBePositive,
-        /// Too many token batches (should not happen with lazy cleanup)
-        TooManyBatches,
-        /// Arithmetic overflow
-        Overflow,
+impl EconomicParameters {
+    /// Current value of a parameter
+    pub fn get(&self, key: UbiParameterKey) -> UbiParameter {
+        let rep = &self.reputation;
+        match key {
+            UbiParameterKey::MinSenderWeight => UbiParameter::MinSenderWeight(rep.min_sender_weight),
+            UbiParameterKey::MaxSenderWeight => UbiParameter::MaxSenderWeight(rep.max_sender_weight),
+            UbiParameterKey::DecayFactor => UbiParameter::DecayFactor(rep.decay_factor),
+            UbiParameterKey::PointsPerUniqueRecipient =>
+                UbiParameter::PointsPerUniqueRecipient(rep.points_per_unique_recipient),
+            UbiParameterKey::PointsPerStreakDay => UbiParameter::PointsPerStreakDay(rep.points_per_streak_day),
+            UbiParameterKey::MaxStreakBonus => UbiParameter::MaxStreakBonus(rep.max_streak_bonus),
+            UbiParameterKey::WeightedReceivedMultiplier =>
+                UbiParameter::WeightedReceivedMultiplier(rep.weighted_received_multiplier),
+            UbiParameterKey::StreakGracePeriods => UbiParameter::StreakGracePeriods(rep.streak_grace_periods),
+            UbiParameterKey::ShortExpiryRatio => UbiParameter::ShortExpiryRatio(self.short_expiry_ratio),
+            UbiParameterKey::ShortExpiryBonus => UbiParameter::ShortExpiryBonus(self.short_expiry_bonus),
+            UbiParameterKey::CommitmentCostPerPeriod =>
+                UbiParameter::CommitmentCostPerPeriod(self.commitment_cost_per_period),
+            UbiParameterKey::PointsPerSweptAccount =>
+                UbiParameter::PointsPerSweptAccount(self.points_per_swept_account),
+            UbiParameterKey::BacklogCostPerPeriod => UbiParameter::BacklogCostPerPeriod(self.backlog_cost_per_period),
+        }
     }
 
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
-        /// Claim your daily UBI tokens (UNSIGNED - no gas fees!)
-        ///
-        /// Each wallet can claim once per period (default: 1 day).
-        /// If you miss days, you can claim up to 3 periods of backlog.
-        /// Claimed tokens expire after 7 days if not used.
-        ///
-        /// This is an UNSIGNED transaction - anyone can submit it without paying fees.
-        /// The `account` parameter specifies who receives the UBI.
-        ///
-        /// # Errors
-        /// - `NothingToClaim` if you've already claimed this period and have no backlog
-        #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
-        pub fn claim(origin: OriginFor<T>, account: T::AccountId) -> DispatchResult {
-            ensure_none(origin)?;
+    /// Replace the value of a parameter
+    pub fn set(&mut self, parameter: UbiParameter) {
+        let rep = &mut self.reputation;
+        match parameter {
+            UbiParameter::MinSenderWeight(v) => rep.min_sender_weight = v,
+            UbiParameter::MaxSenderWeight(v) => rep.max_sender_weight = v,
+            UbiParameter::DecayFactor(v) => rep.decay_factor = v,
+            UbiParameter::PointsPerUniqueRecipient(v) => rep.points_per_unique_recipient = v,
+            UbiParameter::PointsPerStreakDay(v) => rep.points_per_streak_day = v,
+            UbiParameter::MaxStreakBonus(v) => rep.max_streak_bonus = v,
+            UbiParameter::WeightedReceivedMultiplier(v) => rep.weighted_received_multiplier = v,
+            UbiParameter::StreakGracePeriods(v) => rep.streak_grace_periods = v,
+            UbiParameter::ShortExpiryRatio(v) => self.short_expiry_ratio = v,
+            UbiParameter::ShortExpiryBonus(v) => self.short_expiry_bonus = v,
+            UbiParameter::CommitmentCostPerPeriod(v) => self.commitment_cost_per_period = v,
+            UbiParameter::PointsPerSweptAccount(v) => self.points_per_swept_account = v,
+            UbiParameter::BacklogCostPerPeriod(v) => self.backlog_cost_per_period = v,
+        }
+    }
 
-            let who = account;
-            let current_block = frame_system::Pallet::<T>::block_number();
-            let _claim_period = T::ClaimPeriodBlocks::get();
-            let ubi_amount = T::UbiAmount::get();
-            let max_backlog = T::MaxBacklogPeriods::get();
+    /// Whether the parameters are consistent
+    pub fn is_valid(&self) -> bool {
+        let rep = &self.reputation;
+        rep.min_sender_weight <= rep.max_sender_weight && rep.decay_factor <= 1000
+    }
+}
 
-            // Calculate claimable periods
-            let claimable_periods = Self::calculate_claimable_periods(&who, current_block);
-            ensure!(claimable_periods > 0, Error::<T>::NothingToClaim);
+/// Effective parameters of a community asset, as exposed to wallets
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct UbiParameters<Balance, BlockNumber> {
+    /// Economic parameters of the asset
+    pub asset: AssetParameters<Balance, BlockNumber>,
+    /// Constants of the reputation score calculation
+    pub reputation: ReputationParameters,
+    /// Leading zero bits required in the claim proof-of-work, 0 if none is required
+    pub claim_pow_difficulty: u32,
+    /// Blocks before a period boundary in which the next period's claim can be submitted
+    pub claim_lookahead_blocks: BlockNumber,
+    /// Amount paid per claim period now, following the emission schedule and velocity policy
+    pub current_ubi_amount: Balance,
+}
 
-            // Cap at max backlog
-            let periods_to_claim = claimable_periods.min(max_backlog);
-            let amount_to_claim = ubi_amount.saturating_mul(periods_to_claim as u128);
+/// How claimed tokens expire
+#[derive(Clone, Copy, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClaimMode {
+    /// Tokens expire after the asset's full expiration
+    Standard,
+    /// Tokens expire sooner in exchange for a bonus amount
+    ShortExpiry,
+}
 
-            // Clean up expired batches first
-            let expired = Self::cleanup_expired_batches(&who, current_block);
-            if expired > 0 {
-                Self::deposit_event(Event::Expired {
-                    who: who.clone(),
-                    amount: expired,
-                });
-            }
+/// A step of an asset's emission schedule
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct EmissionStep<Balance> {
+    /// Claim period from which the step applies
+    pub start_period: u64,
+    /// Amount of tokens distributed per claim period from then on
+    pub ubi_amount: Balance,
+}
 
-            // Calculate expiration for new batch
-            let expires_at = current_block.saturating_add(T::ExpirationBlocks::get());
+/// Governance bounds of velocity-responsive issuance, all in permille
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct VelocityPolicy {
+    /// Burned per claimed tokens aimed for (600 = 60% of claims are burned)
+    pub target_velocity: u32,
+    /// Change of the UBI amount adjustment after each period
+    pub step: u32,
+    /// Lowest UBI amount adjustment (500 = half the amount)
+    pub min_adjustment: u32,
+    /// Highest UBI amount adjustment (1500 = 1.5x the amount)
+    pub max_adjustment: u32,
+}
 
-            // Create new batch
-            let new_batch = TokenBatch {
-                amount: amount_to_claim,
-                expires_at,
-            };
+impl VelocityPolicy {
+    /// Whether the bounds are ordered, non-zero and within `MAX_VELOCITY_ADJUSTMENT`
+    pub fn is_valid(&self) -> bool {
+        self.step > 0
+            && self.min_adjustment > 0
+            && self.min_adjustment <= self.max_adjustment
+            && self.max_adjustment <= MAX_VELOCITY_ADJUSTMENT
+    }
+}
 
-            // Add to balances
-            Balances::<T>::try_mutate(&who, |batches| -> DispatchResult {
-                // Try to merge with existing batch that has same expiration
-                let merged = batches.iter_mut().any(|b| {
-                    if b.expires_at == expires_at {
-                        b.amount = b.amount.saturating_add(amount_to_claim);
-                        true
-                    } else {
-                        false
-                    }
-                });
+/// Governance ceiling on the circulating supply of an asset
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct SupplyCap<Balance> {
+    /// Supply claims never take the asset past
+    pub ceiling: Balance,
+    /// Share of the ceiling from which claims are reduced
+    pub throttle_from: Perbill,
+}
 
-                if !merged {
-                    batches
-                        .try_push(new_batch)
-                        .map_err(|_| Error::<T>::TooManyBatches)?;
-                }
-                Ok(())
-            })?;
+impl<Balance: AtLeast32BitUnsigned + Copy> SupplyCap<Balance> {
+    /// Part of a claim of `amount` paid out at `supply`
+    ///
+    /// Above `throttle_from` of the ceiling the claim shrinks linearly with the
+    /// headroom left, reaching zero at the ceiling.
+    pub fn throttle(&self, supply: Balance, amount: Balance) -> Balance {
+        let headroom = self.ceiling.saturating_sub(supply);
+        let throttle_from = self.throttle_from * self.ceiling;
+        let granted = if supply <= throttle_from {
+            amount
+        } else {
+            Perbill::from_rational(headroom, self.ceiling.saturating_sub(throttle_from)) * amount
+        };
+        granted.min(headroom)
+    }
+}
+
+/// Issuance flows of an asset in its current claim period
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct VelocityState<Balance> {
+    /// Claim period the totals belong to
+    pub period: u64,
+    /// Tokens claimed in the period
+    pub claimed: Balance,
+    /// Tokens burned in the period
+    pub burned: Balance,
+    /// Velocity of the last closed period with claims, in permille
+    pub last_velocity: Option<u32>,
+    /// Current UBI amount adjustment in permille (1000 = unchanged)
+    pub adjustment: u32,
+}
+
+impl<Balance: Default> Default for VelocityState<Balance> {
+    fn default() -> Self {
+        Self {
+            period: 0,
+            claimed: Balance::default(),
+            burned: Balance::default(),
+            last_velocity: None,
+            adjustment: 1000,
+        }
+    }
+}
+
+/// A governance-declared window with boosted claims
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct BonusPeriod<BlockNumber> {
+    /// First block of the bonus period
+    pub start: BlockNumber,
+    /// First block after the bonus period
+    pub end: BlockNumber,
+    /// Claim multiplier in percent (100 = no change, 200 = double UBI)
+    pub claim_multiplier: u32,
+    /// Whether each claim adds two streak days instead of one
+    pub double_streak: bool,
+}
+
+/// What a burn paid for, as tagged by the sender
+#[derive(Clone, Copy, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum BurnCategory {
+    /// Groceries, meals and other food
+    Food,
+    /// Rent, utilities and housing costs
+    Housing,
+    /// Work done by another person (repairs, care, lessons, ...)
+    Services,
+    /// Gifts and donations
+    Donation,
+    /// Anything else
+    Other,
+}
+
+/// Aggregated burns of one category within one claim period
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategoryStats<Balance> {
+    /// Number of burns tagged with the category
+    pub count: u32,
+    /// Total amount burned with the category
+    pub volume: Balance,
+}
+
+/// Identifier of a burn, assigned in order by each pallet instance
+pub type BurnId = u64;
+
+/// On-chain record of a burn, kept so both parties can refer to it later
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct BurnReceipt<AssetId, AccountId, Balance, BlockNumber> {
+    /// Asset that was burned
+    pub asset_id: AssetId,
+    /// Account that burned the tokens
+    pub from: AccountId,
+    /// Named recipient of the burn
+    pub to: AccountId,
+    /// Number of tokens burned
+    pub amount: Balance,
+    /// Category tagged by the sender
+    pub category: Option<BurnCategory>,
+    /// Hash of an off-chain document (receipt, contract, invoice) the burn pays for
+    pub reference: Option<H256>,
+    /// Block in which the burn happened
+    pub block: BlockNumber,
+    /// Amount added to the recipient's `weighted_received`
+    pub weighted_amount: Balance,
+    /// Bonus added to the sender's `burns_sent_volume` for burning to a verified charity
+    pub sender_bonus: Balance,
+    /// Whether the burn made the recipient a new unique recipient of the sender
+    pub new_recipient: bool,
+    /// Whether the recipient rejected the burn
+    pub rejected: bool,
+    /// Whether the recipient refunded the burn after a failed transaction
+    pub refunded: bool,
+}
+
+impl<AssetId, AccountId, Balance, BlockNumber> BurnReceipt<AssetId, AccountId, Balance, BlockNumber> {
+    /// Whether the reputation effects of the burn were reversed by a rejection or refund
+    pub fn reversed(&self) -> bool {
+        self.rejected || self.refunded
+    }
+}
+
+/// Commitment recorded by `burn_ticket`, redeemable once by the recipient
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ticket<BlockNumber> {
+    /// `blake2_256` hash of the secret presented to `redeem`
+    pub commitment: H256,
+    /// Block in which the recipient redeemed the ticket, once redeemed
+    pub redeemed_at: Option<BlockNumber>,
+}
+
+/// A sender in a recipient's list of recent supporters
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Supporter<AccountId, Balance, BlockNumber> {
+    /// Account that burned to the recipient
+    pub who: AccountId,
+    /// Number of burns sent while on the list
+    pub count: u32,
+    /// Volume of those burns
+    pub volume: Balance,
+    /// Block of the latest burn
+    pub last_burn: BlockNumber,
+}
+
+/// A sender in a recipient's ranking of top supporters
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct TopSupporter<AccountId, Balance> {
+    /// Account that burned to the recipient
+    pub who: AccountId,
+    /// Volume burned to the recipient while on the ranking
+    pub volume: Balance,
+}
+
+/// A member of an organization and its share of the recognition received
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrganizationMember<AccountId> {
+    /// Member account
+    pub who: AccountId,
+    /// Share of the member, relative to the sum of all members' shares
+    pub share: u32,
+}
+
+/// Settings of an account in an asset, chosen by the account itself
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountPreferences<Balance> {
+    /// Whether claim relayers may claim for the account without being asked
+    pub auto_claim: bool,
+    /// Whether the account may be claimed for in relayer batches (`claim_for_many`)
+    pub allow_third_party_claims: bool,
+    /// URL notification services call with the account's events
+    pub webhook: Option<BoundedVec<u8, ConstU32<MAX_WEBHOOK_LEN>>>,
+    /// Smallest burn the account accepts as a recipient
+    pub min_incoming_burn: Balance,
+}
+
+impl<Balance: Zero> Default for AccountPreferences<Balance> {
+    fn default() -> Self {
+        Self { auto_claim: false, allow_third_party_claims: true, webhook: None, min_incoming_burn: Zero::zero() }
+    }
+}
+
+/// Burn pattern flagged by the ring-detection worker
+#[derive(Clone, Copy, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum SuspicionKind {
+    /// Two accounts burning to each other
+    Reciprocal,
+    /// Three accounts burning around in a circle
+    Cycle,
+}
+
+/// A ring of burns queued for governance review
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuspicionReport<AssetId, AccountId, BlockNumber> {
+    /// Asset the burns were made in
+    pub asset_id: AssetId,
+    /// Pattern the burns form
+    pub kind: SuspicionKind,
+    /// Accounts of the ring, sorted
+    pub accounts: BoundedVec<AccountId, ConstU32<MAX_RING_LENGTH>>,
+    /// Burns forming the ring, in ring order
+    pub burns: BoundedVec<BurnId, ConstU32<MAX_RING_LENGTH>>,
+    /// Block in which the report was queued
+    pub reported_at: BlockNumber,
+}
+
+/// A dispute raised on a burn by one of its parties
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct BurnDispute<AccountId, BlockNumber> {
+    /// Sender or recipient that flagged the burn
+    pub flagged_by: AccountId,
+    /// Hash of the off-chain explanation of the dispute
+    pub reason_hash: H256,
+    /// Block in which the burn was flagged
+    pub flagged_at: BlockNumber,
+}
+
+/// Reputation a sender staked on a boosted burn
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct BurnBoost<Balance, BlockNumber> {
+    /// Reputation staked by the sender and added to the recipient's `weighted_received`
+    pub stake: Balance,
+    /// Block from which the stake can be released
+    pub unlocks_at: BlockNumber,
+}
+
+/// A budget an owner allows an operator to burn on their behalf
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Approval<Balance, BlockNumber> {
+    /// Amount the operator may still burn
+    pub remaining: Balance,
+    /// Block from which the approval can no longer be used
+    pub expires_at: BlockNumber,
+}
+
+/// Identifier of a registered service
+pub type ServiceId = u32;
+
+/// A named service offered by a provider at a fixed price
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(NameLimit))]
+pub struct Service<AssetId, AccountId, Balance, NameLimit: Get<u32>> {
+    /// Asset the price is denominated in
+    pub asset_id: AssetId,
+    /// Account that offers the service and is named as recipient of payments
+    pub provider: AccountId,
+    /// Human readable name, e.g. `haircut`
+    pub name: BoundedVec<u8, NameLimit>,
+    /// Exact amount burned by `pay_service`
+    pub price: Balance,
+}
+
+/// Identifier of an invoice
+pub type InvoiceId = u32;
+
+/// Identifier of a payment intent, used as the `reference` of the burn paying it
+pub type PaymentIntentId = H256;
+
+/// An exact payment a merchant expects, paid by a single burn
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct PaymentIntent<AssetId, AccountId, Balance, BlockNumber> {
+    /// Asset the intent is denominated in
+    pub asset_id: AssetId,
+    /// Account that created the intent and receives the burn
+    pub merchant: AccountId,
+    /// Exact amount the burn must pay
+    pub amount: Balance,
+    /// Hash of off-chain metadata of the payment, e.g. the order
+    pub metadata_hash: H256,
+    /// Block in which the intent is removed
+    pub expires_at: BlockNumber,
+    /// Burn that paid the intent, once paid
+    pub fulfilled_by: Option<BurnId>,
+}
+
+/// An open invoice that one or more burns pay towards
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Invoice<AssetId, AccountId, Balance, BlockNumber> {
+    /// Asset the invoice is denominated in
+    pub asset_id: AssetId,
+    /// Account that issued the invoice and receives its payments
+    pub payee: AccountId,
+    /// Amount due in total
+    pub total: Balance,
+    /// Amount paid so far, always below `total` while the invoice is open
+    pub paid: Balance,
+    /// Block in which the invoice closes if it is not paid in full
+    pub expires_at: BlockNumber,
+}
+
+/// Display name an account presents to wallets in an asset
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(NameLimit))]
+pub struct Profile<BlockNumber, NameLimit: Get<u32>> {
+    /// UTF-8 display name, e.g. `Maria's Bakery`
+    pub display_name: BoundedVec<u8, NameLimit>,
+    /// Hash of off-chain metadata (avatar, description), if any
+    pub metadata_hash: Option<H256>,
+    /// Block of the latest change
+    pub updated_at: BlockNumber,
+}
+
+/// A registered community asset
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssetDetails<AccountId, Balance, BlockNumber> {
+    /// Account allowed to update the asset parameters
+    pub owner: AccountId,
+    /// Economic parameters of the asset
+    pub params: AssetParameters<Balance, BlockNumber>,
+}
+
+/// Hash of a claim proof-of-work
+///
+/// A nonce is valid if the hash has at least `ClaimPowDifficulty` leading zero bits.
+pub fn claim_pow_hash<AssetId: Encode, AccountId: Encode>(
+    asset_id: &AssetId,
+    who: &AccountId,
+    period: u64,
+    nonce: u64,
+) -> [u8; 32] {
+    sp_io::hashing::blake2_256(&(asset_id, who, period, nonce).encode())
+}
+
+/// Number of leading zero bits of a hash
+pub fn leading_zero_bits(hash: &[u8; 32]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// Highest `ClaimPowDifficulty` a runtime may configure
+pub const MAX_CLAIM_POW_DIFFICULTY: u32 = 32;
+
+/// Nonces `find_claim_nonce` tries before giving up
+///
+/// 256 times the expected work at `MAX_CLAIM_POW_DIFFICULTY`, so the search
+/// only fails for difficulties no runtime may configure.
+pub const MAX_CLAIM_NONCE_ATTEMPTS: u64 = 1 << 40;
+
+/// Find the first nonce that meets `difficulty` for a claim in `period`
+///
+/// Meant for wallets and relayers; the expected work is `2^difficulty` hashes.
+/// Returns `None` if none of the first `MAX_CLAIM_NONCE_ATTEMPTS` nonces does.
+pub fn find_claim_nonce<AssetId: Encode, AccountId: Encode>(
+    asset_id: &AssetId,
+    who: &AccountId,
+    period: u64,
+    difficulty: u32,
+) -> Option<u64> {
+    (0..MAX_CLAIM_NONCE_ATTEMPTS)
+        .find(|nonce| leading_zero_bits(&claim_pow_hash(asset_id, who, period, *nonce)) >= difficulty)
+}
+
+/// Context string opening every burn authorization payload
+pub const BURN_AUTHORIZATION_CONTEXT: &[u8] = b"nst:ubi-token:burn-authorization:v1";
+
+/// Burn an account authorizes off-chain, submitted with `burn_authorized`
+///
+/// The signed bytes are the SCALE encoding of this struct. Wallets should get
+/// it from the `burn_authorization` runtime API rather than build it.
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct BurnAuthorization<AssetId, AccountId, Balance, Hash> {
+    /// Always `BURN_AUTHORIZATION_CONTEXT`
+    pub context: Vec<u8>,
+    /// Name of the pallet instance the burn is meant for
+    pub pallet: Vec<u8>,
+    /// Genesis hash of the chain the burn is meant for
+    pub genesis_hash: Hash,
+    /// Runtime spec version the signature is valid for
+    pub spec_version: u32,
+    pub asset_id: AssetId,
+    pub from: AccountId,
+    pub to: AccountId,
+    pub amount: Balance,
+    pub category: Option<BurnCategory>,
+    pub reference: Option<H256>,
+    /// Burn nonce of `from`, see `burn_nonce`
+    pub nonce: u64,
+}
+
+// Calls such as `burn` take more arguments, and so do their generated constructors
+#[frame_support::pallet]
+#[allow(clippy::too_many_arguments)]
+pub mod pallet {
+    use super::*;
+
+    /// Maximum number of token batches per account
+    pub const MAX_BATCHES: u32 = 10;
+    
+    /// Maximum unique recipients tracked per account; further ones do not count as new
+    pub const MAX_UNIQUE_RECIPIENTS: u32 = 1000;
+
+    /// Maximum recent supporters kept per recipient
+    pub const MAX_RECENT_SUPPORTERS: u32 = 20;
+
+    /// Maximum top supporters ranked per recipient
+    pub const MAX_TOP_SUPPORTERS: u32 = 10;
+
+    /// Number of power-of-two buckets of the reputation score histogram
+    ///
+    /// Bucket 0 holds the zero scores and bucket `n` the scores from `2^(n-1)` below
+    /// `2^n`, the last one everything above.
+    pub const SCORE_HISTOGRAM_BUCKETS: u32 = 32;
+
+    /// Maximum length of a webhook URL in the account preferences
+    pub const MAX_WEBHOOK_LEN: u32 = 256;
+
+    /// Longest ring of burns the ring-detection worker looks for
+    pub const MAX_RING_LENGTH: u32 = 3;
+
+    /// Number of latest burns the ring-detection worker scans
+    pub const RING_SCAN_BURNS: u32 = 256;
+
+    /// Maximum number of expiry index entries the offchain worker reads per asset and period
+    pub const MAX_EXPIRY_SWEEP_ACCOUNTS: u32 = 1024;
+
+    /// Number of past expiry periods the offchain worker sweeps
+    pub const EXPIRY_SWEEP_LOOKBACK_PERIODS: u64 = 30;
+
+    /// Maximum suspicion reports awaiting review
+    pub const MAX_SUSPICION_REPORTS: u32 = 64;
+
+    /// Maximum suspicion reports the offchain worker submits per block
+    pub const MAX_REPORTS_PER_RUN: usize = 4;
+
+    /// Maximum pre-expiry warnings emitted in a single block (further ones are dropped)
+    pub const MAX_EXPIRY_WARNINGS_PER_BLOCK: u32 = 512;
+
+    /// Maximum invoices expiring in a single block
+    pub const MAX_INVOICE_EXPIRIES_PER_BLOCK: u32 = 64;
+
+    /// Maximum payment intents expiring in a single block
+    pub const MAX_INTENT_EXPIRIES_PER_BLOCK: u32 = 64;
+
+    /// Maximum length of a ticket secret in bytes
+    pub const MAX_TICKET_SECRET_LEN: u32 = 128;
+
+    /// Maximum reputation entries checked for dormancy by `on_idle` in one block
+    pub const MAX_DORMANT_CHECKS_PER_BLOCK: u32 = 64;
+
+    /// Maximum length of the raw storage key kept as `on_idle` pruning cursor
+    pub const MAX_PRUNE_CURSOR_LEN: u32 = 256;
+
+    /// Weight of one token batch expired or drawn from by a claim or burn
+    pub const WEIGHT_PER_BATCH: u64 = 2_000;
+
+    /// Weight of one claim period paid out by a claim
+    pub const WEIGHT_PER_PERIOD: u64 = 1_000;
+
+    /// Weight of checking the signature of `burn_authorized` (~50µs for sr25519)
+    pub const SIGNATURE_CHECK_WEIGHT: u64 = 50_000_000;
+    
+    // Reputation calculation constants (using fixed-point math with 1000 = 1.0)
+    /// Minimum sender weight (0.5 = 500/1000)
+    pub const MIN_SENDER_WEIGHT: u32 = 500;
+    /// Maximum sender weight (2.0 = 2000/1000)
+    pub const MAX_SENDER_WEIGHT: u32 = 2000;
+    /// Decay factor per elapsed claim period (95% = 950/1000, i.e., 5% decay)
+    pub const DECAY_FACTOR: u32 = 950;
+    /// Reputation points per unique recipient
+    pub const POINTS_PER_UNIQUE_RECIPIENT: u32 = 50;
+    /// Reputation points per streak day (capped at 50 days = 500 points)
+    pub const POINTS_PER_STREAK_DAY: u32 = 10;
+    /// Maximum streak bonus
+    pub const MAX_STREAK_BONUS: u32 = 500;
+    /// Multiplier for weighted received in score (2x)
+    pub const WEIGHTED_RECEIVED_MULTIPLIER: u32 = 2;
+    /// Grace period for streak (can miss up to 2 periods)
+    pub const STREAK_GRACE_PERIODS: u64 = 2;
+    /// Reputation credit per account swept by `sweep_expired`
+    pub const POINTS_PER_SWEPT_ACCOUNT: u32 = 1;
+    /// Reputation points per period claimed beyond the backlog
+    pub const BACKLOG_COST_PER_PERIOD: u32 = 20;
+    /// Maximum missed periods streak freezes can cover at once
+    pub const MAX_STREAK_FREEZE: u32 = 14;
+    /// Maximum reputation credit that can be earned by sweeping
+    pub const MAX_MAINTENANCE_CREDIT: u32 = 100;
+    /// Reputation points a voucher pays per `vouch`
+    pub const VOUCH_COST: u32 = 20;
+    /// Reputation credit a target gains per `vouch`
+    pub const VOUCH_BONUS: u32 = 50;
+    /// Maximum reputation credit that can be gained from vouches
+    pub const MAX_VOUCH_CREDIT: u32 = 250;
+    /// Maximum number of open commitments per account
+    pub const MAX_COMMITMENTS: u32 = 10;
+    /// Reputation points paid per committed period
+    pub const COMMITMENT_COST_PER_PERIOD: u32 = 5;
+    /// Maximum number of disputes per burn (one by each party)
+    pub const MAX_DISPUTES_PER_BURN: u32 = 2;
+    /// Maximum number of scheduled bonus periods per asset
+    pub const MAX_BONUS_PERIODS: u32 = 16;
+    /// Maximum steps in an asset's emission schedule
+    pub const MAX_EMISSION_STEPS: u32 = 32;
+    /// Highest UBI amount adjustment of a velocity policy, in permille (5x)
+    pub const MAX_VELOCITY_ADJUSTMENT: u32 = 5_000;
+    /// Maximum claim multiplier of a bonus period, in percent (5x)
+    pub const MAX_CLAIM_MULTIPLIER: u32 = 500;
+
+    /// Balance type used by an instance of the pallet
+    pub type BalanceOf<T, I = ()> = <T as Config<I>>::Balance;
+
+    /// Supply cap type of a pallet instance
+    pub type SupplyCapOf<T, I = ()> = SupplyCap<BalanceOf<T, I>>;
+
+    /// Token batch type used in storage
+    pub type TokenBatchOf<T, I = ()> = TokenBatch<BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Reputation type used in storage
+    pub type ReputationOf<T, I = ()> = Reputation<BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Asset parameter type used in calls and storage
+    pub type AssetParametersOf<T, I = ()> = AssetParameters<BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Parameter type returned by the `ubi_parameters` runtime API
+    pub type UbiParametersOf<T, I = ()> = UbiParameters<BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Category statistics type used in storage
+    pub type CategoryStatsOf<T, I = ()> = CategoryStats<BalanceOf<T, I>>;
+
+    /// Burn receipt type used in storage
+    pub type BurnReceiptOf<T, I = ()> = BurnReceipt<
+        <T as Config<I>>::AssetId,
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T, I>,
+        BlockNumberFor<T>,
+    >;
+
+    /// Grants of one `allocate_community_pool` call
+    pub type PoolGrantsOf<T, I = ()> = BoundedVec<
+        (<T as frame_system::Config>::AccountId, PoolGrant<BalanceOf<T, I>>),
+        <T as Config<I>>::MaxPoolGrants,
+    >;
+
+    /// Burn authorization payload of the pallet
+    pub type BurnAuthorizationOf<T, I = ()> = BurnAuthorization<
+        <T as Config<I>>::AssetId,
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T, I>,
+        <T as frame_system::Config>::Hash,
+    >;
+
+    /// Supporter type used in storage
+    pub type SupporterOf<T, I = ()> =
+        Supporter<<T as frame_system::Config>::AccountId, BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Top supporter type used in storage
+    pub type TopSupporterOf<T, I = ()> = TopSupporter<<T as frame_system::Config>::AccountId, BalanceOf<T, I>>;
+
+    /// Members of an organization, as passed to `set_organization`
+    pub type OrganizationMembersOf<T, I = ()> = BoundedVec<
+        OrganizationMember<<T as frame_system::Config>::AccountId>,
+        <T as Config<I>>::MaxOrganizationMembers,
+    >;
+
+    /// Recognition of an organization burn credited to each member
+    pub type OrganizationSplitOf<T, I = ()> = BoundedVec<
+        (<T as frame_system::Config>::AccountId, BalanceOf<T, I>),
+        <T as Config<I>>::MaxOrganizationMembers,
+    >;
+
+    /// Suspicion report type used in storage
+    pub type SuspicionReportOf<T, I = ()> =
+        SuspicionReport<<T as Config<I>>::AssetId, <T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    /// Burns naming a ring in `report_suspicion`
+    pub type RingBurns = BoundedVec<BurnId, ConstU32<MAX_RING_LENGTH>>;
+
+    /// Burn dispute type used in storage
+    pub type BurnDisputeOf<T> = BurnDispute<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    /// Burn boost type used in storage
+    pub type BurnBoostOf<T, I = ()> = BurnBoost<BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Approval type used in storage
+    pub type ApprovalOf<T, I = ()> = Approval<BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Account preferences type used in calls and storage
+    pub type AccountPreferencesOf<T, I = ()> = AccountPreferences<BalanceOf<T, I>>;
+
+    /// Service type used in storage
+    pub type ServiceOf<T, I = ()> = Service<
+        <T as Config<I>>::AssetId,
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T, I>,
+        <T as Config<I>>::MaxServiceNameLen,
+    >;
+
+    /// Reputation snapshot type used in storage
+    pub type ReputationSnapshotOf<T, I = ()> = ReputationSnapshot<
+        <T as Config<I>>::AssetId,
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T, I>,
+        BlockNumberFor<T>,
+    >;
+
+    /// Payment intent type used in storage
+    pub type PaymentIntentOf<T, I = ()> = PaymentIntent<
+        <T as Config<I>>::AssetId,
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T, I>,
+        BlockNumberFor<T>,
+    >;
+
+    /// Invoice type used in storage
+    pub type InvoiceOf<T, I = ()> = Invoice<
+        <T as Config<I>>::AssetId,
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T, I>,
+        BlockNumberFor<T>,
+    >;
+
+    /// Role tag of an attestation
+    pub type RoleOf<T, I = ()> = BoundedVec<u8, <T as Config<I>>::MaxRoleLen>;
+
+    /// Profile type used in storage
+    pub type ProfileOf<T, I = ()> = Profile<BlockNumberFor<T>, <T as Config<I>>::MaxDisplayNameLen>;
+
+    /// Commitment type used in storage
+    pub type CommitmentOf<T, I = ()> = Commitment<BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Emission step type used in calls and storage
+    pub type EmissionStepOf<T, I = ()> = EmissionStep<BalanceOf<T, I>>;
+
+    /// Emission schedule type used in calls and storage
+    pub type EmissionScheduleOf<T, I = ()> = BoundedVec<EmissionStepOf<T, I>, ConstU32<MAX_EMISSION_STEPS>>;
+
+    /// Velocity state type used in storage
+    pub type VelocityStateOf<T, I = ()> = VelocityState<BalanceOf<T, I>>;
+
+    /// Bonus period type used in calls and storage
+    pub type BonusPeriodOf<T> = BonusPeriod<BlockNumberFor<T>>;
+
+    /// Native balance type of the asset deposits
+    pub type DepositBalanceOf<T, I = ()> =
+        <<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// Asset details type used in storage
+    pub type AssetDetailsOf<T, I = ()> =
+        AssetDetails<<T as frame_system::Config>::AccountId, BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// Genesis balance entry: asset, holder and token batches
+    pub type GenesisBalanceOf<T, I = ()> =
+        (<T as Config<I>>::AssetId, <T as frame_system::Config>::AccountId, Vec<TokenBatchOf<T, I>>);
+
+    /// Genesis reputation entry: asset, account and reputation
+    pub type GenesisReputationOf<T, I = ()> =
+        (<T as Config<I>>::AssetId, <T as frame_system::Config>::AccountId, ReputationOf<T, I>);
+
+    /// The in-code storage version
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T, I = ()>(_);
+
+    /// Configuration trait for the UBI token pallet
+    #[pallet::config]
+    pub trait Config<I: 'static = ()>:
+        frame_system::Config<RuntimeEvent: From<Event<Self, I>>>
+        + frame_system::offchain::CreateBare<Call<Self, I>>
+    {
+        /// The balance type used for token amounts and reputation volumes
+        type Balance: Parameter
+            + Member
+            + AtLeast32BitUnsigned
+            + Default
+            + Copy
+            + MaxEncodedLen
+            + MaybeSerializeDeserialize
+            + frame_support::traits::tokens::Balance;
+
+        /// Identifier of a community asset
+        type AssetId: Parameter + Member + Copy + MaxEncodedLen + MaybeSerializeDeserialize;
+
+        /// Origin allowed to create new community assets; the returned account becomes the owner
+        type CreateOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+        /// Native currency the asset deposits are reserved in
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// Deposit reserved from the creator of a community asset, for as long as the asset exists
+        #[pallet::constant]
+        type AssetDeposit: Get<DepositBalanceOf<Self, I>>;
+
+        /// Maximum number of community assets
+        ///
+        /// Reaping an account visits every asset, so this bounds `on_killed_account`.
+        #[pallet::constant]
+        type MaxAssets: Get<u32>;
+
+        /// Origin allowed to declare bonus periods (e.g. root or a committee)
+        type BonusOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origin reviewing the suspicion reports of the ring-detection worker
+        type ReviewOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origin managing the registry of verified charities
+        type CharityOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origin allowed to tune the reputation and issuance parameters
+        type ParameterOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Default amount of tokens distributed per claim period (used for genesis assets)
+        #[pallet::constant]
+        type UbiAmount: Get<Self::Balance>;
+
+        /// Default number of blocks in one claim period (e.g., 1 day worth of blocks)
+        #[pallet::constant]
+        type ClaimPeriodBlocks: Get<BlockNumberFor<Self>>;
+
+        /// Default number of blocks until tokens expire (e.g., 7 days worth of blocks)
+        #[pallet::constant]
+        type ExpirationBlocks: Get<BlockNumberFor<Self>>;
+
+        /// Default maximum number of claim periods that can be claimed as backlog
+        #[pallet::constant]
+        type MaxBacklogPeriods: Get<u32>;
+
+        /// Maximum number of accounts handled by one `sweep_expired` or `prune_dormant` call
+        #[pallet::constant]
+        type MaxSweepAccounts: Get<u32>;
+
+        /// Number of claim periods without activity after which an account may be pruned
+        #[pallet::constant]
+        type DormantPeriods: Get<u32>;
+
+        /// Highest reputation score an account may have to be pruned as dormant
+        #[pallet::constant]
+        type DormantScoreThreshold: Get<Self::Balance>;
+
+        /// Number of blocks after a burn during which the recipient may reject it
+        #[pallet::constant]
+        type RejectionWindow: Get<BlockNumberFor<Self>>;
+
+        /// Maximum length of a service name in bytes
+        #[pallet::constant]
+        type MaxServiceNameLen: Get<u32>;
+
+        /// Maximum length of a profile display name in bytes
+        #[pallet::constant]
+        type MaxDisplayNameLen: Get<u32>;
+
+        /// Reputation score needed to set a profile (zero for none)
+        #[pallet::constant]
+        type MinProfileReputation: Get<BalanceOf<Self, I>>;
+
+        /// Maximum number of accounts handled by one `claim_for_many` call
+        #[pallet::constant]
+        type MaxClaimBatch: Get<u32>;
+
+        /// Default expiration of short-expiry claims, as a fraction of the asset's expiration
+        #[pallet::constant]
+        type ShortExpiryRatio: Get<Perbill>;
+
+        /// Default extra tokens granted on short-expiry claims, as a fraction of the claim
+        #[pallet::constant]
+        type ShortExpiryBonus: Get<Perbill>;
+
+        /// Maximum number of claim periods tokens can be committed for
+        #[pallet::constant]
+        type MaxCommitPeriods: Get<u32>;
+
+        /// Maximum number of periods beyond the backlog `claim_extra_backlog` can pay
+        #[pallet::constant]
+        type MaxExtraBacklogPeriods: Get<u32>;
+
+        /// Tokens burned by `freeze_streak`
+        #[pallet::constant]
+        type StreakFreezeCost: Get<BalanceOf<Self, I>>;
+
+        /// Missed claim periods one `freeze_streak` covers
+        #[pallet::constant]
+        type StreakFreezePeriods: Get<u32>;
+
+        /// Maximum length of a vacation, in claim periods
+        #[pallet::constant]
+        type MaxVacationPeriods: Get<u64>;
+
+        /// Claim periods between the end of a vacation and the start of the next
+        #[pallet::constant]
+        type VacationCooldownPeriods: Get<u64>;
+
+        /// Leading zero bits required in the proof-of-work of unsigned claims (0 disables it)
+        ///
+        /// At most `MAX_CLAIM_POW_DIFFICULTY`.
+        #[pallet::constant]
+        type ClaimPowDifficulty: Get<u32>;
+
+        /// Maximum number of unsigned burns an account may send per claim period
+        #[pallet::constant]
+        type MaxUnsignedBurnsPerPeriod: Get<u32>;
+
+        /// Blocks before a period boundary in which next period's claims are accepted
+        /// into the pool (0 disables pre-submission)
+        #[pallet::constant]
+        type ClaimLookaheadBlocks: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of grants paid by one `allocate_community_pool` call
+        #[pallet::constant]
+        type MaxPoolGrants: Get<u32>;
+
+        /// Share of a burn to a verified charity added to the sender's sent volume
+        #[pallet::constant]
+        type CharityBonus: Get<Perbill>;
+
+        /// Maximum number of members of a household, besides its claimer
+        #[pallet::constant]
+        type MaxHouseholdMembers: Get<u32>;
+
+        /// Maximum number of members of an organization
+        #[pallet::constant]
+        type MaxOrganizationMembers: Get<u32>;
+
+        /// Maximum length of an attested role tag in bytes
+        #[pallet::constant]
+        type MaxRoleLen: Get<u32>;
+
+        /// Reputation score required to attest roles to other accounts
+        #[pallet::constant]
+        type MinAttesterReputation: Get<BalanceOf<Self, I>>;
+
+        /// Maximum number of accounts an account may vouch for per claim period
+        #[pallet::constant]
+        type MaxVouchesPerPeriod: Get<u32>;
+
+        /// Number of blocks a stake of `boosted_burn` stays at risk of being slashed
+        #[pallet::constant]
+        type BoostLockBlocks: Get<BlockNumberFor<Self>>;
+
+        /// Tokens added to an account's first claim of an asset (0 disables it)
+        #[pallet::constant]
+        type WelcomeBonus: Get<BalanceOf<Self, I>>;
+
+        /// Reputation credited to an account with its first claim of an asset (0 disables it)
+        #[pallet::constant]
+        type WelcomeReputation: Get<BalanceOf<Self, I>>;
+
+        /// Reputation `claim_with_referral` grants each side when referred by an
+        /// account at `max_sender_weight`; lower weights get a proportional share
+        #[pallet::constant]
+        type ReferralBonus: Get<BalanceOf<Self, I>>;
+
+        /// Maximum number of accounts an account may refer per claim period
+        #[pallet::constant]
+        type MaxReferralsPerPeriod: Get<u32>;
+
+        /// Blocks during which the idempotency key of a burn cannot be used again
+        #[pallet::constant]
+        type IdempotencyWindow: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of idempotency keys an account may have in use at once
+        #[pallet::constant]
+        type MaxIdempotencyKeys: Get<u32>;
+
+        /// Maximum number of reputation snapshots waiting for a bridge to take them
+        #[pallet::constant]
+        type MaxOutboundSnapshots: Get<u32>;
+
+        /// Weight `on_poll` may spend per block on expiring indexed batches
+        #[pallet::constant]
+        type ExpiryPollWeight: Get<Weight>;
+
+        /// Signature of a burn authorized off-chain (`burn_authorized`)
+        type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+        /// Public key checking an `OffchainSignature`, identifying the signing account
+        type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+    }
+
+    /// Default of `Economics`: the crate constants and the configured short-expiry terms
+    #[pallet::type_value]
+    pub fn DefaultEconomics<T: Config<I>, I: 'static>() -> EconomicParameters {
+        EconomicParameters {
+            reputation: ReputationParameters::default(),
+            short_expiry_ratio: T::ShortExpiryRatio::get(),
+            short_expiry_bonus: T::ShortExpiryBonus::get(),
+            commitment_cost_per_period: COMMITMENT_COST_PER_PERIOD,
+            points_per_swept_account: POINTS_PER_SWEPT_ACCOUNT,
+            backlog_cost_per_period: BACKLOG_COST_PER_PERIOD,
+        }
+    }
+
+    /// Reputation and issuance parameters tuned by `T::ParameterOrigin`
+    #[pallet::storage]
+    #[pallet::getter(fn economics)]
+    pub type Economics<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, EconomicParameters, ValueQuery, DefaultEconomics<T, I>>;
+
+    /// Registered community assets and their parameters
+    #[pallet::storage]
+    #[pallet::getter(fn asset)]
+    pub type Assets<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, AssetDetailsOf<T, I>, OptionQuery>;
+
+    /// Number of `Assets` entries, at most `T::MaxAssets`
+    #[pallet::storage]
+    pub type AssetCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+    /// Token balances stored as batches with expiration
+    #[pallet::storage]
+    #[pallet::getter(fn balances)]
+    pub type Balances<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<TokenBatchOf<T, I>, ConstU32<MAX_BATCHES>>,
+        ValueQuery,
+    >;
+
+    /// Block number of last claim for each account
+    #[pallet::storage]
+    #[pallet::getter(fn last_claim)]
+    pub type LastClaim<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BlockNumberFor<T>,
+        OptionQuery,
+    >;
+
+    /// Display name and metadata of each account that set a profile
+    #[pallet::storage]
+    #[pallet::getter(fn profile)]
+    pub type Profiles<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AssetId, Blake2_128Concat, T::AccountId, ProfileOf<T, I>, OptionQuery>;
+
+    /// Settings of each account, absent while they are the defaults
+    #[pallet::storage]
+    #[pallet::getter(fn preferences)]
+    pub type Preferences<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        AccountPreferencesOf<T, I>,
+        ValueQuery,
+    >;
+
+    /// Offset of each account's claim windows from the global period boundary
+    #[pallet::storage]
+    #[pallet::getter(fn claim_offset)]
+    pub type ClaimOffsets<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BlockNumberFor<T>,
+        ValueQuery,
+    >;
+
+    /// Reputation data for each account
+    ///
+    /// Decay and streak lapses are applied lazily; read through `Pallet::reputation`
+    /// for the current values.
+    #[pallet::storage]
+    pub type ReputationStore<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        ReputationOf<T, I>,
+        ValueQuery,
+    >;
+
+    /// Number of reputation records of each asset per score bucket
+    ///
+    /// See `SCORE_HISTOGRAM_BUCKETS`.
+    #[pallet::storage]
+    #[pallet::getter(fn score_histogram)]
+    pub type ScoreHistogram<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, [u32; SCORE_HISTOGRAM_BUCKETS as usize], ValueQuery>;
+
+    /// Recipients each sender has burned to (for the reputation breadth bonus)
+    ///
+    /// One bounded set per sender, so a burn to a new recipient writes a single
+    /// entry and an account's records are bounded by `MAX_UNIQUE_RECIPIENTS`.
+    /// Once the set is full, burns to further recipients no longer count as new.
+    #[pallet::storage]
+    pub type UniqueRecipientSets<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedBTreeSet<T::AccountId, ConstU32<MAX_UNIQUE_RECIPIENTS>>,
+        ValueQuery,
+    >;
+
+    /// Web of trust: asset -> voucher -> target -> block of the vouch
+    #[pallet::storage]
+    #[pallet::getter(fn vouched_at)]
+    pub type Vouches<T: Config<I>, I: 'static = ()> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AssetId>,
+            NMapKey<Blake2_128Concat, T::AccountId>,  // voucher
+            NMapKey<Blake2_128Concat, T::AccountId>,  // target
+        ),
+        BlockNumberFor<T>,
+        OptionQuery,
+    >;
+
+    /// Vouches given by each account: claim period and count in that period
+    #[pallet::storage]
+    pub type VouchCounts<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        (u64, u32),
+        ValueQuery,
+    >;
+
+    /// Referrer of each account that joined with `claim_with_referral`
+    ///
+    /// Kept when the account is pruned, so it cannot be referred twice.
+    #[pallet::storage]
+    #[pallet::getter(fn referrer_of)]
+    pub type Referrers<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AssetId, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+    /// Referrals made by each account: claim period and count in that period
+    #[pallet::storage]
+    pub type ReferralCounts<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        (u64, u32),
+        ValueQuery,
+    >;
+
+    /// Reputation snapshots waiting for a bridge, by sequence number
+    #[pallet::storage]
+    #[pallet::getter(fn outbound_snapshot)]
+    pub type OutboundSnapshots<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, u64, ReputationSnapshotOf<T, I>, OptionQuery>;
+
+    /// Sequence numbers of the oldest queued snapshot and of the next one
+    #[pallet::storage]
+    pub type OutboundQueueBounds<T: Config<I>, I: 'static = ()> = StorageValue<_, (u64, u64), ValueQuery>;
+
+    /// Claim period of each account's last `export_reputation`
+    #[pallet::storage]
+    pub type LastReputationExport<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        u64,
+        OptionQuery,
+    >;
+
+    /// Total tokens currently in circulation (not expired)
+    #[pallet::storage]
+    #[pallet::getter(fn total_supply)]
+    pub type TotalSupply<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, BalanceOf<T, I>, ValueQuery>;
+
+    /// Expired tokens of each asset not yet allocated to campaigns
+    ///
+    /// A virtual counter: expired tokens are destroyed, and allocations from
+    /// the pool issue new ones.
+    #[pallet::storage]
+    #[pallet::getter(fn community_pool)]
+    pub type CommunityPool<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, BalanceOf<T, I>, ValueQuery>;
+
+    /// Accounts holding a batch that expires in a claim period, read by the expiry sweeper
+    ///
+    /// Keyed by asset, expiry period and holder. Entries are removed by
+    /// `sweep_expiry_index` once the period has passed.
+    #[pallet::storage]
+    pub type ExpiryIndex<T: Config<I>, I: 'static = ()> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AssetId>,
+            NMapKey<Twox64Concat, u64>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        (),
+        OptionQuery,
+    >;
+
+    /// First expiry period of each asset whose index `on_poll` has not fully swept
+    #[pallet::storage]
+    pub type ExpiryPollCursor<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, u64, OptionQuery>;
+
+    /// Last asset the previous `on_poll` reached; the next one starts after it
+    #[pallet::storage]
+    pub type ExpiryPollAsset<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AssetId, OptionQuery>;
+
+    /// Scheduled pre-expiry warnings, keyed by the block in which they are emitted
+    ///
+    /// Each entry is the asset, the holder and the expiry block of a claimed batch.
+    #[pallet::storage]
+    pub type ExpiryWarnings<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<
+            (T::AssetId, T::AccountId, BlockNumberFor<T>),
+            ConstU32<MAX_EXPIRY_WARNINGS_PER_BLOCK>,
+        >,
+        ValueQuery,
+    >;
+
+    /// Number and total amount of the batches expired in the current block, per asset
+    ///
+    /// Summarized into `ExpiredBatch` events and cleared in `on_finalize`.
+    #[pallet::storage]
+    pub type BlockExpirations<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, T::AssetId, (u32, BalanceOf<T, I>), ValueQuery>;
+
+    /// Burn statistics per asset, claim period and category
+    ///
+    /// Only burns tagged with a category are counted.
+    #[pallet::storage]
+    #[pallet::getter(fn category_stats)]
+    pub type BurnCategoryStats<T: Config<I>, I: 'static = ()> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AssetId>,
+            NMapKey<Twox64Concat, u64>,  // claim period
+            NMapKey<Twox64Concat, BurnCategory>,
+        ),
+        CategoryStatsOf<T, I>,
+        ValueQuery,
+    >;
+
+    /// Tokens locked with `commit_tokens`, released back into `Balances` once unlocked
+    #[pallet::storage]
+    #[pallet::getter(fn commitments)]
+    pub type Commitments<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<CommitmentOf<T, I>, ConstU32<MAX_COMMITMENTS>>,
+        ValueQuery,
+    >;
+
+    /// Id that will be assigned to the next burn
+    #[pallet::storage]
+    #[pallet::getter(fn next_burn_id)]
+    pub type NextBurnId<T: Config<I>, I: 'static = ()> = StorageValue<_, BurnId, ValueQuery>;
+
+    /// Receipts of all burns, by burn id
+    #[pallet::storage]
+    #[pallet::getter(fn burn_receipt)]
+    pub type BurnReceipts<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, BurnId, BurnReceiptOf<T, I>, OptionQuery>;
+
+    /// Tickets of burns made with `burn_ticket`, by burn id
+    #[pallet::storage]
+    #[pallet::getter(fn ticket)]
+    pub type Tickets<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, BurnId, Ticket<BlockNumberFor<T>>, OptionQuery>;
+
+    /// Most recent senders of burns to each recipient, latest first
+    ///
+    /// When the list is full, the supporter with the oldest burn is dropped.
+    #[pallet::storage]
+    #[pallet::getter(fn recent_supporters)]
+    pub type RecentSupporters<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<SupporterOf<T, I>, ConstU32<MAX_RECENT_SUPPORTERS>>,
+        ValueQuery,
+    >;
+
+    /// Senders that burned the most to each recipient, largest volume first
+    ///
+    /// When the list is full, a sender burning more than the smallest volume
+    /// replaces it.
+    #[pallet::storage]
+    #[pallet::getter(fn top_supporters)]
+    pub type TopSupporters<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<TopSupporterOf<T, I>, ConstU32<MAX_TOP_SUPPORTERS>>,
+        ValueQuery,
+    >;
+
+    /// Rings of burns reported by the ring-detection worker, awaiting review
+    #[pallet::storage]
+    #[pallet::getter(fn suspicion_reports)]
+    pub type SuspicionReports<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BoundedVec<SuspicionReportOf<T, I>, ConstU32<MAX_SUSPICION_REPORTS>>, ValueQuery>;
+
+    /// Disputes raised on burns, by burn id
+    #[pallet::storage]
+    #[pallet::getter(fn burn_disputes)]
+    pub type BurnDisputes<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Twox64Concat,
+        BurnId,
+        BoundedVec<BurnDisputeOf<T>, ConstU32<MAX_DISPUTES_PER_BURN>>,
+        ValueQuery,
+    >;
+
+    /// Reputation staked on boosted burns until released or slashed, by burn id
+    #[pallet::storage]
+    #[pallet::getter(fn burn_boost)]
+    pub type BurnBoosts<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, BurnId, BurnBoostOf<T, I>, OptionQuery>;
+
+    /// Budgets approved by owners for operators: asset -> owner -> operator -> approval
+    #[pallet::storage]
+    #[pallet::getter(fn approval)]
+    pub type Approvals<T: Config<I>, I: 'static = ()> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AssetId>,
+            NMapKey<Blake2_128Concat, T::AccountId>,  // owner
+            NMapKey<Blake2_128Concat, T::AccountId>,  // operator
+        ),
+        ApprovalOf<T, I>,
+        OptionQuery,
+    >;
+
+    /// Id that will be assigned to the next registered service
+    #[pallet::storage]
+    #[pallet::getter(fn next_service_id)]
+    pub type NextServiceId<T: Config<I>, I: 'static = ()> = StorageValue<_, ServiceId, ValueQuery>;
+
+    /// Services registered by providers, by service id
+    #[pallet::storage]
+    #[pallet::getter(fn service)]
+    pub type Services<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, ServiceId, ServiceOf<T, I>, OptionQuery>;
+
+    /// Id that will be assigned to the next invoice
+    #[pallet::storage]
+    #[pallet::getter(fn next_invoice_id)]
+    pub type NextInvoiceId<T: Config<I>, I: 'static = ()> = StorageValue<_, InvoiceId, ValueQuery>;
+
+    /// Open invoices, by invoice id
+    #[pallet::storage]
+    #[pallet::getter(fn invoice)]
+    pub type Invoices<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, InvoiceId, InvoiceOf<T, I>, OptionQuery>;
+
+    /// Invoices closed by `on_initialize`, keyed by their expiry block
+    ///
+    /// Invoices paid in full before are skipped.
+    #[pallet::storage]
+    pub type InvoiceExpiries<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<InvoiceId, ConstU32<MAX_INVOICE_EXPIRIES_PER_BLOCK>>,
+        ValueQuery,
+    >;
+
+    /// Number of payment intents created so far, part of the next intent's id
+    #[pallet::storage]
+    pub type PaymentIntentCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u64, ValueQuery>;
+
+    /// Payment intents until their expiry, by intent id
+    #[pallet::storage]
+    #[pallet::getter(fn payment_intent)]
+    pub type PaymentIntents<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, PaymentIntentId, PaymentIntentOf<T, I>, OptionQuery>;
+
+    /// Payment intents removed by `on_initialize`, keyed by their expiry block
+    #[pallet::storage]
+    pub type PaymentIntentExpiries<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<PaymentIntentId, ConstU32<MAX_INTENT_EXPIRIES_PER_BLOCK>>,
+        ValueQuery,
+    >;
+
+    /// Members of each organization account
+    #[pallet::storage]
+    #[pallet::getter(fn organization)]
+    pub type Organizations<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        OrganizationMembersOf<T, I>,
+        OptionQuery,
+    >;
+
+    /// Verified charities of each asset, with the block they were verified in
+    #[pallet::storage]
+    #[pallet::getter(fn verified_charity)]
+    pub type VerifiedCharities<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BlockNumberFor<T>,
+        OptionQuery,
+    >;
+
+    /// Members of each household, by claimer
+    #[pallet::storage]
+    #[pallet::getter(fn household)]
+    pub type Households<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<T::AccountId, T::MaxHouseholdMembers>,
+        ValueQuery,
+    >;
+
+    /// Claimer of the household each member joined
+    #[pallet::storage]
+    #[pallet::getter(fn household_of)]
+    pub type HouseholdOf<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AssetId, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+    /// How the recognition of each burn to an organization was split, by burn id
+    #[pallet::storage]
+    #[pallet::getter(fn organization_split)]
+    pub type OrganizationSplits<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Twox64Concat, BurnId, OrganizationSplitOf<T, I>, OptionQuery>;
+
+    /// Role attestations: asset -> role -> subject -> attester -> block of the attestation
+    #[pallet::storage]
+    #[pallet::getter(fn attestation)]
+    pub type Attestations<T: Config<I>, I: 'static = ()> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AssetId>,
+            NMapKey<Blake2_128Concat, RoleOf<T, I>>,
+            NMapKey<Blake2_128Concat, T::AccountId>,  // subject
+            NMapKey<Blake2_128Concat, T::AccountId>,  // attester
+        ),
+        BlockNumberFor<T>,
+        OptionQuery,
+    >;
+
+    /// Number of attesters of each role of an account: asset -> role -> subject -> count
+    #[pallet::storage]
+    #[pallet::getter(fn attestation_count)]
+    pub type AttestationCounts<T: Config<I>, I: 'static = ()> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AssetId>,
+            NMapKey<Blake2_128Concat, RoleOf<T, I>>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        u32,
+        ValueQuery,
+    >;
+
+    /// Declared bonus periods of each asset, ordered by start block
+    #[pallet::storage]
+    #[pallet::getter(fn bonus_periods)]
+    pub type BonusPeriods<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        BoundedVec<BonusPeriodOf<T>, ConstU32<MAX_BONUS_PERIODS>>,
+        ValueQuery,
+    >;
+
+    /// Emission schedule of each asset, ordered by start period
+    #[pallet::storage]
+    #[pallet::getter(fn emission_schedule)]
+    pub type EmissionSchedules<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, EmissionScheduleOf<T, I>, ValueQuery>;
+
+    /// Velocity policy of each asset, if its issuance follows spending
+    #[pallet::storage]
+    #[pallet::getter(fn velocity_policy)]
+    pub type VelocityPolicies<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, VelocityPolicy, OptionQuery>;
+
+    /// Supply cap of each asset, if its circulating supply is bounded
+    #[pallet::storage]
+    #[pallet::getter(fn supply_cap)]
+    pub type SupplyCaps<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, SupplyCapOf<T, I>, OptionQuery>;
+
+    /// Claimed and burned totals of each asset's current period and its UBI amount adjustment
+    #[pallet::storage]
+    #[pallet::getter(fn velocity)]
+    pub type Velocity<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, VelocityStateOf<T, I>, ValueQuery>;
+
+    /// Raw key of the last reputation entry checked by the `on_idle` pruning
+    #[pallet::storage]
+    pub type PruneCursor<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BoundedVec<u8, ConstU32<MAX_PRUNE_CURSOR_LEN>>, OptionQuery>;
+
+    /// Unsigned burns sent by each account: claim period and count in that period
+    #[pallet::storage]
+    pub type UnsignedBurns<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        (u64, u32),
+        ValueQuery,
+    >;
+
+    /// Idempotency keys of each account's recent burns, with the block they can be used again
+    #[pallet::storage]
+    pub type IdempotencyKeys<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<(H256, BlockNumberFor<T>), T::MaxIdempotencyKeys>,
+        ValueQuery,
+    >;
+
+    /// Next nonce of each account's unsigned burns
+    #[pallet::storage]
+    #[pallet::getter(fn burn_nonce)]
+    pub type BurnNonces<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AssetId, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+    /// Genesis configuration: community assets that exist from block zero
+    ///
+    /// `balances` and `reputations` import the state of another chain, e.g. the
+    /// `genesis` section written by the node's `export-ubi-state` command.
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+        /// Assets created with the default parameters from `Config`, with their owner
+        pub assets: Vec<(T::AssetId, T::AccountId)>,
+        /// Parameters replacing the `Config` defaults of genesis assets
+        pub asset_parameters: Vec<(T::AssetId, AssetParametersOf<T, I>)>,
+        /// Token batches of accounts in genesis assets, at most `MAX_BATCHES` each
+        pub balances: Vec<GenesisBalanceOf<T, I>>,
+        /// Reputations of accounts in genesis assets
+        pub reputations: Vec<GenesisReputationOf<T, I>>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config<I>, I: 'static> BuildGenesisConfig for GenesisConfig<T, I> {
+        fn build(&self) {
+            for (asset_id, owner) in &self.assets {
+                assert!(!Assets::<T, I>::contains_key(asset_id), "Duplicate asset in genesis");
+                Assets::<T, I>::insert(
+                    asset_id,
+                    AssetDetails { owner: owner.clone(), params: Pallet::<T, I>::default_parameters() },
+                );
+            }
+            assert!(self.assets.len() <= T::MaxAssets::get() as usize, "More genesis assets than `MaxAssets`");
+            AssetCount::<T, I>::put(self.assets.len() as u32);
+
+            for (asset_id, params) in &self.asset_parameters {
+                assert!(
+                    Pallet::<T, I>::ensure_valid_parameters(params).is_ok(),
+                    "Invalid genesis asset parameters"
+                );
+                Assets::<T, I>::mutate(asset_id, |details| {
+                    details.as_mut().expect("Genesis parameters of an unknown asset").params = params.clone();
+                });
+            }
+
+            for (asset_id, who, batches) in &self.balances {
+                assert!(Assets::<T, I>::contains_key(asset_id), "Genesis balance of an unknown asset");
+                assert!(!Balances::<T, I>::contains_key(asset_id, who), "Duplicate balance in genesis");
+                let batches: BoundedVec<_, ConstU32<MAX_BATCHES>> =
+                    batches.clone().try_into().expect("Too many batches in genesis balance");
+                let total = batches
+                    .iter()
+                    .map(|b| b.amount)
+                    .fold(Zero::zero(), |acc: BalanceOf<T, I>, x| acc.saturating_add(x));
+                TotalSupply::<T, I>::mutate(asset_id, |supply| *supply = supply.saturating_add(total));
+                Balances::<T, I>::insert(asset_id, who, batches);
+            }
+
+            for (asset_id, who, reputation) in &self.reputations {
+                assert!(Assets::<T, I>::contains_key(asset_id), "Genesis reputation of an unknown asset");
+                Pallet::<T, I>::insert_reputation(*asset_id, who, reputation.clone());
+            }
+        }
+    }
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config<I>, I: 'static = ()> {
+        /// Tokens were claimed from UBI
+        ///
+        /// `period` is the claim period index and `remaining` the spendable balance after the claim.
+        Claimed {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            amount: BalanceOf<T, I>,
+            periods: u32,
+            expires_at: BlockNumberFor<T>,
+            mode: ClaimMode,
+            period: u64,
+            remaining: BalanceOf<T, I>,
+        },
+        /// Tokens were burned (payment made)
+        ///
+        /// `remaining` is the sender's spendable balance after the burn and `sender_weight` the
+        /// weight applied to the recipient's reputation (1000 = 1.0).
+        Burned {
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            category: Option<BurnCategory>,
+            reference: Option<H256>,
+            period: u64,
+            remaining: BalanceOf<T, I>,
+            sender_weight: u32,
+        },
+        /// Tokens expired and were removed
+        ///
+        /// `remaining` is the spendable balance left after the removal.
+        Expired {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            amount: BalanceOf<T, I>,
+            period: u64,
+            remaining: BalanceOf<T, I>,
+        },
+        /// Summary of all batches of an asset that expired in this block
+        ///
+        /// Emitted in `on_finalize`, after the per-account `Expired` events.
+        ExpiredBatch {
+            asset_id: T::AssetId,
+            count: u32,
+            total: BalanceOf<T, I>,
+        },
+        /// Tokens will expire within one claim period unless they are spent
+        ExpiringSoon {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            amount: BalanceOf<T, I>,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// A new community asset was created
+        AssetCreated {
+            asset_id: T::AssetId,
+            owner: T::AccountId,
+        },
+        /// The parameters of a community asset were changed by its owner
+        AssetParametersUpdated {
+            asset_id: T::AssetId,
+        },
+        /// The emission schedule of an asset was replaced
+        EmissionScheduleSet {
+            asset_id: T::AssetId,
+            steps: u32,
+        },
+        /// The velocity policy of an asset was set or removed
+        VelocityPolicySet {
+            asset_id: T::AssetId,
+            policy: Option<VelocityPolicy>,
+        },
+        /// The supply cap of an asset was set or removed
+        SupplyCapSet {
+            asset_id: T::AssetId,
+            cap: Option<SupplyCapOf<T, I>>,
+        },
+        /// A claim was reduced because the supply is close to its cap
+        ClaimThrottled {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            requested: BalanceOf<T, I>,
+            granted: BalanceOf<T, I>,
+        },
+        /// A closed period moved the UBI amount adjustment of an asset
+        VelocityAdjusted {
+            asset_id: T::AssetId,
+            period: u64,
+            velocity: u32,
+            adjustment: u32,
+        },
+        /// A bonus period was declared for an asset
+        BonusPeriodDeclared {
+            asset_id: T::AssetId,
+            period: BonusPeriodOf<T>,
+        },
+        /// The recipient rejected a burn and its reputation effects were reversed
+        BurnRejected {
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// The recipient acknowledged a failed transaction and the burn's reputation effects were reversed
+        BurnRefunded {
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// A party of a burn flagged it as disputed
+        BurnFlagged {
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            flagged_by: T::AccountId,
+            reason_hash: H256,
+        },
+        /// An owner allowed an operator to burn up to `max_amount` on their behalf
+        ApprovalSet {
+            asset_id: T::AssetId,
+            owner: T::AccountId,
+            operator: T::AccountId,
+            max_amount: BalanceOf<T, I>,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// An owner revoked the approval of an operator
+        ApprovalRevoked {
+            asset_id: T::AssetId,
+            owner: T::AccountId,
+            operator: T::AccountId,
+        },
+        /// Claims were processed in bulk; ineligible accounts were skipped
+        BatchClaimed {
+            asset_id: T::AssetId,
+            claimed: u32,
+            skipped: u32,
+        },
+        /// Periods beyond the backlog were claimed for reputation
+        ExtraBacklogClaimed {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            periods: u32,
+            cost: BalanceOf<T, I>,
+        },
+        /// Tokens were burned to protect a claim streak
+        StreakFrozen {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            cost: BalanceOf<T, I>,
+            streak_freeze: u32,
+        },
+        /// An account set or changed its profile
+        ProfileSet {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+        },
+        /// An account removed its profile
+        ProfileCleared {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+        },
+        /// An account changed its preferences
+        PreferencesSet {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+        },
+        /// An account started, changed or ended a vacation
+        VacationSet {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            start: u64,
+            end: u64,
+        },
+        /// Tokens were locked in exchange for a later expiration
+        TokensCommitted {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            amount: BalanceOf<T, I>,
+            unlocks_at: BlockNumberFor<T>,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// Committed tokens were unlocked and can be burned again
+        CommitmentReleased {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            amount: BalanceOf<T, I>,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// A provider registered a service
+        ServiceRegistered {
+            service_id: ServiceId,
+            asset_id: T::AssetId,
+            provider: T::AccountId,
+            price: BalanceOf<T, I>,
+        },
+        /// A provider removed a service
+        ServiceUnregistered {
+            service_id: ServiceId,
+        },
+        /// A service was paid for by burning its listed price
+        ServicePaid {
+            service_id: ServiceId,
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            payer: T::AccountId,
+            provider: T::AccountId,
+            price: BalanceOf<T, I>,
+        },
+        /// A payee issued an invoice
+        InvoiceCreated {
+            invoice_id: InvoiceId,
+            asset_id: T::AssetId,
+            payee: T::AccountId,
+            total: BalanceOf<T, I>,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// A burn paid part of an invoice, which stays open
+        ///
+        /// `paid` is the amount paid so far, including this burn.
+        InvoicePartiallyPaid {
+            invoice_id: InvoiceId,
+            burn_id: BurnId,
+            payer: T::AccountId,
+            amount: BalanceOf<T, I>,
+            paid: BalanceOf<T, I>,
+        },
+        /// A burn paid the rest of an invoice, which is closed
+        InvoicePaid {
+            invoice_id: InvoiceId,
+            burn_id: BurnId,
+            payer: T::AccountId,
+            amount: BalanceOf<T, I>,
+        },
+        /// An invoice expired before it was paid in full and is closed
+        InvoiceExpired {
+            invoice_id: InvoiceId,
+            paid: BalanceOf<T, I>,
+        },
+        /// A merchant created a payment intent
+        PaymentIntentCreated {
+            intent_id: PaymentIntentId,
+            asset_id: T::AssetId,
+            merchant: T::AccountId,
+            amount: BalanceOf<T, I>,
+            metadata_hash: H256,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// A burn paid a payment intent
+        PaymentIntentFulfilled {
+            intent_id: PaymentIntentId,
+            burn_id: BurnId,
+            payer: T::AccountId,
+        },
+        /// A payment intent expired without being paid
+        PaymentIntentExpired {
+            intent_id: PaymentIntentId,
+        },
+        /// A burn recorded a ticket commitment
+        TicketIssued {
+            burn_id: BurnId,
+            commitment: H256,
+        },
+        /// The recipient of a burn redeemed its ticket
+        TicketRedeemed {
+            burn_id: BurnId,
+            recipient: T::AccountId,
+        },
+        /// An account queued a snapshot of its reputation for other chains
+        ReputationExported {
+            sequence: u64,
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            score: BalanceOf<T, I>,
+        },
+        /// A scheduled bonus period was cancelled
+        BonusPeriodCancelled {
+            asset_id: T::AssetId,
+            start: BlockNumberFor<T>,
+        },
+        /// A matching bonus of a campaign was paid out of the community pool
+        CommunityPoolGranted {
+            asset_id: T::AssetId,
+            campaign: H256,
+            who: T::AccountId,
+            grant: PoolGrant<BalanceOf<T, I>>,
+        },
+        /// A sender staked reputation on a burn to boost its recipient
+        BurnBoosted {
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            stake: BalanceOf<T, I>,
+            unlocks_at: BlockNumberFor<T>,
+        },
+        /// The stake of a boosted burn was given back to its sender
+        BoostReleased {
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            stake: BalanceOf<T, I>,
+        },
+        /// The stake of a disputed boosted burn was slashed
+        BoostSlashed {
+            burn_id: BurnId,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            stake: BalanceOf<T, I>,
+        },
+        /// An account vouched for another, which gained `credit` reputation
+        Vouched {
+            asset_id: T::AssetId,
+            voucher: T::AccountId,
+            target: T::AccountId,
+            credit: BalanceOf<T, I>,
+        },
+        /// An account attested a role to another
+        Attested {
+            asset_id: T::AssetId,
+            attester: T::AccountId,
+            subject: T::AccountId,
+            role: RoleOf<T, I>,
+        },
+        /// An attester revoked a role attestation
+        AttestationRevoked {
+            asset_id: T::AssetId,
+            attester: T::AccountId,
+            subject: T::AccountId,
+            role: RoleOf<T, I>,
+        },
+        /// An account registered as an organization or changed its members
+        OrganizationSet {
+            asset_id: T::AssetId,
+            organization: T::AccountId,
+            members: u32,
+        },
+        /// An organization went back to being a plain account
+        OrganizationRemoved {
+            asset_id: T::AssetId,
+            organization: T::AccountId,
+        },
+        /// An account consented to be claimed for by a household's claimer
+        HouseholdJoined {
+            asset_id: T::AssetId,
+            claimer: T::AccountId,
+            member: T::AccountId,
+        },
+        /// An account left its household
+        HouseholdLeft {
+            asset_id: T::AssetId,
+            claimer: T::AccountId,
+            member: T::AccountId,
+        },
+        /// A claimer claimed for its household; members that could not claim were skipped
+        HouseholdClaimed {
+            asset_id: T::AssetId,
+            claimer: T::AccountId,
+            claimed: u32,
+            skipped: u32,
+        },
+        /// An account was verified as a charity
+        CharityVerified {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+        },
+        /// An account lost its verified charity status
+        CharityRevoked {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+        },
+        /// The reputation records of a dormant account were removed
+        DormantPruned {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+        },
+        /// A ring of burns was queued for review
+        SuspicionReported {
+            asset_id: T::AssetId,
+            kind: SuspicionKind,
+            accounts: BoundedVec<T::AccountId, ConstU32<MAX_RING_LENGTH>>,
+        },
+        /// A suspicion report was reviewed and removed
+        SuspicionDismissed {
+            asset_id: T::AssetId,
+            accounts: BoundedVec<T::AccountId, ConstU32<MAX_RING_LENGTH>>,
+        },
+        /// A reputation or issuance parameter was changed
+        ParameterSet {
+            parameter: UbiParameter,
+        },
+        /// A reputation or issuance parameter was restored to its default
+        ParameterReset {
+            parameter: UbiParameter,
+        },
+        /// A reaped account's tokens and claim state in an asset were removed
+        AccountReaped {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            /// Tokens removed from the supply, including committed ones
+            amount: BalanceOf<T, I>,
+        },
+        /// Expired batches of other accounts were purged by a sweeper
+        ExpiredSwept {
+            asset_id: T::AssetId,
+            sweeper: T::AccountId,
+            accounts: u32,
+            amount: BalanceOf<T, I>,
+        },
+        /// The legacy `UniqueRecipients` map was drained by the version 1 migration
+        UniqueRecipientsDrained {
+            /// Recipients moved into their sender's set
+            moved: u32,
+            /// Recipients dropped because their sender's set was full
+            dropped: u32,
+        },
+        /// An account claimed an asset for the first time
+        ///
+        /// Emitted before its `Claimed` event. Pruned and reaped accounts lose
+        /// their claim history and are onboarded again with their next claim.
+        AccountOnboarded {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            /// Welcome tokens included in the claimed amount
+            bonus: BalanceOf<T, I>,
+            /// Welcome reputation credited to the account
+            reputation: BalanceOf<T, I>,
+        },
+        /// An account joined with a referral; both sides gained `bonus` reputation
+        Referred {
+            asset_id: T::AssetId,
+            who: T::AccountId,
+            referrer: T::AccountId,
+            bonus: BalanceOf<T, I>,
+        },
+    }
+
+    /// Errors that can occur in this pallet
+    #[pallet::error]
+    pub enum Error<T, I = ()> {
+        /// No claimable periods available (must wait for next period)
+        NothingToClaim,
+        /// The reputation score does not cover the cost of the extra backlog, a boost stake or a vouch
+        InsufficientReputation,
+        /// Extra backlog needs 1 to `MaxExtraBacklogPeriods` periods
+        InvalidExtraBacklog,
+        /// Streak freezes would cover more than `MAX_STREAK_FREEZE` periods
+        StreakFreezeLimit,
+        /// The vacation is longer than `MaxVacationPeriods`, or there is none to end
+        InvalidVacation,
+        /// The previous vacation ended less than `VacationCooldownPeriods` ago
+        VacationCooldown,
+        /// The account is on vacation and cannot claim
+        OnVacation,
+        /// The recipient does not accept burns this small
+        BelowRecipientMinimum,
+        /// The signature does not authorize the burn
+        InvalidBurnAuthorization,
+        /// The period has not passed yet or an account is not indexed for it
+        InvalidExpirySweep,
+        /// Display names must be non-empty UTF-8
+        InvalidDisplayName,
+        /// The reputation score is below `MinProfileReputation` or `MinAttesterReputation`
+        ReputationTooLow,
+        /// The account has no profile
+        NoProfile,
+        /// Insufficient balance for burn operation
+        InsufficientBalance,
+        /// Cannot burn to yourself
+        CannotBurnToSelf,
+        /// Amount must be greater than zero
+        AmountMustBePositive,
+        /// Too many token batches (should not happen with lazy cleanup)
+        TooManyBatches,
+        /// The grants exceed the asset's community pool
+        InsufficientCommunityPool,
+        /// Token grants only go to accounts that have claimed
+        NotAClaimant,
+        /// The burn has no unreleased boost
+        UnknownBoost,
+        /// The boost stake is still locked
+        BoostLocked,
+        /// Only disputed burns can have their boost slashed
+        BurnNotDisputed,
+        /// Accounts cannot vouch for themselves
+        CannotVouchForSelf,
+        /// The caller already vouched for the target
+        AlreadyVouched,
+        /// The caller vouched for `MaxVouchesPerPeriod` accounts this period
+        TooManyVouches,
+        /// Role tags must be non-empty lowercase ASCII letters, digits and dashes
+        InvalidRole,
+        /// Accounts cannot attest roles to themselves
+        CannotAttestSelf,
+        /// The caller already attested this role to the account
+        AlreadyAttested,
+        /// The caller did not attest this role to the account
+        UnknownAttestation,
+        /// Organizations need members with positive shares, each listed once and not the
+        /// organization itself
+        InvalidOrganization,
+        /// The account is not an organization
+        NotAnOrganization,
+        /// Organizations cannot receive boosted burns
+        OrganizationRecipient,
+        /// The account already belongs to a household
+        AlreadyInHousehold,
+        /// The household has `MaxHouseholdMembers` members
+        HouseholdFull,
+        /// The account does not belong to a household
+        NotInHousehold,
+        /// The account is already a verified charity
+        AlreadyVerifiedCharity,
+        /// The account is not a verified charity
+        NotVerifiedCharity,
+        /// Arithmetic overflow
+        Overflow,
+        /// The asset does not exist
+        UnknownAsset,
+        /// An asset with this id already exists
+        AssetAlreadyExists,
+        /// `T::MaxAssets` assets exist already
+        TooManyAssets,
+        /// Only the asset owner can do this
+        NotAssetOwner,
+        /// Asset amount, claim period and expiration must be non-zero
+        InvalidAssetParameters,
+        /// None of the listed accounts had expired tokens
+        NothingToSweep,
+        /// None of the listed accounts is dormant
+        NothingToPrune,
+        /// Bonus period must end after it starts, in the future, with a 100-500% multiplier
+        InvalidBonusPeriod,
+        /// The bonus period overlaps with an already declared one
+        OverlappingBonusPeriod,
+        /// Too many bonus periods are scheduled for the asset
+        TooManyBonusPeriods,
+        /// No bonus period starts at the given block
+        UnknownBonusPeriod,
+        /// No burn with this id exists
+        UnknownBurn,
+        /// Only the recipient of a burn can do this
+        NotBurnRecipient,
+        /// The burn is too old to be rejected
+        RejectionWindowClosed,
+        /// The burn was already rejected
+        BurnAlreadyRejected,
+        /// The burn was already refunded
+        BurnAlreadyRefunded,
+        /// Only the sender or recipient of a burn can do this
+        NotBurnParty,
+        /// The caller already flagged this burn
+        AlreadyFlagged,
+        /// The operator has no approval from the owner
+        NoApproval,
+        /// The approval is expired
+        ApprovalExpired,
+        /// The amount exceeds what is left of the approval
+        AllowanceExceeded,
+        /// The approval must be for another account, a positive amount and expire in the future
+        InvalidApproval,
+        /// No service with this id exists
+        UnknownService,
+        /// The service belongs to another provider
+        NotServiceProvider,
+        /// Service name must not be empty and price must be positive
+        InvalidService,
+        /// No open invoice with this id
+        UnknownInvoice,
+        /// The invoice is payable to another account
+        NotInvoicePayee,
+        /// Invoice totals must be positive and expiry blocks in the future
+        InvalidInvoice,
+        /// The payment exceeds what is left to pay on the invoice
+        InvoiceOverpaid,
+        /// `MAX_INVOICE_EXPIRIES_PER_BLOCK` invoices already expire in this block
+        TooManyInvoices,
+        /// Commitment needs a positive amount and 1 to `MaxCommitPeriods` periods
+        InvalidCommitment,
+        /// Too many open commitments
+        TooManyCommitments,
+        /// The claim nonce does not meet the proof-of-work difficulty
+        InvalidClaimProof,
+        /// The account sent its maximum of unsigned burns in this claim period
+        TooManyUnsignedBurns,
+        /// The burn nonce is not the sender's next one
+        InvalidBurnNonce,
+        /// The burns do not form a recent ring
+        InvalidRing,
+        /// The ring is already awaiting review
+        RingAlreadyReported,
+        /// Too many suspicion reports are awaiting review
+        SuspicionQueueFull,
+        /// No suspicion report at this index
+        UnknownReport,
+        /// The parameter value is out of range or conflicts with another parameter
+        InvalidParameter,
+        /// Emission steps must have increasing start periods and non-zero amounts
+        InvalidEmissionSchedule,
+        /// Velocity bounds must be ordered, non-zero and at most `MAX_VELOCITY_ADJUSTMENT`
+        InvalidVelocityPolicy,
+        /// Accounts cannot refer themselves
+        CannotReferSelf,
+        /// Only an account's first claim can name a referrer
+        AlreadyOnboarded,
+        /// The referrer never claimed the asset
+        UnknownReferrer,
+        /// The referrer referred `MaxReferralsPerPeriod` accounts this period
+        TooManyReferrals,
+        /// The burn was submitted for inclusion up to a block that has passed
+        BurnDeadlinePassed,
+        /// The sender used the idempotency key within `IdempotencyWindow`
+        DuplicateIdempotencyKey,
+        /// The sender has `MaxIdempotencyKeys` idempotency keys in use
+        TooManyIdempotencyKeys,
+        /// Payment intent amounts must be positive and expiry blocks in the future
+        InvalidPaymentIntent,
+        /// `MAX_INTENT_EXPIRIES_PER_BLOCK` payment intents already expire in this block
+        TooManyPaymentIntents,
+        /// The burn does not pay the referenced intent's amount to its merchant
+        PaymentIntentMismatch,
+        /// The referenced payment intent was already paid
+        PaymentIntentFulfilled,
+        /// The burn was not made with `burn_ticket`
+        NoTicket,
+        /// The secret does not hash to the ticket's commitment
+        InvalidTicketSecret,
+        /// The ticket was already redeemed
+        TicketAlreadyRedeemed,
+        /// The account already exported its reputation this claim period
+        AlreadyExported,
+        /// `MaxOutboundSnapshots` snapshots are waiting for a bridge
+        OutboundQueueFull,
+        /// The supply cap ceiling must be positive
+        InvalidSupplyCap,
+        /// The asset's supply is at its cap; claims resume once it falls
+        SupplyCapReached,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let warnings = ExpiryWarnings::<T, I>::take(n);
+
+            for (asset_id, who, expires_at) in warnings.iter() {
+                // Only warn about what is left of the batch
+                let amount = Balances::<T, I>::get(asset_id, who)
+                    .iter()
+                    .filter(|b| b.expires_at == *expires_at)
+                    .map(|b| b.amount)
+                    .fold(Zero::zero(), |acc: BalanceOf<T, I>, x| acc.saturating_add(x));
+
+                if !amount.is_zero() {
+                    Self::deposit_event(Event::ExpiringSoon {
+                        asset_id: *asset_id,
+                        who: who.clone(),
+                        amount,
+                        expires_at: *expires_at,
+                    });
+                }
+            }
+
+            let expired = InvoiceExpiries::<T, I>::take(n);
+            for invoice_id in expired.iter() {
+                if let Some(invoice) = Invoices::<T, I>::take(invoice_id) {
+                    Self::deposit_event(Event::InvoiceExpired { invoice_id: *invoice_id, paid: invoice.paid });
+                }
+            }
+
+            let expired_intents = PaymentIntentExpiries::<T, I>::take(n);
+            for intent_id in expired_intents.iter() {
+                let intent = PaymentIntents::<T, I>::take(intent_id);
+                if intent.is_some_and(|intent| intent.fulfilled_by.is_none()) {
+                    Self::deposit_event(Event::PaymentIntentExpired { intent_id: *intent_id });
+                }
+            }
+            let expired = expired.len().saturating_add(expired_intents.len()) as u64;
+
+            // The `on_finalize` summary, usually of a single asset
+            T::DbWeight::get().reads_writes(
+                4u64.saturating_add(warnings.len() as u64).saturating_add(expired),
+                4u64.saturating_add(expired),
+            )
+        }
+
+        fn on_finalize(_n: BlockNumberFor<T>) {
+            for (asset_id, (count, total)) in BlockExpirations::<T, I>::drain() {
+                Self::deposit_event(Event::ExpiredBatch { asset_id, count, total });
+            }
+        }
+
+        fn on_poll(n: BlockNumberFor<T>, weight: &mut WeightMeter) {
+            let mut meter = WeightMeter::with_limit(T::ExpiryPollWeight::get().min(weight.remaining()));
+            Self::poll_expiry_index(n, &mut meter);
+            weight.consume(meter.consumed());
+        }
+
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::prune_dormant_idle(remaining_weight)
+        }
+
+        fn offchain_worker(_n: BlockNumberFor<T>) {
+            // Other nodes would only submit the same reports again
+            if sp_io::offchain::is_validator() {
+                Self::scan_for_rings();
+                Self::submit_expiry_sweeps();
+            }
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::ClaimPowDifficulty::get() <= MAX_CLAIM_POW_DIFFICULTY,
+                "ClaimPowDifficulty must be at most MAX_CLAIM_POW_DIFFICULTY"
+            );
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        /// Claim your daily UBI tokens (UNSIGNED - no gas fees!)
+        ///
+        /// Each wallet can claim once per period of the given asset (default: 1 day).
+        /// If you miss days, you can claim up to the asset's backlog of periods.
+        /// Claimed tokens expire after the asset's expiration if not used; an
+        /// `ExpiringSoon` event is emitted one period before that.
+        ///
+        /// This is an UNSIGNED transaction - anyone can submit it without paying fees.
+        /// The `account` parameter specifies who receives the UBI. `nonce` is the
+        /// claim proof-of-work, ignored unless `T::ClaimPowDifficulty` is set.
+        ///
+        /// The weight is refunded down to the batches expired and periods paid out.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidClaimProof` if the nonce does not meet the proof-of-work difficulty
+        /// - `NothingToClaim` if you've already claimed this period and have no backlog
+        #[pallet::call_index(0)]
+        #[pallet::weight(Pallet::<T, I>::claim_weight(MAX_BATCHES, T::MaxBacklogPeriods::get()))]
+        pub fn claim(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            account: T::AccountId,
+            nonce: u64,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+
+            let params = Self::asset_parameters(&asset_id)?;
+            let current_block = frame_system::Pallet::<T>::block_number();
+            Self::ensure_claim_proof(&asset_id, &params, &account, current_block, nonce)?;
+            let weight = Self::do_claim(asset_id, &params, account, current_block, ClaimMode::Standard, 0)?;
+            Ok(Some(weight).into())
+        }
+
+        /// Claim UBI with a shorter expiration in exchange for a bonus (UNSIGNED)
+        ///
+        /// Works like `claim`, but the tokens expire after `T::ShortExpiryRatio`
+        /// of the asset's expiration and `T::ShortExpiryBonus` is added on top,
+        /// rewarding people who spend quickly.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidClaimProof` if the nonce does not meet the proof-of-work difficulty
+        /// - `NothingToClaim` if you've already claimed this period and have no backlog
+        #[pallet::call_index(18)]
+        #[pallet::weight(Pallet::<T, I>::claim_weight(MAX_BATCHES, T::MaxBacklogPeriods::get()))]
+        pub fn claim_short_expiry(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            account: T::AccountId,
+            nonce: u64,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+
+            let params = Self::asset_parameters(&asset_id)?;
+            let current_block = frame_system::Pallet::<T>::block_number();
+            Self::ensure_claim_proof(&asset_id, &params, &account, current_block, nonce)?;
+            let weight = Self::do_claim(asset_id, &params, account, current_block, ClaimMode::ShortExpiry, 0)?;
+            Ok(Some(weight).into())
+        }
+
+        /// Claim UBI for the first time, naming the account that referred you (UNSIGNED)
+        ///
+        /// Works like `claim`, but only for an account's first claim. Both the
+        /// account and its referrer gain up to `T::ReferralBonus` reputation,
+        /// weighted by the referrer's score like the recognition of a burn, so
+        /// fresh accounts gain little from referring each other. An account can
+        /// refer `T::MaxReferralsPerPeriod` accounts per claim period.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidClaimProof` if the nonce does not meet the proof-of-work difficulty
+        /// - `CannotReferSelf` if the referrer is the account
+        /// - `AlreadyOnboarded` if the account claimed or was referred before
+        /// - `UnknownReferrer` if the referrer never claimed the asset
+        /// - `TooManyReferrals` if the referrer reached its referrals of this period
+        /// - `NothingToClaim` if the account cannot claim
+        #[pallet::call_index(51)]
+        #[pallet::weight(
+            Pallet::<T, I>::claim_weight(MAX_BATCHES, T::MaxBacklogPeriods::get())
+                + T::DbWeight::get().reads_writes(6, 4)
+        )]
+        pub fn claim_with_referral(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            account: T::AccountId,
+            referrer: T::AccountId,
+            nonce: u64,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+
+            let params = Self::asset_parameters(&asset_id)?;
+            let current_block = frame_system::Pallet::<T>::block_number();
+            Self::ensure_claim_proof(&asset_id, &params, &account, current_block, nonce)?;
+            Self::ensure_referral(&asset_id, &params, &account, &referrer, current_block)?;
+            let weight = Self::do_claim(asset_id, &params, account.clone(), current_block, ClaimMode::Standard, 0)?;
+
+            let current_period = Self::block_to_period(&params, current_block);
+            ReferralCounts::<T, I>::mutate(asset_id, &referrer, |(period, count)| {
+                if *period != current_period {
+                    *period = current_period;
+                    *count = 0;
+                }
+                *count = count.saturating_add(1);
+            });
+            Referrers::<T, I>::insert(asset_id, &account, &referrer);
+
+            let sender_weight = Self::calculate_sender_weight(Self::reputation_score(&asset_id, &referrer));
+            let max_weight = Economics::<T, I>::get().reputation.max_sender_weight;
+            let bonus = Perbill::from_rational(sender_weight, max_weight) * T::ReferralBonus::get();
+            for who in [&account, &referrer] {
+                Self::mutate_reputation(asset_id, who, |rep| {
+                    Self::apply_elapsed_decay(rep, current_period);
+                    rep.bonus_credit = rep.bonus_credit.saturating_add(bonus);
+                    rep.score = Self::recalculate_score(rep);
+                });
+            }
+
+            Self::deposit_event(Event::Referred { asset_id, who: account, referrer, bonus });
+
+            Ok(Some(weight.saturating_add(T::DbWeight::get().reads_writes(6, 4))).into())
+        }
+
+        /// Claim missed periods beyond the backlog by paying reputation
+        ///
+        /// Works like `claim` for the caller, but pays up to `extra_periods`
+        /// more missed periods than the asset's backlog allows, each costing
+        /// `backlog_cost_per_period` reputation points. Signed, since it spends
+        /// the caller's reputation.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidExtraBacklog` if `extra_periods` is not within 1 to `MaxExtraBacklogPeriods`
+        /// - `NothingToClaim` if the caller cannot claim
+        /// - `InsufficientReputation` if the caller's score does not cover the cost
+        #[pallet::call_index(26)]
+        #[pallet::weight(Pallet::<T, I>::claim_weight(
+            MAX_BATCHES,
+            T::MaxBacklogPeriods::get().saturating_add(T::MaxExtraBacklogPeriods::get())
+        ))]
+        pub fn claim_extra_backlog(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            extra_periods: u32,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            ensure!(
+                extra_periods > 0 && extra_periods <= T::MaxExtraBacklogPeriods::get(),
+                Error::<T, I>::InvalidExtraBacklog
+            );
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let weight = Self::do_claim(asset_id, &params, who, current_block, ClaimMode::Standard, extra_periods)?;
+            Ok(Some(weight).into())
+        }
+
+        /// Claim UBI for several accounts at once (UNSIGNED, or signed by a relayer)
+        ///
+        /// Lets community claim bots process many accounts in one extrinsic.
+        /// Accounts that cannot claim right now are skipped instead of failing
+        /// the whole batch; each claimed account gets its usual `Claimed` event.
+        /// While a claim proof-of-work is required, only signed batches are accepted.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidClaimProof` if unsigned while a claim proof-of-work is required
+        /// - `NothingToClaim` if none of the accounts could claim
+        #[pallet::call_index(16)]
+        #[pallet::weight(
+            Pallet::<T, I>::claim_weight(MAX_BATCHES, T::MaxBacklogPeriods::get())
+                .saturating_mul(accounts.len() as u64)
+        )]
+        pub fn claim_for_many(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            accounts: BoundedVec<T::AccountId, T::MaxClaimBatch>,
+        ) -> DispatchResultWithPostInfo {
+            if ensure_none(origin.clone()).is_ok() {
+                ensure!(T::ClaimPowDifficulty::get() == 0, Error::<T, I>::InvalidClaimProof);
+            } else {
+                ensure_signed(origin)?;
+            }
+
+            let params = Self::asset_parameters(&asset_id)?;
+            let current_block = frame_system::Pallet::<T>::block_number();
+
+            let mut claimed = 0u32;
+            let mut weight = Weight::zero();
+            for who in accounts.iter() {
+                if !Preferences::<T, I>::get(asset_id, who).allow_third_party_claims {
+                    weight.saturating_accrue(Self::claim_weight(0, 0));
+                    continue;
+                }
+                match Self::claim_or_skip(asset_id, &params, who, current_block) {
+                    Ok(claim_weight) => {
+                        claimed = claimed.saturating_add(1);
+                        weight.saturating_accrue(claim_weight);
+                    }
+                    Err(skip_weight) => weight.saturating_accrue(skip_weight),
+                }
+            }
+            ensure!(claimed > 0, Error::<T, I>::NothingToClaim);
+
+            let skipped = (accounts.len() as u32).saturating_sub(claimed);
+            Self::deposit_event(Event::BatchClaimed { asset_id, claimed, skipped });
+
+            Ok(Some(weight).into())
+        }
+
+        /// Burn tokens to a recipient (UNSIGNED - no gas fees!)
+        ///
+        /// This is the ONLY way to "spend" tokens. The recipient does not
+        /// receive any tokens - they only see the burn event. This prevents
+        /// trading and speculation.
+        ///
+        /// Both parties' reputation is updated:
+        /// - Sender: burns_sent increases
+        /// - Recipient: burns_received increases
+        ///
+        /// This is an UNSIGNED transaction - anyone can submit it without paying fees.
+        /// The `from` parameter specifies who is burning tokens.
+        ///
+        /// # Arguments
+        /// - `asset_id`: The community asset being burned
+        /// - `from`: The sender address (who is burning tokens)
+        /// - `to`: The recipient address (for reputation tracking and event)
+        /// - `amount`: Number of tokens to burn
+        /// - `category`: Optional tag of what was paid for, aggregated per claim period
+        /// - `reference`: Optional hash of an off-chain document the burn pays for
+        /// - `nonce`: The sender's next burn nonce, see `burn_nonce`
+        /// - `valid_until`: Optional last block the burn may be included in, so a
+        ///   payment meant for now cannot be included much later
+        /// - `idempotency_key`: Optional key of the payment, e.g. a merchant's order
+        ///   id; another burn of `from` with the same key within
+        ///   `T::IdempotencyWindow` is rejected, so retried submissions cannot pay twice
+        ///
+        /// A `BurnReceipt` is stored under a new burn id, which is part of the event.
+        /// The weight is refunded down to the batches expired and drawn from.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `BurnDeadlinePassed` if the current block is after `valid_until`
+        /// - `DuplicateIdempotencyKey` if `from` used `idempotency_key` recently
+        /// - `TooManyIdempotencyKeys` if `from` has `MaxIdempotencyKeys` keys in use
+        /// - `CannotBurnToSelf` if trying to burn to your own address
+        /// - `AmountMustBePositive` if amount is zero
+        /// - `InsufficientBalance` if you don't have enough tokens
+        /// - `TooManyUnsignedBurns` if `from` sent its maximum of unsigned burns this period
+        /// - `InvalidBurnNonce` if `nonce` is not the sender's next burn nonce
+        #[pallet::call_index(1)]
+        #[pallet::weight(
+            Pallet::<T, I>::burn_weight(MAX_BATCHES, MAX_BATCHES) + T::DbWeight::get().reads_writes(1, 1)
+        )]
+        pub fn burn(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            category: Option<BurnCategory>,
+            reference: Option<H256>,
+            nonce: u64,
+            valid_until: Option<BlockNumberFor<T>>,
+            idempotency_key: Option<H256>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+            ensure!(
+                valid_until.is_none_or(|deadline| frame_system::Pallet::<T>::block_number() <= deadline),
+                Error::<T, I>::BurnDeadlinePassed
+            );
+            if let Some(key) = idempotency_key {
+                Self::use_idempotency_key(&asset_id, &from, key)?;
+            }
+
+            let (_, mut weight) = Self::do_unsigned_burn(asset_id, from, to, amount, category, reference, nonce)?;
+            if idempotency_key.is_some() {
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+            }
+            Ok(Some(weight).into())
+        }
+
+        /// Burn tokens with the sender's off-chain signature (UNSIGNED)
+        ///
+        /// Like `burn`, but the sender proves it authorized the burn:
+        /// `signature` must be `authorization.from`'s signature of the SCALE
+        /// encoded `authorization`, so a relayer can submit the burn without
+        /// holding the sender's key. The authorization must name this pallet,
+        /// this chain's genesis hash, the current spec version and the
+        /// sender's next burn nonce, so the signature cannot be replayed on
+        /// another chain, after a runtime upgrade, as a second burn or as any
+        /// other signed message. Pending authorizations expire with each
+        /// runtime upgrade and must be signed again.
+        ///
+        /// # Errors
+        /// - `InvalidBurnAuthorization` if the authorization is for another
+        ///   context or the signature does not match it
+        /// - Any error of `burn`
+        #[pallet::call_index(32)]
+        #[pallet::weight(
+            Pallet::<T, I>::burn_weight(MAX_BATCHES, MAX_BATCHES)
+                .saturating_add(Weight::from_parts(SIGNATURE_CHECK_WEIGHT, 0))
+        )]
+        pub fn burn_authorized(
+            origin: OriginFor<T>,
+            authorization: BurnAuthorizationOf<T, I>,
+            signature: T::OffchainSignature,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+            ensure!(
+                Self::is_burn_authorized(&authorization, &signature),
+                Error::<T, I>::InvalidBurnAuthorization
+            );
+
+            let BurnAuthorization { asset_id, from, to, amount, category, reference, nonce, .. } = authorization;
+            let (_, weight) = Self::do_unsigned_burn(asset_id, from, to, amount, category, reference, nonce)?;
+            Ok(Some(weight).into())
+        }
+
+        /// Create a new community asset with its own UBI parameters
+        ///
+        /// The account returned by `T::CreateOrigin` becomes the asset owner
+        /// and may later update the parameters. `T::AssetDeposit` is reserved
+        /// from it for as long as the asset exists.
+        ///
+        /// # Errors
+        /// - `AssetAlreadyExists` if the id is taken
+        /// - `TooManyAssets` if `T::MaxAssets` assets exist already
+        /// - `InvalidAssetParameters` if amount, period or expiration is zero
+        /// - The currency's error if the deposit cannot be reserved
+        #[pallet::call_index(2)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+        pub fn create_asset(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            params: AssetParametersOf<T, I>,
+        ) -> DispatchResult {
+            let owner = T::CreateOrigin::ensure_origin(origin)?;
+
+            ensure!(!Assets::<T, I>::contains_key(asset_id), Error::<T, I>::AssetAlreadyExists);
+            ensure!(AssetCount::<T, I>::get() < T::MaxAssets::get(), Error::<T, I>::TooManyAssets);
+            Self::ensure_valid_parameters(&params)?;
+            T::Currency::reserve(&owner, T::AssetDeposit::get())?;
+
+            Assets::<T, I>::insert(asset_id, AssetDetails { owner: owner.clone(), params });
+            AssetCount::<T, I>::mutate(|count| *count = count.saturating_add(1));
+
+            Self::deposit_event(Event::AssetCreated { asset_id, owner });
+
+            Ok(())
+        }
+
+        /// Update the UBI parameters of an asset (owner only)
+        ///
+        /// New parameters apply to future claims; existing batches keep their expiry.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `NotAssetOwner` if the caller does not own the asset
+        /// - `InvalidAssetParameters` if amount, period or expiration is zero
+        #[pallet::call_index(3)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_asset_parameters(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            params: AssetParametersOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_valid_parameters(&params)?;
+
+            Assets::<T, I>::try_mutate(asset_id, |maybe_details| -> DispatchResult {
+                let details = maybe_details.as_mut().ok_or(Error::<T, I>::UnknownAsset)?;
+                ensure!(details.owner == who, Error::<T, I>::NotAssetOwner);
+                details.params = params;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::AssetParametersUpdated { asset_id });
+
+            Ok(())
+        }
+
+        /// Replace the emission schedule of an asset (owner only)
+        ///
+        /// Each step sets the per-period UBI amount from its start period on.
+        /// An empty schedule goes back to the asset's `ubi_amount`.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `NotAssetOwner` if the caller does not own the asset
+        /// - `InvalidEmissionSchedule` if start periods do not increase or an amount is zero
+        #[pallet::call_index(24)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_emission_schedule(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            schedule: EmissionScheduleOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let details = Assets::<T, I>::get(asset_id).ok_or(Error::<T, I>::UnknownAsset)?;
+            ensure!(details.owner == who, Error::<T, I>::NotAssetOwner);
+            ensure!(
+                schedule.iter().all(|step| !step.ubi_amount.is_zero())
+                    && schedule.windows(2).all(|w| w[0].start_period < w[1].start_period),
+                Error::<T, I>::InvalidEmissionSchedule
+            );
+
+            let steps = schedule.len() as u32;
+            EmissionSchedules::<T, I>::insert(asset_id, schedule);
+
+            Self::deposit_event(Event::EmissionScheduleSet { asset_id, steps });
+
+            Ok(())
+        }
+
+        /// Set or remove the velocity policy of an asset
+        ///
+        /// The policy takes effect when the current period closes. Removing it
+        /// restores the unadjusted UBI amount right away.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidVelocityPolicy` if the bounds are out of order or out of range
+        #[pallet::call_index(25)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn set_velocity_policy(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            policy: Option<VelocityPolicy>,
+        ) -> DispatchResult {
+            T::ParameterOrigin::ensure_origin(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(policy.as_ref().is_none_or(VelocityPolicy::is_valid), Error::<T, I>::InvalidVelocityPolicy);
+
+            match &policy {
+                Some(policy) => VelocityPolicies::<T, I>::insert(asset_id, policy),
+                None => {
+                    VelocityPolicies::<T, I>::remove(asset_id);
+                    Velocity::<T, I>::mutate(asset_id, |state| state.adjustment = 1000);
+                }
+            }
+
+            Self::deposit_event(Event::VelocityPolicySet { asset_id, policy });
+
+            Ok(())
+        }
+
+        /// Set or remove the supply cap of an asset
+        ///
+        /// Applies from the next claim on. A ceiling below the current supply
+        /// stops claims until burns and expiry bring the supply under it.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidSupplyCap` if the ceiling is zero
+        #[pallet::call_index(56)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_supply_cap(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            cap: Option<SupplyCapOf<T, I>>,
+        ) -> DispatchResult {
+            T::ParameterOrigin::ensure_origin(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(cap.as_ref().is_none_or(|cap| !cap.ceiling.is_zero()), Error::<T, I>::InvalidSupplyCap);
+
+            SupplyCaps::<T, I>::set(asset_id, cap.clone());
+
+            Self::deposit_event(Event::SupplyCapSet { asset_id, cap });
+
+            Ok(())
+        }
+
+        /// Purge expired batches of the listed accounts (cleanup bounty)
+        ///
+        /// Anyone can call this. For every listed account that actually had
+        /// expired tokens, the caller earns `POINTS_PER_SWEPT_ACCOUNT` reputation
+        /// in the asset, up to `MAX_MAINTENANCE_CREDIT` in total.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `NothingToSweep` if none of the accounts had expired tokens
+        #[pallet::call_index(4)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(
+                    2u64.saturating_add(accounts.len() as u64),
+                    2u64.saturating_add(accounts.len() as u64),
+                )
+        )]
+        pub fn sweep_expired(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            accounts: BoundedVec<T::AccountId, T::MaxSweepAccounts>,
+        ) -> DispatchResult {
+            let sweeper = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let mut swept: u32 = 0;
+            let mut total: BalanceOf<T, I> = Zero::zero();
+
+            for who in accounts.iter() {
+                let (expired, _) = Self::expire_batches(asset_id, who, &params, current_block);
+                if expired.is_zero() {
+                    continue;
+                }
+                swept = swept.saturating_add(1);
+                total = total.saturating_add(expired);
+            }
+            ensure!(swept > 0, Error::<T, I>::NothingToSweep);
+
+            let current_period = Self::block_to_period(&params, current_block);
+            Self::mutate_reputation(asset_id, &sweeper, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                let credit = BalanceOf::<T, I>::from(swept)
+                    .saturating_mul(Economics::<T, I>::get().points_per_swept_account.into());
+                rep.maintenance_credit = rep
+                    .maintenance_credit
+                    .saturating_add(credit)
+                    .min(MAX_MAINTENANCE_CREDIT.into());
+
+                if rep.first_activity == Zero::zero() {
+                    rep.first_activity = current_block;
+                }
+                rep.last_activity = current_block;
+
+                rep.score = Self::recalculate_score(rep);
+            });
+
+            Self::deposit_event(Event::ExpiredSwept { asset_id, sweeper, accounts: swept, amount: total });
+
+            Ok(())
+        }
+
+        /// Purge the expired batches of accounts in the expiry index (UNSIGNED)
+        ///
+        /// Submitted by the expiry-sweep offchain worker once `period` has
+        /// passed: expires the batches of the listed accounts and removes
+        /// their `ExpiryIndex` entries for the period. Accounts that spent or
+        /// purged their tokens in the meantime only lose the index entry.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidExpirySweep` if the period has not passed or an account
+        ///   is not indexed for it
+        #[pallet::call_index(33)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(
+                    1u64.saturating_add(2u64.saturating_mul(accounts.len() as u64)),
+                    2u64.saturating_mul(accounts.len() as u64),
+                )
+        )]
+        pub fn sweep_expiry_index(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            period: u64,
+            accounts: BoundedVec<T::AccountId, T::MaxSweepAccounts>,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            ensure!(Self::is_expiry_sweep_valid(&asset_id, period, &accounts), Error::<T, I>::InvalidExpirySweep);
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            for who in accounts.iter() {
+                ExpiryIndex::<T, I>::remove((asset_id, period, who));
+                Self::expire_batches(asset_id, who, &params, current_block);
+            }
+
+            Ok(())
+        }
+
+        /// Declare a bonus period for an asset
+        ///
+        /// Claims made in `[start, end)` are multiplied by `claim_multiplier`
+        /// percent and, if `double_streak` is set, add two streak days.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidBonusPeriod` if the window is empty or already started, or the multiplier is out of range
+        /// - `OverlappingBonusPeriod` if it overlaps with a declared period
+        /// - `TooManyBonusPeriods` if the schedule is full
+        #[pallet::call_index(6)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn declare_bonus_period(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            period: BonusPeriodOf<T>,
+        ) -> DispatchResult {
+            T::BonusOrigin::ensure_origin(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                period.start > current_block
+                    && period.end > period.start
+                    && (100..=MAX_CLAIM_MULTIPLIER).contains(&period.claim_multiplier),
+                Error::<T, I>::InvalidBonusPeriod
+            );
+
+            BonusPeriods::<T, I>::try_mutate(asset_id, |periods| -> DispatchResult {
+                // Finished periods are no longer needed
+                periods.retain(|p| p.end > current_block);
+                ensure!(
+                    !periods.iter().any(|p| p.start < period.end && period.start < p.end),
+                    Error::<T, I>::OverlappingBonusPeriod
+                );
+                let index = periods.partition_point(|p| p.start < period.start);
+                periods
+                    .try_insert(index, period.clone())
+                    .map_err(|_| Error::<T, I>::TooManyBonusPeriods)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::BonusPeriodDeclared { asset_id, period });
+
+            Ok(())
+        }
+
+        /// Cancel a declared bonus period, identified by its start block
+        ///
+        /// # Errors
+        /// - `UnknownBonusPeriod` if no period of the asset starts at `start`
+        #[pallet::call_index(7)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn cancel_bonus_period(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            start: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            T::BonusOrigin::ensure_origin(origin)?;
+
+            BonusPeriods::<T, I>::try_mutate(asset_id, |periods| -> DispatchResult {
+                let index = periods
+                    .iter()
+                    .position(|p| p.start == start)
+                    .ok_or(Error::<T, I>::UnknownBonusPeriod)?;
+                periods.remove(index);
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::BonusPeriodCancelled { asset_id, start });
+
+            Ok(())
+        }
+
+        /// Pay matching bonuses of a campaign out of the community pool
+        ///
+        /// Each grant is either an extra claim, a fresh batch expiring like a
+        /// claim of the asset, or reputation credited as recognition received.
+        /// Both draw their amount from `CommunityPool`. `campaign` identifies
+        /// the campaign in the emitted events.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `AmountMustBePositive` if a grant is zero
+        /// - `InsufficientCommunityPool` if the grants exceed the pool
+        /// - `NotAClaimant` if tokens are granted to an account that never claimed
+        /// - `TooManyBatches` if a token grant does not fit in the account's batches
+        #[pallet::call_index(34)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + Weight::from_parts(WEIGHT_PER_BATCH, 0).saturating_mul(grants.len() as u64)
+                + T::DbWeight::get().reads_writes(
+                    2u64.saturating_add(6u64.saturating_mul(grants.len() as u64)),
+                    1u64.saturating_add(5u64.saturating_mul(grants.len() as u64)),
+                )
+        )]
+        pub fn allocate_community_pool(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            campaign: H256,
+            grants: PoolGrantsOf<T, I>,
+        ) -> DispatchResult {
+            T::BonusOrigin::ensure_origin(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+
+            let mut total: BalanceOf<T, I> = Zero::zero();
+            for (_, grant) in grants.iter() {
+                let (PoolGrant::Tokens(amount) | PoolGrant::Reputation(amount)) = *grant;
+                ensure!(!amount.is_zero(), Error::<T, I>::AmountMustBePositive);
+                total = total.saturating_add(amount);
+            }
+            let remaining = CommunityPool::<T, I>::get(asset_id)
+                .checked_sub(&total)
+                .ok_or(Error::<T, I>::InsufficientCommunityPool)?;
+            CommunityPool::<T, I>::insert(asset_id, remaining);
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let current_period = Self::block_to_period(&params, current_block);
+            for (who, grant) in grants {
+                match grant {
+                    PoolGrant::Tokens(amount) => {
+                        ensure!(LastClaim::<T, I>::contains_key(asset_id, &who), Error::<T, I>::NotAClaimant);
+                        Self::expire_batches(asset_id, &who, &params, current_block);
+                        let expires_at = current_block.saturating_add(params.expiration_blocks);
+                        Self::credit_batch(asset_id, &who, &params, current_block, amount, expires_at)?;
+                        TotalSupply::<T, I>::mutate(asset_id, |supply| *supply = supply.saturating_add(amount));
+                    },
+                    PoolGrant::Reputation(amount) => {
+                        Self::mutate_reputation(asset_id, &who, |rep| {
+                            Self::apply_elapsed_decay(rep, current_period);
+                            rep.weighted_received = rep.weighted_received.saturating_add(amount);
+                            rep.score = Self::recalculate_score(rep);
+                        });
+                    },
+                }
+                Self::deposit_event(Event::CommunityPoolGranted { asset_id, campaign, who, grant });
+            }
+
+            Ok(())
+        }
+
+        /// Burn tokens to a recipient and stake reputation to boost it
+        ///
+        /// Works like `burn` from the caller, and `stake` points of the
+        /// caller's reputation score are added to the recipient's score in
+        /// `boost_received`. Boosts the caller received itself cannot be
+        /// staked again. The stake is held out of the caller's score
+        /// until `release_boost` returns it and ends the boost, and lost if
+        /// governance slashes the boost of a disputed burn within
+        /// `T::BoostLockBlocks`.
+        ///
+        /// Unlike `burn`, there is no `from` argument: the call is signed and
+        /// the signer is always the sender, so nobody can stake another
+        /// account's reputation.
+        ///
+        /// # Errors
+        /// - `AmountMustBePositive` if the stake is zero
+        /// - `OrganizationRecipient` if the recipient is an organization
+        /// - `InsufficientReputation` if the stake exceeds the caller's score
+        ///   without the boosts it received
+        /// - any error of `burn`
+        #[pallet::call_index(35)]
+        #[pallet::weight(
+            Pallet::<T, I>::burn_weight(MAX_BATCHES, MAX_BATCHES)
+                .saturating_add(T::DbWeight::get().reads_writes(3, 4))
+        )]
+        pub fn boosted_burn(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            stake: BalanceOf<T, I>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            ensure!(!stake.is_zero(), Error::<T, I>::AmountMustBePositive);
+            ensure!(!Organizations::<T, I>::contains_key(asset_id, &to), Error::<T, I>::OrganizationRecipient);
+            // Received boosts are lent, not owned, so they cannot be passed on
+            let sender = Self::reputation(&asset_id, &from);
+            ensure!(
+                stake <= sender.score.saturating_sub(sender.boost_received),
+                Error::<T, I>::InsufficientReputation
+            );
+
+            let (burn_id, weight) = Self::do_burn(asset_id, from.clone(), to.clone(), amount, None, None)?;
+
+            Self::mutate_reputation(asset_id, &from, |rep| {
+                rep.boost_stake = rep.boost_stake.saturating_add(stake);
+                rep.score = Self::recalculate_score(rep);
+            });
+            Self::mutate_reputation(asset_id, &to, |rep| {
+                rep.boost_received = rep.boost_received.saturating_add(stake);
+                rep.score = Self::recalculate_score(rep);
+            });
+
+            let unlocks_at = frame_system::Pallet::<T>::block_number().saturating_add(T::BoostLockBlocks::get());
+            BurnBoosts::<T, I>::insert(burn_id, BurnBoost { stake, unlocks_at });
+
+            Self::deposit_event(Event::BurnBoosted { burn_id, asset_id, from, to, stake, unlocks_at });
+
+            Ok(Some(weight.saturating_add(T::DbWeight::get().reads_writes(3, 4))).into())
+        }
+
+        /// End the boost of a burn and give the stake back to its sender
+        ///
+        /// Anyone can call this once the stake is unlocked. The boost is taken
+        /// out of the recipient's `boost_received` (unless the burn was
+        /// rejected or refunded, which already did), so only the burn itself
+        /// keeps counting.
+        ///
+        /// # Errors
+        /// - `UnknownBoost` if the burn has no unreleased boost
+        /// - `BoostLocked` if `T::BoostLockBlocks` have not passed yet
+        #[pallet::call_index(36)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 4))]
+        pub fn release_boost(origin: OriginFor<T>, burn_id: BurnId) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let boost = BurnBoosts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBoost)?;
+            let current_block = frame_system::Pallet::<T>::block_number();
+            ensure!(current_block >= boost.unlocks_at, Error::<T, I>::BoostLocked);
+            let receipt = BurnReceipts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBurn)?;
+            let params = Self::asset_parameters(&receipt.asset_id)?;
+            let current_period = Self::block_to_period(&params, current_block);
+            let asset_id = receipt.asset_id;
+
+            BurnBoosts::<T, I>::remove(burn_id);
+            Self::mutate_reputation(asset_id, &receipt.from, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                rep.boost_stake = rep.boost_stake.saturating_sub(boost.stake);
+                rep.score = Self::recalculate_score(rep);
+            });
+            if !receipt.reversed() {
+                Self::mutate_reputation(asset_id, &receipt.to, |rep| {
+                    Self::apply_elapsed_decay(rep, current_period);
+                    rep.boost_received = rep.boost_received.saturating_sub(boost.stake);
+                    rep.score = Self::recalculate_score(rep);
+                });
+            }
+            Self::deposit_event(Event::BoostReleased { burn_id, asset_id, from: receipt.from, stake: boost.stake });
+
+            Ok(())
+        }
+
+        /// Slash the stake of a disputed boosted burn
+        ///
+        /// The boost is taken out of the recipient's `boost_received` (unless
+        /// the burn was rejected or refunded, which already did) and the sender
+        /// loses the stake. Possible until the stake is released.
+        ///
+        /// # Errors
+        /// - `UnknownBoost` if the burn has no unreleased boost
+        /// - `BurnNotDisputed` if no party flagged the burn
+        #[pallet::call_index(37)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 4))]
+        pub fn slash_boost(origin: OriginFor<T>, burn_id: BurnId) -> DispatchResult {
+            T::ReviewOrigin::ensure_origin(origin)?;
+
+            let boost = BurnBoosts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBoost)?;
+            ensure!(!BurnDisputes::<T, I>::get(burn_id).is_empty(), Error::<T, I>::BurnNotDisputed);
+            let receipt = BurnReceipts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBurn)?;
+            let params = Self::asset_parameters(&receipt.asset_id)?;
+            let current_period = Self::block_to_period(&params, frame_system::Pallet::<T>::block_number());
+            let asset_id = receipt.asset_id;
+
+            BurnBoosts::<T, I>::remove(burn_id);
+            Self::mutate_reputation(asset_id, &receipt.from, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                rep.boost_stake = rep.boost_stake.saturating_sub(boost.stake);
+                rep.slashed_stake = rep.slashed_stake.saturating_add(boost.stake);
+                rep.score = Self::recalculate_score(rep);
+            });
+            if !receipt.reversed() {
+                Self::mutate_reputation(asset_id, &receipt.to, |rep| {
+                    Self::apply_elapsed_decay(rep, current_period);
+                    rep.boost_received = rep.boost_received.saturating_sub(boost.stake);
+                    rep.score = Self::recalculate_score(rep);
+                });
+            }
+            let (from, to) = (receipt.from, receipt.to);
+
+            Self::deposit_event(Event::BoostSlashed { burn_id, asset_id, from, to, stake: boost.stake });
+
+            Ok(())
+        }
+
+        /// Vouch for another account
+        ///
+        /// Costs the caller `VOUCH_COST` reputation points and grants the
+        /// target `VOUCH_BONUS` points of `vouch_credit`, up to
+        /// `MAX_VOUCH_CREDIT`. Each pair can vouch once.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `CannotVouchForSelf` if the target is the caller
+        /// - `AlreadyVouched` if the caller already vouched for the target
+        /// - `InsufficientReputation` if the caller's score does not cover the cost
+        /// - `TooManyVouches` if the caller vouched `MaxVouchesPerPeriod` times this period
+        #[pallet::call_index(38)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(6, 5))]
+        pub fn vouch(origin: OriginFor<T>, asset_id: T::AssetId, target: T::AccountId) -> DispatchResult {
+            let voucher = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            ensure!(voucher != target, Error::<T, I>::CannotVouchForSelf);
+            ensure!(
+                !Vouches::<T, I>::contains_key((asset_id, &voucher, &target)),
+                Error::<T, I>::AlreadyVouched
+            );
+
+            let cost = BalanceOf::<T, I>::from(VOUCH_COST);
+            ensure!(cost <= Self::reputation_score(&asset_id, &voucher), Error::<T, I>::InsufficientReputation);
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let current_period = Self::block_to_period(&params, current_block);
+            VouchCounts::<T, I>::try_mutate(asset_id, &voucher, |(period, count)| -> DispatchResult {
+                if *period != current_period {
+                    *period = current_period;
+                    *count = 0;
+                }
+                ensure!(*count < T::MaxVouchesPerPeriod::get(), Error::<T, I>::TooManyVouches);
+                *count = count.saturating_add(1);
+                Ok(())
+            })?;
+            Self::mutate_reputation(asset_id, &voucher, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                rep.vouch_cost = rep.vouch_cost.saturating_add(cost);
+                rep.last_activity = current_block;
+                rep.score = Self::recalculate_score(rep);
+            });
+
+            let credit = Self::mutate_reputation(asset_id, &target, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                let before = rep.vouch_credit;
+                rep.vouch_credit = rep
+                    .vouch_credit
+                    .saturating_add(VOUCH_BONUS.into())
+                    .min(MAX_VOUCH_CREDIT.into());
+                rep.score = Self::recalculate_score(rep);
+                rep.vouch_credit.saturating_sub(before)
+            });
+            Vouches::<T, I>::insert((asset_id, &voucher, &target), current_block);
+
+            Self::deposit_event(Event::Vouched { asset_id, voucher, target, credit });
+
+            Ok(())
+        }
+
+        /// Attest a role to another account
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidRole` if the role tag is empty or not lowercase
+        /// - `CannotAttestSelf` if the subject is the caller
+        /// - `ReputationTooLow` if the caller's score is below `MinAttesterReputation`
+        /// - `AlreadyAttested` if the caller already attested the role to the subject
+        #[pallet::call_index(39)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 2))]
+        pub fn attest(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            subject: T::AccountId,
+            role: RoleOf<T, I>,
+        ) -> DispatchResult {
+            let attester = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                !role.is_empty() && role.iter().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || *b == b'-'),
+                Error::<T, I>::InvalidRole
+            );
+            ensure!(attester != subject, Error::<T, I>::CannotAttestSelf);
+            ensure!(
+                Self::reputation_score(&asset_id, &attester) >= T::MinAttesterReputation::get(),
+                Error::<T, I>::ReputationTooLow
+            );
+            ensure!(
+                !Attestations::<T, I>::contains_key((asset_id, &role, &subject, &attester)),
+                Error::<T, I>::AlreadyAttested
+            );
+
+            Attestations::<T, I>::insert(
+                (asset_id, &role, &subject, &attester),
+                frame_system::Pallet::<T>::block_number(),
+            );
+            AttestationCounts::<T, I>::mutate((asset_id, &role, &subject), |count| {
+                *count = count.saturating_add(1);
+            });
+
+            Self::deposit_event(Event::Attested { asset_id, attester, subject, role });
+
+            Ok(())
+        }
+
+        /// Revoke a role attested to another account
+        ///
+        /// # Errors
+        /// - `UnknownAttestation` if the caller did not attest the role to the subject
+        #[pallet::call_index(40)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn revoke_attestation(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            subject: T::AccountId,
+            role: RoleOf<T, I>,
+        ) -> DispatchResult {
+            let attester = ensure_signed(origin)?;
+            ensure!(
+                Attestations::<T, I>::take((asset_id, &role, &subject, &attester)).is_some(),
+                Error::<T, I>::UnknownAttestation
+            );
+            AttestationCounts::<T, I>::mutate_exists((asset_id, &role, &subject), |count| {
+                *count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+            });
+
+            Self::deposit_event(Event::AttestationRevoked { asset_id, attester, subject, role });
+
+            Ok(())
+        }
+
+        /// Register the caller as an organization, or replace its members
+        ///
+        /// Burns received afterwards split their recognition across `members`
+        /// by share. Earlier burns keep their split.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidOrganization` if there are no members, a share is zero, a
+        ///   member is listed twice or the organization lists itself
+        #[pallet::call_index(41)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_organization(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            members: OrganizationMembersOf<T, I>,
+        ) -> DispatchResult {
+            let organization = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                !members.is_empty()
+                    && members.iter().enumerate().all(|(i, m)| {
+                        m.share > 0 && m.who != organization && members[..i].iter().all(|other| other.who != m.who)
+                    }),
+                Error::<T, I>::InvalidOrganization
+            );
+
+            let count = members.len() as u32;
+            Organizations::<T, I>::insert(asset_id, &organization, members);
+
+            Self::deposit_event(Event::OrganizationSet { asset_id, organization, members: count });
+
+            Ok(())
+        }
+
+        /// Stop splitting the caller's received recognition across members
+        ///
+        /// # Errors
+        /// - `NotAnOrganization` if the caller is not an organization
+        #[pallet::call_index(42)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn remove_organization(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+            let organization = ensure_signed(origin)?;
+            ensure!(
+                Organizations::<T, I>::take(asset_id, &organization).is_some(),
+                Error::<T, I>::NotAnOrganization
+            );
+
+            Self::deposit_event(Event::OrganizationRemoved { asset_id, organization });
+
+            Ok(())
+        }
+
+        /// Consent to `claimer` claiming for the caller
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `AlreadyInHousehold` if the caller or the claimer belongs to a
+        ///   household already, other than as the claimer's own, or both are the same
+        /// - `HouseholdFull` if the household has `MaxHouseholdMembers` members
+        #[pallet::call_index(43)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 2))]
+        pub fn join_household(origin: OriginFor<T>, asset_id: T::AssetId, claimer: T::AccountId) -> DispatchResult {
+            let member = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                member != claimer
+                    && !HouseholdOf::<T, I>::contains_key(asset_id, &member)
+                    && !HouseholdOf::<T, I>::contains_key(asset_id, &claimer)
+                    && Households::<T, I>::get(asset_id, &member).is_empty(),
+                Error::<T, I>::AlreadyInHousehold
+            );
+
+            Households::<T, I>::try_mutate(asset_id, &claimer, |members| {
+                members.try_push(member.clone()).map_err(|_| Error::<T, I>::HouseholdFull)
+            })?;
+            HouseholdOf::<T, I>::insert(asset_id, &member, &claimer);
+
+            Self::deposit_event(Event::HouseholdJoined { asset_id, claimer, member });
+
+            Ok(())
+        }
+
+        /// Withdraw the caller's consent to be claimed for by its household's claimer
+        ///
+        /// # Errors
+        /// - `NotInHousehold` if the caller is not a household member
+        #[pallet::call_index(44)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn leave_household(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+            let member = ensure_signed(origin)?;
+            let claimer = HouseholdOf::<T, I>::take(asset_id, &member).ok_or(Error::<T, I>::NotInHousehold)?;
+            Households::<T, I>::mutate_exists(asset_id, &claimer, |members| {
+                if let Some(list) = members {
+                    list.retain(|m| *m != member);
+                    if list.is_empty() {
+                        *members = None;
+                    }
+                }
+            });
+
+            Self::deposit_event(Event::HouseholdLeft { asset_id, claimer, member });
+
+            Ok(())
+        }
+
+        /// Claim UBI for the caller and every member of its household
+        ///
+        /// Signed by the claimer, so no claim proof-of-work is needed. Members
+        /// that cannot claim right now are skipped; each claimed account gets
+        /// its usual `Claimed` event.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `NotInHousehold` if the caller has no household members
+        /// - `NothingToClaim` if nobody in the household could claim
+        #[pallet::call_index(45)]
+        #[pallet::weight(
+            Pallet::<T, I>::claim_weight(MAX_BATCHES, T::MaxBacklogPeriods::get())
+                .saturating_mul(T::MaxHouseholdMembers::get().saturating_add(1).into())
+        )]
+        pub fn claim_household(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResultWithPostInfo {
+            let claimer = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            let members = Households::<T, I>::get(asset_id, &claimer);
+            ensure!(!members.is_empty(), Error::<T, I>::NotInHousehold);
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let mut claimed = 0u32;
+            let mut weight = Weight::zero();
+            for who in core::iter::once(&claimer).chain(members.iter()) {
+                match Self::claim_or_skip(asset_id, &params, who, current_block) {
+                    Ok(claim_weight) => {
+                        claimed = claimed.saturating_add(1);
+                        weight.saturating_accrue(claim_weight);
+                    }
+                    Err(skip_weight) => weight.saturating_accrue(skip_weight),
+                }
+            }
+            ensure!(claimed > 0, Error::<T, I>::NothingToClaim);
+
+            let skipped = (members.len() as u32).saturating_add(1).saturating_sub(claimed);
+            Self::deposit_event(Event::HouseholdClaimed { asset_id, claimer, claimed, skipped });
+
+            Ok(Some(weight).into())
+        }
+
+        /// Verify an account as a charity
+        ///
+        /// Burns to it earn their senders `T::CharityBonus` from then on.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `AlreadyVerifiedCharity` if the account is already verified
+        #[pallet::call_index(46)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn verify_charity(origin: OriginFor<T>, asset_id: T::AssetId, who: T::AccountId) -> DispatchResult {
+            T::CharityOrigin::ensure_origin(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                !VerifiedCharities::<T, I>::contains_key(asset_id, &who),
+                Error::<T, I>::AlreadyVerifiedCharity
+            );
+
+            VerifiedCharities::<T, I>::insert(asset_id, &who, frame_system::Pallet::<T>::block_number());
+
+            Self::deposit_event(Event::CharityVerified { asset_id, who });
+
+            Ok(())
+        }
+
+        /// Revoke the verified charity status of an account
+        ///
+        /// Bonuses already earned by its donors are kept.
+        ///
+        /// # Errors
+        /// - `NotVerifiedCharity` if the account is not a verified charity
+        #[pallet::call_index(47)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn revoke_charity(origin: OriginFor<T>, asset_id: T::AssetId, who: T::AccountId) -> DispatchResult {
+            T::CharityOrigin::ensure_origin(origin)?;
+            ensure!(
+                VerifiedCharities::<T, I>::take(asset_id, &who).is_some(),
+                Error::<T, I>::NotVerifiedCharity
+            );
+
+            Self::deposit_event(Event::CharityRevoked { asset_id, who });
+
+            Ok(())
+        }
+
+        /// Issue an invoice for `total`, payable until `expires_at`
+        ///
+        /// The caller becomes the payee and receives the returned id in the
+        /// `InvoiceCreated` event.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidInvoice` if the total is zero or `expires_at` is not in the future
+        /// - `TooManyInvoices` if too many invoices already expire in that block
+        #[pallet::call_index(48)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+        pub fn create_invoice(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            total: BalanceOf<T, I>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let payee = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                !total.is_zero() && expires_at > frame_system::Pallet::<T>::block_number(),
+                Error::<T, I>::InvalidInvoice
+            );
+
+            let invoice_id = NextInvoiceId::<T, I>::get();
+            InvoiceExpiries::<T, I>::try_append(expires_at, invoice_id)
+                .map_err(|_| Error::<T, I>::TooManyInvoices)?;
+            NextInvoiceId::<T, I>::put(invoice_id.saturating_add(1));
+            Invoices::<T, I>::insert(
+                invoice_id,
+                Invoice { asset_id, payee: payee.clone(), total, paid: Zero::zero(), expires_at },
+            );
+
+            Self::deposit_event(Event::InvoiceCreated { invoice_id, asset_id, payee, total, expires_at });
+
+            Ok(())
+        }
+
+        /// Ask for a payment of exactly `amount`, valid until `expires_at`
+        ///
+        /// The caller becomes the merchant. The intent id in the
+        /// `PaymentIntentCreated` event is the `reference` of the burn paying it.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidPaymentIntent` if the amount is zero or `expires_at` is not in the future
+        /// - `TooManyPaymentIntents` if too many payment intents already expire in that block
+        #[pallet::call_index(52)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+        pub fn create_payment_intent(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            amount: BalanceOf<T, I>,
+            metadata_hash: H256,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let merchant = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                !amount.is_zero() && expires_at > frame_system::Pallet::<T>::block_number(),
+                Error::<T, I>::InvalidPaymentIntent
+            );
+
+            let count = PaymentIntentCount::<T, I>::get();
+            let intent_id = (b"ubi/payment-intent", <Self as PalletInfoAccess>::name(), count)
+                .using_encoded(|bytes| H256(sp_io::hashing::blake2_256(bytes)));
+            PaymentIntentExpiries::<T, I>::try_append(expires_at, intent_id)
+                .map_err(|_| Error::<T, I>::TooManyPaymentIntents)?;
+            PaymentIntentCount::<T, I>::put(count.saturating_add(1));
+            PaymentIntents::<T, I>::insert(
+                intent_id,
+                PaymentIntent {
+                    asset_id,
+                    merchant: merchant.clone(),
+                    amount,
+                    metadata_hash,
+                    expires_at,
+                    fulfilled_by: None,
+                },
+            );
+
+            Self::deposit_event(Event::PaymentIntentCreated {
+                intent_id,
+                asset_id,
+                merchant,
+                amount,
+                metadata_hash,
+                expires_at,
+            });
+
+            Ok(())
+        }
+
+        /// Pay `amount` towards an invoice by burning it to the payee (UNSIGNED)
+        ///
+        /// Works like `burn` with the payee as recipient, and carries the
+        /// sender's next burn `nonce` like `burn`. `payee` must match the
+        /// invoice, so a payer never pays a different merchant than intended.
+        /// Emits `InvoicePartiallyPaid` while part of the total is left, and
+        /// `InvoicePaid` when the payment completes the invoice.
+        ///
+        /// # Errors
+        /// - `UnknownInvoice` if the invoice does not exist or is closed
+        /// - `NotInvoicePayee` if the invoice is payable to another account
+        /// - `InvoiceOverpaid` if `amount` exceeds what is left to pay
+        /// - any error of `burn`, including `TooManyUnsignedBurns` and `InvalidBurnNonce`
+        #[pallet::call_index(49)]
+        #[pallet::weight(
+            Pallet::<T, I>::burn_weight(MAX_BATCHES, MAX_BATCHES) + T::DbWeight::get().reads_writes(2, 2)
+        )]
+        pub fn pay_invoice(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            payee: T::AccountId,
+            invoice_id: InvoiceId,
+            amount: BalanceOf<T, I>,
+            nonce: u64,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+
+            let mut invoice = Invoices::<T, I>::get(invoice_id).ok_or(Error::<T, I>::UnknownInvoice)?;
+            ensure!(invoice.payee == payee, Error::<T, I>::NotInvoicePayee);
+            ensure!(amount <= invoice.total.saturating_sub(invoice.paid), Error::<T, I>::InvoiceOverpaid);
+
+            let (burn_id, weight) =
+                Self::do_unsigned_burn(invoice.asset_id, from.clone(), payee, amount, None, None, nonce)?;
+
+            invoice.paid = invoice.paid.saturating_add(amount);
+            if invoice.paid < invoice.total {
+                let paid = invoice.paid;
+                Invoices::<T, I>::insert(invoice_id, invoice);
+                Self::deposit_event(Event::InvoicePartiallyPaid { invoice_id, burn_id, payer: from, amount, paid });
+            } else {
+                Invoices::<T, I>::remove(invoice_id);
+                Self::deposit_event(Event::InvoicePaid { invoice_id, burn_id, payer: from, amount });
+            }
+
+            Ok(Some(weight.saturating_add(T::DbWeight::get().reads_writes(2, 2))).into())
+        }
+
+        /// Remove the reputation records of dormant accounts
+        ///
+        /// Anyone can call this. Accounts that are not dormant are skipped.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `NothingToPrune` if none of the accounts is dormant
+        #[pallet::call_index(5)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(
+                    1u64.saturating_add(4u64.saturating_mul(accounts.len() as u64)),
+                    5u64.saturating_mul(accounts.len() as u64),
+                )
+        )]
+        pub fn prune_dormant(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            accounts: BoundedVec<T::AccountId, T::MaxSweepAccounts>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            let current_block = frame_system::Pallet::<T>::block_number();
+
+            let mut pruned = false;
+            for who in accounts.iter() {
+                let rep = ReputationStore::<T, I>::get(asset_id, who);
+                if Self::is_dormant(&asset_id, who, &rep, &params, current_block) {
+                    Self::prune_account(asset_id, who);
+                    pruned = true;
+                }
+            }
+            ensure!(pruned, Error::<T, I>::NothingToPrune);
+
+            Ok(())
+        }
+
+        /// Reject a burn received from someone else
+        ///
+        /// Reverses the reputation effects of the burn on both sides, so nobody
+        /// can force an unwanted association onto the recipient's record. The
+        /// tokens stay destroyed. Only possible within `T::RejectionWindow`
+        /// blocks of the burn.
+        ///
+        /// # Errors
+        /// - `UnknownBurn` if the burn does not exist
+        /// - `NotBurnRecipient` if the caller is not the recipient
+        /// - `BurnAlreadyRejected` if the burn was already rejected
+        /// - `RejectionWindowClosed` if the burn is too old
+        #[pallet::call_index(8)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(
+                    8u64.saturating_add(T::MaxOrganizationMembers::get().into()),
+                    6u64.saturating_add(T::MaxOrganizationMembers::get().into()),
+                )
+        )]
+        pub fn reject_burn(origin: OriginFor<T>, burn_id: BurnId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut receipt = BurnReceipts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBurn)?;
+            ensure!(receipt.to == who, Error::<T, I>::NotBurnRecipient);
+            ensure!(!receipt.rejected, Error::<T, I>::BurnAlreadyRejected);
+            ensure!(!receipt.refunded, Error::<T, I>::BurnAlreadyRefunded);
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                current_block <= receipt.block.saturating_add(T::RejectionWindow::get()),
+                Error::<T, I>::RejectionWindowClosed
+            );
+
+            Self::reverse_burn(burn_id, &receipt)?;
+
+            receipt.rejected = true;
+            let (asset_id, from) = (receipt.asset_id, receipt.from.clone());
+            BurnReceipts::<T, I>::insert(burn_id, receipt);
+
+            Self::deposit_event(Event::BurnRejected { burn_id, asset_id, from, to: who });
+
+            Ok(())
+        }
+
+        /// Refund a burn received for a transaction that failed
+        ///
+        /// The recipient acknowledges that the goods or services the burn paid
+        /// for were not delivered. The sender's sent reputation components are
+        /// restored and the recipient's received components reversed, as with
+        /// `reject_burn`, but at any time. The tokens stay destroyed.
+        ///
+        /// # Errors
+        /// - `UnknownBurn` if the burn does not exist
+        /// - `NotBurnRecipient` if the caller is not the recipient
+        /// - `BurnAlreadyRejected` if the burn was rejected
+        /// - `BurnAlreadyRefunded` if the burn was already refunded
+        #[pallet::call_index(50)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(
+                    8u64.saturating_add(T::MaxOrganizationMembers::get().into()),
+                    6u64.saturating_add(T::MaxOrganizationMembers::get().into()),
+                )
+        )]
+        pub fn issue_refund(origin: OriginFor<T>, burn_id: BurnId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut receipt = BurnReceipts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBurn)?;
+            ensure!(receipt.to == who, Error::<T, I>::NotBurnRecipient);
+            ensure!(!receipt.rejected, Error::<T, I>::BurnAlreadyRejected);
+            ensure!(!receipt.refunded, Error::<T, I>::BurnAlreadyRefunded);
+
+            Self::reverse_burn(burn_id, &receipt)?;
+
+            receipt.refunded = true;
+            let (asset_id, from) = (receipt.asset_id, receipt.from.clone());
+            BurnReceipts::<T, I>::insert(burn_id, receipt);
+
+            Self::deposit_event(Event::BurnRefunded { burn_id, asset_id, from, to: who });
+
+            Ok(())
+        }
+
+        /// Burn tokens to a recipient, recording a ticket commitment (UNSIGNED)
+        ///
+        /// Like `burn`, but `commitment` is stored with the burn: the
+        /// `blake2_256` hash of a secret the sender hands out as a ticket or
+        /// voucher. The recipient presents the secret to `redeem` once.
+        ///
+        /// # Errors
+        /// - Any error of `burn`
+        #[pallet::call_index(53)]
+        #[pallet::weight(
+            Pallet::<T, I>::burn_weight(MAX_BATCHES, MAX_BATCHES) + T::DbWeight::get().writes(1)
+        )]
+        pub fn burn_ticket(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            category: Option<BurnCategory>,
+            commitment: H256,
+            nonce: u64,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+
+            let (burn_id, weight) = Self::do_unsigned_burn(asset_id, from, to, amount, category, None, nonce)?;
+            Tickets::<T, I>::insert(burn_id, Ticket { commitment, redeemed_at: None });
+
+            Self::deposit_event(Event::TicketIssued { burn_id, commitment });
+            Ok(Some(weight.saturating_add(T::DbWeight::get().writes(1))).into())
+        }
+
+        /// Redeem the ticket of a burn received, presenting its secret
+        ///
+        /// Marks the ticket consumed, so the same proof of payment cannot be
+        /// presented again.
+        ///
+        /// # Errors
+        /// - `UnknownBurn` if the burn does not exist
+        /// - `NotBurnRecipient` if the caller is not the recipient
+        /// - `BurnAlreadyRejected` if the burn was rejected
+        /// - `BurnAlreadyRefunded` if the burn was refunded
+        /// - `NoTicket` if the burn was not made with `burn_ticket`
+        /// - `TicketAlreadyRedeemed` if the ticket was already redeemed
+        /// - `InvalidTicketSecret` if `secret` does not hash to the commitment
+        #[pallet::call_index(54)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn redeem(
+            origin: OriginFor<T>,
+            burn_id: BurnId,
+            secret: BoundedVec<u8, ConstU32<MAX_TICKET_SECRET_LEN>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let receipt = BurnReceipts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBurn)?;
+            ensure!(receipt.to == who, Error::<T, I>::NotBurnRecipient);
+            ensure!(!receipt.rejected, Error::<T, I>::BurnAlreadyRejected);
+            ensure!(!receipt.refunded, Error::<T, I>::BurnAlreadyRefunded);
+
+            Tickets::<T, I>::try_mutate(burn_id, |ticket| {
+                let ticket = ticket.as_mut().ok_or(Error::<T, I>::NoTicket)?;
+                ensure!(ticket.redeemed_at.is_none(), Error::<T, I>::TicketAlreadyRedeemed);
+                ensure!(
+                    H256(sp_io::hashing::blake2_256(&secret)) == ticket.commitment,
+                    Error::<T, I>::InvalidTicketSecret
+                );
+                ticket.redeemed_at = Some(frame_system::Pallet::<T>::block_number());
+                Ok::<_, DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::TicketRedeemed { burn_id, recipient: who });
+            Ok(())
+        }
+
+        /// Queue a snapshot of the caller's reputation for other chains
+        ///
+        /// The snapshot holds the current score and joins the outbound queue a
+        /// bridge consumes through `ReputationOracle::take_snapshots`.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `AlreadyExported` if the caller exported its reputation this claim period
+        /// - `OutboundQueueFull` if `MaxOutboundSnapshots` snapshots are queued
+        #[pallet::call_index(55)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 3))]
+        pub fn export_reputation(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let current_period = Self::block_to_period(&params, current_block);
+            LastReputationExport::<T, I>::try_mutate(asset_id, &who, |period| {
+                ensure!(*period != Some(current_period), Error::<T, I>::AlreadyExported);
+                *period = Some(current_period);
+                Ok::<_, DispatchError>(())
+            })?;
+
+            let (head, sequence) = OutboundQueueBounds::<T, I>::get();
+            ensure!(
+                sequence.saturating_sub(head) < u64::from(T::MaxOutboundSnapshots::get()),
+                Error::<T, I>::OutboundQueueFull
+            );
+            let score = Self::reputation_score(&asset_id, &who);
+            OutboundSnapshots::<T, I>::insert(
+                sequence,
+                ReputationSnapshot { sequence, asset_id, who: who.clone(), score, block: current_block },
+            );
+            OutboundQueueBounds::<T, I>::put((head, sequence.saturating_add(1)));
+
+            Self::deposit_event(Event::ReputationExported { sequence, asset_id, who, score });
+            Ok(())
+        }
+
+        /// Flag a burn as disputed
+        ///
+        /// Either party of the burn can attach a dispute with the hash of an
+        /// off-chain explanation. Disputes are kept next to the receipt for
+        /// both parties and governance to see; they do not change balances or
+        /// reputation.
+        ///
+        /// # Errors
+        /// - `UnknownBurn` if the burn does not exist
+        /// - `NotBurnParty` if the caller is neither sender nor recipient
+        /// - `AlreadyFlagged` if the caller already flagged the burn
+        #[pallet::call_index(9)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn flag_burn(origin: OriginFor<T>, burn_id: BurnId, reason_hash: H256) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let receipt = BurnReceipts::<T, I>::get(burn_id).ok_or(Error::<T, I>::UnknownBurn)?;
+            ensure!(receipt.from == who || receipt.to == who, Error::<T, I>::NotBurnParty);
+
+            BurnDisputes::<T, I>::try_mutate(burn_id, |disputes| -> DispatchResult {
+                ensure!(!disputes.iter().any(|d| d.flagged_by == who), Error::<T, I>::AlreadyFlagged);
+                disputes
+                    .try_push(BurnDispute {
+                        flagged_by: who.clone(),
+                        reason_hash,
+                        flagged_at: frame_system::Pallet::<T>::block_number(),
+                    })
+                    .map_err(|_| Error::<T, I>::AlreadyFlagged)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::BurnFlagged {
+                burn_id,
+                asset_id: receipt.asset_id,
+                flagged_by: who,
+                reason_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Allow an operator to burn up to `max_amount` of the caller's tokens
+        ///
+        /// Meant for trusted services such as point-of-sale apps or subscription
+        /// managers. Replaces any earlier approval of the same operator.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidApproval` if the operator is the caller, the amount is zero or
+        ///   the expiry is not in the future
+        #[pallet::call_index(10)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn approve(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            operator: T::AccountId,
+            max_amount: BalanceOf<T, I>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                owner != operator
+                    && !max_amount.is_zero()
+                    && expires_at > frame_system::Pallet::<T>::block_number(),
+                Error::<T, I>::InvalidApproval
+            );
+
+            Approvals::<T, I>::insert(
+                (asset_id, &owner, &operator),
+                Approval { remaining: max_amount, expires_at },
+            );
+
+            Self::deposit_event(Event::ApprovalSet { asset_id, owner, operator, max_amount, expires_at });
+
+            Ok(())
+        }
+
+        /// Revoke the approval of an operator
+        ///
+        /// # Errors
+        /// - `NoApproval` if the operator has no approval
+        #[pallet::call_index(11)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn revoke_approval(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            operator: T::AccountId,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Approvals::<T, I>::take((asset_id, &owner, &operator)).ok_or(Error::<T, I>::NoApproval)?;
+
+            Self::deposit_event(Event::ApprovalRevoked { asset_id, owner, operator });
+
+            Ok(())
+        }
+
+        /// Burn tokens of `owner` as an approved operator
+        ///
+        /// Works like `burn`, but is signed by the operator and reduces their
+        /// approval. The approval is removed once it is used up.
+        ///
+        /// # Errors
+        /// - `NoApproval` if the caller has no approval from the owner
+        /// - `ApprovalExpired` if the approval is expired
+        /// - `AllowanceExceeded` if the amount exceeds the remaining approval
+        /// - any error of `burn`
+        #[pallet::call_index(12)]
+        #[pallet::weight(
+            Pallet::<T, I>::burn_weight(MAX_BATCHES, MAX_BATCHES) + T::DbWeight::get().reads_writes(1, 1)
+        )]
+        pub fn burn_from(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            owner: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            category: Option<BurnCategory>,
+            reference: Option<H256>,
+        ) -> DispatchResultWithPostInfo {
+            let operator = ensure_signed(origin)?;
+
+            let mut approval =
+                Approvals::<T, I>::get((asset_id, &owner, &operator)).ok_or(Error::<T, I>::NoApproval)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < approval.expires_at,
+                Error::<T, I>::ApprovalExpired
+            );
+            ensure!(amount <= approval.remaining, Error::<T, I>::AllowanceExceeded);
+
+            let (_, weight) = Self::do_burn(asset_id, owner.clone(), to, amount, category, reference)?;
+
+            approval.remaining = approval.remaining.saturating_sub(amount);
+            if approval.remaining.is_zero() {
+                Approvals::<T, I>::remove((asset_id, &owner, &operator));
+            } else {
+                Approvals::<T, I>::insert((asset_id, &owner, &operator), approval);
+            }
+
+            Ok(Some(weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))).into())
+        }
+
+        /// Register a named service with a fixed price
+        ///
+        /// The caller becomes the provider and receives the returned id in the
+        /// `ServiceRegistered` event.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidService` if the name is empty or the price is zero
+        #[pallet::call_index(13)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn register_service(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            name: BoundedVec<u8, T::MaxServiceNameLen>,
+            price: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let provider = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(!name.is_empty() && !price.is_zero(), Error::<T, I>::InvalidService);
+
+            let service_id = NextServiceId::<T, I>::mutate(|next| {
+                let id = *next;
+                *next = next.saturating_add(1);
+                id
+            });
+            Services::<T, I>::insert(
+                service_id,
+                Service { asset_id, provider: provider.clone(), name, price },
+            );
+
+            Self::deposit_event(Event::ServiceRegistered { service_id, asset_id, provider, price });
+
+            Ok(())
+        }
+
+        /// Remove a service (provider only)
+        ///
+        /// # Errors
+        /// - `UnknownService` if the service does not exist
+        /// - `NotServiceProvider` if the caller is not the provider
+        #[pallet::call_index(14)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn unregister_service(origin: OriginFor<T>, service_id: ServiceId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let service = Services::<T, I>::get(service_id).ok_or(Error::<T, I>::UnknownService)?;
+            ensure!(service.provider == who, Error::<T, I>::NotServiceProvider);
+
+            Services::<T, I>::remove(service_id);
+
+            Self::deposit_event(Event::ServiceUnregistered { service_id });
+
+            Ok(())
+        }
+
+        /// Pay for a service by burning exactly its listed price (UNSIGNED)
+        ///
+        /// Works like `burn` with the provider as recipient and the `Services`
+        /// category, and carries the sender's next burn `nonce` like `burn`.
+        /// `provider` must match the service, so a payer never pays a different
+        /// merchant than intended.
+        ///
+        /// # Errors
+        /// - `UnknownService` if the service does not exist
+        /// - `NotServiceProvider` if the service belongs to another provider
+        /// - any error of `burn`, including `TooManyUnsignedBurns` and `InvalidBurnNonce`
+        #[pallet::call_index(15)]
+        #[pallet::weight(
+            Pallet::<T, I>::burn_weight(MAX_BATCHES, MAX_BATCHES) + T::DbWeight::get().reads_writes(2, 1)
+        )]
+        pub fn pay_service(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            provider: T::AccountId,
+            service_id: ServiceId,
+            nonce: u64,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+
+            let service = Services::<T, I>::get(service_id).ok_or(Error::<T, I>::UnknownService)?;
+            ensure!(service.provider == provider, Error::<T, I>::NotServiceProvider);
+
+            let (burn_id, weight) = Self::do_unsigned_burn(
+                service.asset_id,
+                from.clone(),
+                provider.clone(),
+                service.price,
+                Some(BurnCategory::Services),
+                None,
+                nonce,
+            )?;
+
+            Self::deposit_event(Event::ServicePaid {
+                service_id,
+                burn_id,
+                asset_id: service.asset_id,
+                payer: from,
+                provider,
+                price: service.price,
+            });
+
+            Ok(Some(weight.saturating_add(T::DbWeight::get().reads_writes(2, 1))).into())
+        }
+
+        /// Lock tokens to extend their expiration
+        ///
+        /// Takes `amount` from the caller's oldest batches and locks it for
+        /// `extra_periods` claim periods, during which it cannot be burned.
+        /// The tokens then expire `extra_periods` periods after the oldest batch
+        /// they were taken from would have. This lets people save for a larger
+        /// purchase without enabling hoarding; each period costs
+        /// `COMMITMENT_COST_PER_PERIOD` reputation points.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidCommitment` if the amount is zero or `extra_periods` is not
+        ///   within 1 to `MaxCommitPeriods`
+        /// - `InsufficientBalance` if the caller does not have enough tokens
+        /// - `TooManyCommitments` if the caller has too many open commitments
+        #[pallet::call_index(19)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 5))]
+        pub fn commit_tokens(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            amount: BalanceOf<T, I>,
+            extra_periods: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            ensure!(
+                !amount.is_zero() && extra_periods > 0 && extra_periods <= T::MaxCommitPeriods::get(),
+                Error::<T, I>::InvalidCommitment
+            );
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            Self::expire_batches(asset_id, &who, &params, current_block);
+
+            let (oldest_expiry, _) = Self::burn_fifo(&asset_id, &who, amount, current_block)?;
+            let extension = params.claim_period_blocks.saturating_mul(extra_periods.into());
+            let unlocks_at = current_block.saturating_add(extension);
+            let expires_at = oldest_expiry.saturating_add(extension);
+
+            Commitments::<T, I>::try_mutate(asset_id, &who, |commitments| {
+                commitments
+                    .try_push(Commitment { amount, unlocks_at, expires_at })
+                    .map_err(|_| Error::<T, I>::TooManyCommitments)
+            })?;
+
+            let current_period = Self::block_to_period(&params, current_block);
+            Self::mutate_reputation(asset_id, &who, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                let cost = BalanceOf::<T, I>::from(extra_periods)
+                    .saturating_mul(Economics::<T, I>::get().commitment_cost_per_period.into());
+                rep.commitment_cost = rep.commitment_cost.saturating_add(cost);
+                rep.last_activity = current_block;
+                rep.score = Self::recalculate_score(rep);
+            });
+
+            Self::deposit_event(Event::TokensCommitted { asset_id, who, amount, unlocks_at, expires_at });
+
+            Ok(())
+        }
+
+        /// Burn tokens to protect the claim streak during an absence
+        ///
+        /// Burns `T::StreakFreezeCost` of the caller's tokens, without a
+        /// recipient, and lets the streak survive `T::StreakFreezePeriods` more
+        /// missed periods beyond the grace period. Freezes add up to at most
+        /// `MAX_STREAK_FREEZE` periods and are used up as periods are missed.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `StreakFreezeLimit` if the freezes would cover more than `MAX_STREAK_FREEZE` periods
+        /// - `InsufficientBalance` if the caller cannot pay the cost
+        #[pallet::call_index(27)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                .saturating_add(Weight::from_parts(WEIGHT_PER_BATCH, 0).saturating_mul(MAX_BATCHES.into()))
+                .saturating_add(T::DbWeight::get().reads_writes(5, 4))
+        )]
+        pub fn freeze_streak(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let current_period = Self::block_to_period(&params, current_block);
+
+            let mut rep = ReputationStore::<T, I>::get(asset_id, &who);
+            Self::apply_elapsed_decay(&mut rep, current_period);
+            rep.streak_freeze = rep.streak_freeze.saturating_add(T::StreakFreezePeriods::get());
+            ensure!(rep.streak_freeze <= MAX_STREAK_FREEZE, Error::<T, I>::StreakFreezeLimit);
+
+            let cost = T::StreakFreezeCost::get();
+            Self::expire_batches(asset_id, &who, &params, current_block);
+            Self::burn_fifo(&asset_id, &who, cost, current_block)?;
+            TotalSupply::<T, I>::mutate(asset_id, |supply| *supply = supply.saturating_sub(cost));
+            Self::record_flow(asset_id, current_period, Zero::zero(), cost);
+
+            rep.last_activity = current_block;
+            let streak_freeze = rep.streak_freeze;
+            Self::insert_reputation(asset_id, &who, rep);
+
+            Self::deposit_event(Event::StreakFrozen { asset_id, who, cost, streak_freeze });
+
+            Ok(())
+        }
+
+        /// Set the caller's display name and metadata hash in an asset
+        ///
+        /// Lets burn recipients present a name in wallets that is bound to
+        /// their account on-chain. Requires a reputation score of at least
+        /// `T::MinProfileReputation`, so fresh accounts cannot impersonate
+        /// known shops.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidDisplayName` if the name is empty or not UTF-8
+        /// - `ReputationTooLow` if the caller's score is below the minimum
+        #[pallet::call_index(30)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+        pub fn set_profile(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            display_name: BoundedVec<u8, T::MaxDisplayNameLen>,
+            metadata_hash: Option<H256>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+            ensure!(
+                !display_name.is_empty() && core::str::from_utf8(&display_name).is_ok(),
+                Error::<T, I>::InvalidDisplayName
+            );
+            ensure!(
+                Self::reputation_score(&asset_id, &who) >= T::MinProfileReputation::get(),
+                Error::<T, I>::ReputationTooLow
+            );
+
+            let updated_at = frame_system::Pallet::<T>::block_number();
+            Profiles::<T, I>::insert(asset_id, &who, Profile { display_name, metadata_hash, updated_at });
+
+            Self::deposit_event(Event::ProfileSet { asset_id, who });
+
+            Ok(())
+        }
+
+        /// Remove the caller's profile in an asset
+        ///
+        /// # Errors
+        /// - `NoProfile` if the caller has no profile
+        #[pallet::call_index(31)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn clear_profile(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Profiles::<T, I>::take(asset_id, &who).is_some(), Error::<T, I>::NoProfile);
+
+            Self::deposit_event(Event::ProfileCleared { asset_id, who });
+
+            Ok(())
+        }
+
+        /// Replace the caller's preferences in an asset
+        ///
+        /// Collects the per-account settings: relayer opt-in, whether relayer
+        /// batches may claim for the account, a webhook for notification
+        /// services and the smallest burn the account accepts. Setting the
+        /// defaults removes the entry.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        #[pallet::call_index(29)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_preferences(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            preferences: AccountPreferencesOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Assets::<T, I>::contains_key(asset_id), Error::<T, I>::UnknownAsset);
+
+            if preferences == Default::default() {
+                Preferences::<T, I>::remove(asset_id, &who);
+            } else {
+                Preferences::<T, I>::insert(asset_id, &who, preferences);
+            }
+
+            Self::deposit_event(Event::PreferencesSet { asset_id, who });
+
+            Ok(())
+        }
+
+        /// Go on vacation until `until_period`, or come back early
+        ///
+        /// From the current claim period up to, but excluding, `until_period`
+        /// the claim streak does not lapse and nobody can claim for the caller.
+        /// A vacation lasts at most `T::MaxVacationPeriods` and can only start
+        /// `T::VacationCooldownPeriods` after the previous one ended. An
+        /// `until_period` that is not in the future ends the current vacation.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        /// - `InvalidVacation` if the vacation is too long, or there is no vacation to end
+        /// - `VacationCooldown` if the previous vacation ended too recently
+        #[pallet::call_index(28)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn set_vacation(origin: OriginFor<T>, asset_id: T::AssetId, until_period: u64) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+            let current_period = Self::block_to_period(&params, frame_system::Pallet::<T>::block_number());
+
+            let mut rep = ReputationStore::<T, I>::get(asset_id, &who);
+            Self::apply_elapsed_decay(&mut rep, current_period);
+            let on_vacation = rep.vacation_start <= current_period && current_period < rep.vacation_end;
+            if until_period <= current_period {
+                ensure!(on_vacation, Error::<T, I>::InvalidVacation);
+                rep.vacation_end = current_period;
+            } else {
+                ensure!(
+                    until_period - current_period <= T::MaxVacationPeriods::get(),
+                    Error::<T, I>::InvalidVacation
+                );
+                ensure!(
+                    rep.vacation_end == 0
+                        || current_period >= rep.vacation_end.saturating_add(T::VacationCooldownPeriods::get()),
+                    Error::<T, I>::VacationCooldown
+                );
+                rep.vacation_start = current_period;
+                rep.vacation_end = until_period;
+            }
+
+            let (start, end) = (rep.vacation_start, rep.vacation_end);
+            Self::insert_reputation(asset_id, &who, rep);
+
+            Self::deposit_event(Event::VacationSet { asset_id, who, start, end });
+
+            Ok(())
+        }
+
+        /// Purge the expired batches of an account (UNSIGNED - no gas fees!)
+        ///
+        /// Only runs the expiry cleanup that claims and burns do lazily, so
+        /// wallets can reconcile an account's balance and `TotalSupply` without
+        /// claiming or burning. Emits `Expired` if anything expired and is a
+        /// no-op otherwise; the transaction pool only accepts it in the former
+        /// case.
+        ///
+        /// # Errors
+        /// - `UnknownAsset` if the asset does not exist
+        #[pallet::call_index(17)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 2))]
+        pub fn tidy(origin: OriginFor<T>, asset_id: T::AssetId, account: T::AccountId) -> DispatchResult {
+            ensure_none(origin)?;
+            let params = Self::asset_parameters(&asset_id)?;
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            Self::expire_batches(asset_id, &account, &params, current_block);
+
+            Ok(())
+        }
+
+        /// Queue a ring of recent burns for governance review (UNSIGNED)
+        ///
+        /// Submitted by the ring-detection offchain worker. `burns` must be two
+        /// or three unrejected burns among the last `RING_SCAN_BURNS` of one
+        /// asset, each one burning to the sender of the next and the last one
+        /// back to the sender of the first.
+        ///
+        /// # Errors
+        /// - `InvalidRing` if the burns do not form such a ring
+        /// - `RingAlreadyReported` if a report on the same accounts is queued
+        /// - `SuspicionQueueFull` if `MAX_SUSPICION_REPORTS` await review
+        #[pallet::call_index(20)]
+        #[pallet::weight(
+            Weight::from_parts(10_000, 0)
+                + T::DbWeight::get().reads_writes(2u64.saturating_add(burns.len() as u64), 1)
+        )]
+        pub fn report_suspicion(origin: OriginFor<T>, burns: RingBurns) -> DispatchResult {
+            ensure_none(origin)?;
+            let report = Self::check_ring(&burns)?;
+
+            SuspicionReports::<T, I>::try_mutate(|reports| {
+                ensure!(!Self::is_reported(reports, &report), Error::<T, I>::RingAlreadyReported);
+                reports.try_push(report.clone()).map_err(|_| Error::<T, I>::SuspicionQueueFull)
+            })?;
+
+            Self::deposit_event(Event::SuspicionReported {
+                asset_id: report.asset_id,
+                kind: report.kind,
+                accounts: report.accounts,
+            });
+
+            Ok(())
+        }
+
+        /// Remove a reviewed suspicion report
+        ///
+        /// `index` is the position of the report in `SuspicionReports`.
+        ///
+        /// # Errors
+        /// - `UnknownReport` if there is no report at `index`
+        #[pallet::call_index(21)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn dismiss_suspicion(origin: OriginFor<T>, index: u32) -> DispatchResult {
+            T::ReviewOrigin::ensure_origin(origin)?;
+
+            let report = SuspicionReports::<T, I>::try_mutate(|reports| {
+                let index = index as usize;
+                ensure!(index < reports.len(), Error::<T, I>::UnknownReport);
+                Ok::<_, Error<T, I>>(reports.remove(index))
+            })?;
+
+            Self::deposit_event(Event::SuspicionDismissed { asset_id: report.asset_id, accounts: report.accounts });
+
+            Ok(())
+        }
+
+        /// Change a reputation or issuance parameter
+        ///
+        /// Applies from the next score calculation or claim on. Scores are not
+        /// recalculated eagerly.
+        ///
+        /// # Errors
+        /// - `InvalidParameter` if the minimum sender weight would exceed the
+        ///   maximum or the decay factor exceeds 1000
+        #[pallet::call_index(22)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_parameter(origin: OriginFor<T>, parameter: UbiParameter) -> DispatchResult {
+            T::ParameterOrigin::ensure_origin(origin)?;
+            Self::update_economics(parameter)?;
+
+            Self::deposit_event(Event::ParameterSet { parameter });
+
+            Ok(())
+        }
+
+        /// Restore a reputation or issuance parameter to its default
+        ///
+        /// # Errors
+        /// - `InvalidParameter` if the default conflicts with another tuned parameter
+        #[pallet::call_index(23)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn reset_parameter(origin: OriginFor<T>, key: UbiParameterKey) -> DispatchResult {
+            T::ParameterOrigin::ensure_origin(origin)?;
+            let parameter = DefaultEconomics::<T, I>::get().get(key);
+            Self::update_economics(parameter)?;
+
+            Self::deposit_event(Event::ParameterReset { parameter });
+
+            Ok(())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config<I>, I: 'static> ValidateUnsigned for Pallet<T, I> {
+        type Call = Call<T, I>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            match call {
+                Call::claim { asset_id, account, nonce }
+                | Call::claim_short_expiry { asset_id, account, nonce }
+                | Call::claim_with_referral { asset_id, account, nonce, .. } => {
+                    let Ok(params) = Self::asset_parameters(asset_id) else {
+                        return InvalidTransaction::Custom(5).into();
+                    };
+
+                    // Validate that the account can actually claim, now or at the next boundary
+                    let current_block = frame_system::Pallet::<T>::block_number();
+                    let claim_block = Self::claim_inclusion_block(asset_id, &params, account, current_block);
+                    if Self::ensure_claim_proof(asset_id, &params, account, claim_block, *nonce).is_err() {
+                        return InvalidTransaction::Custom(8).into();
+                    }
+                    if let Call::claim_with_referral { referrer, .. } = call {
+                        if Self::ensure_referral(asset_id, &params, account, referrer, claim_block).is_err() {
+                            return InvalidTransaction::Custom(15).into();
+                        }
+                    }
+                    let claimable = Self::calculate_claimable_periods(asset_id, &params, account, claim_block);
+                    
+                    if claimable == 0 {
+                        return InvalidTransaction::Custom(1).into();
+                    }
+                    if Self::on_vacation(asset_id, &params, account, claim_block) {
+                        return InvalidTransaction::Custom(12).into();
+                    }
+                    if Self::supply_cap_reached(asset_id) {
+                        return InvalidTransaction::Custom(17).into();
+                    }
+                    
+                    // One claim of `account` per claim window in the pool
+                    let mut builder = ValidTransaction::with_tag_prefix("UbiClaim").and_provides((
+                        <Self as PalletInfoAccess>::name(),
+                        asset_id,
+                        account,
+                        Self::account_period(asset_id, &params, account, claim_block),
+                    ));
+                    let mut longevity = 5;
+                    if claim_block > current_block {
+                        // Nothing provides this tag: the claim waits in the future queue
+                        // until it is validated again at the boundary
+                        let claim_period = Self::block_to_period(&params, claim_block);
+                        builder = builder.and_requires((<Self as PalletInfoAccess>::name(), asset_id, claim_period));
+                        let wait: u64 = (claim_block - current_block).try_into().unwrap_or(u64::MAX);
+                        longevity = wait.saturating_add(longevity);
+                    }
+                    builder.longevity(longevity).propagate(true).build()
+                }
+                Call::claim_for_many { asset_id, accounts } => {
+                    let Ok(params) = Self::asset_parameters(asset_id) else {
+                        return InvalidTransaction::Custom(5).into();
+                    };
+                    // Batches cannot carry proofs
+                    if T::ClaimPowDifficulty::get() > 0 {
+                        return InvalidTransaction::Custom(8).into();
+                    }
+
+                    let current_block = frame_system::Pallet::<T>::block_number();
+                    let mut builder = ValidTransaction::with_tag_prefix("UbiClaim");
+                    let mut eligible = false;
+                    // Provide the same tags as single claims, so the pool keeps one of each
+                    for account in accounts.iter() {
+                        if Preferences::<T, I>::get(asset_id, account).allow_third_party_claims
+                            && Self::calculate_claimable_periods(asset_id, &params, account, current_block) > 0
+                        {
+                            eligible = true;
+                            builder = builder.and_provides((
+                                <Self as PalletInfoAccess>::name(),
+                                asset_id,
+                                account,
+                                Self::account_period(asset_id, &params, account, current_block),
+                            ));
+                        }
+                    }
+                    if !eligible {
+                        return InvalidTransaction::Custom(1).into();
+                    }
+
+                    builder.longevity(5).propagate(true).build()
+                }
+                Call::burn { asset_id, from, to, amount, nonce, valid_until, idempotency_key, .. } => {
+                    Self::validate_burn(asset_id, from, to, amount, *nonce, *valid_until, *idempotency_key)
+                }
+                Call::burn_ticket { asset_id, from, to, amount, nonce, .. } => {
+                    Self::validate_burn(asset_id, from, to, amount, *nonce, None, None)
+                }
+                Call::burn_authorized { authorization, signature } => {
+                    if !Self::is_burn_authorized(authorization, signature) {
+                        return InvalidTransaction::BadProof.into();
+                    }
+                    let BurnAuthorization { asset_id, from, to, amount, nonce, .. } = authorization;
+                    Self::validate_burn(asset_id, from, to, amount, *nonce, None, None)
+                }
+                Call::pay_service { from, provider, service_id, nonce } => {
+                    let Some(service) = Services::<T, I>::get(service_id) else {
+                        return InvalidTransaction::Custom(6).into();
+                    };
+                    if service.provider != *provider {
+                        return InvalidTransaction::Custom(6).into();
+                    }
+                    Self::validate_burn(&service.asset_id, from, provider, &service.price, *nonce, None, None)
+                }
+                Call::pay_invoice { from, payee, invoice_id, amount, nonce } => {
+                    let Some(invoice) = Invoices::<T, I>::get(invoice_id) else {
+                        return InvalidTransaction::Custom(6).into();
+                    };
+                    if invoice.payee != *payee || *amount > invoice.total.saturating_sub(invoice.paid) {
+                        return InvalidTransaction::Custom(6).into();
+                    }
+                    Self::validate_burn(&invoice.asset_id, from, payee, amount, *nonce, None, None)
+                }
+                Call::tidy { asset_id, account } => {
+                    if !Assets::<T, I>::contains_key(asset_id) {
+                        return InvalidTransaction::Custom(5).into();
+                    }
+                    // Free transactions that would not change anything are not accepted
+                    let kept = Self::spendable_balance(asset_id, account)
+                        .saturating_add(Self::locked_balance(asset_id, account));
+                    if Self::total_balance(asset_id, account) == kept {
+                        return InvalidTransaction::Custom(7).into();
+                    }
+
+                    ValidTransaction::with_tag_prefix("UbiTidy")
+                        .and_provides((
+                            <Self as PalletInfoAccess>::name(),
+                            asset_id,
+                            account,
+                            frame_system::Pallet::<T>::block_number(),
+                        ))
+                        .longevity(5)
+                        .propagate(true)
+                        .build()
+                }
+                Call::report_suspicion { burns } => {
+                    let Ok(report) = Self::check_ring(burns) else {
+                        return InvalidTransaction::Custom(10).into();
+                    };
+                    let reports = SuspicionReports::<T, I>::get();
+                    if reports.is_full() || Self::is_reported(&reports, &report) {
+                        return InvalidTransaction::Custom(11).into();
+                    }
+
+                    // One report per ring in the pool
+                    ValidTransaction::with_tag_prefix("UbiRing")
+                        .and_provides((<Self as PalletInfoAccess>::name(), report.asset_id, report.accounts))
+                        .longevity(5)
+                        .propagate(true)
+                        .build()
+                }
+                Call::sweep_expiry_index { asset_id, period, accounts } => {
+                    if !Assets::<T, I>::contains_key(asset_id) {
+                        return InvalidTransaction::Custom(5).into();
+                    }
+                    if !Self::is_expiry_sweep_valid(asset_id, *period, accounts) {
+                        return InvalidTransaction::Custom(14).into();
+                    }
+
+                    // Every validator submits the same sweeps, one of each is kept
+                    ValidTransaction::with_tag_prefix("UbiExpirySweep")
+                        .and_provides((<Self as PalletInfoAccess>::name(), asset_id, period, accounts))
+                        .longevity(5)
+                        .propagate(true)
+                        .build()
+                }
+                _ => InvalidTransaction::Call.into(),
+            }
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> fungibles::Inspect<T::AccountId> for Pallet<T, I> {
+        type AssetId = T::AssetId;
+        type Balance = BalanceOf<T, I>;
+
+        fn total_issuance(asset: Self::AssetId) -> Self::Balance {
+            TotalSupply::<T, I>::get(asset)
+        }
+
+        /// There is no existential deposit
+        fn minimum_balance(_asset: Self::AssetId) -> Self::Balance {
+            Zero::zero()
+        }
+
+        /// Unexpired tokens including locked commitments
+        fn total_balance(asset: Self::AssetId, who: &T::AccountId) -> Self::Balance {
+            Self::spendable_balance(&asset, who).saturating_add(Self::locked_balance(&asset, who))
+        }
+
+        fn balance(asset: Self::AssetId, who: &T::AccountId) -> Self::Balance {
+            Self::spendable_balance(&asset, who)
+        }
+
+        fn reducible_balance(
+            asset: Self::AssetId,
+            who: &T::AccountId,
+            _preservation: Preservation,
+            _force: Fortitude,
+        ) -> Self::Balance {
+            Self::spendable_balance(&asset, who)
+        }
+
+        /// Tokens are only created by claims, nothing can be deposited
+        fn can_deposit(
+            asset: Self::AssetId,
+            _who: &T::AccountId,
+            _amount: Self::Balance,
+            _provenance: Provenance,
+        ) -> DepositConsequence {
+            if !Assets::<T, I>::contains_key(asset) {
+                return DepositConsequence::UnknownAsset;
+            }
+            DepositConsequence::Blocked
+        }
+
+        fn can_withdraw(
+            asset: Self::AssetId,
+            who: &T::AccountId,
+            amount: Self::Balance,
+        ) -> WithdrawConsequence<Self::Balance> {
+            if !Assets::<T, I>::contains_key(asset) {
+                return WithdrawConsequence::UnknownAsset;
+            }
+            if Self::spendable_balance(&asset, who) < amount {
+                return WithdrawConsequence::BalanceLow;
+            }
+            WithdrawConsequence::Success
+        }
+
+        fn asset_exists(asset: Self::AssetId) -> bool {
+            Assets::<T, I>::contains_key(asset)
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> ReputationOracle<T::AccountId> for Pallet<T, I> {
+        type AssetId = T::AssetId;
+        type Score = BalanceOf<T, I>;
+        type BlockNumber = BlockNumberFor<T>;
+
+        fn score(asset_id: &Self::AssetId, who: &T::AccountId) -> Self::Score {
+            Self::reputation_score(asset_id, who)
+        }
+
+        fn queued_snapshots() -> u32 {
+            let (head, next) = OutboundQueueBounds::<T, I>::get();
+            next.saturating_sub(head).try_into().unwrap_or(u32::MAX)
+        }
+
+        fn take_snapshots(max: u32) -> Vec<ReputationSnapshotOf<T, I>> {
+            let (head, next) = OutboundQueueBounds::<T, I>::get();
+            let end = next.min(head.saturating_add(max.into()));
+            let snapshots = (head..end).filter_map(OutboundSnapshots::<T, I>::take).collect();
+            OutboundQueueBounds::<T, I>::put((end, next));
+            snapshots
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> frame_support::traits::OnKilledAccount<T::AccountId> for Pallet<T, I> {
+        /// Reap the account in every asset, at most `T::MaxAssets` of them
+        fn on_killed_account(who: &T::AccountId) {
+            for asset_id in Assets::<T, I>::iter_keys() {
+                Self::reap_account(asset_id, who);
+            }
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        /// Parameters built from the `Config` constants (used for genesis assets)
+        pub fn default_parameters() -> AssetParametersOf<T, I> {
+            AssetParameters {
+                ubi_amount: T::UbiAmount::get(),
+                claim_period_blocks: T::ClaimPeriodBlocks::get(),
+                expiration_blocks: T::ExpirationBlocks::get(),
+                max_backlog_periods: T::MaxBacklogPeriods::get(),
+            }
+        }
+
+        /// Whether the supply of an asset is at its cap, so claims would get nothing
+        pub fn supply_cap_reached(asset_id: &T::AssetId) -> bool {
+            SupplyCaps::<T, I>::get(asset_id).is_some_and(|cap| TotalSupply::<T, I>::get(asset_id) >= cap.ceiling)
+        }
+
+        /// Per-period UBI amount of an asset in claim period `period`
+        ///
+        /// The amount of the last emission step started by `period`, or the
+        /// asset's `ubi_amount` before the first step.
+        pub fn ubi_amount_at(
+            asset_id: &T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            period: u64,
+        ) -> BalanceOf<T, I> {
+            let amount = EmissionSchedules::<T, I>::get(asset_id)
+                .iter()
+                .rev()
+                .find(|step| step.start_period <= period)
+                .map_or(params.ubi_amount, |step| step.ubi_amount);
+            match Self::velocity_at(asset_id, period).adjustment {
+                1000 => amount,
+                adjustment => amount.saturating_mul(adjustment.into()) / 1000u32.into(),
+            }
+        }
+
+        /// Velocity state of an asset as of `period`
+        ///
+        /// Closes the stored period if `period` is later: records its velocity
+        /// and, under a velocity policy, moves the adjustment one step toward
+        /// the target. Periods without claims leave the adjustment unchanged.
+        pub fn velocity_at(asset_id: &T::AssetId, period: u64) -> VelocityStateOf<T, I> {
+            let mut state = Velocity::<T, I>::get(asset_id);
+            if state.period >= period {
+                return state;
+            }
+            if !state.claimed.is_zero() {
+                let velocity: u32 =
+                    (state.burned.saturating_mul(1000u32.into()) / state.claimed).unique_saturated_into();
+                if let Some(policy) = VelocityPolicies::<T, I>::get(asset_id) {
+                    state.adjustment = match velocity.cmp(&policy.target_velocity) {
+                        core::cmp::Ordering::Less => state.adjustment.saturating_sub(policy.step),
+                        core::cmp::Ordering::Greater => state.adjustment.saturating_add(policy.step),
+                        core::cmp::Ordering::Equal => state.adjustment,
+                    }
+                    .clamp(policy.min_adjustment, policy.max_adjustment);
+                }
+                state.last_velocity = Some(velocity);
+            }
+            VelocityStateOf::<T, I> { period, claimed: Zero::zero(), burned: Zero::zero(), ..state }
+        }
+
+        /// Add a claim or burn to the issuance flows of the current period
+        fn record_flow(asset_id: T::AssetId, period: u64, claimed: BalanceOf<T, I>, burned: BalanceOf<T, I>) {
+            let previous = Velocity::<T, I>::get(asset_id).adjustment;
+            let mut state = Self::velocity_at(&asset_id, period);
+            if state.adjustment != previous {
+                Self::deposit_event(Event::VelocityAdjusted {
+                    asset_id,
+                    period,
+                    velocity: state.last_velocity.unwrap_or_default(),
+                    adjustment: state.adjustment,
+                });
+            }
+            state.claimed = state.claimed.saturating_add(claimed);
+            state.burned = state.burned.saturating_add(burned);
+            Velocity::<T, I>::insert(asset_id, state);
+        }
+
+        /// Check that `account` can join with `referrer` at `current_block`
+        fn ensure_referral(
+            asset_id: &T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            account: &T::AccountId,
+            referrer: &T::AccountId,
+            current_block: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            ensure!(account != referrer, Error::<T, I>::CannotReferSelf);
+            // Pruned accounts lose their `LastClaim`, but not their referrer
+            ensure!(
+                !LastClaim::<T, I>::contains_key(asset_id, account)
+                    && !Referrers::<T, I>::contains_key(asset_id, account),
+                Error::<T, I>::AlreadyOnboarded
+            );
+            ensure!(LastClaim::<T, I>::contains_key(asset_id, referrer), Error::<T, I>::UnknownReferrer);
+            let (period, count) = ReferralCounts::<T, I>::get(asset_id, referrer);
+            ensure!(
+                period != Self::block_to_period(params, current_block) || count < T::MaxReferralsPerPeriod::get(),
+                Error::<T, I>::TooManyReferrals
+            );
+            Ok(())
+        }
+
+        /// Claim all claimable periods of `who` at `current_block`
+        ///
+        /// Shared by `claim`, `claim_short_expiry` and `claim_for_many`. Returns the
+        /// actual weight of the claim.
+        fn do_claim(
+            asset_id: T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            who: T::AccountId,
+            current_block: BlockNumberFor<T>,
+            mode: ClaimMode,
+            extra_periods: u32,
+        ) -> Result<Weight, DispatchError> {
+            // Calculate claimable periods
+            let claimable_periods = Self::calculate_claimable_periods(&asset_id, params, &who, current_block);
+            ensure!(claimable_periods > 0, Error::<T, I>::NothingToClaim);
+            ensure!(!Self::on_vacation(&asset_id, params, &who, current_block), Error::<T, I>::OnVacation);
+
+            // Cap at max backlog, plus the extra periods paid with reputation
+            let periods_to_claim = claimable_periods.min(params.max_backlog_periods.saturating_add(extra_periods));
+            let extra_claimed = periods_to_claim.saturating_sub(params.max_backlog_periods);
+            let extra_cost = BalanceOf::<T, I>::from(extra_claimed)
+                .saturating_mul(Economics::<T, I>::get().backlog_cost_per_period.into());
+            ensure!(
+                extra_cost <= Self::reputation_score(&asset_id, &who),
+                Error::<T, I>::InsufficientReputation
+            );
+            let current_period = Self::block_to_period(params, current_block);
+            let bonus = Self::active_bonus_period(&asset_id, current_block);
+            let multiplier = bonus.as_ref().map_or(100, |b| b.claim_multiplier);
+            let mut amount_to_claim = Self::ubi_amount_at(&asset_id, params, current_period)
+                .saturating_mul(periods_to_claim.into())
+                .saturating_mul(multiplier.into())
+                / 100u32.into();
+            let mut expiration_blocks = params.expiration_blocks;
+            if mode == ClaimMode::ShortExpiry {
+                let economics = Economics::<T, I>::get();
+                amount_to_claim = amount_to_claim.saturating_add(economics.short_expiry_bonus * amount_to_claim);
+                expiration_blocks = (economics.short_expiry_ratio * expiration_blocks).max(One::one());
+            }
+
+            // An account without a last claim claims for the first time, or again after
+            // being pruned or reaped, and gets the welcome bonuses with it
+            let onboarding = !LastClaim::<T, I>::contains_key(asset_id, &who);
+            let (welcome_bonus, welcome_reputation) = if onboarding {
+                (T::WelcomeBonus::get(), T::WelcomeReputation::get())
+            } else {
+                (Zero::zero(), Zero::zero())
+            };
+            amount_to_claim = amount_to_claim.saturating_add(welcome_bonus);
+
+            // Close to the supply cap, the claim shrinks with the headroom left
+            let requested = amount_to_claim;
+            if let Some(cap) = SupplyCaps::<T, I>::get(asset_id) {
+                amount_to_claim = cap.throttle(TotalSupply::<T, I>::get(asset_id), requested);
+                ensure!(!amount_to_claim.is_zero(), Error::<T, I>::SupplyCapReached);
+            }
+
+            // Clean up expired batches first
+            let (_, cleaned) = Self::expire_batches(asset_id, &who, params, current_block);
+
+            // Add the new batch
+            let expires_at = current_block.saturating_add(expiration_blocks);
+            Self::credit_batch(asset_id, &who, params, current_block, amount_to_claim, expires_at)?;
+
+            // Keep the account alive from its first claim on, it may have no native balance
+            // and anchor its claim windows to this block
+            if onboarding {
+                frame_system::Pallet::<T>::inc_providers(&who);
+                ClaimOffsets::<T, I>::insert(asset_id, &who, current_block % params.claim_period_blocks);
+            }
+
+            // Update last claim block
+            LastClaim::<T, I>::insert(asset_id, &who, current_block);
+
+            // Update total supply
+            TotalSupply::<T, I>::mutate(asset_id, |supply| {
+                *supply = supply.saturating_add(amount_to_claim);
+            });
+            Self::record_flow(asset_id, current_period, amount_to_claim, Zero::zero());
+
+            // Update reputation: decay, streak, and recalculate score
+            Self::mutate_reputation(asset_id, &who, |rep| {
+                // Set first activity if this is the first time
+                if rep.first_activity == Zero::zero() {
+                    rep.first_activity = current_block;
+                }
+                rep.last_activity = current_block;
+                
+                // Apply 5% decay per period elapsed since the last update
+                Self::apply_elapsed_decay(rep, current_period);
+                
+                // Update claim streak (handles grace period logic)
+                let streak_days = if bonus.as_ref().is_some_and(|b| b.double_streak) { 2 } else { 1 };
+                Self::update_streak(rep, current_period, streak_days);
+
+                rep.backlog_cost = rep.backlog_cost.saturating_add(extra_cost);
+                rep.bonus_credit = rep.bonus_credit.saturating_add(welcome_reputation);
+                
+                // Recalculate full score from components
+                rep.score = Self::recalculate_score(rep);
+            });
+
+            if extra_claimed > 0 {
+                Self::deposit_event(Event::ExtraBacklogClaimed {
+                    asset_id,
+                    who: who.clone(),
+                    periods: extra_claimed,
+                    cost: extra_cost,
+                });
+            }
+
+            if amount_to_claim < requested {
+                Self::deposit_event(Event::ClaimThrottled {
+                    asset_id,
+                    who: who.clone(),
+                    requested,
+                    granted: amount_to_claim,
+                });
+            }
+
+            if onboarding {
+                Self::deposit_event(Event::AccountOnboarded {
+                    asset_id,
+                    who: who.clone(),
+                    bonus: welcome_bonus,
+                    reputation: welcome_reputation,
+                });
+            }
+
+            let remaining = Self::spendable_balance(&asset_id, &who);
+            Self::deposit_event(Event::Claimed {
+                asset_id,
+                who,
+                amount: amount_to_claim,
+                periods: periods_to_claim,
+                expires_at,
+                mode,
+                period: current_period,
+                remaining,
+            });
+
+            Ok(Self::claim_weight(cleaned, periods_to_claim))
+        }
+
+        /// Burn tokens of `from` with `to` as the named recipient
+        ///
+        /// Shared by `burn`, `burn_from` and `pay_service`; updates supply, reputation, category
+        /// statistics and stores the receipt. Returns the burn id and the actual weight.
+        fn do_burn(
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            category: Option<BurnCategory>,
+            reference: Option<H256>,
+        ) -> Result<(BurnId, Weight), DispatchError> {
+            // Validation
+            let params = Self::asset_parameters(&asset_id)?;
+            ensure!(from != to, Error::<T, I>::CannotBurnToSelf);
+            ensure!(!amount.is_zero(), Error::<T, I>::AmountMustBePositive);
+            ensure!(
+                amount >= Preferences::<T, I>::get(asset_id, &to).min_incoming_burn,
+                Error::<T, I>::BelowRecipientMinimum
+            );
+
+            // A burn referencing a payment intent must pay it exactly, and only once
+            let intent = reference.and_then(|id| PaymentIntents::<T, I>::get(id).map(|intent| (id, intent)));
+            if let Some((_, intent)) = &intent {
+                ensure!(intent.fulfilled_by.is_none(), Error::<T, I>::PaymentIntentFulfilled);
+                ensure!(
+                    intent.asset_id == asset_id && intent.merchant == to && intent.amount == amount,
+                    Error::<T, I>::PaymentIntentMismatch
+                );
+            }
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+
+            // Clean up expired batches first
+            let (_, cleaned) = Self::expire_batches(asset_id, &from, &params, current_block);
+
+            // Check balance and burn using FIFO
+            let (_, consumed) = Self::burn_fifo(&asset_id, &from, amount, current_block)?;
+
+            // Update total supply
+            TotalSupply::<T, I>::mutate(asset_id, |supply| {
+                *supply = supply.saturating_sub(amount);
+            });
+
+            // Get sender's current (decayed) reputation score for weighting
+            let current_period = Self::block_to_period(&params, current_block);
+            Self::record_flow(asset_id, current_period, Zero::zero(), amount);
+            let sender_score = Self::reputation_score(&asset_id, &from);
+            let sender_weight = Self::calculate_sender_weight(sender_score);
+            
+            // Calculate weighted amount: amount * weight / 1000
+            let weighted_amount = amount.saturating_mul(sender_weight.into()) / 1000u32.into();
+
+            // Check if this is a new unique recipient for the sender
+            let mut recipients = UniqueRecipientSets::<T, I>::get(asset_id, &from);
+            let is_new_recipient = !recipients.contains(&to) && recipients.try_insert(to.clone()).is_ok();
+            if is_new_recipient {
+                UniqueRecipientSets::<T, I>::insert(asset_id, &from, recipients);
+            }
+
+            // Burns to verified charities count extra for the sender
+            let sender_bonus = if VerifiedCharities::<T, I>::contains_key(asset_id, &to) {
+                T::CharityBonus::get().mul_floor(amount)
+            } else {
+                Zero::zero()
+            };
+
+            // Update sender reputation
+            Self::mutate_reputation(asset_id, &from, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                rep.burns_sent_count = rep.burns_sent_count.saturating_add(1);
+                rep.burns_sent_volume = rep.burns_sent_volume.saturating_add(amount).saturating_add(sender_bonus);
+                
+                // Track unique recipients
+                if is_new_recipient {
+                    rep.unique_recipients_count = rep.unique_recipients_count.saturating_add(1);
+                }
+                
+                if rep.first_activity == Zero::zero() {
+                    rep.first_activity = current_block;
+                }
+                rep.last_activity = current_block;
+                
+                // Recalculate sender's score
+                rep.score = Self::recalculate_score(rep);
+            });
+
+            // Update recipient reputation
+            let members = Organizations::<T, I>::get(asset_id, &to);
+            Self::mutate_reputation(asset_id, &to, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                rep.burns_received_count = rep.burns_received_count.saturating_add(1);
+                rep.burns_received_volume = rep.burns_received_volume.saturating_add(amount);
+                
+                // Add weighted received (weighted by sender's reputation), unless
+                // an organization's members get it
+                if members.is_none() {
+                    rep.weighted_received = rep.weighted_received.saturating_add(weighted_amount);
+                }
+                
+                if rep.first_activity == Zero::zero() {
+                    rep.first_activity = current_block;
+                }
+                rep.last_activity = current_block;
+                
+                // Recalculate recipient's score
+                rep.score = Self::recalculate_score(rep);
+            });
+
+            let split = members
+                .map(|members| Self::credit_organization_members(asset_id, &members, weighted_amount, current_period));
+
+            Self::record_supporter_burn(asset_id, &to, &from, amount, current_block);
+
+            if let Some(category) = category {
+                BurnCategoryStats::<T, I>::mutate((asset_id, current_period, category), |stats| {
+                    stats.count = stats.count.saturating_add(1);
+                    stats.volume = stats.volume.saturating_add(amount);
+                });
+            }
+
+            let burn_id = NextBurnId::<T, I>::mutate(|next| {
+                let id = *next;
+                *next = next.saturating_add(1);
+                id
+            });
+            if let Some(split) = split {
+                OrganizationSplits::<T, I>::insert(burn_id, split);
+            }
+            BurnReceipts::<T, I>::insert(
+                burn_id,
+                BurnReceipt {
+                    asset_id,
+                    from: from.clone(),
+                    to: to.clone(),
+                    amount,
+                    category,
+                    reference,
+                    block: current_block,
+                    weighted_amount,
+                    sender_bonus,
+                    new_recipient: is_new_recipient,
+                    rejected: false,
+                    refunded: false,
+                },
+            );
+            if let Some((intent_id, mut intent)) = intent {
+                intent.fulfilled_by = Some(burn_id);
+                PaymentIntents::<T, I>::insert(intent_id, intent);
+                Self::deposit_event(Event::PaymentIntentFulfilled { intent_id, burn_id, payer: from.clone() });
+            }
+
+            let remaining = Self::spendable_balance(&asset_id, &from);
+            Self::deposit_event(Event::Burned {
+                burn_id,
+                asset_id,
+                from,
+                to,
+                amount,
+                category,
+                reference,
+                period: current_period,
+                remaining,
+                sender_weight,
+            });
+
+            Ok((burn_id, Self::burn_weight(cleaned, consumed)))
+        }
+
+        /// Claim for one account of a batch, rolling back a failed claim without failing the batch
+        ///
+        /// Returns the weight of the claim, or as error the weight of the
+        /// skipped account, which still costs the eligibility check.
+        fn claim_or_skip(
+            asset_id: T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            who: &T::AccountId,
+            current_block: BlockNumberFor<T>,
+        ) -> Result<Weight, Weight> {
+            frame_support::storage::with_storage_layer(|| {
+                Self::do_claim(asset_id, params, who.clone(), current_block, ClaimMode::Standard, 0)
+            })
+            .map_err(|_| Self::claim_weight(0, 0))
+        }
+
+        /// Weight of a claim that expired `cleaned` batches and paid out `periods` periods
+        ///
+        /// Claims declare the worst case and refund down to the actual counts.
+        pub fn claim_weight(cleaned: u32, periods: u32) -> Weight {
+            Weight::from_parts(10_000, 0)
+                .saturating_add(Weight::from_parts(WEIGHT_PER_BATCH, 0).saturating_mul(cleaned.into()))
+                .saturating_add(Weight::from_parts(WEIGHT_PER_PERIOD, 0).saturating_mul(periods.into()))
+                .saturating_add(T::DbWeight::get().reads_writes(10, 7))
+        }
+
+        /// Weight of a burn that expired `cleaned` batches and drew from `consumed` batches
+        ///
+        /// Burns declare the worst case and refund down to the actual counts.
+        pub fn burn_weight(cleaned: u32, consumed: u32) -> Weight {
+            Weight::from_parts(10_000, 0)
+                .saturating_add(
+                    Weight::from_parts(WEIGHT_PER_BATCH, 0).saturating_mul(cleaned.saturating_add(consumed).into()),
+                )
+                .saturating_add(T::DbWeight::get().reads_writes(18, 15))
+                .saturating_add(T::DbWeight::get().reads_writes(
+                    T::MaxOrganizationMembers::get().into(),
+                    T::MaxOrganizationMembers::get().into(),
+                ))
+        }
+
+        /// Credit the recognition of a burn to an organization's members by share
+        ///
+        /// The rounding remainder goes to the first member. Returns what each
+        /// member was credited.
+        fn credit_organization_members(
+            asset_id: T::AssetId,
+            members: &OrganizationMembersOf<T, I>,
+            weighted_amount: BalanceOf<T, I>,
+            current_period: u64,
+        ) -> OrganizationSplitOf<T, I> {
+            let total_shares = members.iter().fold(0u32, |acc, m| acc.saturating_add(m.share));
+            let mut credited: BalanceOf<T, I> = Zero::zero();
+            let mut split: Vec<_> = members
+                .iter()
+                .map(|m| {
+                    let part = weighted_amount.saturating_mul(m.share.into()) / total_shares.into();
+                    credited = credited.saturating_add(part);
+                    (m.who.clone(), part)
+                })
+                .collect();
+            if let Some((_, part)) = split.first_mut() {
+                *part = part.saturating_add(weighted_amount.saturating_sub(credited));
+            }
+
+            for (member, part) in &split {
+                Self::mutate_reputation(asset_id, member, |rep| {
+                    Self::apply_elapsed_decay(rep, current_period);
+                    rep.weighted_received = rep.weighted_received.saturating_add(*part);
+                    rep.score = Self::recalculate_score(rep);
+                });
+            }
+            BoundedVec::truncate_from(split)
+        }
+
+        /// Add a batch of new tokens to an account, merged into a batch with the same expiration
+        ///
+        /// Schedules the expiry warning of a new batch and indexes it for the
+        /// expiry sweeper. Does not touch `TotalSupply`.
+        fn credit_batch(
+            asset_id: T::AssetId,
+            who: &T::AccountId,
+            params: &AssetParametersOf<T, I>,
+            current_block: BlockNumberFor<T>,
+            amount: BalanceOf<T, I>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let merged = Balances::<T, I>::try_mutate(asset_id, who, |batches| -> Result<bool, DispatchError> {
+                // Try to merge with existing batch that has same expiration
+                let merged = batches.iter_mut().any(|b| {
+                    if b.expires_at == expires_at {
+                        b.amount = b.amount.saturating_add(amount);
+                        true
+                    } else {
+                        false
+                    }
+                });
+
+                if !merged {
+                    batches
+                        .try_push(TokenBatch { amount, expires_at })
+                        .map_err(|_| Error::<T, I>::TooManyBatches)?;
+                }
+                Ok(merged)
+            })?;
+
+            // A merged batch already has its warning scheduled
+            if !merged {
+                Self::schedule_expiry_warning(asset_id, who, params, current_block, expires_at);
+            }
+            ExpiryIndex::<T, I>::insert((asset_id, Self::block_to_period(params, expires_at), who), ());
+            Ok(())
+        }
+
+        /// Purge the expired batches of an account and emit `Expired` if anything expired
+        ///
+        /// Returns the expired amount and the number of expired batches.
+        fn expire_batches(
+            asset_id: T::AssetId,
+            who: &T::AccountId,
+            params: &AssetParametersOf<T, I>,
+            current_block: BlockNumberFor<T>,
+        ) -> (BalanceOf<T, I>, u32) {
+            let (expired, count) = Self::cleanup_expired_batches(&asset_id, who, current_block);
+            if !expired.is_zero() {
+                BlockExpirations::<T, I>::mutate(asset_id, |(batches, total)| {
+                    *batches = batches.saturating_add(count);
+                    *total = total.saturating_add(expired);
+                });
+                Self::deposit_event(Event::Expired {
+                    asset_id,
+                    who: who.clone(),
+                    amount: expired,
+                    period: Self::block_to_period(params, current_block),
+                    remaining: Self::spendable_balance(&asset_id, who),
+                });
+            }
+            (expired, count)
+        }
+
+        /// Get the parameters of an asset, failing if it does not exist
+        pub fn asset_parameters(asset_id: &T::AssetId) -> Result<AssetParametersOf<T, I>, Error<T, I>> {
+            Assets::<T, I>::get(asset_id)
+                .map(|details| details.params)
+                .ok_or(Error::<T, I>::UnknownAsset)
+        }
+
+        /// Effective parameters of an asset together with the reputation constants
+        ///
+        /// Backs the `ubi_parameters` runtime API. Returns `None` for unknown assets.
+        pub fn ubi_parameters(asset_id: &T::AssetId) -> Option<UbiParametersOf<T, I>> {
+            let asset = Assets::<T, I>::get(asset_id)?.params;
+            let current_period = Self::block_to_period(&asset, frame_system::Pallet::<T>::block_number());
+            let current_ubi_amount = Self::ubi_amount_at(asset_id, &asset, current_period);
+            Some(UbiParameters {
+                asset,
+                reputation: Economics::<T, I>::get().reputation,
+                claim_pow_difficulty: T::ClaimPowDifficulty::get(),
+                claim_lookahead_blocks: T::ClaimLookaheadBlocks::get(),
+                current_ubi_amount,
+            })
+        }
+
+        /// Check the proof-of-work of a claim of `who` included at `current_block`
+        fn ensure_claim_proof(
+            asset_id: &T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            who: &T::AccountId,
+            current_block: BlockNumberFor<T>,
+            nonce: u64,
+        ) -> DispatchResult {
+            let difficulty = T::ClaimPowDifficulty::get();
+            if difficulty == 0 {
+                return Ok(());
+            }
+            let period = Self::block_to_period(params, current_block);
+            let hash = claim_pow_hash(asset_id, who, period, nonce);
+            ensure!(leading_zero_bits(&hash) >= difficulty, Error::<T, I>::InvalidClaimProof);
+            Ok(())
+        }
+
+        /// Block whose claim period a claim of `who` validated at `current_block` is for
+        ///
+        /// This is `current_block` itself, unless `who` cannot claim now but can
+        /// at the next period boundary and that boundary is at most
+        /// `T::ClaimLookaheadBlocks` away, in which case it is the boundary block.
+        fn claim_inclusion_block(
+            asset_id: &T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            who: &T::AccountId,
+            current_block: BlockNumberFor<T>,
+        ) -> BlockNumberFor<T> {
+            if Self::calculate_claimable_periods(asset_id, params, who, current_block) > 0 {
+                return current_block;
+            }
+            let period_blocks = params.claim_period_blocks;
+            let offset = ClaimOffsets::<T, I>::get(asset_id, who) % period_blocks;
+            let boundary = current_block
+                .saturating_sub(current_block.saturating_sub(offset) % period_blocks)
+                .saturating_add(period_blocks);
+            if boundary.saturating_sub(current_block) <= T::ClaimLookaheadBlocks::get()
+                && Self::calculate_claimable_periods(asset_id, params, who, boundary) > 0
+            {
+                boundary
+            } else {
+                current_block
+            }
+        }
+
+        /// Number of unsigned burns `who` may still send in the current claim period
+        pub fn unsigned_burns_left(asset_id: &T::AssetId, who: &T::AccountId) -> u32 {
+            let Ok(params) = Self::asset_parameters(asset_id) else {
+                return 0;
+            };
+            let current_period = Self::block_to_period(&params, frame_system::Pallet::<T>::block_number());
+            let (period, count) = UnsignedBurns::<T, I>::get(asset_id, who);
+            let sent = if period == current_period { count } else { 0 };
+            T::MaxUnsignedBurnsPerPeriod::get().saturating_sub(sent)
+        }
+
+        /// Pool validity of an unsigned burn, ordered by the sender's burn nonce
+        fn validate_burn(
+            asset_id: &T::AssetId,
+            from: &T::AccountId,
+            to: &T::AccountId,
+            amount: &BalanceOf<T, I>,
+            nonce: u64,
+            valid_until: Option<BlockNumberFor<T>>,
+            idempotency_key: Option<H256>,
+        ) -> TransactionValidity {
+            // Basic validation
+            if !Assets::<T, I>::contains_key(asset_id) {
+                return InvalidTransaction::Custom(5).into();
+            }
+            let current_block = frame_system::Pallet::<T>::block_number();
+            if valid_until.is_some_and(|deadline| current_block > deadline) {
+                return InvalidTransaction::Stale.into();
+            }
+            if idempotency_key.is_some_and(|key| Self::idempotency_key_used(asset_id, from, key)) {
+                return InvalidTransaction::Custom(16).into();
+            }
+            if Self::unsigned_burns_left(asset_id, from) == 0 {
+                return InvalidTransaction::Custom(9).into();
+            }
+            if from == to {
+                return InvalidTransaction::Custom(2).into();
+            }
+            if amount.is_zero() {
+                return InvalidTransaction::Custom(3).into();
+            }
+            if *amount < Preferences::<T, I>::get(asset_id, to).min_incoming_burn {
+                return InvalidTransaction::Custom(13).into();
+            }
+
+            // Check balance
+            let balance = Self::spendable_balance(asset_id, from);
+            if balance < *amount {
+                return InvalidTransaction::Custom(4).into();
+            }
+
+            let next_nonce = BurnNonces::<T, I>::get(asset_id, from);
+            if nonce < next_nonce {
+                return InvalidTransaction::Stale.into();
+            }
+
+            let mut builder = ValidTransaction::with_tag_prefix("UbiBurn").and_provides((
+                <Self as PalletInfoAccess>::name(),
+                asset_id,
+                from,
+                nonce,
+            ));
+            // Later burns wait in the pool until the previous one is included
+            if nonce > next_nonce {
+                builder = builder.and_requires((
+                    <Self as PalletInfoAccess>::name(),
+                    asset_id,
+                    from,
+                    nonce - 1,
+                ));
+            }
+            // The pool keeps one burn per key, like the chain
+            if let Some(key) = idempotency_key {
+                builder = builder.and_provides((<Self as PalletInfoAccess>::name(), asset_id, from, key));
+            }
+            // Leave the pool once the deadline has passed
+            let mut longevity = 5;
+            if let Some(deadline) = valid_until {
+                let left: u64 = (deadline - current_block).try_into().unwrap_or(u64::MAX);
+                longevity = longevity.min(left.saturating_add(1));
+            }
+            builder.longevity(longevity).propagate(true).build()
+        }
+
+        /// Payload `from` signs to authorize a `burn_authorized`
+        ///
+        /// Bound to the chain's genesis hash and the current spec version.
+        pub fn burn_authorization_payload(
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            category: Option<BurnCategory>,
+            reference: Option<H256>,
+            nonce: u64,
+        ) -> BurnAuthorizationOf<T, I> {
+            BurnAuthorization {
+                context: BURN_AUTHORIZATION_CONTEXT.to_vec(),
+                pallet: <Self as PalletInfoAccess>::name().as_bytes().to_vec(),
+                genesis_hash: frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero()),
+                spec_version: <T as frame_system::Config>::Version::get().spec_version,
+                asset_id,
+                from,
+                to,
+                amount,
+                category,
+                reference,
+                nonce,
+            }
+        }
+
+        /// Whether `authorization` is meant for this chain, runtime and pallet and signed by its sender
+        fn is_burn_authorized(authorization: &BurnAuthorizationOf<T, I>, signature: &T::OffchainSignature) -> bool {
+            let expected = Self::burn_authorization_payload(
+                authorization.asset_id,
+                authorization.from.clone(),
+                authorization.to.clone(),
+                authorization.amount,
+                authorization.category,
+                authorization.reference,
+                authorization.nonce,
+            );
+            *authorization == expected && signature.verify(&authorization.encode()[..], &authorization.from)
+        }
+
+        /// Apply an unsigned burn: count it, consume the nonce and burn
+        fn do_unsigned_burn(
+            asset_id: T::AssetId,
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: BalanceOf<T, I>,
+            category: Option<BurnCategory>,
+            reference: Option<H256>,
+            nonce: u64,
+        ) -> Result<(BurnId, Weight), DispatchError> {
+            Self::note_unsigned_burn(&asset_id, &from)?;
+            BurnNonces::<T, I>::try_mutate(asset_id, &from, |next| {
+                ensure!(*next == nonce, Error::<T, I>::InvalidBurnNonce);
+                *next = next.saturating_add(1);
+                Ok::<_, DispatchError>(())
+            })?;
+            Self::do_burn(asset_id, from, to, amount, category, reference)
+        }
+
+        /// Whether `who` used the idempotency key `key` within `T::IdempotencyWindow`
+        pub fn idempotency_key_used(asset_id: &T::AssetId, who: &T::AccountId, key: H256) -> bool {
+            let current_block = frame_system::Pallet::<T>::block_number();
+            IdempotencyKeys::<T, I>::get(asset_id, who)
+                .iter()
+                .any(|(used, reusable_at)| *used == key && *reusable_at > current_block)
+        }
+
+        /// Record the idempotency key of a burn of `who`, dropping the keys past their window
+        fn use_idempotency_key(asset_id: &T::AssetId, who: &T::AccountId, key: H256) -> DispatchResult {
+            let current_block = frame_system::Pallet::<T>::block_number();
+            IdempotencyKeys::<T, I>::try_mutate(asset_id, who, |keys| {
+                keys.retain(|(_, reusable_at)| *reusable_at > current_block);
+                ensure!(keys.iter().all(|(used, _)| *used != key), Error::<T, I>::DuplicateIdempotencyKey);
+                keys.try_push((key, current_block.saturating_add(T::IdempotencyWindow::get())))
+                    .map_err(|_| Error::<T, I>::TooManyIdempotencyKeys)?;
+                Ok(())
+            })
+        }
+
+        /// Count an unsigned burn of `who`, failing if it exceeds the per-period limit
+        fn note_unsigned_burn(asset_id: &T::AssetId, who: &T::AccountId) -> DispatchResult {
+            let params = Self::asset_parameters(asset_id)?;
+            let current_period = Self::block_to_period(&params, frame_system::Pallet::<T>::block_number());
+            UnsignedBurns::<T, I>::try_mutate(asset_id, who, |(period, count)| {
+                if *period != current_period {
+                    *period = current_period;
+                    *count = 0;
+                }
+                ensure!(*count < T::MaxUnsignedBurnsPerPeriod::get(), Error::<T, I>::TooManyUnsignedBurns);
+                *count = count.saturating_add(1);
+                Ok(())
+            })
+        }
+
+        /// Reject parameters that would make claims meaningless or divide by zero
+        fn ensure_valid_parameters(params: &AssetParametersOf<T, I>) -> DispatchResult {
+            ensure!(
+                !params.ubi_amount.is_zero()
+                    && !params.claim_period_blocks.is_zero()
+                    && !params.expiration_blocks.is_zero(),
+                Error::<T, I>::InvalidAssetParameters
+            );
+            Ok(())
+        }
+
+        /// Calculate how many periods the account can claim
+        fn calculate_claimable_periods(
+            asset_id: &T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            who: &T::AccountId,
+            current_block: BlockNumberFor<T>,
+        ) -> u32 {
+            match LastClaim::<T, I>::get(asset_id, who) {
+                None => {
+                    // Never claimed before - can claim 1 period
+                    1
+                }
+                Some(last_claim_block) => {
+                    // Count window boundaries crossed rather than blocks elapsed, so
+                    // the block within a window at which a claim lands is irrelevant
+                    let periods_since = Self::account_period(asset_id, params, who, current_block)
+                        .saturating_sub(Self::account_period(asset_id, params, who, last_claim_block));
+
+                    periods_since.try_into().unwrap_or(u32::MAX)
+                }
+            }
+        }
+
+        /// Schedule an `ExpiringSoon` event one claim period before a batch expires
+        ///
+        /// Nothing is scheduled if that block has already passed. Warnings are
+        /// best-effort: once a block is full, further ones are dropped.
+        fn schedule_expiry_warning(
+            asset_id: T::AssetId,
+            who: &T::AccountId,
+            params: &AssetParametersOf<T, I>,
+            current_block: BlockNumberFor<T>,
+            expires_at: BlockNumberFor<T>,
+        ) {
+            let warn_at = expires_at.saturating_sub(params.claim_period_blocks);
+            if warn_at <= current_block {
+                return;
+            }
+            let _ = ExpiryWarnings::<T, I>::try_append(warn_at, (asset_id, who.clone(), expires_at));
+        }
+
+        /// The bonus period of an asset covering the given block, if any
+        pub fn active_bonus_period(
+            asset_id: &T::AssetId,
+            block: BlockNumberFor<T>,
+        ) -> Option<BonusPeriodOf<T>> {
+            BonusPeriods::<T, I>::get(asset_id)
+                .into_iter()
+                .find(|p| p.start <= block && block < p.end)
+        }
+
+        /// Whether an account may be pruned as dormant in an asset
+        fn is_dormant(
+            asset_id: &T::AssetId,
+            who: &T::AccountId,
+            rep: &ReputationOf<T, I>,
+            params: &AssetParametersOf<T, I>,
+            current_block: BlockNumberFor<T>,
+        ) -> bool {
+            if !ReputationStore::<T, I>::contains_key(asset_id, who) {
+                return false;
+            }
+            let dormant_after = params
+                .claim_period_blocks
+                .saturating_mul(T::DormantPeriods::get().into());
+
+            let mut rep = rep.clone();
+            Self::apply_elapsed_decay(&mut rep, Self::block_to_period(params, current_block));
+
+            rep.score <= T::DormantScoreThreshold::get()
+                && current_block.saturating_sub(rep.last_activity) >= dormant_after
+                && Self::spendable_balance(asset_id, who).is_zero()
+                && !Commitments::<T, I>::contains_key(asset_id, who)
+        }
+
+        /// Set one parameter in `Economics`, keeping the set consistent
+        fn update_economics(parameter: UbiParameter) -> DispatchResult {
+            Economics::<T, I>::try_mutate(|economics| {
+                economics.set(parameter);
+                ensure!(economics.is_valid(), Error::<T, I>::InvalidParameter);
+                Ok(())
+            })
+        }
+
+        /// Check that `burns` form a recent ring and build its report
+        fn check_ring(burns: &[BurnId]) -> Result<SuspicionReportOf<T, I>, Error<T, I>> {
+            ensure!(burns.len() >= 2, Error::<T, I>::InvalidRing);
+            let oldest = NextBurnId::<T, I>::get().saturating_sub(RING_SCAN_BURNS.into());
+
+            let mut receipts = Vec::with_capacity(burns.len());
+            for id in burns {
+                ensure!(*id >= oldest, Error::<T, I>::InvalidRing);
+                let receipt = BurnReceipts::<T, I>::get(id)
+                    .filter(|r| !r.reversed())
+                    .ok_or(Error::<T, I>::InvalidRing)?;
+                receipts.push(receipt);
+            }
+
+            let asset_id = receipts[0].asset_id;
+            let mut accounts = BoundedVec::<T::AccountId, ConstU32<MAX_RING_LENGTH>>::new();
+            for (index, receipt) in receipts.iter().enumerate() {
+                let next = &receipts[(index + 1) % receipts.len()];
+                ensure!(receipt.asset_id == asset_id && receipt.to == next.from, Error::<T, I>::InvalidRing);
+                ensure!(!accounts.contains(&receipt.from), Error::<T, I>::InvalidRing);
+                accounts.try_push(receipt.from.clone()).map_err(|_| Error::<T, I>::InvalidRing)?;
+            }
+            accounts.sort();
+
+            Ok(SuspicionReport {
+                asset_id,
+                kind: if burns.len() == 2 { SuspicionKind::Reciprocal } else { SuspicionKind::Cycle },
+                accounts,
+                burns: burns.to_vec().try_into().map_err(|_| Error::<T, I>::InvalidRing)?,
+                reported_at: frame_system::Pallet::<T>::block_number(),
+            })
+        }
+
+        /// Whether a report on the same accounts of the same asset is queued
+        fn is_reported(reports: &[SuspicionReportOf<T, I>], report: &SuspicionReportOf<T, I>) -> bool {
+            reports.iter().any(|r| r.asset_id == report.asset_id && r.accounts == report.accounts)
+        }
+
+        /// Rings among the last `RING_SCAN_BURNS` burns that contain a burn from `since` on
+        ///
+        /// Returns at most `MAX_REPORTS_PER_RUN` rings that can be reported, one
+        /// per group of accounts.
+        pub fn find_rings(since: BurnId) -> Vec<RingBurns> {
+            let next = NextBurnId::<T, I>::get();
+            let oldest = next.saturating_sub(RING_SCAN_BURNS.into());
+            let edges: Vec<(BurnId, BurnReceiptOf<T, I>)> = (oldest..next)
+                .filter_map(|id| BurnReceipts::<T, I>::get(id).filter(|r| !r.reversed()).map(|r| (id, r)))
+                .collect();
+            let reported = SuspicionReports::<T, I>::get();
+
+            let mut found: Vec<SuspicionReportOf<T, I>> = Vec::new();
+            let mut rings = Vec::new();
+            for (id, burn) in edges.iter().filter(|(id, _)| *id >= since) {
+                let from = |sender: T::AccountId| {
+                    edges.iter().filter(move |(_, r)| r.asset_id == burn.asset_id && r.from == sender)
+                };
+                // A burn straight back, otherwise a way back through one other account
+                let ring = from(burn.to.clone())
+                    .find(|(_, back)| back.to == burn.from)
+                    .map(|(back, _)| alloc::vec![*id, *back])
+                    .or_else(|| {
+                        from(burn.to.clone()).filter(|(_, mid)| mid.to != burn.from).find_map(|(mid_id, mid)| {
+                            from(mid.to.clone())
+                                .find(|(_, last)| last.to == burn.from)
+                                .map(|(last, _)| alloc::vec![*id, *mid_id, *last])
+                        })
+                    });
+                let Some(Ok(ring)) = ring.map(RingBurns::try_from) else {
+                    continue;
+                };
+                let Ok(report) = Self::check_ring(&ring) else {
+                    continue;
+                };
+                if Self::is_reported(&reported, &report) || Self::is_reported(&found, &report) {
+                    continue;
+                }
+                found.push(report);
+                rings.push(ring);
+                if rings.len() >= MAX_REPORTS_PER_RUN {
+                    break;
+                }
+            }
+            rings
+        }
+
+        /// Report the rings formed by the burns made since the last scan of this node
+        fn scan_for_rings() {
+            use frame_system::offchain::SubmitTransaction;
+            use sp_runtime::offchain::storage::StorageValueRef;
+
+            let key = [b"ubi-token::ring-scan::".as_slice(), <Self as PalletInfoAccess>::name().as_bytes()].concat();
+            let cursor = StorageValueRef::persistent(&key);
+            let since: BurnId = cursor.get().ok().flatten().unwrap_or_default();
+            let next = NextBurnId::<T, I>::get();
+            if since >= next {
+                return;
+            }
+
+            for burns in Self::find_rings(since) {
+                let xt = T::create_bare(Call::<T, I>::report_suspicion { burns }.into());
+                // Rejected reports are found again while their burns are still recent
+                let _ = SubmitTransaction::<T, Call<T, I>>::submit_transaction(xt);
+            }
+            cursor.set(&next);
+        }
 
-            // Update last claim block
-            LastClaim::<T>::insert(&who, current_block);
+        /// Expire the indexed batches of passed periods until `meter` runs out
+        ///
+        /// Visits the assets round-robin, from the one after `ExpiryPollAsset`
+        /// and wrapping around at the end of `Assets`. Starts every asset at its
+        /// `ExpiryPollCursor`, or `EXPIRY_SWEEP_LOOKBACK_PERIODS` back on the
+        /// first poll, and moves the cursor past every period whose index is empty.
+        pub(crate) fn poll_expiry_index(now: BlockNumberFor<T>, meter: &mut WeightMeter) {
+            if meter.try_consume(T::DbWeight::get().reads_writes(2, 1)).is_err() {
+                return;
+            }
+            let last = ExpiryPollAsset::<T, I>::get();
+            let after_last = match &last {
+                Some(asset_id) => Assets::<T, I>::iter_from(Assets::<T, I>::hashed_key_for(asset_id)),
+                None => Assets::<T, I>::iter(),
+            };
+            let assets = after_last.chain(Assets::<T, I>::iter()).take(AssetCount::<T, I>::get() as usize);
+            let mut reached = last;
 
-            // Update total supply
-            TotalSupply::<T>::mutate(|supply| {
-                *supply = supply.saturating_add(amount_to_claim);
-            });
+            let asset_weight = T::DbWeight::get().reads_writes(2, 1);
+            let read_weight = T::DbWeight::get().reads(1);
+            // Worst case of one account: every batch expires
+            let entry_weight = T::DbWeight::get()
+                .reads_writes(3, 4)
+                .saturating_add(Weight::from_parts(WEIGHT_PER_BATCH, 0).saturating_mul(MAX_BATCHES.into()));
 
-            // Update reputation: decay, streak, and recalculate score
-            let current_period = Self::block_to_period(current_block);
-            ReputationStore::<T>::mutate(&who, |rep| {
-                // Set first activity if this is the first time
-                if rep.first_activity == Zero::zero() {
-                    rep.first_activity = current_block;
+            for (asset_id, details) in assets {
+                if meter.try_consume(asset_weight).is_err() {
+                    break;
                 }
-                
-                // Apply 5% decay to current score
-                rep.score = Self::apply_decay(rep.score);
-                
-                // Update claim streak (handles grace period logic)
-                Self::update_streak(rep, current_period);
-                
-                // Recalculate full score from components
-                rep.score = Self::recalculate_score(rep);
-            });
+                reached = Some(asset_id);
+                let current_period = Self::block_to_period(&details.params, now);
+                let start = ExpiryPollCursor::<T, I>::get(asset_id)
+                    .unwrap_or_else(|| current_period.saturating_sub(EXPIRY_SWEEP_LOOKBACK_PERIODS));
 
-            Self::deposit_event(Event::Claimed {
-                who,
-                amount: amount_to_claim,
-                periods: periods_to_claim,
-                expires_at,
-            });
+                let mut period = start;
+                let mut exhausted = false;
+                while period < current_period && meter.try_consume(read_weight).is_ok() {
+                    match ExpiryIndex::<T, I>::iter_key_prefix((asset_id, period)).next() {
+                        Some(who) => {
+                            if meter.try_consume(entry_weight).is_err() {
+                                exhausted = true;
+                                break;
+                            }
+                            ExpiryIndex::<T, I>::remove((asset_id, period, &who));
+                            Self::expire_batches(asset_id, &who, &details.params, now);
+                        },
+                        None => period = period.saturating_add(1),
+                    }
+                }
+                if ExpiryPollCursor::<T, I>::get(asset_id) != Some(period) {
+                    ExpiryPollCursor::<T, I>::insert(asset_id, period);
+                }
+                // The next asset would not get to expire anything either
+                if exhausted {
+                    break;
+                }
+            }
+            if reached != last {
+                ExpiryPollAsset::<T, I>::set(reached);
+            }
+        }
 
-            Ok(())
+        /// Whether `accounts` are a non-empty list of accounts indexed for a past expiry period
+        fn is_expiry_sweep_valid(asset_id: &T::AssetId, period: u64, accounts: &[T::AccountId]) -> bool {
+            let Ok(params) = Self::asset_parameters(asset_id) else {
+                return false;
+            };
+            let current_period = Self::block_to_period(&params, frame_system::Pallet::<T>::block_number());
+            period < current_period
+                && !accounts.is_empty()
+                && accounts.iter().all(|who| ExpiryIndex::<T, I>::contains_key((asset_id, period, who)))
         }
 
-        /// Burn tokens to a recipient (UNSIGNED - no gas fees!)
-        ///
-        /// This is the ONLY way to "spend" tokens. The recipient does not
-        /// receive any tokens - they only see the burn event. This prevents
-        /// trading and speculation.
-        ///
-        /// Both parties' reputation is updated:
-        /// - Sender: burns_sent increases
-        /// - Recipient: burns_received increases
-        ///
-        /// This is an UNSIGNED transaction - anyone can submit it without paying fees.
-        /// The `from` parameter specifies who is burning tokens.
-        ///
-        /// # Arguments
-        /// - `from`: The sender address (who is burning tokens)
-        /// - `to`: The recipient address (for reputation tracking and event)
-        /// - `amount`: Number of tokens to burn
+        /// Submit `sweep_expiry_index` for the expired periods of the index, once per claim period
         ///
-        /// # Errors
-        /// - `CannotBurnToSelf` if trying to burn to your own address
-        /// - `AmountMustBePositive` if amount is zero
-        /// - `InsufficientBalance` if you don't have enough tokens
-        #[pallet::call_index(1)]
-        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(6, 6))]
-        pub fn burn(origin: OriginFor<T>, from: T::AccountId, to: T::AccountId, amount: u128) -> DispatchResult {
-            ensure_none(origin)?;
-
-            // Validation
-            ensure!(from != to, Error::<T>::CannotBurnToSelf);
-            ensure!(amount > 0, Error::<T>::AmountMustBePositive);
+        /// Looks back `EXPIRY_SWEEP_LOOKBACK_PERIODS` periods and reads at most
+        /// `MAX_EXPIRY_SWEEP_ACCOUNTS` index entries per asset; the rest are
+        /// picked up in the next period.
+        fn submit_expiry_sweeps() {
+            use frame_system::offchain::SubmitTransaction;
+            use sp_runtime::offchain::storage::StorageValueRef;
 
             let current_block = frame_system::Pallet::<T>::block_number();
+            let pallet = <Self as PalletInfoAccess>::name().as_bytes();
+            let prefix = [b"ubi-token::expiry-sweep::".as_slice(), pallet].concat();
+            for (asset_id, details) in Assets::<T, I>::iter() {
+                let current_period = Self::block_to_period(&details.params, current_block);
+                let key = [prefix.as_slice(), &asset_id.encode()].concat();
+                let last_run = StorageValueRef::persistent(&key);
+                if last_run.get::<u64>().ok().flatten() == Some(current_period) {
+                    continue;
+                }
 
-            // Clean up expired batches first
-            let expired = Self::cleanup_expired_batches(&from, current_block);
-            if expired > 0 {
-                Self::deposit_event(Event::Expired {
-                    who: from.clone(),
-                    amount: expired,
-                });
+                let mut budget = MAX_EXPIRY_SWEEP_ACCOUNTS as usize;
+                for period in current_period.saturating_sub(EXPIRY_SWEEP_LOOKBACK_PERIODS)..current_period {
+                    let accounts: Vec<T::AccountId> =
+                        ExpiryIndex::<T, I>::iter_key_prefix((asset_id, period)).take(budget).collect();
+                    budget = budget.saturating_sub(accounts.len());
+                    for chunk in accounts.chunks(T::MaxSweepAccounts::get().max(1) as usize) {
+                        let Ok(accounts) = BoundedVec::try_from(chunk.to_vec()) else {
+                            continue;
+                        };
+                        let xt = T::create_bare(Call::<T, I>::sweep_expiry_index { asset_id, period, accounts }.into());
+                        // Sweeps rejected now are submitted again next period
+                        let _ = SubmitTransaction::<T, Call<T, I>>::submit_transaction(xt);
+                    }
+                }
+                last_run.set(&current_period);
             }
+        }
 
-            // Check balance and burn using FIFO
-            Self::burn_fifo(&from, amount, current_block)?;
+        /// Move `from` to the front of the recent supporters of `to` and count the burn
+        fn record_supporter_burn(
+            asset_id: T::AssetId,
+            to: &T::AccountId,
+            from: &T::AccountId,
+            amount: BalanceOf<T, I>,
+            block: BlockNumberFor<T>,
+        ) {
+            RecentSupporters::<T, I>::mutate(asset_id, to, |supporters| {
+                let mut supporter = match supporters.iter().position(|s| &s.who == from) {
+                    Some(index) => supporters.remove(index),
+                    None => Supporter { who: from.clone(), count: 0, volume: Zero::zero(), last_burn: block },
+                };
+                supporter.count = supporter.count.saturating_add(1);
+                supporter.volume = supporter.volume.saturating_add(amount);
+                supporter.last_burn = block;
+                // Drops the supporter with the oldest burn if the list is full
+                let _ = supporters.force_insert_keep_left(0, supporter);
+            });
 
-            // Update total supply
-            TotalSupply::<T>::mutate(|supply| {
-                *supply = supply.saturating_sub(amount);
+            TopSupporters::<T, I>::mutate(asset_id, to, |supporters| {
+                let supporter = match supporters.iter().position(|s| &s.who == from) {
+                    Some(index) => {
+                        let mut supporter = supporters.remove(index);
+                        supporter.volume = supporter.volume.saturating_add(amount);
+                        supporter
+                    },
+                    None => TopSupporter { who: from.clone(), volume: amount },
+                };
+                Self::insert_top_supporter(supporters, supporter);
             });
+        }
 
-            // Get sender's current reputation score for weighting
-            let sender_score = ReputationStore::<T>::get(&from).score;
-            let sender_weight = Self::calculate_sender_weight(sender_score);
-            
-            // Calculate weighted amount: amount * weight / 1000
-            let weighted_amount = amount.saturating_mul(sender_weight) / 1000;
+        /// Insert a supporter into a ranking sorted by volume
+        ///
+        /// If the ranking is full, the smallest volume is evicted, or the
+        /// supporter is dropped if it does not exceed it.
+        fn insert_top_supporter(
+            supporters: &mut BoundedVec<TopSupporterOf<T, I>, ConstU32<MAX_TOP_SUPPORTERS>>,
+            supporter: TopSupporterOf<T, I>,
+        ) {
+            let index = supporters.partition_point(|s| s.volume >= supporter.volume);
+            let _ = supporters.force_insert_keep_left(index, supporter);
+        }
 
-            // Check if this is a new unique recipient for the sender
-            let is_new_recipient = !UniqueRecipients::<T>::get(&from, &to);
-            if is_new_recipient {
-                UniqueRecipients::<T>::insert(&from, &to, true);
+        /// Reverse the reputation effects of a burn on its sender, recipient and organization members
+        fn reverse_burn(burn_id: BurnId, receipt: &BurnReceiptOf<T, I>) -> DispatchResult {
+            let params = Self::asset_parameters(&receipt.asset_id)?;
+            let current_period = Self::block_to_period(&params, frame_system::Pallet::<T>::block_number());
+            let asset_id = receipt.asset_id;
+
+            if receipt.new_recipient {
+                UniqueRecipientSets::<T, I>::mutate(asset_id, &receipt.from, |recipients| {
+                    recipients.remove(&receipt.to);
+                });
             }
 
-            // Update sender reputation
-            ReputationStore::<T>::mutate(&from, |rep| {
-                rep.burns_sent_count = rep.burns_sent_count.saturating_add(1);
-                rep.burns_sent_volume = rep.burns_sent_volume.saturating_add(amount);
-                
-                // Track unique recipients
-                if is_new_recipient {
-                    rep.unique_recipients_count = rep.unique_recipients_count.saturating_add(1);
+            Self::mutate_reputation(asset_id, &receipt.from, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                rep.burns_sent_count = rep.burns_sent_count.saturating_sub(1);
+                rep.burns_sent_volume = rep
+                    .burns_sent_volume
+                    .saturating_sub(receipt.amount.saturating_add(receipt.sender_bonus));
+                if receipt.new_recipient {
+                    rep.unique_recipients_count = rep.unique_recipients_count.saturating_sub(1);
                 }
-                
-                if rep.first_activity == Zero::zero() {
-                    rep.first_activity = current_block;
-                }
-                
-                // Recalculate sender's score
                 rep.score = Self::recalculate_score(rep);
             });
 
-            // Update recipient reputation
-            ReputationStore::<T>::mutate(&to, |rep| {
-                rep.burns_received_count = rep.burns_received_count.saturating_add(1);
-                rep.burns_received_volume = rep.burns_received_volume.saturating_add(amount);
-                
-                // Add weighted received (weighted by sender's reputation)
-                rep.weighted_received = rep.weighted_received.saturating_add(weighted_amount);
-                
-                if rep.first_activity == Zero::zero() {
-                    rep.first_activity = current_block;
+            let split = OrganizationSplits::<T, I>::take(burn_id);
+            Self::mutate_reputation(asset_id, &receipt.to, |rep| {
+                Self::apply_elapsed_decay(rep, current_period);
+                rep.burns_received_count = rep.burns_received_count.saturating_sub(1);
+                rep.burns_received_volume = rep.burns_received_volume.saturating_sub(receipt.amount);
+                if split.is_none() {
+                    rep.weighted_received = rep.weighted_received.saturating_sub(receipt.weighted_amount);
                 }
-                
-                // Recalculate recipient's score
                 rep.score = Self::recalculate_score(rep);
             });
+            for (member, part) in split.into_iter().flatten() {
+                Self::mutate_reputation(asset_id, &member, |rep| {
+                    Self::apply_elapsed_decay(rep, current_period);
+                    rep.weighted_received = rep.weighted_received.saturating_sub(part);
+                    rep.score = Self::recalculate_score(rep);
+                });
+            }
+
+            // The stake stays held until the boost is released or slashed
+            if let Some(boost) = BurnBoosts::<T, I>::get(burn_id) {
+                Self::mutate_reputation(asset_id, &receipt.to, |rep| {
+                    rep.boost_received = rep.boost_received.saturating_sub(boost.stake);
+                    rep.score = Self::recalculate_score(rep);
+                });
+            }
 
-            Self::deposit_event(Event::Burned { from, to, amount });
+            Self::remove_supporter_burn(asset_id, &receipt.to, &receipt.from, receipt.amount);
 
             Ok(())
         }
-    }
-
-    #[pallet::validate_unsigned]
-    impl<T: Config> ValidateUnsigned for Pallet<T> {
-        type Call = Call<T>;
 
-        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-            match call {
-                Call::claim { account } => {
-                    // Validate that the account can actually claim
-                    let current_block = frame_system::Pallet::<T>::block_number();
-                    let claimable = Self::calculate_claimable_periods(account, current_block);
-                    
-                    if claimable == 0 {
-                        return InvalidTransaction::Custom(1).into();
+        /// Take a rejected or refunded burn out of the recent and top supporters of `to`
+        fn remove_supporter_burn(
+            asset_id: T::AssetId,
+            to: &T::AccountId,
+            from: &T::AccountId,
+            amount: BalanceOf<T, I>,
+        ) {
+            RecentSupporters::<T, I>::mutate(asset_id, to, |supporters| {
+                if let Some(index) = supporters.iter().position(|s| &s.who == from) {
+                    let supporter = &mut supporters[index];
+                    supporter.count = supporter.count.saturating_sub(1);
+                    supporter.volume = supporter.volume.saturating_sub(amount);
+                    if supporter.count == 0 {
+                        supporters.remove(index);
                     }
-                    
-                    ValidTransaction::with_tag_prefix("UbiClaim")
-                        .and_provides((account, current_block / T::ClaimPeriodBlocks::get()))
-                        .longevity(5)
-                        .propagate(true)
-                        .build()
                 }
-                Call::burn { from, to, amount } => {
-                    // Basic validation
-                    if from == to {
-                        return InvalidTransaction::Custom(2).into();
-                    }
-                    if *amount == 0 {
-                        return InvalidTransaction::Custom(3).into();
-                    }
-                    
-                    // Check balance
-                    let balance = Self::spendable_balance(from);
-                    if balance < *amount {
-                        return InvalidTransaction::Custom(4).into();
+            });
+
+            TopSupporters::<T, I>::mutate(asset_id, to, |supporters| {
+                if let Some(index) = supporters.iter().position(|s| &s.who == from) {
+                    let mut supporter = supporters.remove(index);
+                    supporter.volume = supporter.volume.saturating_sub(amount);
+                    if !supporter.volume.is_zero() {
+                        Self::insert_top_supporter(supporters, supporter);
                     }
-                    
-                    ValidTransaction::with_tag_prefix("UbiBurn")
-                        .and_provides((from, frame_system::Pallet::<T>::block_number()))
-                        .longevity(5)
-                        .propagate(true)
-                        .build()
                 }
-                _ => InvalidTransaction::Call.into(),
+            });
+        }
+
+        /// Remove the reputation records and the claim anchor of an account in an asset
+        ///
+        /// The provider reference of the account's first claim is released with
+        /// its `LastClaim`. If `frame_system` refuses to release it (consumers
+        /// remain), `LastClaim` is kept so the reference stays accounted for.
+        fn prune_account(asset_id: T::AssetId, who: &T::AccountId) {
+            if ReputationStore::<T, I>::contains_key(asset_id, who) {
+                Self::untrack_score(asset_id, ReputationStore::<T, I>::take(asset_id, who).score);
+            }
+            RecentSupporters::<T, I>::remove(asset_id, who);
+            TopSupporters::<T, I>::remove(asset_id, who);
+            UnsignedBurns::<T, I>::remove(asset_id, who);
+            IdempotencyKeys::<T, I>::remove(asset_id, who);
+            UniqueRecipientSets::<T, I>::remove(asset_id, who);
+            let _ = Vouches::<T, I>::clear_prefix((asset_id, who), MAX_UNIQUE_RECIPIENTS, None);
+            VouchCounts::<T, I>::remove(asset_id, who);
+            ReferralCounts::<T, I>::remove(asset_id, who);
+            LastReputationExport::<T, I>::remove(asset_id, who);
+            // May reap the account, see `on_killed_account`
+            if LastClaim::<T, I>::contains_key(asset_id, who) && frame_system::Pallet::<T>::dec_providers(who).is_ok() {
+                LastClaim::<T, I>::remove(asset_id, who);
+                ClaimOffsets::<T, I>::remove(asset_id, who);
             }
+            Self::deposit_event(Event::DormantPruned { asset_id, who: who.clone() });
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Calculate how many periods the account can claim
-        fn calculate_claimable_periods(
-            who: &T::AccountId,
-            current_block: BlockNumberFor<T>,
-        ) -> u32 {
-            let claim_period = T::ClaimPeriodBlocks::get();
+        /// Remove the tokens and claim state of a reaped account in an asset
+        fn reap_account(asset_id: T::AssetId, who: &T::AccountId) {
+            let batches = Balances::<T, I>::take(asset_id, who);
+            let commitments = Commitments::<T, I>::take(asset_id, who);
+            let claimed = LastClaim::<T, I>::take(asset_id, who).is_some();
+            ClaimOffsets::<T, I>::remove(asset_id, who);
+            Preferences::<T, I>::remove(asset_id, who);
+            Profiles::<T, I>::remove(asset_id, who);
+            UnsignedBurns::<T, I>::remove(asset_id, who);
+            IdempotencyKeys::<T, I>::remove(asset_id, who);
+            let _ = Approvals::<T, I>::clear_prefix((asset_id, who), u32::MAX, None);
 
-            match LastClaim::<T>::get(who) {
-                None => {
-                    // Never claimed before - can claim 1 period
-                    1
-                }
-                Some(last_claim_block) => {
-                    // Calculate periods since last claim
-                    let blocks_since = current_block.saturating_sub(last_claim_block);
+            let amount = batches
+                .iter()
+                .map(|batch| batch.amount)
+                .chain(commitments.iter().map(|commitment| commitment.amount))
+                .fold(Zero::zero(), |total: BalanceOf<T, I>, amount| total.saturating_add(amount));
+            if !amount.is_zero() {
+                TotalSupply::<T, I>::mutate(asset_id, |supply| *supply = supply.saturating_sub(amount));
+            }
+            if !amount.is_zero() || claimed {
+                Self::deposit_event(Event::AccountReaped { asset_id, who: who.clone(), amount });
+            }
+        }
+
+        /// Check reputation entries for dormancy within the idle weight of a block
+        ///
+        /// Walks `ReputationStore` from `PruneCursor`, wrapping around at the end.
+        fn prune_dormant_idle(remaining_weight: Weight) -> Weight {
+            let db = T::DbWeight::get();
+            let per_entry = db.reads_writes(5, 5);
+            let mut used = db.reads_writes(1, 1);
+            if !remaining_weight.all_gte(used.saturating_add(per_entry)) {
+                return Weight::zero();
+            }
+
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let mut iter = match PruneCursor::<T, I>::get() {
+                Some(cursor) => ReputationStore::<T, I>::iter_from(cursor.into_inner()),
+                None => ReputationStore::<T, I>::iter(),
+            };
 
-                    // Convert to periods (integer division)
-                    let periods_since: u32 = (blocks_since / claim_period)
-                        .try_into()
-                        .unwrap_or(u32::MAX);
+            let mut checked = 0;
+            let mut exhausted = false;
+            while checked < MAX_DORMANT_CHECKS_PER_BLOCK
+                && remaining_weight.all_gte(used.saturating_add(per_entry))
+            {
+                let Some((asset_id, who, rep)) = iter.next() else {
+                    exhausted = true;
+                    break;
+                };
+                used = used.saturating_add(per_entry);
+                checked += 1;
 
-                    periods_since
+                if let Ok(params) = Self::asset_parameters(&asset_id) {
+                    if Self::is_dormant(&asset_id, &who, &rep, &params, current_block) {
+                        Self::prune_account(asset_id, &who);
+                    }
                 }
             }
+
+            // Resume after the last checked entry, or start over once the map is exhausted
+            let cursor = if exhausted {
+                None
+            } else {
+                BoundedVec::try_from(iter.last_raw_key().to_vec()).ok()
+            };
+            PruneCursor::<T, I>::set(cursor);
+
+            used
+        }
+
+        /// Move unlocked commitments back into the spendable batches
+        fn release_commitments(asset_id: &T::AssetId, who: &T::AccountId, current_block: BlockNumberFor<T>) {
+            let mut commitments = Commitments::<T, I>::get(asset_id, who);
+            if !commitments.iter().any(|c| c.unlocks_at <= current_block) {
+                return;
+            }
+
+            Balances::<T, I>::mutate(asset_id, who, |batches| {
+                commitments.retain(|c| {
+                    if c.unlocks_at > current_block {
+                        return true;
+                    }
+                    if let Some(batch) = batches.iter_mut().find(|b| b.expires_at == c.expires_at) {
+                        batch.amount = batch.amount.saturating_add(c.amount);
+                    } else if batches
+                        .try_push(TokenBatch { amount: c.amount, expires_at: c.expires_at })
+                        .is_err()
+                    {
+                        // No room yet, retried on the next cleanup
+                        return true;
+                    }
+                    Self::deposit_event(Event::CommitmentReleased {
+                        asset_id: *asset_id,
+                        who: who.clone(),
+                        amount: c.amount,
+                        expires_at: c.expires_at,
+                    });
+                    false
+                });
+            });
+
+            if commitments.is_empty() {
+                Commitments::<T, I>::remove(asset_id, who);
+            } else {
+                Commitments::<T, I>::insert(asset_id, who, commitments);
+            }
         }
 
-        /// Remove expired batches and return total expired amount
+        /// Remove expired batches and return total expired amount and number of batches
+        ///
+        /// Unlocked commitments are released first, so they expire like any batch.
         fn cleanup_expired_batches(
+            asset_id: &T::AssetId,
             who: &T::AccountId,
             current_block: BlockNumberFor<T>,
-        ) -> u128 {
-            let mut expired_amount: u128 = 0;
+        ) -> (BalanceOf<T, I>, u32) {
+            Self::release_commitments(asset_id, who, current_block);
 
-            Balances::<T>::mutate(who, |batches| {
+            let mut expired_amount: BalanceOf<T, I> = Zero::zero();
+            let mut expired_count = 0u32;
+
+            Balances::<T, I>::mutate(asset_id, who, |batches| {
                 let mut i = 0;
                 while i < batches.len() {
                     if batches[i].expires_at <= current_block {
                         expired_amount = expired_amount.saturating_add(batches[i].amount);
+                        expired_count = expired_count.saturating_add(1);
                         batches.remove(i);
                     } else {
                         i += 1;
@@ -552,90 +7059,212 @@ pub mod pallet {
                 }
             });
 
-            // Update total supply for expired tokens
-            if expired_amount > 0 {
-                TotalSupply::<T>::mutate(|supply| {
+            // Update total supply for expired tokens, which feed the community pool
+            if !expired_amount.is_zero() {
+                TotalSupply::<T, I>::mutate(asset_id, |supply| {
                     *supply = supply.saturating_sub(expired_amount);
                 });
+                CommunityPool::<T, I>::mutate(asset_id, |pool| *pool = pool.saturating_add(expired_amount));
             }
 
-            expired_amount
+            (expired_amount, expired_count)
         }
 
         /// Burn tokens using FIFO (oldest batches first)
+        ///
+        /// Returns the expiration of the oldest batch that was drawn from and the
+        /// number of batches drawn from.
         fn burn_fifo(
+            asset_id: &T::AssetId,
             who: &T::AccountId,
-            amount: u128,
+            amount: BalanceOf<T, I>,
             current_block: BlockNumberFor<T>,
-        ) -> DispatchResult {
-            Balances::<T>::try_mutate(who, |batches| -> DispatchResult {
+        ) -> Result<(BlockNumberFor<T>, u32), DispatchError> {
+            Balances::<T, I>::try_mutate(asset_id, who, |batches| -> Result<(BlockNumberFor<T>, u32), DispatchError> {
                 // Sort by expiration (oldest first) for FIFO
                 batches.sort_by(|a, b| a.expires_at.cmp(&b.expires_at));
                 
                 let mut remaining = amount;
+                let oldest = batches
+                    .iter()
+                    .find(|b| b.expires_at > current_block)
+                    .map_or(current_block, |b| b.expires_at);
 
+                let mut consumed = 0u32;
                 for batch in batches.iter_mut() {
                     // Skip expired batches (should be cleaned up, but just in case)
                     if batch.expires_at <= current_block {
                         continue;
                     }
 
+                    consumed = consumed.saturating_add(1);
                     if batch.amount >= remaining {
                         batch.amount = batch.amount.saturating_sub(remaining);
-                        remaining = 0;
+                        remaining = Zero::zero();
                         break;
                     } else {
                         remaining = remaining.saturating_sub(batch.amount);
-                        batch.amount = 0;
+                        batch.amount = Zero::zero();
                     }
                 }
 
                 // Remove empty batches
-                batches.retain(|b| b.amount > 0);
+                batches.retain(|b| !b.amount.is_zero());
 
-                ensure!(remaining == 0, Error::<T>::InsufficientBalance);
-                Ok(())
+                ensure!(remaining.is_zero(), Error::<T, I>::InsufficientBalance);
+                Ok((oldest, consumed))
             })
         }
 
         /// Get the spendable balance (non-expired tokens) for an account
-        pub fn spendable_balance(who: &T::AccountId) -> u128 {
-            let current_block = frame_system::Pallet::<T>::block_number();
-            let batches = Balances::<T>::get(who);
+        ///
+        /// Includes unlocked commitments that were not released yet.
+        pub fn spendable_balance(asset_id: &T::AssetId, who: &T::AccountId) -> BalanceOf<T, I> {
+            Self::spendable_balance_at(asset_id, who, frame_system::Pallet::<T>::block_number())
+        }
+
+        /// Get the spendable balance of an account with `block` taken as the current block
+        ///
+        /// Evaluated against the stored batches and commitments. Called on the state
+        /// of block N with `block = N`, it answers what the account could spend at
+        /// that block; with a later block, what will be left of the current tokens.
+        pub fn spendable_balance_at(
+            asset_id: &T::AssetId,
+            who: &T::AccountId,
+            block: BlockNumberFor<T>,
+        ) -> BalanceOf<T, I> {
+            let batches = Balances::<T, I>::get(asset_id, who);
+            let unlocked = Commitments::<T, I>::get(asset_id, who)
+                .iter()
+                .filter(|c| c.unlocks_at <= block && c.expires_at > block)
+                .map(|c| c.amount)
+                .fold(Zero::zero(), |acc: BalanceOf<T, I>, x| acc.saturating_add(x));
 
             batches
                 .iter()
-                .filter(|b| b.expires_at > current_block)
+                .filter(|b| b.expires_at > block)
                 .map(|b| b.amount)
-                .fold(0u128, |acc, x| acc.saturating_add(x))
+                .fold(unlocked, |acc: BalanceOf<T, I>, x| acc.saturating_add(x))
+        }
+
+        /// Get the unexpired token batches of an account, oldest expiry first
+        ///
+        /// This is the order in which burns consume them. Committed tokens are
+        /// not included.
+        pub fn token_batches(asset_id: &T::AssetId, who: &T::AccountId) -> Vec<TokenBatchOf<T, I>> {
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let mut batches: Vec<_> = Balances::<T, I>::get(asset_id, who)
+                .into_iter()
+                .filter(|b| b.expires_at > current_block)
+                .collect();
+            batches.sort_by_key(|b| b.expires_at);
+            batches
         }
 
-        /// Get the total balance including expired (for informational purposes)
-        pub fn total_balance(who: &T::AccountId) -> u128 {
-            let batches = Balances::<T>::get(who);
+        /// Get a page of the recipients a sender has burned to
+        ///
+        /// Recipients are listed in account order, starting after `start_key`
+        /// (the last recipient of the previous page, `None` for the first page).
+        /// At most `limit` recipients are returned, capped at `MAX_UNIQUE_RECIPIENTS`.
+        pub fn unique_recipients(
+            asset_id: &T::AssetId,
+            sender: &T::AccountId,
+            start_key: Option<T::AccountId>,
+            limit: u32,
+        ) -> Vec<T::AccountId> {
+            let limit = limit.min(MAX_UNIQUE_RECIPIENTS) as usize;
+            UniqueRecipientSets::<T, I>::get(asset_id, sender)
+                .into_iter()
+                .filter(|recipient| start_key.as_ref().is_none_or(|last| recipient > last))
+                .take(limit)
+                .collect()
+        }
+
+        /// Receipt of a burn with the storage key it is stored under
+        ///
+        /// A read proof of the key at the same block lets anyone holding a
+        /// trusted header of that block check the receipt, see
+        /// `pallet-ubi-token-receipt-verifier`.
+        pub fn burn_receipt_with_key(burn_id: BurnId) -> Option<(BurnReceiptOf<T, I>, Vec<u8>)> {
+            BurnReceipts::<T, I>::get(burn_id).map(|receipt| (receipt, BurnReceipts::<T, I>::hashed_key_for(burn_id)))
+        }
+
+        /// Get the amount of committed tokens that are still locked
+        pub fn locked_balance(asset_id: &T::AssetId, who: &T::AccountId) -> BalanceOf<T, I> {
+            let current_block = frame_system::Pallet::<T>::block_number();
+            Commitments::<T, I>::get(asset_id, who)
+                .iter()
+                .filter(|c| c.unlocks_at > current_block)
+                .map(|c| c.amount)
+                .fold(Zero::zero(), |acc: BalanceOf<T, I>, x| acc.saturating_add(x))
+        }
+
+        /// Get the total balance including expired and committed (for informational purposes)
+        pub fn total_balance(asset_id: &T::AssetId, who: &T::AccountId) -> BalanceOf<T, I> {
+            let committed = Commitments::<T, I>::get(asset_id, who)
+                .iter()
+                .map(|c| c.amount)
+                .fold(Zero::zero(), |acc: BalanceOf<T, I>, x| acc.saturating_add(x));
+            let batches = Balances::<T, I>::get(asset_id, who);
             batches
                 .iter()
                 .map(|b| b.amount)
-                .fold(0u128, |acc, x| acc.saturating_add(x))
+                .fold(committed, |acc: BalanceOf<T, I>, x| acc.saturating_add(x))
         }
 
         /// Check if an account can claim UBI now
-        pub fn can_claim(who: &T::AccountId) -> bool {
-            let current_block = frame_system::Pallet::<T>::block_number();
-            Self::calculate_claimable_periods(who, current_block) > 0
+        pub fn can_claim(asset_id: &T::AssetId, who: &T::AccountId) -> bool {
+            Self::claimable_periods(asset_id, who) > 0
         }
 
         /// Get the number of claimable periods for an account
-        pub fn claimable_periods(who: &T::AccountId) -> u32 {
+        pub fn claimable_periods(asset_id: &T::AssetId, who: &T::AccountId) -> u32 {
+            let Ok(params) = Self::asset_parameters(asset_id) else {
+                return 0;
+            };
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let periods = Self::calculate_claimable_periods(asset_id, &params, who, current_block);
+            periods.min(params.max_backlog_periods)
+        }
+
+        /// Get the claim anchor of an account: the index of its last claim window
+        ///
+        /// Backlog is computed from this index only, so the exact block at
+        /// which anyone submitted the claim does not matter. Returns `None` if
+        /// the account never claimed.
+        pub fn claim_anchor(asset_id: &T::AssetId, who: &T::AccountId) -> Option<u64> {
+            let params = Self::asset_parameters(asset_id).ok()?;
+            LastClaim::<T, I>::get(asset_id, who).map(|block| Self::account_period(asset_id, &params, who, block))
+        }
+
+        /// First block from which the account can claim again
+        ///
+        /// The current block if it can claim now, `None` if the asset does not exist.
+        pub fn next_claim_block(asset_id: &T::AssetId, who: &T::AccountId) -> Option<BlockNumberFor<T>> {
+            let params = Self::asset_parameters(asset_id).ok()?;
             let current_block = frame_system::Pallet::<T>::block_number();
-            let periods = Self::calculate_claimable_periods(who, current_block);
-            periods.min(T::MaxBacklogPeriods::get())
+            if Self::calculate_claimable_periods(asset_id, &params, who, current_block) > 0 {
+                return Some(current_block);
+            }
+            let anchor = Self::claim_anchor(asset_id, who)?;
+            let offset = ClaimOffsets::<T, I>::get(asset_id, who) % params.claim_period_blocks;
+            let next: BlockNumberFor<T> = anchor.saturating_add(1).try_into().ok()?;
+            Some(next.saturating_mul(params.claim_period_blocks).saturating_add(offset))
         }
 
         /// Get the claimable amount for an account
-        pub fn claimable_amount(who: &T::AccountId) -> u128 {
-            let periods = Self::claimable_periods(who);
-            T::UbiAmount::get().saturating_mul(periods as u128)
+        pub fn claimable_amount(asset_id: &T::AssetId, who: &T::AccountId) -> BalanceOf<T, I> {
+            let Ok(params) = Self::asset_parameters(asset_id) else {
+                return Zero::zero();
+            };
+            let periods = Self::claimable_periods(asset_id, who);
+            let current_block = frame_system::Pallet::<T>::block_number();
+            let multiplier = Self::active_bonus_period(asset_id, current_block)
+                .map_or(100, |b| b.claim_multiplier);
+            Self::ubi_amount_at(asset_id, &params, Self::block_to_period(&params, current_block))
+                .saturating_mul(periods.into())
+                .saturating_mul(multiplier.into())
+                / 100u32.into()
         }
 
         // === New reputation system helpers ===
@@ -645,7 +7274,7 @@ pub mod pallet {
         /// 
         /// Formula: weight = clamp(log10(score + 10) / 2, 0.5, 2.0)
         /// Approximated using integer math
-        fn calculate_sender_weight(sender_score: u128) -> u128 {
+        fn calculate_sender_weight(sender_score: BalanceOf<T, I>) -> u32 {
             // Approximate log10 using leading zeros / bit counting
             // log10(x) ≈ log2(x) / 3.32
             // We use a simpler tiered approach for efficiency:
@@ -655,84 +7284,241 @@ pub mod pallet {
             //   score 1000-9999:   weight = 1500 (1.5x)
             //   score 10000+:      weight = 2000 (2.0x)
             
-            if sender_score < 10 {
-                MIN_SENDER_WEIGHT  // 500 = 0.5x
-            } else if sender_score < 100 {
+            // Tuned bounds also bound the fixed middle tiers
+            let rep = Economics::<T, I>::get().reputation;
+            let tier = if sender_score < 10u32.into() {
+                rep.min_sender_weight  // 500 = 0.5x
+            } else if sender_score < 100u32.into() {
                 750  // 0.75x
-            } else if sender_score < 1000 {
+            } else if sender_score < 1000u32.into() {
                 1000  // 1.0x
-            } else if sender_score < 10000 {
+            } else if sender_score < 10000u32.into() {
                 1500  // 1.5x
             } else {
-                MAX_SENDER_WEIGHT  // 2000 = 2.0x
-            }
+                rep.max_sender_weight  // 2000 = 2.0x
+            };
+            tier.clamp(rep.min_sender_weight, rep.max_sender_weight)
+        }
+
+        /// Index of the claim window of `who` containing `block`
+        fn account_period(
+            asset_id: &T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            who: &T::AccountId,
+            block: BlockNumberFor<T>,
+        ) -> u64 {
+            let offset = ClaimOffsets::<T, I>::get(asset_id, who) % params.claim_period_blocks;
+            Self::block_to_period(params, block.saturating_sub(offset))
         }
 
-        /// Calculate the current period number from a block number
-        fn block_to_period(block: BlockNumberFor<T>) -> u64 {
-            let period_blocks: u64 = T::ClaimPeriodBlocks::get()
+        /// Calculate the current period number of an asset from a block number
+        pub(crate) fn block_to_period(params: &AssetParametersOf<T, I>, block: BlockNumberFor<T>) -> u64 {
+            let period_blocks: u64 = params.claim_period_blocks
                 .try_into()
                 .unwrap_or(1);
             let block_num: u64 = block.try_into().unwrap_or(0);
             block_num / period_blocks
         }
 
-        /// Update claim streak based on current period
+        /// Claim periods since the last claim, not counting vacation periods in between
+        fn periods_missed(rep: &ReputationOf<T, I>, current_period: u64) -> u64 {
+            let vacation = current_period
+                .min(rep.vacation_end)
+                .saturating_sub(rep.last_claim_period.saturating_add(1).max(rep.vacation_start));
+            current_period.saturating_sub(rep.last_claim_period).saturating_sub(vacation)
+        }
+
+        /// Whether `who` is on vacation at `block`
+        pub fn on_vacation(
+            asset_id: &T::AssetId,
+            params: &AssetParametersOf<T, I>,
+            who: &T::AccountId,
+            block: BlockNumberFor<T>,
+        ) -> bool {
+            let rep = ReputationStore::<T, I>::get(asset_id, who);
+            let period = Self::block_to_period(params, block);
+            rep.vacation_start <= period && period < rep.vacation_end
+        }
+
+        /// Update claim streak based on current period, adding `days` on a continued streak
         /// Returns the new streak value
-        fn update_streak(rep: &mut Reputation<BlockNumberFor<T>>, current_period: u64) -> u32 {
-            let periods_missed = current_period.saturating_sub(rep.last_claim_period);
-            
-            if periods_missed <= STREAK_GRACE_PERIODS + 1 {
+        fn update_streak(rep: &mut ReputationOf<T, I>, current_period: u64, days: u32) -> u32 {
+            let periods_missed = Self::periods_missed(rep, current_period);
+            let grace_periods = Economics::<T, I>::get().reputation.streak_grace_periods;
+            // Periods missed beyond the grace period, covered by streak freezes if any
+            let uncovered = periods_missed.saturating_sub(grace_periods.saturating_add(1));
+
+            if uncovered <= rep.streak_freeze.into() {
                 // Within grace period (0, 1, or 2 periods since last = consecutive or grace)
                 // +1 because claiming in next period is periods_missed=1
-                rep.claim_streak = rep.claim_streak.saturating_add(1);
+                rep.streak_freeze = rep.streak_freeze.saturating_sub(uncovered as u32);
+                rep.claim_streak = rep.claim_streak.saturating_add(days);
             } else {
-                // Streak broken - reset to 1
-                rep.claim_streak = 1;
+                // Streak broken - restart
+                rep.claim_streak = days;
             }
             
             rep.last_claim_period = current_period;
             rep.claim_streak
         }
 
-        /// Apply 5% decay to reputation score
-        fn apply_decay(score: u128) -> u128 {
-            // score * 0.95 = score * 950 / 1000
-            score.saturating_mul(DECAY_FACTOR) / 1000
+        /// Apply 5% decay per claim period elapsed since the last decay
+        ///
+        /// Decays the sent and weighted received volumes, drops a claim streak
+        /// that lapsed beyond the grace period and refreshes the score.
+        pub(crate) fn apply_elapsed_decay(rep: &mut ReputationOf<T, I>, current_period: u64) {
+            let params = Economics::<T, I>::get().reputation;
+            // The next claim would restart the streak anyway
+            let allowed = params.streak_grace_periods.saturating_add(1).saturating_add(rep.streak_freeze.into());
+            if Self::periods_missed(rep, current_period) > allowed {
+                rep.claim_streak = 0;
+                rep.streak_freeze = 0;
+            }
+
+            let elapsed = current_period.saturating_sub(rep.last_decay_period);
+            if elapsed > 0 {
+                // 0.95^elapsed
+                let factor = Perbill::from_perthousand(params.decay_factor)
+                    .saturating_pow(elapsed.try_into().unwrap_or(usize::MAX));
+                rep.burns_sent_volume = factor.mul_floor(rep.burns_sent_volume);
+                rep.weighted_received = factor.mul_floor(rep.weighted_received);
+                rep.commitment_cost = factor.mul_floor(rep.commitment_cost);
+                rep.backlog_cost = factor.mul_floor(rep.backlog_cost);
+                rep.slashed_stake = factor.mul_floor(rep.slashed_stake);
+                rep.vouch_cost = factor.mul_floor(rep.vouch_cost);
+                rep.bonus_credit = factor.mul_floor(rep.bonus_credit);
+            }
+            rep.last_decay_period = rep.last_decay_period.max(current_period);
+            rep.score = Self::recalculate_score(rep);
+        }
+
+        /// Calculate streak bonus (10 points per day, max 500 by default)
+        fn calculate_streak_bonus(params: &ReputationParameters, streak: u32) -> BalanceOf<T, I> {
+            let bonus = BalanceOf::<T, I>::from(streak).saturating_mul(params.points_per_streak_day.into());
+            bonus.min(params.max_streak_bonus.into())
+        }
+
+        /// Mutate the reputation record of an account, keeping `ScoreHistogram` in sync
+        pub(crate) fn mutate_reputation<R>(
+            asset_id: T::AssetId,
+            who: &T::AccountId,
+            f: impl FnOnce(&mut ReputationOf<T, I>) -> R,
+        ) -> R {
+            let mut rep = ReputationStore::<T, I>::get(asset_id, who);
+            let result = f(&mut rep);
+            Self::insert_reputation(asset_id, who, rep);
+            result
+        }
+
+        /// Store the reputation record of an account, keeping `ScoreHistogram` in sync
+        fn insert_reputation(asset_id: T::AssetId, who: &T::AccountId, rep: ReputationOf<T, I>) {
+            if ReputationStore::<T, I>::contains_key(asset_id, who) {
+                Self::untrack_score(asset_id, ReputationStore::<T, I>::get(asset_id, who).score);
+            }
+            let bucket = Self::score_bucket(rep.score);
+            ScoreHistogram::<T, I>::mutate(asset_id, |histogram| {
+                histogram[bucket] = histogram[bucket].saturating_add(1);
+            });
+            ReputationStore::<T, I>::insert(asset_id, who, rep);
+        }
+
+        /// Take a stored score out of `ScoreHistogram`
+        fn untrack_score(asset_id: T::AssetId, score: BalanceOf<T, I>) {
+            let bucket = Self::score_bucket(score);
+            ScoreHistogram::<T, I>::mutate(asset_id, |histogram| {
+                histogram[bucket] = histogram[bucket].saturating_sub(1);
+            });
         }
 
-        /// Calculate streak bonus (10 points per day, max 500)
-        fn calculate_streak_bonus(streak: u32) -> u128 {
-            let bonus = (streak as u128).saturating_mul(POINTS_PER_STREAK_DAY);
-            bonus.min(MAX_STREAK_BONUS)
+        /// Histogram bucket of a score: the number of bits it takes, capped at the last bucket
+        pub(crate) fn score_bucket(score: BalanceOf<T, I>) -> usize {
+            let score: u128 = score.unique_saturated_into();
+            let bits = u128::BITS - score.leading_zeros();
+            bits.min(SCORE_HISTOGRAM_BUCKETS - 1) as usize
         }
 
         /// Recalculate the full reputation score from components
-        fn recalculate_score(rep: &Reputation<BlockNumberFor<T>>) -> u128 {
-            let unique_bonus = (rep.unique_recipients_count as u128)
-                .saturating_mul(POINTS_PER_UNIQUE_RECIPIENT);
+        pub(crate) fn recalculate_score(rep: &ReputationOf<T, I>) -> BalanceOf<T, I> {
+            let params = Economics::<T, I>::get().reputation;
+            let unique_bonus = BalanceOf::<T, I>::from(rep.unique_recipients_count)
+                .saturating_mul(params.points_per_unique_recipient.into());
             
             let sent_bonus = rep.burns_sent_volume;  // 1x multiplier
             
             let received_bonus = rep.weighted_received
-                .saturating_mul(WEIGHTED_RECEIVED_MULTIPLIER);
+                .saturating_mul(params.weighted_received_multiplier.into());
             
-            let streak_bonus = Self::calculate_streak_bonus(rep.claim_streak);
+            let streak_bonus = Self::calculate_streak_bonus(&params, rep.claim_streak);
             
             unique_bonus
                 .saturating_add(sent_bonus)
                 .saturating_add(received_bonus)
                 .saturating_add(streak_bonus)
+                .saturating_add(rep.maintenance_credit)
+                .saturating_add(rep.vouch_credit)
+                .saturating_add(rep.bonus_credit)
+                .saturating_add(rep.boost_received)
+                .saturating_sub(rep.commitment_cost)
+                .saturating_sub(rep.backlog_cost)
+                .saturating_sub(rep.boost_stake)
+                .saturating_sub(rep.slashed_stake)
+                .saturating_sub(rep.vouch_cost)
+        }
+
+        /// Get the reputation of an account (public API)
+        ///
+        /// The stored record is brought up to date first: the decay of the periods
+        /// elapsed since the last update and a lapsed claim streak are applied and
+        /// the score is recalculated, so accounts that have not transacted for a
+        /// while are not read with a stale score.
+        pub fn reputation(asset_id: &T::AssetId, who: &T::AccountId) -> ReputationOf<T, I> {
+            let mut rep = ReputationStore::<T, I>::get(asset_id, who);
+            if let Ok(params) = Self::asset_parameters(asset_id) {
+                let current_block = frame_system::Pallet::<T>::block_number();
+                Self::apply_elapsed_decay(&mut rep, Self::block_to_period(&params, current_block));
+            }
+            rep
         }
 
         /// Get reputation score for an account (public API)
-        pub fn reputation_score(who: &T::AccountId) -> u128 {
-            ReputationStore::<T>::get(who).score
+        ///
+        /// Includes the decay of the periods elapsed since the last update.
+        pub fn reputation_score(asset_id: &T::AssetId, who: &T::AccountId) -> BalanceOf<T, I> {
+            Self::reputation(asset_id, who).score
+        }
+
+        /// Share of the asset's accounts whose reputation score falls in a lower bucket
+        ///
+        /// Compares stored scores at the granularity of `ScoreHistogram`, so
+        /// accounts in the same power-of-two bucket rank equally. Zero for an
+        /// account without a reputation record.
+        pub fn reputation_percentile(asset_id: &T::AssetId, who: &T::AccountId) -> Perbill {
+            if !ReputationStore::<T, I>::contains_key(asset_id, who) {
+                return Perbill::zero();
+            }
+            let rep = ReputationStore::<T, I>::get(asset_id, who);
+            let histogram = ScoreHistogram::<T, I>::get(asset_id);
+            let total = histogram.iter().fold(0u64, |acc, count| acc.saturating_add((*count).into()));
+            let below = histogram[..Self::score_bucket(rep.score)]
+                .iter()
+                .fold(0u64, |acc, count| acc.saturating_add((*count).into()));
+            Perbill::from_rational(below, total.max(1))
+        }
+
+        /// Accounts attested a role and their number of attesters
+        ///
+        /// Iterates every account with the role; meant for runtime API queries.
+        pub fn attested_accounts(asset_id: T::AssetId, role: &RoleOf<T, I>) -> Vec<(T::AccountId, u32)> {
+            AttestationCounts::<T, I>::iter_prefix((asset_id, role)).collect()
         }
 
         /// Check if sender has already burned to this recipient before
-        pub fn has_burned_to(sender: &T::AccountId, recipient: &T::AccountId) -> bool {
-            UniqueRecipients::<T>::get(sender, recipient)
+        pub fn has_burned_to(
+            asset_id: &T::AssetId,
+            sender: &T::AccountId,
+            recipient: &T::AccountId,
+        ) -> bool {
+            UniqueRecipientSets::<T, I>::get(asset_id, sender).contains(recipient)
         }
     }
 }
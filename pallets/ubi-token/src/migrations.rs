@@ -0,0 +1,877 @@
+//! Storage migrations of the UBI token pallet
+//!
+//! Every migration is a `SteppedMigration`: a multi-block migrator calls it
+//! block after block with the weight left over, and it moves as many entries
+//! as fit before returning a cursor to continue from. Each one only runs on
+//! chains at its source storage version and sets the target version when it
+//! is done, so add the `LazyMigrationV*ToV*` types to the runtime in order.
+
+use crate::{Config, Event, Pallet, Reputation, ReputationOf, TokenBatch, UniqueRecipientSets, MAX_BATCHES};
+use core::marker::PhantomData;
+use frame_support::{
+    migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+    pallet_prelude::*,
+    weights::WeightMeter,
+};
+
+/// Prefix of the identifiers of this pallet's migrations
+const PALLET_MIGRATIONS_ID: &[u8; 16] = b"pallet-ubi-token";
+
+/// Version 1: the single-asset storage is keyed by asset and unique recipients
+/// are kept as one bounded set per sender
+pub mod v1 {
+    use super::*;
+    use crate::{AssetCount, AssetDetails, Assets, Balances, LastClaim, ReputationStore, ScoreHistogram, TotalSupply};
+    use alloc::vec::Vec;
+    use frame_support::storage::{unhashed, StoragePrefixedMap};
+    use frame_system::pallet_prelude::BlockNumberFor;
+    use frame_support::StorageHasher;
+    use parity_scale_codec::DecodeAll;
+    use sp_runtime::traits::{Saturating, UniqueSaturatedInto};
+
+    /// The former `UniqueRecipients` map of the single-asset pallet: sender -> recipient -> `true`
+    #[frame_support::storage_alias]
+    pub type UniqueRecipients<T: Config<I>, I: 'static> = StorageDoubleMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        bool,
+        ValueQuery,
+    >;
+
+    /// `Reputation` of the single-asset pallet
+    #[derive(Encode, Decode)]
+    pub struct LegacyReputation<BlockNumber> {
+        pub burns_sent_count: u64,
+        pub burns_sent_volume: u128,
+        pub burns_received_count: u64,
+        pub burns_received_volume: u128,
+        pub first_activity: BlockNumber,
+        pub weighted_received: u128,
+        pub unique_recipients_count: u32,
+        pub claim_streak: u32,
+        pub last_claim_period: u64,
+        pub score: u128,
+    }
+
+    /// Progress of draining `UniqueRecipients`
+    #[derive(Encode, Decode, MaxEncodedLen)]
+    pub struct DrainCursor<AccountId> {
+        /// Last moved key
+        pub last: (AccountId, AccountId),
+        /// Recipients moved into a set so far
+        pub moved: u32,
+        /// Recipients dropped so far because their sender's set was full
+        pub dropped: u32,
+    }
+
+    /// Progress of `LazyMigrationV0ToV1`
+    #[derive(Encode, Decode, MaxEncodedLen)]
+    pub enum MigrationCursor<AccountId> {
+        /// Last account whose legacy `Balances` entry was moved
+        Balances(Option<AccountId>),
+        /// Last account whose legacy `LastClaim` entry was moved
+        LastClaims(Option<AccountId>),
+        /// Last account whose legacy `ReputationStore` entry was moved
+        Reputations(Option<AccountId>),
+        /// Progress of draining `UniqueRecipients`
+        UniqueRecipients(Option<DrainCursor<AccountId>>),
+    }
+
+    /// Move the single-asset storage under the asset `A` that carries on the single token of the chain
+    ///
+    /// The first step creates `A`, owned by `O` and with the default
+    /// parameters, unless it exists, and moves `TotalSupply` into it. No
+    /// deposit is reserved for `A`, like for the genesis assets. Every
+    /// following step moves as many entries as its weight allows, map after
+    /// map:
+    ///
+    /// - `Balances`, `LastClaim` and `ReputationStore` entries are re-keyed by
+    ///   `(A, account)` in the encoding of version 1, so the later migrations
+    ///   re-encode them like any other entry. Moved reputations are counted in
+    ///   `ScoreHistogram`, start decaying from the current period and count as
+    ///   active now, and every moved claimant gets the provider reference the
+    ///   first claim takes.
+    /// - `UniqueRecipients` is drained into the senders' `UniqueRecipientSets`
+    ///   entries. Recipients beyond `MAX_UNIQUE_RECIPIENTS` of a sender are
+    ///   dropped; the sender's `unique_recipients_count` is left as it was.
+    ///   `UniqueRecipientsDrained` reports the totals once the map is empty.
+    pub struct LazyMigrationV0ToV1<T, A, O, I = ()>(PhantomData<(T, A, O, I)>);
+
+    impl<T: Config<I>, A: Get<T::AssetId>, O: Get<T::AccountId>, I: 'static> LazyMigrationV0ToV1<T, A, O, I> {
+        /// The account of the legacy entry that follows `last` in the map under `prefix`
+        ///
+        /// The single-asset maps share their prefixes with the asset-keyed maps
+        /// that replace them, so the keys are walked raw. A legacy key ends in
+        /// `blake2_128(account) ++ account`; the keys this migration already
+        /// wrote under `A` are skipped in one jump.
+        fn next_legacy_account(prefix: &[u8], last: Option<&T::AccountId>) -> Option<T::AccountId> {
+            let asset_keys = [prefix, &Blake2_128Concat::hash(&A::get().encode())].concat();
+            let mut next = match last {
+                Some(who) => sp_io::storage::next_key(&Self::legacy_key(prefix, who)),
+                None => sp_io::storage::next_key(prefix),
+            };
+            while let Some(key) = next.filter(|key| key.starts_with(prefix)) {
+                if key.starts_with(&asset_keys) {
+                    let after = Self::key_after(&asset_keys)?;
+                    next = if sp_io::storage::exists(&after) { Some(after) } else { sp_io::storage::next_key(&after) };
+                    continue;
+                }
+                let (hash, mut account) = key[prefix.len()..].split_at_checked(16)?;
+                if let Ok(who) = T::AccountId::decode_all(&mut account) {
+                    if sp_io::hashing::blake2_128(&who.encode()) == hash {
+                        return Some(who);
+                    }
+                }
+                next = sp_io::storage::next_key(&key);
+            }
+            None
+        }
+
+        /// The smallest key greater than every key starting with `prefix`
+        fn key_after(prefix: &[u8]) -> Option<Vec<u8>> {
+            let mut key = prefix.to_vec();
+            while key.pop()? == u8::MAX {}
+            let last = prefix[key.len()];
+            key.push(last + 1);
+            Some(key)
+        }
+
+        /// The raw key of the legacy entry of `who` in the map under `prefix`
+        fn legacy_key(prefix: &[u8], who: &T::AccountId) -> Vec<u8> {
+            [prefix, &Blake2_128Concat::hash(&who.encode())].concat()
+        }
+
+        /// Move the legacy `Balances` entry of `who` under `A`
+        fn move_balance(who: &T::AccountId) {
+            let key = Self::legacy_key(&Balances::<T, I>::final_prefix(), who);
+            let batches: Vec<v3::OldTokenBatch<u128, BlockNumberFor<T>>> = unhashed::take(&key).unwrap_or_default();
+            let batches: Vec<_> = batches
+                .into_iter()
+                .map(|batch| v3::OldTokenBatch { amount: batch.amount.unique_saturated_into(), expires_at: batch.expires_at })
+                .collect();
+            // Same bound as before, so nothing is truncated
+            v3::Balances::<T, I>::insert(A::get(), who, BoundedVec::truncate_from(batches));
+        }
+
+        /// Move the legacy `LastClaim` entry of `who` under `A`
+        fn move_last_claim(who: &T::AccountId) {
+            let key = Self::legacy_key(&LastClaim::<T, I>::final_prefix(), who);
+            if let Some(last_claim) = unhashed::take::<BlockNumberFor<T>>(&key) {
+                frame_system::Pallet::<T>::inc_providers(who);
+                LastClaim::<T, I>::insert(A::get(), who, last_claim);
+            }
+        }
+
+        /// Move the legacy `ReputationStore` entry of `who` under `A`
+        fn move_reputation(who: &T::AccountId) {
+            let key = Self::legacy_key(&ReputationStore::<T, I>::final_prefix(), who);
+            let Some(old) = unhashed::take::<LegacyReputation<BlockNumberFor<T>>>(&key) else {
+                return;
+            };
+            let asset_id = A::get();
+            let now = frame_system::Pallet::<T>::block_number();
+            let current_period = Pallet::<T, I>::asset_parameters(&asset_id)
+                .map_or(0, |params| Pallet::<T, I>::block_to_period(&params, now));
+            let score = old.score.unique_saturated_into();
+            let bucket = Pallet::<T, I>::score_bucket(score);
+            ScoreHistogram::<T, I>::mutate(asset_id, |histogram| {
+                histogram[bucket] = histogram[bucket].saturating_add(1);
+            });
+            v2::ReputationStore::<T, I>::insert(
+                asset_id,
+                who,
+                v2::OldReputation {
+                    burns_sent_count: old.burns_sent_count,
+                    burns_sent_volume: old.burns_sent_volume.unique_saturated_into(),
+                    burns_received_count: old.burns_received_count,
+                    burns_received_volume: old.burns_received_volume.unique_saturated_into(),
+                    first_activity: old.first_activity,
+                    weighted_received: old.weighted_received.unique_saturated_into(),
+                    unique_recipients_count: old.unique_recipients_count,
+                    claim_streak: old.claim_streak,
+                    last_claim_period: old.last_claim_period,
+                    score,
+                    maintenance_credit: Default::default(),
+                    last_activity: now,
+                    last_decay_period: current_period,
+                    commitment_cost: Default::default(),
+                    backlog_cost: Default::default(),
+                    streak_freeze: 0,
+                    vacation_start: 0,
+                    vacation_end: 0,
+                    boost_stake: Default::default(),
+                    slashed_stake: Default::default(),
+                    vouch_credit: Default::default(),
+                    vouch_cost: Default::default(),
+                },
+            );
+        }
+
+        /// Drain the next `UniqueRecipients` entry, or finish the migration if the map is empty
+        fn drain_recipient(cursor: Option<DrainCursor<T::AccountId>>) -> Option<DrainCursor<T::AccountId>> {
+            let mut entries = match &cursor {
+                Some(DrainCursor { last: (sender, recipient), .. }) => {
+                    UniqueRecipients::<T, I>::iter_from(UniqueRecipients::<T, I>::hashed_key_for(sender, recipient))
+                }
+                None => UniqueRecipients::<T, I>::iter(),
+            };
+            let (moved, dropped) = cursor.map_or((0, 0), |cursor| (cursor.moved, cursor.dropped));
+            let Some((sender, recipient, _)) = entries.next() else {
+                Pallet::<T, I>::deposit_event(Event::UniqueRecipientsDrained { moved, dropped });
+                StorageVersion::new(1).put::<Pallet<T, I>>();
+                return None;
+            };
+
+            UniqueRecipients::<T, I>::remove(&sender, &recipient);
+            let inserted = UniqueRecipientSets::<T, I>::mutate(A::get(), &sender, |recipients| {
+                recipients.try_insert(recipient.clone()).is_ok()
+            });
+            Some(DrainCursor {
+                last: (sender, recipient),
+                moved: moved.saturating_add(inserted as u32),
+                dropped: dropped.saturating_add(!inserted as u32),
+            })
+        }
+    }
+
+    impl<T: Config<I>, A: Get<T::AssetId>, O: Get<T::AccountId>, I: 'static> SteppedMigration
+        for LazyMigrationV0ToV1<T, A, O, I>
+    {
+        type Cursor = MigrationCursor<T::AccountId>;
+        type Identifier = MigrationId<16>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 0, version_to: 1 }
+        }
+
+        fn step(
+            mut cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            if cursor.is_none() && Pallet::<T, I>::on_chain_storage_version() != 0 {
+                return Ok(None);
+            }
+            // Walking past the keys already moved under `A` takes up to two more reads
+            let required = T::DbWeight::get().reads_writes(4, 3);
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            while meter.try_consume(required).is_ok() {
+                cursor = Some(match cursor {
+                    None => {
+                        let asset_id = A::get();
+                        if !Assets::<T, I>::contains_key(asset_id) {
+                            Assets::<T, I>::insert(
+                                asset_id,
+                                AssetDetails { owner: O::get(), params: Pallet::<T, I>::default_parameters() },
+                            );
+                            AssetCount::<T, I>::mutate(|count| *count = count.saturating_add(1));
+                        }
+                        // The single-asset `TotalSupply` value sits at the prefix of the map
+                        let supply = unhashed::take::<u128>(&TotalSupply::<T, I>::final_prefix()).unwrap_or_default();
+                        TotalSupply::<T, I>::mutate(asset_id, |total| {
+                            *total = total.saturating_add(supply.unique_saturated_into());
+                        });
+                        MigrationCursor::Balances(None)
+                    }
+                    Some(MigrationCursor::Balances(last)) => {
+                        match Self::next_legacy_account(&Balances::<T, I>::final_prefix(), last.as_ref()) {
+                            Some(who) => {
+                                Self::move_balance(&who);
+                                MigrationCursor::Balances(Some(who))
+                            }
+                            None => MigrationCursor::LastClaims(None),
+                        }
+                    }
+                    Some(MigrationCursor::LastClaims(last)) => {
+                        match Self::next_legacy_account(&LastClaim::<T, I>::final_prefix(), last.as_ref()) {
+                            Some(who) => {
+                                Self::move_last_claim(&who);
+                                MigrationCursor::LastClaims(Some(who))
+                            }
+                            None => MigrationCursor::Reputations(None),
+                        }
+                    }
+                    Some(MigrationCursor::Reputations(last)) => {
+                        match Self::next_legacy_account(&ReputationStore::<T, I>::final_prefix(), last.as_ref()) {
+                            Some(who) => {
+                                Self::move_reputation(&who);
+                                MigrationCursor::Reputations(Some(who))
+                            }
+                            None => MigrationCursor::UniqueRecipients(None),
+                        }
+                    }
+                    Some(MigrationCursor::UniqueRecipients(drain)) => match Self::drain_recipient(drain) {
+                        Some(drain) => MigrationCursor::UniqueRecipients(Some(drain)),
+                        None => return Ok(None),
+                    },
+                });
+            }
+            Ok(cursor)
+        }
+    }
+}
+
+/// Version 2: reputation records are encoded compactly
+pub mod v2 {
+    use super::*;
+    use frame_system::pallet_prelude::BlockNumberFor;
+
+    /// `Reputation` as encoded before version 2, with every field in full width
+    #[derive(Encode, Decode)]
+    pub struct OldReputation<Balance, BlockNumber> {
+        pub burns_sent_count: u64,
+        pub burns_sent_volume: Balance,
+        pub burns_received_count: u64,
+        pub burns_received_volume: Balance,
+        pub first_activity: BlockNumber,
+        pub weighted_received: Balance,
+        pub unique_recipients_count: u32,
+        pub claim_streak: u32,
+        pub last_claim_period: u64,
+        pub score: Balance,
+        pub maintenance_credit: Balance,
+        pub last_activity: BlockNumber,
+        pub last_decay_period: u64,
+        pub commitment_cost: Balance,
+        pub backlog_cost: Balance,
+        pub streak_freeze: u32,
+        pub vacation_start: u64,
+        pub vacation_end: u64,
+        pub boost_stake: Balance,
+        pub slashed_stake: Balance,
+        pub vouch_credit: Balance,
+        pub vouch_cost: Balance,
+    }
+
+    impl<Balance: Default, BlockNumber> From<OldReputation<Balance, BlockNumber>> for Reputation<Balance, BlockNumber> {
+        fn from(old: OldReputation<Balance, BlockNumber>) -> Self {
+            Reputation {
+                burns_sent_count: old.burns_sent_count,
+                burns_sent_volume: old.burns_sent_volume,
+                burns_received_count: old.burns_received_count,
+                burns_received_volume: old.burns_received_volume,
+                first_activity: old.first_activity,
+                weighted_received: old.weighted_received,
+                unique_recipients_count: old.unique_recipients_count,
+                claim_streak: old.claim_streak,
+                last_claim_period: old.last_claim_period,
+                score: old.score,
+                maintenance_credit: old.maintenance_credit,
+                last_activity: old.last_activity,
+                last_decay_period: old.last_decay_period,
+                commitment_cost: old.commitment_cost,
+                backlog_cost: old.backlog_cost,
+                streak_freeze: old.streak_freeze,
+                vacation_start: old.vacation_start,
+                vacation_end: old.vacation_end,
+                boost_stake: old.boost_stake,
+                slashed_stake: old.slashed_stake,
+                vouch_credit: old.vouch_credit,
+                vouch_cost: old.vouch_cost,
+                bonus_credit: Default::default(),
+                boost_received: Default::default(),
+            }
+        }
+    }
+
+    /// Old reputation type of a pallet instance
+    pub type OldReputationOf<T, I> = OldReputation<crate::BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// `ReputationStore` read with the old encoding
+    #[frame_support::storage_alias]
+    pub type ReputationStore<T: Config<I>, I: 'static> = StorageDoubleMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as Config<I>>::AssetId,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        OldReputationOf<T, I>,
+        OptionQuery,
+    >;
+
+    /// Re-encode every `ReputationStore` entry compactly
+    ///
+    /// Entries are rewritten in key order and the cursor is the last rewritten
+    /// key, so every entry after it still has the old encoding. Scores are
+    /// unchanged, so `ScoreHistogram` stays valid.
+    pub struct LazyMigrationV1ToV2<T, I = ()>(PhantomData<(T, I)>);
+
+    impl<T: Config<I>, I: 'static> SteppedMigration for LazyMigrationV1ToV2<T, I> {
+        type Cursor = (T::AssetId, T::AccountId);
+        type Identifier = MigrationId<16>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 1, version_to: 2 }
+        }
+
+        fn step(
+            mut cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            if cursor.is_none() && Pallet::<T, I>::on_chain_storage_version() != 1 {
+                return Ok(None);
+            }
+            let required = T::DbWeight::get().reads_writes(2, 1);
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            while meter.try_consume(required).is_ok() {
+                let mut entries = match &cursor {
+                    Some((asset_id, who)) => {
+                        ReputationStore::<T, I>::iter_from(ReputationStore::<T, I>::hashed_key_for(asset_id, who))
+                    }
+                    None => ReputationStore::<T, I>::iter(),
+                };
+                let Some((asset_id, who, old)) = entries.next() else {
+                    StorageVersion::new(2).put::<Pallet<T, I>>();
+                    return Ok(None);
+                };
+
+                crate::ReputationStore::<T, I>::insert(asset_id, &who, ReputationOf::<T, I>::from(old));
+                cursor = Some((asset_id, who));
+            }
+            Ok(cursor)
+        }
+    }
+}
+
+/// Version 3: token batches are encoded compactly
+pub mod v3 {
+    use super::*;
+    use alloc::vec::Vec;
+    use frame_system::pallet_prelude::BlockNumberFor;
+
+    /// `TokenBatch` as encoded before version 3
+    #[derive(Encode, Decode)]
+    pub struct OldTokenBatch<Balance, BlockNumber> {
+        pub amount: Balance,
+        pub expires_at: BlockNumber,
+    }
+
+    /// Old batch type of a pallet instance
+    pub type OldTokenBatchOf<T, I> = OldTokenBatch<crate::BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// `Balances` read with the old encoding
+    #[frame_support::storage_alias]
+    pub type Balances<T: Config<I>, I: 'static> = StorageDoubleMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as Config<I>>::AssetId,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<OldTokenBatchOf<T, I>, ConstU32<MAX_BATCHES>>,
+        OptionQuery,
+    >;
+
+    /// Re-encode the batches of every `Balances` entry compactly
+    ///
+    /// Works through the map in key order like `v2::LazyMigrationV1ToV2`.
+    pub struct LazyMigrationV2ToV3<T, I = ()>(PhantomData<(T, I)>);
+
+    impl<T: Config<I>, I: 'static> SteppedMigration for LazyMigrationV2ToV3<T, I> {
+        type Cursor = (T::AssetId, T::AccountId);
+        type Identifier = MigrationId<16>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 2, version_to: 3 }
+        }
+
+        fn step(
+            mut cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            if cursor.is_none() && Pallet::<T, I>::on_chain_storage_version() != 2 {
+                return Ok(None);
+            }
+            let required = T::DbWeight::get().reads_writes(2, 1);
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            while meter.try_consume(required).is_ok() {
+                let mut entries = match &cursor {
+                    Some((asset_id, who)) => {
+                        Balances::<T, I>::iter_from(Balances::<T, I>::hashed_key_for(asset_id, who))
+                    }
+                    None => Balances::<T, I>::iter(),
+                };
+                let Some((asset_id, who, old)) = entries.next() else {
+                    StorageVersion::new(3).put::<Pallet<T, I>>();
+                    return Ok(None);
+                };
+
+                // Same bound as before, so nothing is truncated
+                let batches: Vec<_> = old
+                    .into_iter()
+                    .map(|batch| TokenBatch { amount: batch.amount, expires_at: batch.expires_at })
+                    .collect();
+                crate::Balances::<T, I>::insert(asset_id, &who, BoundedVec::truncate_from(batches));
+                cursor = Some((asset_id, who));
+            }
+            Ok(cursor)
+        }
+    }
+}
+
+/// Version 4: reputation records carry a bonus credit
+pub mod v4 {
+    use super::*;
+    use frame_system::pallet_prelude::BlockNumberFor;
+
+    /// `Reputation` as encoded in versions 2 and 3, without `bonus_credit`
+    #[derive(Encode, Decode)]
+    pub struct OldReputation<Balance, BlockNumber> {
+        #[codec(compact)]
+        pub burns_sent_count: u64,
+        #[codec(compact)]
+        pub burns_sent_volume: Balance,
+        #[codec(compact)]
+        pub burns_received_count: u64,
+        #[codec(compact)]
+        pub burns_received_volume: Balance,
+        #[codec(compact)]
+        pub first_activity: BlockNumber,
+        #[codec(compact)]
+        pub weighted_received: Balance,
+        #[codec(compact)]
+        pub unique_recipients_count: u32,
+        #[codec(compact)]
+        pub claim_streak: u32,
+        #[codec(compact)]
+        pub last_claim_period: u64,
+        #[codec(compact)]
+        pub score: Balance,
+        #[codec(compact)]
+        pub maintenance_credit: Balance,
+        #[codec(compact)]
+        pub last_activity: BlockNumber,
+        #[codec(compact)]
+        pub last_decay_period: u64,
+        #[codec(compact)]
+        pub commitment_cost: Balance,
+        #[codec(compact)]
+        pub backlog_cost: Balance,
+        #[codec(compact)]
+        pub streak_freeze: u32,
+        #[codec(compact)]
+        pub vacation_start: u64,
+        #[codec(compact)]
+        pub vacation_end: u64,
+        #[codec(compact)]
+        pub boost_stake: Balance,
+        #[codec(compact)]
+        pub slashed_stake: Balance,
+        #[codec(compact)]
+        pub vouch_credit: Balance,
+        #[codec(compact)]
+        pub vouch_cost: Balance,
+    }
+
+    impl<Balance: Default, BlockNumber> From<OldReputation<Balance, BlockNumber>> for Reputation<Balance, BlockNumber> {
+        fn from(old: OldReputation<Balance, BlockNumber>) -> Self {
+            Reputation {
+                burns_sent_count: old.burns_sent_count,
+                burns_sent_volume: old.burns_sent_volume,
+                burns_received_count: old.burns_received_count,
+                burns_received_volume: old.burns_received_volume,
+                first_activity: old.first_activity,
+                weighted_received: old.weighted_received,
+                unique_recipients_count: old.unique_recipients_count,
+                claim_streak: old.claim_streak,
+                last_claim_period: old.last_claim_period,
+                score: old.score,
+                maintenance_credit: old.maintenance_credit,
+                last_activity: old.last_activity,
+                last_decay_period: old.last_decay_period,
+                commitment_cost: old.commitment_cost,
+                backlog_cost: old.backlog_cost,
+                streak_freeze: old.streak_freeze,
+                vacation_start: old.vacation_start,
+                vacation_end: old.vacation_end,
+                boost_stake: old.boost_stake,
+                slashed_stake: old.slashed_stake,
+                vouch_credit: old.vouch_credit,
+                vouch_cost: old.vouch_cost,
+                bonus_credit: Default::default(),
+                boost_received: Default::default(),
+            }
+        }
+    }
+
+    /// Old reputation type of a pallet instance
+    pub type OldReputationOf<T, I> = OldReputation<crate::BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// `ReputationStore` read with the old layout
+    #[frame_support::storage_alias]
+    pub type ReputationStore<T: Config<I>, I: 'static> = StorageDoubleMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as Config<I>>::AssetId,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        OldReputationOf<T, I>,
+        OptionQuery,
+    >;
+
+    /// Add an empty `bonus_credit` to every `ReputationStore` entry
+    ///
+    /// Works through the map in key order like `v2::LazyMigrationV1ToV2`.
+    /// Scores are unchanged, so `ScoreHistogram` stays valid.
+    pub struct LazyMigrationV3ToV4<T, I = ()>(PhantomData<(T, I)>);
+
+    impl<T: Config<I>, I: 'static> SteppedMigration for LazyMigrationV3ToV4<T, I> {
+        type Cursor = (T::AssetId, T::AccountId);
+        type Identifier = MigrationId<16>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 3, version_to: 4 }
+        }
+
+        fn step(
+            mut cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            if cursor.is_none() && Pallet::<T, I>::on_chain_storage_version() != 3 {
+                return Ok(None);
+            }
+            let required = T::DbWeight::get().reads_writes(2, 1);
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            while meter.try_consume(required).is_ok() {
+                let mut entries = match &cursor {
+                    Some((asset_id, who)) => {
+                        ReputationStore::<T, I>::iter_from(ReputationStore::<T, I>::hashed_key_for(asset_id, who))
+                    }
+                    None => ReputationStore::<T, I>::iter(),
+                };
+                let Some((asset_id, who, old)) = entries.next() else {
+                    StorageVersion::new(4).put::<Pallet<T, I>>();
+                    return Ok(None);
+                };
+
+                crate::ReputationStore::<T, I>::insert(asset_id, &who, ReputationOf::<T, I>::from(old));
+                cursor = Some((asset_id, who));
+            }
+            Ok(cursor)
+        }
+    }
+}
+
+/// Version 5: boosts are credited to their recipients in `boost_received`
+pub mod v5 {
+    use super::*;
+    use crate::{BurnBoosts, BurnId, BurnReceipts, Economics};
+    use frame_system::pallet_prelude::BlockNumberFor;
+    use sp_runtime::{traits::Saturating, Perbill};
+
+    /// `Reputation` as encoded in version 4, without `boost_received`
+    #[derive(Encode, Decode)]
+    pub struct OldReputation<Balance, BlockNumber> {
+        #[codec(compact)]
+        pub burns_sent_count: u64,
+        #[codec(compact)]
+        pub burns_sent_volume: Balance,
+        #[codec(compact)]
+        pub burns_received_count: u64,
+        #[codec(compact)]
+        pub burns_received_volume: Balance,
+        #[codec(compact)]
+        pub first_activity: BlockNumber,
+        #[codec(compact)]
+        pub weighted_received: Balance,
+        #[codec(compact)]
+        pub unique_recipients_count: u32,
+        #[codec(compact)]
+        pub claim_streak: u32,
+        #[codec(compact)]
+        pub last_claim_period: u64,
+        #[codec(compact)]
+        pub score: Balance,
+        #[codec(compact)]
+        pub maintenance_credit: Balance,
+        #[codec(compact)]
+        pub last_activity: BlockNumber,
+        #[codec(compact)]
+        pub last_decay_period: u64,
+        #[codec(compact)]
+        pub commitment_cost: Balance,
+        #[codec(compact)]
+        pub backlog_cost: Balance,
+        #[codec(compact)]
+        pub streak_freeze: u32,
+        #[codec(compact)]
+        pub vacation_start: u64,
+        #[codec(compact)]
+        pub vacation_end: u64,
+        #[codec(compact)]
+        pub boost_stake: Balance,
+        #[codec(compact)]
+        pub slashed_stake: Balance,
+        #[codec(compact)]
+        pub vouch_credit: Balance,
+        #[codec(compact)]
+        pub vouch_cost: Balance,
+        #[codec(compact)]
+        pub bonus_credit: Balance,
+    }
+
+    impl<Balance: Default, BlockNumber> From<OldReputation<Balance, BlockNumber>> for Reputation<Balance, BlockNumber> {
+        fn from(old: OldReputation<Balance, BlockNumber>) -> Self {
+            Reputation {
+                burns_sent_count: old.burns_sent_count,
+                burns_sent_volume: old.burns_sent_volume,
+                burns_received_count: old.burns_received_count,
+                burns_received_volume: old.burns_received_volume,
+                first_activity: old.first_activity,
+                weighted_received: old.weighted_received,
+                unique_recipients_count: old.unique_recipients_count,
+                claim_streak: old.claim_streak,
+                last_claim_period: old.last_claim_period,
+                score: old.score,
+                maintenance_credit: old.maintenance_credit,
+                last_activity: old.last_activity,
+                last_decay_period: old.last_decay_period,
+                commitment_cost: old.commitment_cost,
+                backlog_cost: old.backlog_cost,
+                streak_freeze: old.streak_freeze,
+                vacation_start: old.vacation_start,
+                vacation_end: old.vacation_end,
+                boost_stake: old.boost_stake,
+                slashed_stake: old.slashed_stake,
+                vouch_credit: old.vouch_credit,
+                vouch_cost: old.vouch_cost,
+                bonus_credit: old.bonus_credit,
+                boost_received: Default::default(),
+            }
+        }
+    }
+
+    /// Old reputation type of a pallet instance
+    pub type OldReputationOf<T, I> = OldReputation<crate::BalanceOf<T, I>, BlockNumberFor<T>>;
+
+    /// `ReputationStore` read with the old layout
+    #[frame_support::storage_alias]
+    pub type ReputationStore<T: Config<I>, I: 'static> = StorageDoubleMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        <T as Config<I>>::AssetId,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        OldReputationOf<T, I>,
+        OptionQuery,
+    >;
+
+    /// Progress of `LazyMigrationV4ToV5`
+    #[derive(Encode, Decode, MaxEncodedLen)]
+    pub enum MigrationCursor<AssetId, AccountId> {
+        /// Last re-encoded `ReputationStore` key
+        Reputations(AssetId, AccountId),
+        /// Last burn whose boost was moved
+        Boosts(BurnId),
+    }
+
+    /// Add `boost_received` to every `ReputationStore` entry, then move the open boosts into it
+    ///
+    /// Reputations are re-encoded in key order like `v2::LazyMigrationV1ToV2`.
+    /// The stake of an open boost used to be added to the recipient's
+    /// `weighted_received` and the burn's `weighted_amount`; it is taken out of
+    /// both, as far as it has decayed since the burn, and credited to the
+    /// recipient's `boost_received` instead.
+    pub struct LazyMigrationV4ToV5<T, I = ()>(PhantomData<(T, I)>);
+
+    impl<T: Config<I>, I: 'static> LazyMigrationV4ToV5<T, I> {
+        /// Credit the boost of `burn_id` to its recipient's `boost_received`
+        fn move_boost(burn_id: BurnId, stake: crate::BalanceOf<T, I>) {
+            let Some(mut receipt) = BurnReceipts::<T, I>::get(burn_id) else {
+                return;
+            };
+            let plain = receipt.weighted_amount.saturating_sub(stake);
+            if !receipt.reversed() {
+                if let Ok(params) = Pallet::<T, I>::asset_parameters(&receipt.asset_id) {
+                    let current_period =
+                        Pallet::<T, I>::block_to_period(&params, frame_system::Pallet::<T>::block_number());
+                    // What is left of the stake in `weighted_received` after the decay since the burn
+                    let elapsed = current_period.saturating_sub(Pallet::<T, I>::block_to_period(&params, receipt.block));
+                    let factor = Perbill::from_perthousand(Economics::<T, I>::get().reputation.decay_factor)
+                        .saturating_pow(elapsed.try_into().unwrap_or(usize::MAX));
+                    let boost_left = factor.mul_floor(receipt.weighted_amount).saturating_sub(factor.mul_floor(plain));
+                    Pallet::<T, I>::mutate_reputation(receipt.asset_id, &receipt.to, |rep| {
+                        Pallet::<T, I>::apply_elapsed_decay(rep, current_period);
+                        rep.weighted_received = rep.weighted_received.saturating_sub(boost_left);
+                        rep.boost_received = rep.boost_received.saturating_add(stake);
+                        rep.score = Pallet::<T, I>::recalculate_score(rep);
+                    });
+                }
+            }
+            receipt.weighted_amount = plain;
+            BurnReceipts::<T, I>::insert(burn_id, receipt);
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> SteppedMigration for LazyMigrationV4ToV5<T, I> {
+        type Cursor = MigrationCursor<T::AssetId, T::AccountId>;
+        type Identifier = MigrationId<16>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 4, version_to: 5 }
+        }
+
+        fn step(
+            mut cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            if cursor.is_none() && Pallet::<T, I>::on_chain_storage_version() != 4 {
+                return Ok(None);
+            }
+            let required = T::DbWeight::get().reads_writes(4, 3);
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            while meter.try_consume(required).is_ok() {
+                cursor = match cursor {
+                    None | Some(MigrationCursor::Reputations(..)) => {
+                        let mut entries = match &cursor {
+                            Some(MigrationCursor::Reputations(asset_id, who)) => ReputationStore::<T, I>::iter_from(
+                                ReputationStore::<T, I>::hashed_key_for(asset_id, who),
+                            ),
+                            _ => ReputationStore::<T, I>::iter(),
+                        };
+                        match entries.next() {
+                            Some((asset_id, who, old)) => {
+                                crate::ReputationStore::<T, I>::insert(asset_id, &who, ReputationOf::<T, I>::from(old));
+                                Some(MigrationCursor::Reputations(asset_id, who))
+                            }
+                            None => {
+                                let Some((burn_id, boost)) = BurnBoosts::<T, I>::iter().next() else {
+                                    StorageVersion::new(5).put::<Pallet<T, I>>();
+                                    return Ok(None);
+                                };
+                                Self::move_boost(burn_id, boost.stake);
+                                Some(MigrationCursor::Boosts(burn_id))
+                            }
+                        }
+                    }
+                    Some(MigrationCursor::Boosts(last)) => {
+                        let mut boosts = BurnBoosts::<T, I>::iter_from(BurnBoosts::<T, I>::hashed_key_for(last));
+                        let Some((burn_id, boost)) = boosts.next() else {
+                            StorageVersion::new(5).put::<Pallet<T, I>>();
+                            return Ok(None);
+                        };
+                        Self::move_boost(burn_id, boost.stake);
+                        Some(MigrationCursor::Boosts(burn_id))
+                    }
+                };
+            }
+            Ok(cursor)
+        }
+    }
+}
@@ -2,12 +2,15 @@ use crate as pallet_ubi_token;
 use frame_support::{
     derive_impl,
     parameter_types,
-    traits::{ConstU16, ConstU32, ConstU64},
+    traits::{ConstU128, ConstU16, ConstU32, ConstU64, Hooks},
+    weights::Weight,
 };
+use frame_system::{EnsureRoot, EnsureSigned};
 use sp_core::H256;
 use sp_runtime::{
+    testing::{TestSignature, UintAuthorityId},
     traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
+    BuildStorage, Perbill,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -15,7 +18,9 @@ type Block = frame_system::mocking::MockBlock<Test>;
 frame_support::construct_runtime!(
     pub enum Test {
         System: frame_system,
+        NativeBalances: pallet_balances,
         UbiToken: pallet_ubi_token,
+        CityToken: pallet_ubi_token::<Instance1>,
     }
 );
 
@@ -23,6 +28,25 @@ parameter_types! {
     pub const BlockHashCount: u64 = 250;
 }
 
+pub type Extrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+
+impl<C> frame_system::offchain::CreateTransactionBase<C> for Test
+where
+    RuntimeCall: From<C>,
+{
+    type Extrinsic = Extrinsic;
+    type RuntimeCall = RuntimeCall;
+}
+
+impl<C> frame_system::offchain::CreateBare<C> for Test
+where
+    RuntimeCall: From<C>,
+{
+    fn create_bare(call: RuntimeCall) -> Extrinsic {
+        Extrinsic::new_bare(call)
+    }
+}
+
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
 impl frame_system::Config for Test {
     type BaseCallFilter = frame_support::traits::Everything;
@@ -41,28 +65,152 @@ impl frame_system::Config for Test {
     type BlockHashCount = ConstU64<250>;
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u64>;
     type OnNewAccount = ();
-    type OnKilledAccount = ();
+    type OnKilledAccount = (UbiToken, CityToken);
     type SystemWeightInfo = ();
     type SS58Prefix = ConstU16<42>;
     type OnSetCode = ();
     type MaxConsumers = ConstU32<16>;
 }
 
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+}
+
 parameter_types! {
     pub const UbiAmount: u128 = 100;           // 100 tokens per claim period
     pub const ClaimPeriodBlocks: u64 = 100;    // 100 blocks = 1 day (for testing)
     pub const ExpirationBlocks: u64 = 700;     // 700 blocks = 7 days (for testing)
     pub const MaxBacklogPeriods: u32 = 3;      // Can claim up to 3 days backlog
+    pub const DormantScoreThreshold: u128 = 10; // Dormant after 5 idle periods below this
+    pub const ShortExpiryRatio: Perbill = Perbill::from_percent(40);
+    pub const ShortExpiryBonus: Perbill = Perbill::from_percent(10);
+    pub const CharityBonus: Perbill = Perbill::from_percent(50);
+    pub static ClaimPowDifficulty: u32 = 0;     // Tests switch the proof-of-work on
+    pub static MaxUnsignedBurnsPerPeriod: u32 = 100;
+    pub static WelcomeBonus: u128 = 0;          // Tests switch the welcome bonuses on
+    pub static WelcomeReputation: u128 = 0;
+    pub static ExpiryPollWeight: Weight = Weight::from_parts(50_000, 0); // Two accounts per block
 }
 
 impl pallet_ubi_token::Config for Test {
-    type RuntimeEvent = RuntimeEvent;
+    type Balance = u128;
+    type AssetId = u32;
+    type CreateOrigin = EnsureSigned<u64>;
+    type Currency = NativeBalances;
+    type AssetDeposit = ConstU64<100>;
+    type MaxAssets = ConstU32<4>;
+    type BonusOrigin = EnsureRoot<u64>;
+    type ReviewOrigin = EnsureRoot<u64>;
+    type CharityOrigin = EnsureRoot<u64>;
+    type ParameterOrigin = EnsureRoot<u64>;
     type UbiAmount = UbiAmount;
     type ClaimPeriodBlocks = ClaimPeriodBlocks;
     type ExpirationBlocks = ExpirationBlocks;
     type MaxBacklogPeriods = MaxBacklogPeriods;
+    type MaxSweepAccounts = ConstU32<4>;
+    type DormantPeriods = ConstU32<5>;
+    type DormantScoreThreshold = DormantScoreThreshold;
+    type RejectionWindow = ConstU64<100>;
+    type MaxServiceNameLen = ConstU32<16>;
+    type MaxDisplayNameLen = ConstU32<16>;
+    type MinProfileReputation = ConstU128<10>;
+    type MaxClaimBatch = ConstU32<4>;
+    type ShortExpiryRatio = ShortExpiryRatio;
+    type ShortExpiryBonus = ShortExpiryBonus;
+    type MaxCommitPeriods = ConstU32<7>;
+    type MaxExtraBacklogPeriods = ConstU32<4>;
+    type StreakFreezeCost = ConstU128<50>;
+    type StreakFreezePeriods = ConstU32<3>;
+    type MaxVacationPeriods = ConstU64<10>;
+    type VacationCooldownPeriods = ConstU64<20>;
+    type ClaimPowDifficulty = ClaimPowDifficulty;
+    type MaxUnsignedBurnsPerPeriod = MaxUnsignedBurnsPerPeriod;
+    type ClaimLookaheadBlocks = ConstU64<5>;
+    type MaxPoolGrants = ConstU32<4>;
+    type CharityBonus = CharityBonus;
+    type MaxHouseholdMembers = ConstU32<2>;
+    type MaxOrganizationMembers = ConstU32<4>;
+    type MaxRoleLen = ConstU32<16>;
+    type MinAttesterReputation = ConstU128<10>;
+    type MaxVouchesPerPeriod = ConstU32<2>;
+    type BoostLockBlocks = ConstU64<100>;
+    type WelcomeBonus = WelcomeBonus;
+    type WelcomeReputation = WelcomeReputation;
+    type ReferralBonus = ConstU128<40>;
+    type MaxReferralsPerPeriod = ConstU32<2>;
+    type IdempotencyWindow = ConstU64<100>;
+    type MaxIdempotencyKeys = ConstU32<2>;
+    type MaxOutboundSnapshots = ConstU32<2>;
+    type ExpiryPollWeight = ExpiryPollWeight;
+    type OffchainSignature = TestSignature;
+    type OffchainPublic = UintAuthorityId;
+}
+
+parameter_types! {
+    pub const CityUbiAmount: u32 = 10;         // Smaller local community token
+    pub const CityClaimPeriodBlocks: u64 = 50; // Claims twice as often
+    pub const CityExpirationBlocks: u64 = 200; // Expires much sooner
+    pub const CityMaxBacklogPeriods: u32 = 1;  // No backlog beyond one period
+    pub const CityDormantScoreThreshold: u32 = 10;
+}
+
+/// Second instance, used to check that communities are isolated
+/// (and that a narrower balance type works)
+impl pallet_ubi_token::Config<pallet_ubi_token::Instance1> for Test {
+    type Balance = u32;
+    type AssetId = u32;
+    type CreateOrigin = EnsureSigned<u64>;
+    type Currency = NativeBalances;
+    type AssetDeposit = ConstU64<100>;
+    type MaxAssets = ConstU32<4>;
+    type BonusOrigin = EnsureRoot<u64>;
+    type ReviewOrigin = EnsureRoot<u64>;
+    type CharityOrigin = EnsureRoot<u64>;
+    type ParameterOrigin = EnsureRoot<u64>;
+    type UbiAmount = CityUbiAmount;
+    type ClaimPeriodBlocks = CityClaimPeriodBlocks;
+    type ExpirationBlocks = CityExpirationBlocks;
+    type MaxBacklogPeriods = CityMaxBacklogPeriods;
+    type MaxSweepAccounts = ConstU32<4>;
+    type DormantPeriods = ConstU32<5>;
+    type DormantScoreThreshold = CityDormantScoreThreshold;
+    type RejectionWindow = ConstU64<50>;
+    type MaxServiceNameLen = ConstU32<16>;
+    type MaxDisplayNameLen = ConstU32<16>;
+    type MinProfileReputation = ConstU32<10>;
+    type MaxClaimBatch = ConstU32<4>;
+    type ShortExpiryRatio = ShortExpiryRatio;
+    type ShortExpiryBonus = ShortExpiryBonus;
+    type MaxCommitPeriods = ConstU32<7>;
+    type MaxExtraBacklogPeriods = ConstU32<4>;
+    type StreakFreezeCost = ConstU32<50>;
+    type StreakFreezePeriods = ConstU32<3>;
+    type MaxVacationPeriods = ConstU64<10>;
+    type VacationCooldownPeriods = ConstU64<20>;
+    type ClaimPowDifficulty = ConstU32<0>;
+    type MaxUnsignedBurnsPerPeriod = ConstU32<100>;
+    type ClaimLookaheadBlocks = ConstU64<5>;
+    type MaxPoolGrants = ConstU32<4>;
+    type CharityBonus = CharityBonus;
+    type MaxHouseholdMembers = ConstU32<2>;
+    type MaxOrganizationMembers = ConstU32<4>;
+    type MaxRoleLen = ConstU32<16>;
+    type MinAttesterReputation = ConstU32<10>;
+    type MaxVouchesPerPeriod = ConstU32<2>;
+    type BoostLockBlocks = ConstU64<100>;
+    type WelcomeBonus = ConstU32<0>;
+    type WelcomeReputation = ConstU32<0>;
+    type ReferralBonus = ConstU32<40>;
+    type MaxReferralsPerPeriod = ConstU32<2>;
+    type IdempotencyWindow = ConstU64<100>;
+    type MaxIdempotencyKeys = ConstU32<2>;
+    type MaxOutboundSnapshots = ConstU32<2>;
+    type ExpiryPollWeight = ExpiryPollWeight;
+    type OffchainSignature = TestSignature;
+    type OffchainPublic = UintAuthorityId;
 }
 
 // Test accounts
@@ -70,19 +218,29 @@ pub const ALICE: u64 = 1;
 pub const BOB: u64 = 2;
 pub const CHARLIE: u64 = 3;
 
+// Community asset created at genesis (owned by Alice) in both instances
+pub const ASSET: u32 = 0;
+
 /// Build genesis storage for testing
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    let t = frame_system::GenesisConfig::<Test>::default()
-        .build_storage()
-        .unwrap();
+    let t = RuntimeGenesisConfig {
+        system: Default::default(),
+        native_balances: pallet_balances::GenesisConfig { balances: vec![(BOB, 1_000)], ..Default::default() },
+        ubi_token: pallet_ubi_token::GenesisConfig { assets: vec![(ASSET, ALICE)], ..Default::default() },
+        city_token: pallet_ubi_token::GenesisConfig { assets: vec![(ASSET, ALICE)], ..Default::default() },
+    }
+    .build_storage()
+    .unwrap();
     let mut ext = sp_io::TestExternalities::new(t);
     ext.execute_with(|| System::set_block_number(1));
     ext
 }
 
-/// Advance to a specific block number
+/// Advance to a specific block number, running the pallet hooks
 pub fn run_to_block(n: u64) {
     while System::block_number() < n {
         System::set_block_number(System::block_number() + 1);
+        UbiToken::on_initialize(System::block_number());
+        CityToken::on_initialize(System::block_number());
     }
 }
@@ -1,5 +1,18 @@
-use crate::{mock::*, Error, Event, Balances, LastClaim, ReputationStore, TotalSupply, UniqueRecipients};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+    mock::*, AccountPreferences, Approval, Approvals, AssetParameters, Balances, BlockExpirations, BonusPeriod,
+    BonusPeriods,
+    BurnCategory, BurnCategoryStats, BurnDispute, BurnDisputes, BurnReceipt, BurnReceipts,
+    CategoryStats, ClaimMode, ClaimOffsets, CommunityPool, EmissionStep, Commitment, Commitments, Error, Event,
+    ExpiryIndex, ExpiryPollCursor,
+    ExpiryWarnings, Instance1, LastClaim, OrganizationMember, PoolGrant, Preferences, PruneCursor, RecentSupporters,
+    ReputationStore,
+    RingBurns,
+    Services, Supporter, SupplyCap,
+    SuspicionKind, SuspicionReport, SuspicionReports, TokenBatch, TopSupporter, TotalSupply, UbiParameter,
+    UbiParameterKey, VelocityPolicy, BURN_AUTHORIZATION_CONTEXT, MAX_RECENT_SUPPORTERS,
+    MAX_TOP_SUPPORTERS, MAX_UNIQUE_RECIPIENTS, MAX_VELOCITY_ADJUSTMENT, UniqueRecipientSets,
+};
+use frame_support::{assert_noop, assert_ok, traits::Hooks, weights::Weight};
 
 // ============================================================================
 // CLAIM TESTS
@@ -9,38 +22,75 @@ use frame_support::{assert_noop, assert_ok};
 fn claim_works_for_new_account() {
     new_test_ext().execute_with(|| {
         // Alice claims for the first time (unsigned tx)
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         // Check balance
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 100);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
 
         // Check total supply
-        assert_eq!(TotalSupply::<Test>::get(), 100);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 100);
 
         // Check last claim updated
-        assert!(LastClaim::<Test>::get(ALICE).is_some());
+        assert!(LastClaim::<Test>::get(ASSET, ALICE).is_some());
 
         // Check event
         System::assert_last_event(
-            Event::Claimed {
+            Event::<Test>::Claimed {
+                asset_id: ASSET,
                 who: ALICE,
                 amount: 100,
                 periods: 1,
                 expires_at: 1 + 700, // current block + expiration
+                mode: ClaimMode::Standard,
+                period: 0,
+                remaining: 100,
             }
             .into(),
         );
     });
 }
 
+#[test]
+fn short_expiry_claim_grants_bonus() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim_short_expiry(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // 10% bonus, expires after 40% of the 700 blocks
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 110);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 110);
+        System::assert_last_event(
+            Event::<Test>::Claimed {
+                asset_id: ASSET,
+                who: ALICE,
+                amount: 110,
+                periods: 1,
+                expires_at: 1 + 280,
+                mode: ClaimMode::ShortExpiry,
+                period: 0,
+                remaining: 110,
+            }
+            .into(),
+        );
+
+        // Counts as the claim of the period
+        assert_noop!(
+            UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0),
+            Error::<Test>::NothingToClaim
+        );
+
+        run_to_block(281);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 0);
+    });
+}
+
 #[test]
 fn cannot_claim_twice_in_same_period() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         // Try to claim again immediately
         assert_noop!(
-            UbiToken::claim(RuntimeOrigin::none(), ALICE),
+            UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0),
             Error::<Test>::NothingToClaim
         );
     });
@@ -49,70 +99,432 @@ fn cannot_claim_twice_in_same_period() {
 #[test]
 fn can_claim_after_one_period() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 100);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
 
         // Advance one claim period (100 blocks)
         run_to_block(101);
 
         // Can claim again
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 200);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 200);
     });
 }
 
 #[test]
 fn can_claim_backlog_up_to_max() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         // Advance 5 periods (500 blocks) - should only get 3 days backlog
         run_to_block(501);
 
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         // Should get 3 periods (max backlog) = 300 tokens
         // Plus the 100 from first claim = 400 total
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 400);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 400);
 
         // Check event shows 3 periods
         System::assert_last_event(
-            Event::Claimed {
+            Event::<Test>::Claimed {
+                asset_id: ASSET,
                 who: ALICE,
                 amount: 300,
                 periods: 3,
                 expires_at: 501 + 700,
+                mode: ClaimMode::Standard,
+                period: 5,
+                remaining: 400,
             }
             .into(),
         );
     });
 }
 
+#[test]
+fn late_third_party_claim_does_not_delay_the_next_one() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(UbiToken::claim_anchor(&ASSET, &ALICE), None);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::claim_anchor(&ASSET, &ALICE), Some(0));
+
+        // Someone submits Alice's claim in the last block of her window 1
+        run_to_block(200);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::claim_anchor(&ASSET, &ALICE), Some(1));
+
+        // Window 2 starts one block later and is claimable right away
+        run_to_block(201);
+        assert_eq!(UbiToken::claimable_periods(&ASSET, &ALICE), 1);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::claim_anchor(&ASSET, &ALICE), Some(2));
+
+        // Backlog counts the windows crossed since the anchor
+        run_to_block(450);
+        assert_eq!(UbiToken::claimable_periods(&ASSET, &ALICE), 2);
+    });
+}
+
+#[test]
+fn claim_windows_are_anchored_to_the_first_claim() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(40);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_eq!(UbiToken::claim_offset(ASSET, ALICE), 1);
+        assert_eq!(UbiToken::claim_offset(ASSET, BOB), 40);
+
+        // The global boundary opens nothing, each window rolls on its own
+        run_to_block(100);
+        assert_eq!(UbiToken::claimable_periods(&ASSET, &ALICE), 0);
+        assert_eq!(UbiToken::next_claim_block(&ASSET, &ALICE), Some(101));
+        assert_eq!(UbiToken::next_claim_block(&ASSET, &BOB), Some(140));
+
+        run_to_block(101);
+        assert_eq!(UbiToken::next_claim_block(&ASSET, &ALICE), Some(101));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_noop!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0), Error::<Test>::NothingToClaim);
+
+        run_to_block(140);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+        // Accounts without an offset keep the global boundary
+        ClaimOffsets::<Test>::remove(ASSET, BOB);
+        run_to_block(200);
+        assert_eq!(UbiToken::claimable_periods(&ASSET, &BOB), 1);
+    });
+}
+
+#[test]
+fn claim_pool_tags_follow_the_claim_window() {
+    use frame_support::pallet_prelude::{TransactionSource, ValidateUnsigned};
+
+    let claim = crate::Call::<Test>::claim { asset_id: ASSET, account: BOB, nonce: 0 };
+    new_test_ext().execute_with(|| {
+        run_to_block(40);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+        // Bob's window 1 runs from block 140 to 239, across the global boundary at 200
+        run_to_block(150);
+        let before = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &claim).unwrap();
+        run_to_block(210);
+        let after = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &claim).unwrap();
+        assert_eq!(before.provides, after.provides);
+    });
+}
+
 #[test]
 fn first_activity_recorded_on_claim() {
     new_test_ext().execute_with(|| {
-        let rep_before = ReputationStore::<Test>::get(ALICE);
+        let rep_before = ReputationStore::<Test>::get(ASSET, ALICE);
         assert_eq!(rep_before.first_activity, 0);
 
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
-        let rep_after = ReputationStore::<Test>::get(ALICE);
+        let rep_after = ReputationStore::<Test>::get(ASSET, ALICE);
         assert_eq!(rep_after.first_activity, 1); // Block 1
     });
 }
 
+#[test]
+fn first_claim_onboards_account_with_welcome_bonuses() {
+    new_test_ext().execute_with(|| {
+        WelcomeBonus::set(50);
+        WelcomeReputation::set(20);
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        System::assert_has_event(
+            Event::<Test>::AccountOnboarded { asset_id: ASSET, who: ALICE, bonus: 50, reputation: 20 }.into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 150);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 150);
+
+        // 10 points for the streak plus the welcome reputation
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!((rep.bonus_credit, rep.score), (20, 30));
+
+        // Later claims are ordinary, and the welcome reputation decays like earned reputation
+        System::reset_events();
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert!(!System::events()
+            .iter()
+            .any(|record| matches!(record.event, RuntimeEvent::UbiToken(Event::AccountOnboarded { .. }))));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 250);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).bonus_credit, 19);
+    });
+}
+
+#[test]
+fn onboarding_is_signalled_without_welcome_bonuses() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        System::assert_has_event(
+            Event::<Test>::AccountOnboarded { asset_id: ASSET, who: ALICE, bonus: 0, reputation: 0 }.into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).bonus_credit, 0);
+    });
+}
+
 #[test]
 fn multiple_accounts_can_claim() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), BOB));
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), CHARLIE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 100);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &CHARLIE), 100);
+
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 300);
+    });
+}
+
+fn claim_batch(accounts: &[u64]) -> frame_support::BoundedVec<u64, frame_support::traits::ConstU32<4>> {
+    accounts.to_vec().try_into().unwrap()
+}
+
+#[test]
+fn claim_for_many_claims_for_every_eligible_account() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+
+        // Charlie already claimed this period and is skipped
+        assert_ok!(UbiToken::claim_for_many(RuntimeOrigin::none(), ASSET, claim_batch(&[ALICE, BOB, CHARLIE])));
+
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 100);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &CHARLIE), 100);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 300);
+        System::assert_has_event(
+            Event::<Test>::Claimed {
+                asset_id: ASSET,
+                who: BOB,
+                amount: 100,
+                periods: 1,
+                expires_at: 701,
+                mode: ClaimMode::Standard,
+                period: 0,
+                remaining: 100,
+            }
+            .into(),
+        );
+        System::assert_last_event(
+            Event::<Test>::BatchClaimed { asset_id: ASSET, claimed: 2, skipped: 1 }.into(),
+        );
+    });
+}
+
+#[test]
+fn claim_for_many_can_be_relayed_and_fails_when_nobody_can_claim() {
+    new_test_ext().execute_with(|| {
+        // A relayer signs the batch
+        assert_ok!(UbiToken::claim_for_many(RuntimeOrigin::signed(CHARLIE), ASSET, claim_batch(&[ALICE, BOB])));
+
+        assert_noop!(
+            UbiToken::claim_for_many(RuntimeOrigin::none(), ASSET, claim_batch(&[ALICE, BOB])),
+            Error::<Test>::NothingToClaim
+        );
+        assert_noop!(
+            UbiToken::claim_for_many(RuntimeOrigin::none(), 42, claim_batch(&[ALICE])),
+            Error::<Test>::UnknownAsset
+        );
+    });
+}
+
+#[test]
+fn household_claimer_claims_for_consenting_members() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::claim_household(RuntimeOrigin::signed(ALICE), ASSET),
+            Error::<Test>::NotInHousehold
+        );
+        assert_ok!(UbiToken::join_household(RuntimeOrigin::signed(BOB), ASSET, ALICE));
+        System::assert_last_event(
+            Event::<Test>::HouseholdJoined { asset_id: ASSET, claimer: ALICE, member: BOB }.into(),
+        );
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::join_household(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE));
+
+        // Bob already claimed this period and is skipped
+        assert_ok!(UbiToken::claim_household(RuntimeOrigin::signed(ALICE), ASSET));
+        System::assert_last_event(
+            Event::<Test>::HouseholdClaimed { asset_id: ASSET, claimer: ALICE, claimed: 2, skipped: 1 }.into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &CHARLIE), 100);
+        assert_noop!(
+            UbiToken::claim_household(RuntimeOrigin::signed(ALICE), ASSET),
+            Error::<Test>::NothingToClaim
+        );
+
+        // A member leaving withdraws its consent
+        assert_ok!(UbiToken::leave_household(RuntimeOrigin::signed(BOB), ASSET));
+        assert_eq!(UbiToken::household(ASSET, ALICE).into_inner(), vec![CHARLIE]);
+        assert_eq!(UbiToken::household_of(ASSET, BOB), None);
+        run_to_block(101);
+        assert_ok!(UbiToken::claim_household(RuntimeOrigin::signed(ALICE), ASSET));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 100);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &CHARLIE), 200);
+    });
+}
+
+#[test]
+fn households_are_bounded_and_exclusive() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::join_household(RuntimeOrigin::signed(BOB), ASSET, ALICE));
+        assert_ok!(UbiToken::join_household(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE));
+        assert_noop!(UbiToken::join_household(RuntimeOrigin::signed(4), ASSET, ALICE), Error::<Test>::HouseholdFull);
+
+        // Members cannot claim for others, claimers cannot join another household
+        assert_noop!(
+            UbiToken::join_household(RuntimeOrigin::signed(4), ASSET, BOB),
+            Error::<Test>::AlreadyInHousehold
+        );
+        assert_noop!(
+            UbiToken::join_household(RuntimeOrigin::signed(ALICE), ASSET, 4),
+            Error::<Test>::AlreadyInHousehold
+        );
+        assert_noop!(
+            UbiToken::join_household(RuntimeOrigin::signed(BOB), ASSET, 4),
+            Error::<Test>::AlreadyInHousehold
+        );
+        assert_noop!(
+            UbiToken::join_household(RuntimeOrigin::signed(4), ASSET, 4),
+            Error::<Test>::AlreadyInHousehold
+        );
+        assert_noop!(UbiToken::leave_household(RuntimeOrigin::signed(4), ASSET), Error::<Test>::NotInHousehold);
+    });
+}
+
+#[test]
+fn claim_for_many_shares_pool_tags_with_single_claims() {
+    use frame_support::pallet_prelude::{TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+        let single = crate::Pallet::<Test>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test>::claim { asset_id: ASSET, account: ALICE, nonce: 0 },
+        )
+        .unwrap();
+        let batch = crate::Pallet::<Test>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test>::claim_for_many { asset_id: ASSET, accounts: claim_batch(&[ALICE, BOB]) },
+        )
+        .unwrap();
+        // Bob cannot claim, so only Alice's tag is provided
+        assert_eq!(batch.provides, single.provides);
+    });
+}
+
+#[test]
+fn claim_refunds_weight_of_unused_batches_and_periods() {
+    new_test_ext().execute_with(|| {
+        let info = UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0).unwrap();
+        assert_eq!(info.actual_weight, Some(UbiToken::claim_weight(0, 1)));
+
+        // The first batch expired, the backlog is capped at 3 periods
+        run_to_block(801);
+        let info = UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0).unwrap();
+        assert_eq!(info.actual_weight, Some(UbiToken::claim_weight(1, 3)));
+        assert!(UbiToken::claim_weight(1, 3).any_lt(UbiToken::claim_weight(crate::MAX_BATCHES, 3)));
+
+        // Bob is claimed, Alice is skipped
+        let info = UbiToken::claim_for_many(RuntimeOrigin::none(), ASSET, claim_batch(&[ALICE, BOB])).unwrap();
+        assert_eq!(info.actual_weight, Some(UbiToken::claim_weight(0, 1) + UbiToken::claim_weight(0, 0)));
+    });
+}
+
+#[test]
+fn claim_requires_proof_of_work_when_configured() {
+    use crate::{claim_pow_hash, find_claim_nonce, leading_zero_bits};
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        ClaimPowDifficulty::set(8);
+        assert_eq!(UbiToken::ubi_parameters(&ASSET).unwrap().claim_pow_difficulty, 8);
+
+        // Block 1 is in claim period 0
+        let bad = (0..).find(|n| leading_zero_bits(&claim_pow_hash(&ASSET, &ALICE, 0, *n)) < 8).unwrap();
+        assert_noop!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, bad), Error::<Test>::InvalidClaimProof);
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::<Test>::claim { asset_id: ASSET, account: ALICE, nonce: bad },
+            ),
+            InvalidTransaction::Custom(8).into(),
+        );
+
+        let nonce = find_claim_nonce(&ASSET, &ALICE, 0, 8).unwrap();
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, nonce));
+
+        // A proof is only valid in its own period
+        run_to_block(101);
+        if leading_zero_bits(&claim_pow_hash(&ASSET, &ALICE, 1, nonce)) < 8 {
+            assert_noop!(
+                UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, nonce),
+                Error::<Test>::InvalidClaimProof
+            );
+        }
+
+        // Unsigned batches cannot carry proofs, relayers have to sign
+        assert_noop!(
+            UbiToken::claim_for_many(RuntimeOrigin::none(), ASSET, claim_batch(&[BOB])),
+            Error::<Test>::InvalidClaimProof
+        );
+        assert_ok!(UbiToken::claim_for_many(RuntimeOrigin::signed(CHARLIE), ASSET, claim_batch(&[BOB])));
+    });
+}
+
+#[test]
+#[should_panic(expected = "ClaimPowDifficulty must be at most MAX_CLAIM_POW_DIFFICULTY")]
+fn integrity_test_bounds_claim_pow_difficulty() {
+    use frame_support::traits::Hooks;
+
+    ClaimPowDifficulty::set(crate::MAX_CLAIM_POW_DIFFICULTY + 1);
+    <UbiToken as Hooks<u64>>::integrity_test();
+}
+
+#[test]
+fn next_period_claims_wait_in_the_pool_until_the_boundary() {
+    use crate::find_claim_nonce;
+    use frame_support::pallet_prelude::{TransactionSource, ValidateUnsigned};
+    use parity_scale_codec::Encode;
+
+    let claim = |nonce| crate::Call::<Test>::claim { asset_id: ASSET, account: ALICE, nonce };
+    let name = <UbiToken as frame_support::traits::PalletInfoAccess>::name();
+
+    new_test_ext().execute_with(|| {
+        ClaimPowDifficulty::set(8);
+        let nonce = find_claim_nonce(&ASSET, &ALICE, 0, 8).unwrap();
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, nonce));
+        let nonce = find_claim_nonce(&ASSET, &ALICE, 1, 8).unwrap();
 
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 100);
-        assert_eq!(UbiToken::spendable_balance(&BOB), 100);
-        assert_eq!(UbiToken::spendable_balance(&CHARLIE), 100);
+        // Too early for Alice's window 1
+        run_to_block(95);
+        assert!(crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &claim(nonce)).is_err());
 
-        assert_eq!(TotalSupply::<Test>::get(), 300);
+        // Within the lookahead the claim is queued for the boundary at block 101
+        run_to_block(96);
+        let queued = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &claim(nonce)).unwrap();
+        assert_eq!(queued.provides, vec![("UbiClaim", name, ASSET, ALICE, 1u64).encode()]);
+        assert_eq!(queued.requires, vec![("UbiClaim", name, ASSET, 1u64).encode()]);
+        assert_eq!(queued.longevity, 10);
+        // It is not executable yet
+        assert!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, nonce).is_err());
+
+        // At the boundary it is ready and goes through
+        run_to_block(101);
+        let ready = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &claim(nonce)).unwrap();
+        assert_eq!(ready.provides, queued.provides);
+        assert!(ready.requires.is_empty());
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, nonce));
     });
 }
 
@@ -123,26 +535,32 @@ fn multiple_accounts_can_claim() {
 #[test]
 fn burn_works() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         // Alice burns 50 tokens to Bob (unsigned tx with from parameter)
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
 
         // Alice balance decreased
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 50);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 50);
 
         // Bob balance unchanged (burn doesn't transfer)
-        assert_eq!(UbiToken::spendable_balance(&BOB), 0);
 
         // Total supply decreased
-        assert_eq!(TotalSupply::<Test>::get(), 50);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 50);
 
         // Check event
         System::assert_last_event(
-            Event::Burned {
+            Event::<Test>::Burned {
+                burn_id: 0,
+                asset_id: ASSET,
                 from: ALICE,
                 to: BOB,
                 amount: 50,
+                category: None,
+                reference: None,
+                period: 0,
+                remaining: 50,
+                sender_weight: 750,
             }
             .into(),
         );
@@ -152,10 +570,10 @@ fn burn_works() {
 #[test]
 fn burn_updates_sender_reputation() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
 
-        let rep = ReputationStore::<Test>::get(ALICE);
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
         assert_eq!(rep.burns_sent_count, 1);
         assert_eq!(rep.burns_sent_volume, 50);
     });
@@ -164,10 +582,10 @@ fn burn_updates_sender_reputation() {
 #[test]
 fn burn_updates_recipient_reputation() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
 
-        let rep = ReputationStore::<Test>::get(BOB);
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
         assert_eq!(rep.burns_received_count, 1);
         assert_eq!(rep.burns_received_volume, 50);
         assert_eq!(rep.first_activity, 1); // First activity via receiving burn
@@ -177,10 +595,10 @@ fn burn_updates_recipient_reputation() {
 #[test]
 fn cannot_burn_to_self() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         assert_noop!(
-            UbiToken::burn(RuntimeOrigin::none(), ALICE, ALICE, 50),
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, ALICE, 50, None, None, 0, None, None),
             Error::<Test>::CannotBurnToSelf
         );
     });
@@ -189,10 +607,10 @@ fn cannot_burn_to_self() {
 #[test]
 fn cannot_burn_zero_amount() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         assert_noop!(
-            UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 0),
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 0, None, None, 0, None, None),
             Error::<Test>::AmountMustBePositive
         );
     });
@@ -201,10 +619,10 @@ fn cannot_burn_zero_amount() {
 #[test]
 fn cannot_burn_more_than_balance() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         assert_noop!(
-            UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 150),
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 150, None, None, 0, None, None),
             Error::<Test>::InsufficientBalance
         );
     });
@@ -214,22 +632,22 @@ fn cannot_burn_more_than_balance() {
 fn burn_uses_fifo() {
     new_test_ext().execute_with(|| {
         // Alice claims at block 1
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         // Advance one period and claim again
         run_to_block(101);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
         // Alice has 200 tokens in 2 batches
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 200);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 200);
 
         // Burn 150 - should use all of first batch (100) + 50 from second
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 150));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 150, None, None, 0, None, None));
 
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 50);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 50);
 
         // Check batches - should have 1 batch with 50
-        let batches = Balances::<Test>::get(ALICE);
+        let batches = Balances::<Test>::get(ASSET, ALICE);
         assert_eq!(batches.len(), 1);
         assert_eq!(batches[0].amount, 50);
     });
@@ -238,504 +656,4406 @@ fn burn_uses_fifo() {
 #[test]
 fn multiple_burns_accumulate_reputation() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
 
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 30));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 20));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, CHARLIE, 10));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 30, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 1, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 10, None, None, 2, None, None));
 
-        let alice_rep = ReputationStore::<Test>::get(ALICE);
+        let alice_rep = ReputationStore::<Test>::get(ASSET, ALICE);
         assert_eq!(alice_rep.burns_sent_count, 3);
         assert_eq!(alice_rep.burns_sent_volume, 60);
 
-        let bob_rep = ReputationStore::<Test>::get(BOB);
+        let bob_rep = ReputationStore::<Test>::get(ASSET, BOB);
         assert_eq!(bob_rep.burns_received_count, 2);
         assert_eq!(bob_rep.burns_received_volume, 50);
 
-        let charlie_rep = ReputationStore::<Test>::get(CHARLIE);
+        let charlie_rep = ReputationStore::<Test>::get(ASSET, CHARLIE);
         assert_eq!(charlie_rep.burns_received_count, 1);
         assert_eq!(charlie_rep.burns_received_volume, 10);
     });
 }
 
-// ============================================================================
-// EXPIRATION TESTS
-// ============================================================================
-
 #[test]
-fn tokens_expire_after_expiration_period() {
+fn burn_category_is_recorded_in_event() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 100);
-
-        // Advance past expiration (700 blocks)
-        run_to_block(702);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(
+            RuntimeOrigin::none(),
+            ASSET,
+            ALICE,
+            BOB,
+            50,
+            Some(BurnCategory::Food),
+            None,
+            0,
+            None,
+            None
+        ));
 
-        // Balance should now show 0 (expired)
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 0);
+        System::assert_last_event(
+            Event::<Test>::Burned {
+                burn_id: 0,
+                asset_id: ASSET,
+                from: ALICE,
+                to: BOB,
+                amount: 50,
+                category: Some(BurnCategory::Food),
+                reference: None,
+                period: 0,
+                remaining: 50,
+                sender_weight: 750,
+            }
+            .into(),
+        );
     });
 }
 
 #[test]
-fn expired_tokens_cleaned_up_on_claim() {
+fn burn_stores_receipt_with_reference() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        let invoice = sp_core::H256::repeat_byte(0xab);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(
+            RuntimeOrigin::none(),
+            ASSET,
+            ALICE,
+            CHARLIE,
+            20,
+            Some(BurnCategory::Services),
+            Some(invoice),
+            1,
+            None,
+            None
+        ));
 
-        // Advance past expiration (700 blocks)
-        run_to_block(702);
+        System::assert_last_event(
+            Event::<Test>::Burned {
+                burn_id: 1,
+                asset_id: ASSET,
+                from: ALICE,
+                to: CHARLIE,
+                amount: 20,
+                category: Some(BurnCategory::Services),
+                reference: Some(invoice),
+                period: 0,
+                remaining: 70,
+                sender_weight: 750,
+            }
+            .into(),
+        );
+        assert_eq!(
+            BurnReceipts::<Test>::get(1),
+            Some(BurnReceipt {
+                asset_id: ASSET,
+                from: ALICE,
+                to: CHARLIE,
+                amount: 20,
+                category: Some(BurnCategory::Services),
+                reference: Some(invoice),
+                block: 1,
+                // Weighted by Alice's score after the first burn
+                weighted_amount: 15,
+                sender_bonus: 0,
+                new_recipient: true,
+                rejected: false,
+                refunded: false,
+            })
+        );
+        assert_eq!(UbiToken::burn_receipt(0).unwrap().reference, None);
+        assert_eq!(UbiToken::next_burn_id(), 2);
+    });
+}
 
-        // Claim again - this should clean up expired tokens and claim backlog
-        // After 702 blocks (7 periods), can claim max backlog of 3 periods = 300 tokens
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+#[test]
+fn burn_receipts_are_returned_with_their_storage_key() {
+    use parity_scale_codec::Encode;
+    use sp_io::hashing::{twox_128, twox_64};
 
-        // Should have 300 (3 periods backlog, max)
-        // Original 100 expired, new 300 from backlog claim
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 300);
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
 
-        // Check expired event was emitted
-        let events = System::events();
-        let expired_event = events.iter().find(|e| {
-            matches!(
-                e.event,
-                RuntimeEvent::UbiToken(Event::Expired { who: ALICE, amount: 100 })
-            )
-        });
-        assert!(expired_event.is_some());
+        let (receipt, key) = UbiToken::burn_receipt_with_key(0).unwrap();
+        assert_eq!(Some(receipt.clone()), UbiToken::burn_receipt(0));
+        // The layout `pallet-ubi-token-receipt-verifier` reads
+        let id = 0u64.encode();
+        let expected = [&twox_128(b"UbiToken")[..], &twox_128(b"BurnReceipts")[..], &twox_64(&id)[..], &id[..]];
+        assert_eq!(key, expected.concat());
+        assert_eq!(sp_io::storage::get(&key).map(|value| value.to_vec()), Some(receipt.encode()));
+
+        assert!(UbiToken::burn_receipt_with_key(1).is_none());
     });
 }
 
 #[test]
-fn expired_tokens_cleaned_up_on_burn() {
+fn reject_burn_reverses_reputation() {
     new_test_ext().execute_with(|| {
-        // Alice claims twice
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        run_to_block(101);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let alice_before = ReputationStore::<Test>::get(ASSET, ALICE);
+        let bob_before = ReputationStore::<Test>::get(ASSET, BOB);
 
-        // Advance so first batch expires (700 blocks from block 1 = 701)
-        // but second batch hasn't (700 blocks from block 101 = 801)
-        run_to_block(702);
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0));
 
-        // Alice tries to burn - should clean up expired batch first
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
+        System::assert_last_event(
+            Event::<Test>::BurnRejected { burn_id: 0, asset_id: ASSET, from: ALICE, to: BOB }.into(),
+        );
 
-        // Should have 50 left from second batch
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 50);
+        let alice = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(alice.burns_sent_count, 0);
+        assert_eq!(alice.burns_sent_volume, 0);
+        assert_eq!(alice.unique_recipients_count, 0);
+        assert_eq!(alice.score, alice_before.score);
+        assert!(!UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+
+        let bob = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!(bob.burns_received_count, 0);
+        assert_eq!(bob.burns_received_volume, 0);
+        assert_eq!(bob.weighted_received, 0);
+        assert_eq!(bob.score, bob_before.score);
+
+        // Tokens stay destroyed
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 50);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 50);
+        assert!(BurnReceipts::<Test>::get(0).unwrap().rejected);
     });
 }
 
 #[test]
-fn cannot_burn_expired_tokens() {
+fn reject_burn_keeps_earlier_recipient_breadth() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 1, None, None));
 
-        // Advance past expiration
-        run_to_block(702);
+        // Only the second burn is rejected, Bob stays a unique recipient
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 1));
+        let alice = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(alice.burns_sent_count, 1);
+        assert_eq!(alice.burns_sent_volume, 10);
+        assert_eq!(alice.unique_recipients_count, 1);
+        assert!(UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+    });
+}
 
-        // Try to burn - should fail (tokens expired)
-        assert_noop!(
-            UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50),
-            Error::<Test>::InsufficientBalance
+#[test]
+fn reject_burn_is_taken_out_of_recent_supporters() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 1, None, None));
+
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 1));
+        assert_eq!(
+            RecentSupporters::<Test>::get(ASSET, BOB).into_inner(),
+            vec![Supporter { who: ALICE, count: 1, volume: 10, last_burn: 1 }]
         );
+
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0));
+        assert!(RecentSupporters::<Test>::get(ASSET, BOB).is_empty());
     });
 }
 
-// ============================================================================
-// HELPER FUNCTION TESTS
-// ============================================================================
-
 #[test]
-fn can_claim_helper_works() {
+fn recent_supporters_keep_latest_senders_first() {
     new_test_ext().execute_with(|| {
-        // New account can claim
-        assert!(UbiToken::can_claim(&ALICE));
-
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 10, None, None, 0, None, None));
+        run_to_block(5);
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, BOB, CHARLIE, 20, None, None, 0, None, None));
+        run_to_block(9);
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 30, None, None, 1, None, None));
 
-        // Just claimed - cannot claim again
-        assert!(!UbiToken::can_claim(&ALICE));
+        // Alice's second burn moves her back to the front
+        assert_eq!(
+            RecentSupporters::<Test>::get(ASSET, CHARLIE).into_inner(),
+            vec![
+                Supporter { who: ALICE, count: 2, volume: 40, last_burn: 9 },
+                Supporter { who: BOB, count: 1, volume: 20, last_burn: 5 },
+            ]
+        );
 
-        // After one period - can claim
-        run_to_block(101);
-        assert!(UbiToken::can_claim(&ALICE));
+        // A full list drops the supporter with the oldest burn
+        for sender in 10..10 + u64::from(MAX_RECENT_SUPPORTERS) {
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, sender, 0));
+            assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, sender, CHARLIE, 1, None, None, 0, None, None));
+        }
+        let supporters = RecentSupporters::<Test>::get(ASSET, CHARLIE);
+        assert_eq!(supporters.len() as u32, MAX_RECENT_SUPPORTERS);
+        assert_eq!(supporters[0].who, 9 + u64::from(MAX_RECENT_SUPPORTERS));
+        assert!(supporters.iter().all(|s| s.who != ALICE && s.who != BOB));
     });
 }
 
 #[test]
-fn claimable_amount_helper_works() {
+fn top_supporters_rank_senders_by_volume() {
     new_test_ext().execute_with(|| {
-        // New account - 1 period = 100
-        assert_eq!(UbiToken::claimable_amount(&ALICE), 100);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, BOB, CHARLIE, 20, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 30, None, None, 1, None, None));
+        assert_eq!(
+            UbiToken::top_supporters(ASSET, CHARLIE).into_inner(),
+            vec![TopSupporter { who: ALICE, volume: 40 }, TopSupporter { who: BOB, volume: 20 }]
+        );
 
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        // A rejected burn is taken out of the ranking
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(CHARLIE), 2));
+        assert_eq!(
+            UbiToken::top_supporters(ASSET, CHARLIE).into_inner(),
+            vec![TopSupporter { who: BOB, volume: 20 }, TopSupporter { who: ALICE, volume: 10 }]
+        );
 
-        // Just claimed - 0
-        assert_eq!(UbiToken::claimable_amount(&ALICE), 0);
+        // Fill the ranking with small supporters
+        for sender in 10..8 + u64::from(MAX_TOP_SUPPORTERS) {
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, sender, 0));
+            assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, sender, CHARLIE, 5, None, None, 0, None, None));
+        }
+        assert_eq!(UbiToken::top_supporters(ASSET, CHARLIE).len() as u32, MAX_TOP_SUPPORTERS);
 
-        // After 2 periods - 200
-        run_to_block(201);
-        assert_eq!(UbiToken::claimable_amount(&ALICE), 200);
+        // Matching the smallest volume is not enough to enter a full ranking, exceeding it is
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, 50, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, 50, CHARLIE, 5, None, None, 0, None, None));
+        assert!(UbiToken::top_supporters(ASSET, CHARLIE).iter().all(|s| s.who != 50));
 
-        // After 5 periods - capped at 3 = 300
-        run_to_block(501);
-        assert_eq!(UbiToken::claimable_amount(&ALICE), 300);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, 51, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, 51, CHARLIE, 6, None, None, 0, None, None));
+        let supporters = UbiToken::top_supporters(ASSET, CHARLIE);
+        assert_eq!(supporters.len() as u32, MAX_TOP_SUPPORTERS);
+        assert_eq!(supporters[2], TopSupporter { who: 51, volume: 6 });
+        assert_eq!(supporters.iter().filter(|s| s.volume == 5).count() as u32, MAX_TOP_SUPPORTERS - 3);
     });
 }
 
-// ============================================================================
-// INTEGRATION TESTS
-// ============================================================================
-
 #[test]
-fn full_lifecycle_pizza_purchase() {
+fn reject_burn_checks_caller_and_window() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 10, None, None, 1, None, None));
+
+        assert_noop!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 7), Error::<Test>::UnknownBurn);
+        assert_noop!(
+            UbiToken::reject_burn(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::NotBurnRecipient
+        );
+
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0));
+        assert_noop!(
+            UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::BurnAlreadyRejected
+        );
+
+        // Window is 100 blocks after the burn
+        run_to_block(102);
+        assert_noop!(
+            UbiToken::reject_burn(RuntimeOrigin::signed(CHARLIE), 1),
+            Error::<Test>::RejectionWindowClosed
+        );
+    });
+}
+
+#[test]
+fn issue_refund_reverses_reputation_after_the_rejection_window() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 10, None, None, 1, None, None));
+
+        assert_noop!(UbiToken::issue_refund(RuntimeOrigin::signed(ALICE), 0), Error::<Test>::NotBurnRecipient);
+        assert_ok!(UbiToken::issue_refund(RuntimeOrigin::signed(BOB), 0));
+        System::assert_last_event(
+            Event::<Test>::BurnRefunded { burn_id: 0, asset_id: ASSET, from: ALICE, to: BOB }.into(),
+        );
+
+        let alice = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(alice.burns_sent_count, 1);
+        assert_eq!(alice.burns_sent_volume, 10);
+        assert_eq!(alice.unique_recipients_count, 1);
+        assert!(!UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+
+        let bob = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!(bob.burns_received_count, 0);
+        assert_eq!(bob.burns_received_volume, 0);
+        assert_eq!(bob.weighted_received, 0);
+
+        // Tokens stay destroyed
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 40);
+        assert!(BurnReceipts::<Test>::get(0).unwrap().refunded);
+
+        assert_noop!(UbiToken::issue_refund(RuntimeOrigin::signed(BOB), 0), Error::<Test>::BurnAlreadyRefunded);
+        assert_noop!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0), Error::<Test>::BurnAlreadyRefunded);
+
+        // Refunds are not bound to the rejection window
+        run_to_block(150);
+        assert_ok!(UbiToken::issue_refund(RuntimeOrigin::signed(CHARLIE), 1));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).burns_sent_count, 0);
+    });
+}
+
+#[test]
+fn rejected_burns_cannot_be_refunded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0));
+
+        assert_noop!(UbiToken::issue_refund(RuntimeOrigin::signed(BOB), 0), Error::<Test>::BurnAlreadyRejected);
+        assert_noop!(UbiToken::issue_refund(RuntimeOrigin::signed(BOB), 1), Error::<Test>::UnknownBurn);
+    });
+}
+
+#[test]
+fn both_parties_can_flag_a_burn() {
+    new_test_ext().execute_with(|| {
+        let reason = sp_core::H256::repeat_byte(1);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+
+        assert_ok!(UbiToken::flag_burn(RuntimeOrigin::signed(BOB), 0, reason));
+        System::assert_last_event(
+            Event::<Test>::BurnFlagged { burn_id: 0, asset_id: ASSET, flagged_by: BOB, reason_hash: reason }
+                .into(),
+        );
+
+        run_to_block(5);
+        assert_ok!(UbiToken::flag_burn(RuntimeOrigin::signed(ALICE), 0, reason));
+
+        assert_eq!(
+            BurnDisputes::<Test>::get(0).to_vec(),
+            vec![
+                BurnDispute { flagged_by: BOB, reason_hash: reason, flagged_at: 1 },
+                BurnDispute { flagged_by: ALICE, reason_hash: reason, flagged_at: 5 },
+            ]
+        );
+        // Flagging leaves the receipt untouched
+        assert!(!UbiToken::burn_receipt(0).unwrap().rejected);
+    });
+}
+
+#[test]
+fn flag_burn_checks_caller() {
+    new_test_ext().execute_with(|| {
+        let reason = sp_core::H256::repeat_byte(1);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+
+        assert_noop!(UbiToken::flag_burn(RuntimeOrigin::signed(BOB), 1, reason), Error::<Test>::UnknownBurn);
+        assert_noop!(
+            UbiToken::flag_burn(RuntimeOrigin::signed(CHARLIE), 0, reason),
+            Error::<Test>::NotBurnParty
+        );
+
+        assert_ok!(UbiToken::flag_burn(RuntimeOrigin::signed(BOB), 0, reason));
+        assert_noop!(
+            UbiToken::flag_burn(RuntimeOrigin::signed(BOB), 0, sp_core::H256::repeat_byte(2)),
+            Error::<Test>::AlreadyFlagged
+        );
+    });
+}
+
+#[test]
+fn boosted_burn_stakes_reputation_until_released() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        // Alice's score of 10 is all she can stake
+        assert_noop!(
+            UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 11),
+            Error::<Test>::InsufficientReputation
+        );
+        assert_noop!(
+            UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 0),
+            Error::<Test>::AmountMustBePositive
+        );
+
+        assert_ok!(UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 10));
+        System::assert_last_event(
+            Event::<Test>::BurnBoosted { burn_id: 0, asset_id: ASSET, from: ALICE, to: BOB, stake: 10, unlocks_at: 101 }
+                .into(),
+        );
+        // Bob gets the 0.75x weighted burn, doubled, plus the stake as is
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!((rep.weighted_received, rep.boost_received, rep.score), (15, 10, 15 * 2 + 10));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.boost_stake, 10);
+        assert_eq!(rep.score, 50 + 20 + 10 - 10);
+        assert_eq!(UbiToken::burn_receipt(0).unwrap().weighted_amount, 15);
+
+        assert_noop!(UbiToken::release_boost(RuntimeOrigin::signed(CHARLIE), 0), Error::<Test>::BoostLocked);
+        run_to_block(101);
+        assert_ok!(UbiToken::release_boost(RuntimeOrigin::signed(CHARLIE), 0));
+        System::assert_last_event(
+            Event::<Test>::BoostReleased { burn_id: 0, asset_id: ASSET, from: ALICE, stake: 10 }.into(),
+        );
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).boost_stake, 0);
+        // Bob keeps the plain burn, decayed from 15 to 14
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!((rep.weighted_received, rep.boost_received), (14, 0));
+        assert_eq!(UbiToken::burn_receipt(0).unwrap().weighted_amount, 15);
+        assert_noop!(UbiToken::release_boost(RuntimeOrigin::signed(CHARLIE), 0), Error::<Test>::UnknownBoost);
+    });
+}
+
+#[test]
+fn released_boost_creates_no_reputation() {
+    // Sum of both scores while the burn is boosted and once it is released, boosted or not
+    let scores = |boosted: bool| {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::DecayFactor(1000)));
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+            let total = || UbiToken::reputation_score(&ASSET, &ALICE) + UbiToken::reputation_score(&ASSET, &BOB);
+            if boosted {
+                assert_ok!(UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 10));
+            } else {
+                assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 0, None, None));
+            }
+            // Lent for the lock period: what Alice holds out, Bob counts once
+            let during = total();
+            run_to_block(101);
+            if boosted {
+                assert_ok!(UbiToken::release_boost(RuntimeOrigin::signed(CHARLIE), 0));
+            }
+            (during, total())
+        })
+    };
+    assert_eq!(scores(true), scores(false));
+}
+
+#[test]
+fn boost_ring_cannot_raise_the_combined_score() {
+    // Sum of both scores after burns back and forth, each boosted with all it may stake or not
+    let ring_total = |boosted: bool| {
+        new_test_ext().execute_with(|| {
+            // Constant sender weights, so only the boosts can make a difference
+            assert_ok!(UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::MinSenderWeight(1000)));
+            assert_ok!(UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::MaxSenderWeight(1000)));
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+            let mut nonces = [0u64; 2];
+            for round in 0..4 {
+                let (from, to) = if round % 2 == 0 { (ALICE, BOB) } else { (BOB, ALICE) };
+                if boosted {
+                    let rep = UbiToken::reputation(&ASSET, &from);
+                    let stakeable = rep.score - rep.boost_received;
+                    // Boosts received cannot be staked on
+                    assert_noop!(
+                        UbiToken::boosted_burn(RuntimeOrigin::signed(from), ASSET, to, 1, stakeable + 1),
+                        Error::<Test>::InsufficientReputation
+                    );
+                    assert_ok!(UbiToken::boosted_burn(RuntimeOrigin::signed(from), ASSET, to, 1, stakeable));
+                } else {
+                    let nonce = &mut nonces[(from != ALICE) as usize];
+                    assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, from, to, 1, None, None, *nonce, None, None));
+                    *nonce += 1;
+                }
+            }
+            UbiToken::reputation_score(&ASSET, &ALICE) + UbiToken::reputation_score(&ASSET, &BOB)
+        })
+    };
+    assert_eq!(ring_total(true), ring_total(false));
+}
+
+#[test]
+fn released_boost_leaves_the_decayed_plain_burn() {
+    // Bob's weighted burns received some periods after a burn, boosted and released or not
+    let received_after = |boosted: bool, block: u64| {
+        new_test_ext().execute_with(|| {
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+            if boosted {
+                assert_ok!(UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 10));
+                run_to_block(block);
+                assert_ok!(UbiToken::release_boost(RuntimeOrigin::signed(CHARLIE), 0));
+            } else {
+                assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 0, None, None));
+                run_to_block(block);
+            }
+            let rep = UbiToken::reputation(&ASSET, &BOB);
+            (rep.weighted_received, rep.score)
+        })
+    };
+    assert_eq!(received_after(true, 101).0, 14);
+    for block in [101, 301, 701] {
+        assert_eq!(received_after(true, block), received_after(false, block));
+    }
+}
+
+#[test]
+fn rejected_boosted_burn_takes_the_boost_out() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 10));
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0));
+
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!((rep.weighted_received, rep.boost_received, rep.score), (0, 0, 0));
+        // The stake stays held until it is released
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).boost_stake, 10);
+        run_to_block(101);
+        assert_ok!(UbiToken::release_boost(RuntimeOrigin::signed(CHARLIE), 0));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).boost_stake, 0);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).boost_received, 0);
+    });
+}
+
+#[test]
+fn disputed_boost_can_be_slashed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 10));
+
+        assert_noop!(UbiToken::slash_boost(RuntimeOrigin::signed(BOB), 0), sp_runtime::DispatchError::BadOrigin);
+        assert_noop!(UbiToken::slash_boost(RuntimeOrigin::root(), 0), Error::<Test>::BurnNotDisputed);
+
+        assert_ok!(UbiToken::flag_burn(RuntimeOrigin::signed(BOB), 0, sp_core::H256::repeat_byte(1)));
+        assert_ok!(UbiToken::slash_boost(RuntimeOrigin::root(), 0));
+        System::assert_last_event(
+            Event::<Test>::BoostSlashed { burn_id: 0, asset_id: ASSET, from: ALICE, to: BOB, stake: 10 }.into(),
+        );
+
+        // Bob keeps the plain burn, Alice's stake is lost instead of locked
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!((rep.weighted_received, rep.boost_received), (15, 0));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!((rep.boost_stake, rep.slashed_stake), (0, 10));
+        assert_eq!(rep.score, 50 + 20 + 10 - 10);
+        assert_eq!(UbiToken::burn_receipt(0).unwrap().weighted_amount, 15);
+        assert_noop!(UbiToken::release_boost(RuntimeOrigin::signed(ALICE), 0), Error::<Test>::UnknownBoost);
+    });
+}
+
+#[test]
+fn vouch_moves_reputation_from_voucher_to_target() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_noop!(
+            UbiToken::vouch(RuntimeOrigin::signed(BOB), ASSET, ALICE),
+            Error::<Test>::InsufficientReputation
+        );
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 50, None, None, 0, None, None));
+        assert_eq!(UbiToken::reputation_score(&ASSET, &ALICE), 110);
+
+        assert_ok!(UbiToken::vouch(RuntimeOrigin::signed(ALICE), ASSET, BOB));
+        System::assert_last_event(
+            Event::<Test>::Vouched { asset_id: ASSET, voucher: ALICE, target: BOB, credit: 50 }.into(),
+        );
+        assert_eq!(UbiToken::vouched_at((ASSET, ALICE, BOB)), Some(1));
+        assert_eq!(UbiToken::reputation_score(&ASSET, &ALICE), 90);
+        assert_eq!(UbiToken::reputation_score(&ASSET, &BOB), 50);
+
+        assert_noop!(UbiToken::vouch(RuntimeOrigin::signed(ALICE), ASSET, BOB), Error::<Test>::AlreadyVouched);
+        assert_noop!(
+            UbiToken::vouch(RuntimeOrigin::signed(ALICE), ASSET, ALICE),
+            Error::<Test>::CannotVouchForSelf
+        );
+    });
+}
+
+#[test]
+fn vouches_are_limited_per_period_and_credit_is_capped() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 50, None, None, 0, None, None));
+
+        assert_ok!(UbiToken::vouch(RuntimeOrigin::signed(ALICE), ASSET, BOB));
+        assert_ok!(UbiToken::vouch(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE));
+        assert_noop!(UbiToken::vouch(RuntimeOrigin::signed(ALICE), ASSET, 4), Error::<Test>::TooManyVouches);
+
+        // A target close to the cap only gains the remainder
+        ReputationStore::<Test>::mutate(ASSET, 4, |rep| rep.vouch_credit = 230);
+        run_to_block(101);
+        assert_ok!(UbiToken::vouch(RuntimeOrigin::signed(ALICE), ASSET, 4));
+        System::assert_last_event(
+            Event::<Test>::Vouched { asset_id: ASSET, voucher: ALICE, target: 4, credit: 20 }.into(),
+        );
+        assert_eq!(ReputationStore::<Test>::get(ASSET, 4).vouch_credit, 250);
+    });
+}
+
+#[test]
+fn referral_rewards_both_sides_by_referrer_reputation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 50, None, None, 0, None, None));
+        assert_eq!(UbiToken::reputation_score(&ASSET, &ALICE), 110);
+
+        // A referrer at 1.0x of the 2.0x maximum weight grants half the bonus
+        assert_ok!(UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, BOB, ALICE, 0));
+        System::assert_last_event(
+            Event::<Test>::Referred { asset_id: ASSET, who: BOB, referrer: ALICE, bonus: 20 }.into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 100);
+        assert_eq!(UbiToken::referrer_of(ASSET, BOB), Some(ALICE));
+        assert_eq!(UbiToken::reputation_score(&ASSET, &ALICE), 130);
+        // 10 points for the streak plus the referral bonus
+        assert_eq!(UbiToken::reputation_score(&ASSET, &BOB), 30);
+
+        // Fresh accounts referring each other gain less
+        assert_ok!(UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, 4, BOB, 0));
+        System::assert_last_event(
+            Event::<Test>::Referred { asset_id: ASSET, who: 4, referrer: BOB, bonus: 15 }.into(),
+        );
+    });
+}
+
+#[test]
+fn referrals_are_limited_to_first_claims_and_per_period() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, BOB, ALICE, 0),
+            Error::<Test>::UnknownReferrer
+        );
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_noop!(
+            UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, ALICE, ALICE, 0),
+            Error::<Test>::CannotReferSelf
+        );
+
+        assert_ok!(UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, BOB, ALICE, 0));
+        assert_ok!(UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, CHARLIE, ALICE, 0));
+        assert_noop!(
+            UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, 4, ALICE, 0),
+            Error::<Test>::TooManyReferrals
+        );
+        let call = crate::Call::<Test>::claim_with_referral { asset_id: ASSET, account: 4, referrer: ALICE, nonce: 0 };
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Custom(15).into()
+        );
+
+        // The limit resets with the next period, but accounts that claimed cannot be referred
+        run_to_block(101);
+        assert_noop!(
+            UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, BOB, ALICE, 0),
+            Error::<Test>::AlreadyOnboarded
+        );
+        assert_ok!(UbiToken::claim_with_referral(RuntimeOrigin::none(), ASSET, 4, ALICE, 0));
+    });
+}
+
+#[test]
+fn roles_can_be_attested_and_revoked() {
+    new_test_ext().execute_with(|| {
+        let baker: crate::RoleOf<Test> = b"baker".to_vec().try_into().unwrap();
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 50, None, None, 0, None, None));
+
+        assert_noop!(
+            UbiToken::attest(RuntimeOrigin::signed(BOB), ASSET, ALICE, baker.clone()),
+            Error::<Test>::ReputationTooLow
+        );
+        assert_noop!(
+            UbiToken::attest(RuntimeOrigin::signed(ALICE), ASSET, BOB, b"Baker".to_vec().try_into().unwrap()),
+            Error::<Test>::InvalidRole
+        );
+        assert_noop!(
+            UbiToken::attest(RuntimeOrigin::signed(ALICE), ASSET, ALICE, baker.clone()),
+            Error::<Test>::CannotAttestSelf
+        );
+
+        assert_ok!(UbiToken::attest(RuntimeOrigin::signed(ALICE), ASSET, BOB, baker.clone()));
+        System::assert_last_event(
+            Event::<Test>::Attested { asset_id: ASSET, attester: ALICE, subject: BOB, role: baker.clone() }.into(),
+        );
+        assert_noop!(
+            UbiToken::attest(RuntimeOrigin::signed(ALICE), ASSET, BOB, baker.clone()),
+            Error::<Test>::AlreadyAttested
+        );
+        assert_ok!(UbiToken::attest(RuntimeOrigin::signed(CHARLIE), ASSET, BOB, baker.clone()));
+        assert_eq!(UbiToken::attested_accounts(ASSET, &baker), vec![(BOB, 2)]);
+        assert_eq!(UbiToken::attestation((ASSET, baker.clone(), BOB, CHARLIE)), Some(1));
+
+        assert_ok!(UbiToken::revoke_attestation(RuntimeOrigin::signed(ALICE), ASSET, BOB, baker.clone()));
+        assert_eq!(UbiToken::attestation_count((ASSET, baker.clone(), BOB)), 1);
+        assert_noop!(
+            UbiToken::revoke_attestation(RuntimeOrigin::signed(ALICE), ASSET, BOB, baker.clone()),
+            Error::<Test>::UnknownAttestation
+        );
+        assert_ok!(UbiToken::revoke_attestation(RuntimeOrigin::signed(CHARLIE), ASSET, BOB, baker.clone()));
+        assert!(UbiToken::attested_accounts(ASSET, &baker).is_empty());
+    });
+}
+
+fn members(shares: &[(u64, u32)]) -> crate::OrganizationMembersOf<Test> {
+    shares
+        .iter()
+        .map(|&(who, share)| OrganizationMember { who, share })
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn burns_to_organizations_split_recognition_across_members() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::set_organization(RuntimeOrigin::signed(CHARLIE), ASSET, members(&[(BOB, 1), (4, 2)])));
+        System::assert_last_event(
+            Event::<Test>::OrganizationSet { asset_id: ASSET, organization: CHARLIE, members: 2 }.into(),
+        );
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 40, None, None, 0, None, None));
+
+        // The organization received the burn, its members the 0.75x weighted recognition
+        let rep = ReputationStore::<Test>::get(ASSET, CHARLIE);
+        assert_eq!((rep.burns_received_volume, rep.weighted_received), (40, 0));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).weighted_received, 10);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, 4).weighted_received, 20);
+        assert_eq!(UbiToken::organization_split(0).unwrap().into_inner(), vec![(BOB, 10), (4, 20)]);
+
+        // A rejection takes the members' parts back even after they changed
+        assert_ok!(UbiToken::set_organization(RuntimeOrigin::signed(CHARLIE), ASSET, members(&[(BOB, 1)])));
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(CHARLIE), 0));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).weighted_received, 0);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, 4).weighted_received, 0);
+        assert_eq!(UbiToken::organization_split(0), None);
+
+        assert_noop!(
+            UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE, 10, 1),
+            Error::<Test>::OrganizationRecipient
+        );
+    });
+}
+
+#[test]
+fn organizations_are_validated_and_can_be_removed() {
+    new_test_ext().execute_with(|| {
+        for invalid in [members(&[]), members(&[(BOB, 0)]), members(&[(BOB, 1), (BOB, 2)]), members(&[(CHARLIE, 1)])] {
+            assert_noop!(
+                UbiToken::set_organization(RuntimeOrigin::signed(CHARLIE), ASSET, invalid),
+                Error::<Test>::InvalidOrganization
+            );
+        }
+
+        assert_noop!(
+            UbiToken::remove_organization(RuntimeOrigin::signed(CHARLIE), ASSET),
+            Error::<Test>::NotAnOrganization
+        );
+        assert_ok!(UbiToken::set_organization(RuntimeOrigin::signed(CHARLIE), ASSET, members(&[(BOB, 1)])));
+        assert_ok!(UbiToken::remove_organization(RuntimeOrigin::signed(CHARLIE), ASSET));
+        assert_eq!(UbiToken::organization(ASSET, CHARLIE), None);
+
+        // Plain accounts keep their recognition again
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 40, None, None, 0, None, None));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, CHARLIE).weighted_received, 30);
+    });
+}
+
+#[test]
+fn burns_to_verified_charities_earn_a_sender_bonus() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::verify_charity(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(UbiToken::verify_charity(RuntimeOrigin::root(), ASSET, CHARLIE));
+        System::assert_last_event(Event::<Test>::CharityVerified { asset_id: ASSET, who: CHARLIE }.into());
+        assert_noop!(
+            UbiToken::verify_charity(RuntimeOrigin::root(), ASSET, CHARLIE),
+            Error::<Test>::AlreadyVerifiedCharity
+        );
+
+        // Half of the amount counts again for the sender
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 40, None, None, 0, None, None));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).burns_sent_volume, 60);
+        assert_eq!(UbiToken::burn_receipt(0).unwrap().sender_bonus, 20);
+
+        // A rejection takes the bonus back too
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(CHARLIE), 0));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).burns_sent_volume, 0);
+
+        assert_ok!(UbiToken::revoke_charity(RuntimeOrigin::root(), ASSET, CHARLIE));
+        assert_noop!(
+            UbiToken::revoke_charity(RuntimeOrigin::root(), ASSET, CHARLIE),
+            Error::<Test>::NotVerifiedCharity
+        );
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 10, None, None, 1, None, None));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).burns_sent_volume, 10);
+    });
+}
+
+#[test]
+fn burn_categories_are_aggregated_per_period() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(
+            RuntimeOrigin::none(),
+            ASSET,
+            ALICE,
+            BOB,
+            30,
+            Some(BurnCategory::Food),
+            None,
+            0,
+            None,
+            None,
+        ));
+        assert_ok!(UbiToken::burn(
+            RuntimeOrigin::none(),
+            ASSET,
+            ALICE,
+            CHARLIE,
+            20,
+            Some(BurnCategory::Food),
+            None,
+            1,
+            None,
+            None,
+        ));
+        assert_ok!(UbiToken::burn(
+            RuntimeOrigin::none(),
+            ASSET,
+            ALICE,
+            BOB,
+            10,
+            Some(BurnCategory::Housing),
+            None,
+            2,
+            None,
+            None,
+        ));
+        // Untagged burns are not counted
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 5, None, None, 3, None, None));
+
+        assert_eq!(
+            BurnCategoryStats::<Test>::get((ASSET, 0, BurnCategory::Food)),
+            CategoryStats { count: 2, volume: 50 }
+        );
+        assert_eq!(
+            BurnCategoryStats::<Test>::get((ASSET, 0, BurnCategory::Housing)),
+            CategoryStats { count: 1, volume: 10 }
+        );
+        assert_eq!(
+            BurnCategoryStats::<Test>::get((ASSET, 0, BurnCategory::Other)),
+            CategoryStats::default()
+        );
+
+        // The next claim period starts fresh statistics
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(
+            RuntimeOrigin::none(),
+            ASSET,
+            ALICE,
+            BOB,
+            40,
+            Some(BurnCategory::Food),
+            None,
+            4,
+            None,
+            None,
+        ));
+        assert_eq!(
+            BurnCategoryStats::<Test>::get((ASSET, 1, BurnCategory::Food)),
+            CategoryStats { count: 1, volume: 40 }
+        );
+        assert_eq!(UbiToken::category_stats((ASSET, 0, BurnCategory::Food)).volume, 50);
+    });
+}
+
+#[test]
+fn burn_refunds_weight_of_untouched_batches() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Drawn from the older batch only
+        let info = UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None).unwrap();
+        assert_eq!(info.actual_weight, Some(UbiToken::burn_weight(0, 1)));
+
+        // Drawn from both batches
+        let info = UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 100, None, None, 1, None, None).unwrap();
+        assert_eq!(info.actual_weight, Some(UbiToken::burn_weight(0, 2)));
+
+        // The remaining batch expired before the burn
+        run_to_block(801);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let info = UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 2, None, None).unwrap();
+        assert_eq!(info.actual_weight, Some(UbiToken::burn_weight(0, 1)));
+    });
+}
+
+#[test]
+fn unsigned_burns_are_limited_per_period() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        MaxUnsignedBurnsPerPeriod::set(2);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::register_service(RuntimeOrigin::signed(CHARLIE), ASSET, service_name(b"haircut"), 10));
+
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::pay_service(RuntimeOrigin::none(), ALICE, CHARLIE, 0, 1));
+        assert_eq!(UbiToken::unsigned_burns_left(&ASSET, &ALICE), 0);
+
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 2, None, None),
+            Error::<Test>::TooManyUnsignedBurns
+        );
+        assert_noop!(
+            UbiToken::pay_service(RuntimeOrigin::none(), ALICE, CHARLIE, 0, 2),
+            Error::<Test>::TooManyUnsignedBurns
+        );
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::<Test>::burn {
+                    asset_id: ASSET,
+                    from: ALICE,
+                    to: BOB,
+                    amount: 10,
+                    category: None,
+                    reference: None,
+                    nonce: 2,
+                    valid_until: None,
+                    idempotency_key: None,
+                },
+            ),
+            InvalidTransaction::Custom(9).into(),
+        );
+
+        // Signed burns by an operator are not limited
+        assert_ok!(UbiToken::approve(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE, 20, 50));
+        assert_ok!(UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, BOB, 10, None, None));
+
+        // The limit resets with the next claim period
+        run_to_block(101);
+        assert_eq!(UbiToken::unsigned_burns_left(&ASSET, &ALICE), 2);
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 2, None, None));
+    });
+}
+
+#[test]
+fn burn_nonces_order_and_deduplicate_pending_burns() {
+    use parity_scale_codec::Encode;
+    use frame_support::{
+        pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned},
+        traits::PalletInfoAccess,
+    };
+
+    let burn = |nonce| crate::Call::<Test>::burn {
+        asset_id: ASSET,
+        from: ALICE,
+        to: BOB,
+        amount: 10,
+        category: None,
+        reference: None,
+        nonce,
+        valid_until: None,
+        idempotency_key: None,
+    };
+    let tag = |nonce: u64| ("UbiBurn", <UbiToken as PalletInfoAccess>::name(), ASSET, ALICE, nonce).encode();
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // The next burn provides its nonce and requires nothing
+        let next = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &burn(0)).unwrap();
+        assert_eq!(next.provides, vec![tag(0)]);
+        assert!(next.requires.is_empty());
+
+        // A later one waits for its predecessor
+        let later = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &burn(2)).unwrap();
+        assert_eq!(later.provides, vec![tag(2)]);
+        assert_eq!(later.requires, vec![tag(1)]);
+
+        // Out of order burns fail on chain
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 1, None, None),
+            Error::<Test>::InvalidBurnNonce
+        );
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_eq!(UbiToken::burn_nonce(ASSET, ALICE), 1);
+
+        // A used nonce is stale, on chain and in the pool
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None),
+            Error::<Test>::InvalidBurnNonce
+        );
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &burn(0)),
+            InvalidTransaction::Stale.into(),
+        );
+    });
+}
+
+#[test]
+fn burn_is_rejected_after_its_deadline() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    let burn = |valid_until| crate::Call::<Test>::burn {
+        asset_id: ASSET,
+        from: ALICE,
+        to: BOB,
+        amount: 10,
+        category: None,
+        reference: None,
+        nonce: 0,
+        valid_until,
+        idempotency_key: None,
+    };
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // A burn valid until block 3 leaves the pool after it
+        let valid = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &burn(Some(3))).unwrap();
+        assert_eq!(valid.longevity, 3);
+
+        run_to_block(3);
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &burn(Some(2))),
+            InvalidTransaction::Stale.into()
+        );
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, Some(2), None),
+            Error::<Test>::BurnDeadlinePassed
+        );
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, Some(3), None));
+    });
+}
+
+#[test]
+fn burn_idempotency_keys_reject_retries_within_the_window() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    let order = |id: u8| Some(sp_core::H256::repeat_byte(id));
+    let burn = |nonce, idempotency_key| {
+        UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, nonce, None, idempotency_key)
+    };
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(burn(0, order(1)));
+        assert!(UbiToken::idempotency_key_used(&ASSET, &ALICE, sp_core::H256::repeat_byte(1)));
+
+        // A retry with a fresh nonce is rejected by the pool and the chain
+        let retry = crate::Call::<Test>::burn {
+            asset_id: ASSET,
+            from: ALICE,
+            to: BOB,
+            amount: 10,
+            category: None,
+            reference: None,
+            nonce: 1,
+            valid_until: None,
+            idempotency_key: order(1),
+        };
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &retry),
+            InvalidTransaction::Custom(16).into()
+        );
+        assert_noop!(burn(1, order(1)), Error::<Test>::DuplicateIdempotencyKey);
+
+        assert_ok!(burn(1, order(2)));
+        assert_noop!(burn(2, order(3)), Error::<Test>::TooManyIdempotencyKeys);
+        assert_ok!(burn(2, None));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 70);
+
+        // Keys can be used again once their window has passed
+        run_to_block(101);
+        assert_ok!(burn(3, order(1)));
+        assert_ok!(burn(4, order(3)));
+    });
+}
+
+#[test]
+fn burn_authorized_requires_a_domain_separated_signature() {
+    use parity_scale_codec::Encode;
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+    use sp_runtime::testing::TestSignature;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        let payload = UbiToken::burn_authorization_payload(ASSET, ALICE, BOB, 10, None, None, 0);
+        assert_eq!(payload.context, BURN_AUTHORIZATION_CONTEXT.to_vec());
+        assert_eq!(payload.genesis_hash, frame_system::Pallet::<Test>::block_hash(0));
+        let signed = TestSignature(ALICE, payload.encode());
+
+        // Authorizations for another chain, runtime version or context are
+        // rejected even when properly signed, as are other accounts' signatures
+        let mut other_chain = payload.clone();
+        other_chain.genesis_hash = sp_core::H256::repeat_byte(1);
+        let mut other_version = payload.clone();
+        other_version.spec_version += 1;
+        let mut other_context = payload.clone();
+        other_context.context = b"nst:ubi-token:claim:v1".to_vec();
+        let forged = [
+            (payload.clone(), TestSignature(CHARLIE, payload.encode())),
+            (other_chain.clone(), TestSignature(ALICE, other_chain.encode())),
+            (other_version.clone(), TestSignature(ALICE, other_version.encode())),
+            (other_context.clone(), TestSignature(ALICE, other_context.encode())),
+            (other_chain, signed.clone()),
+        ];
+        for (authorization, signature) in forged {
+            let call = crate::Call::<Test>::burn_authorized {
+                authorization: authorization.clone(),
+                signature: signature.clone(),
+            };
+            assert_eq!(
+                crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &call),
+                InvalidTransaction::BadProof.into(),
+            );
+            assert_noop!(
+                UbiToken::burn_authorized(RuntimeOrigin::none(), authorization, signature),
+                Error::<Test>::InvalidBurnAuthorization
+            );
+        }
+
+        // The sender's signature authorizes exactly one burn
+        assert_ok!(UbiToken::burn_authorized(RuntimeOrigin::none(), payload.clone(), signed.clone()));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 90);
+        assert_eq!(UbiToken::burn_nonce(ASSET, ALICE), 1);
+        assert_noop!(
+            UbiToken::burn_authorized(RuntimeOrigin::none(), payload, signed),
+            Error::<Test>::InvalidBurnNonce
+        );
+    });
+}
+
+// ============================================================================
+// APPROVAL TESTS
+// ============================================================================
+
+#[test]
+fn operator_can_burn_within_approval() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::approve(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE, 60, 50));
+        System::assert_last_event(
+            Event::<Test>::ApprovalSet {
+                asset_id: ASSET,
+                owner: ALICE,
+                operator: CHARLIE,
+                max_amount: 60,
+                expires_at: 50,
+            }
+            .into(),
+        );
+
+        assert_ok!(UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, BOB, 40, None, None));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 60);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).burns_sent_volume, 40);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).burns_received_count, 1);
+        assert_eq!(
+            Approvals::<Test>::get((ASSET, ALICE, CHARLIE)),
+            Some(Approval { remaining: 20, expires_at: 50 })
+        );
+
+        assert_noop!(
+            UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, BOB, 21, None, None),
+            Error::<Test>::AllowanceExceeded
+        );
+
+        // Using up the approval removes it
+        assert_ok!(UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, BOB, 20, None, None));
+        assert!(Approvals::<Test>::get((ASSET, ALICE, CHARLIE)).is_none());
+    });
+}
+
+#[test]
+fn burn_from_requires_valid_approval() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_noop!(
+            UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, BOB, 10, None, None),
+            Error::<Test>::NoApproval
+        );
+
+        assert_ok!(UbiToken::approve(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE, 60, 10));
+        // Burn errors are not swallowed and leave the approval untouched
+        assert_noop!(
+            UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, ALICE, 10, None, None),
+            Error::<Test>::CannotBurnToSelf
+        );
+
+        run_to_block(10);
+        assert_noop!(
+            UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, BOB, 10, None, None),
+            Error::<Test>::ApprovalExpired
+        );
+    });
+}
+
+#[test]
+fn approval_can_be_revoked() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::approve(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE, 60, 50));
+        assert_ok!(UbiToken::revoke_approval(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE));
+        System::assert_last_event(
+            Event::<Test>::ApprovalRevoked { asset_id: ASSET, owner: ALICE, operator: CHARLIE }.into(),
+        );
+
+        assert_noop!(
+            UbiToken::burn_from(RuntimeOrigin::signed(CHARLIE), ASSET, ALICE, BOB, 10, None, None),
+            Error::<Test>::NoApproval
+        );
+        assert_noop!(
+            UbiToken::revoke_approval(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE),
+            Error::<Test>::NoApproval
+        );
+    });
+}
+
+#[test]
+fn approve_validates_input() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::approve(RuntimeOrigin::signed(ALICE), 42, CHARLIE, 60, 50),
+            Error::<Test>::UnknownAsset
+        );
+        assert_noop!(
+            UbiToken::approve(RuntimeOrigin::signed(ALICE), ASSET, ALICE, 60, 50),
+            Error::<Test>::InvalidApproval
+        );
+        assert_noop!(
+            UbiToken::approve(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE, 0, 50),
+            Error::<Test>::InvalidApproval
+        );
+        assert_noop!(
+            UbiToken::approve(RuntimeOrigin::signed(ALICE), ASSET, CHARLIE, 60, 1),
+            Error::<Test>::InvalidApproval
+        );
+    });
+}
+
+// ============================================================================
+// SERVICE REGISTRY TESTS
+// ============================================================================
+
+fn service_name(name: &[u8]) -> frame_support::BoundedVec<u8, frame_support::traits::ConstU32<16>> {
+    name.to_vec().try_into().unwrap()
+}
+
+#[test]
+fn register_and_pay_service() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::register_service(RuntimeOrigin::signed(BOB), ASSET, service_name(b"haircut"), 30));
+        System::assert_last_event(
+            Event::<Test>::ServiceRegistered { service_id: 0, asset_id: ASSET, provider: BOB, price: 30 }.into(),
+        );
+        assert_eq!(Services::<Test>::get(0).unwrap().name.to_vec(), b"haircut".to_vec());
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::pay_service(RuntimeOrigin::none(), ALICE, BOB, 0, 0));
+
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 70);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).burns_received_volume, 30);
+        assert_eq!(UbiToken::burn_receipt(0).unwrap().category, Some(BurnCategory::Services));
+        System::assert_last_event(
+            Event::<Test>::ServicePaid {
+                service_id: 0,
+                burn_id: 0,
+                asset_id: ASSET,
+                payer: ALICE,
+                provider: BOB,
+                price: 30,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn pay_service_checks_provider_and_balance() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::register_service(RuntimeOrigin::signed(BOB), ASSET, service_name(b"haircut"), 30));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        assert_noop!(
+            UbiToken::pay_service(RuntimeOrigin::none(), ALICE, BOB, 1, 0),
+            Error::<Test>::UnknownService
+        );
+        assert_noop!(
+            UbiToken::pay_service(RuntimeOrigin::none(), ALICE, CHARLIE, 0, 0),
+            Error::<Test>::NotServiceProvider
+        );
+        assert_noop!(
+            UbiToken::pay_service(RuntimeOrigin::none(), CHARLIE, BOB, 0, 0),
+            Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn pay_service_cannot_be_replayed() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    let pay = |nonce| crate::Call::<Test>::pay_service { from: ALICE, provider: BOB, service_id: 0, nonce };
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::register_service(RuntimeOrigin::signed(BOB), ASSET, service_name(b"haircut"), 30));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::pay_service(RuntimeOrigin::none(), ALICE, BOB, 0, 0));
+        assert_eq!(UbiToken::burn_nonce(ASSET, ALICE), 1);
+
+        // The same payment is stale in a later block, in the pool and on chain
+        run_to_block(2);
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &pay(0)),
+            InvalidTransaction::Stale.into()
+        );
+        assert_noop!(
+            UbiToken::pay_service(RuntimeOrigin::none(), ALICE, BOB, 0, 0),
+            Error::<Test>::InvalidBurnNonce
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 70);
+
+        // A new payment is ordered after the sender's other burns
+        let next = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &pay(2)).unwrap();
+        assert!(!next.requires.is_empty());
+    });
+}
+
+#[test]
+fn invoices_accept_partial_payments_until_paid() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::create_invoice(RuntimeOrigin::signed(BOB), ASSET, 80, 50));
+        System::assert_last_event(
+            Event::<Test>::InvoiceCreated { invoice_id: 0, asset_id: ASSET, payee: BOB, total: 80, expires_at: 50 }
+                .into(),
+        );
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+
+        assert_ok!(UbiToken::pay_invoice(RuntimeOrigin::none(), ALICE, BOB, 0, 30, 0));
+        System::assert_last_event(
+            Event::<Test>::InvoicePartiallyPaid { invoice_id: 0, burn_id: 0, payer: ALICE, amount: 30, paid: 30 }
+                .into(),
+        );
+        assert_eq!(UbiToken::invoice(0).unwrap().paid, 30);
+
+        assert_noop!(
+            UbiToken::pay_invoice(RuntimeOrigin::none(), CHARLIE, BOB, 0, 60, 0),
+            Error::<Test>::InvoiceOverpaid
+        );
+        assert_noop!(
+            UbiToken::pay_invoice(RuntimeOrigin::none(), CHARLIE, ALICE, 0, 50, 0),
+            Error::<Test>::NotInvoicePayee
+        );
+        assert_ok!(UbiToken::pay_invoice(RuntimeOrigin::none(), CHARLIE, BOB, 0, 50, 0));
+        System::assert_last_event(
+            Event::<Test>::InvoicePaid { invoice_id: 0, burn_id: 1, payer: CHARLIE, amount: 50 }.into(),
+        );
+        assert!(UbiToken::invoice(0).is_none());
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).burns_received_volume, 80);
+
+        assert_noop!(
+            UbiToken::pay_invoice(RuntimeOrigin::none(), ALICE, BOB, 0, 10, 1),
+            Error::<Test>::UnknownInvoice
+        );
+    });
+}
+
+#[test]
+fn invoices_close_when_they_expire() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::create_invoice(RuntimeOrigin::signed(BOB), ASSET, 0, 50),
+            Error::<Test>::InvalidInvoice
+        );
+        assert_noop!(
+            UbiToken::create_invoice(RuntimeOrigin::signed(BOB), ASSET, 80, 1),
+            Error::<Test>::InvalidInvoice
+        );
+        assert_ok!(UbiToken::create_invoice(RuntimeOrigin::signed(BOB), ASSET, 80, 50));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::pay_invoice(RuntimeOrigin::none(), ALICE, BOB, 0, 30, 0));
+
+        run_to_block(50);
+        System::assert_last_event(Event::<Test>::InvoiceExpired { invoice_id: 0, paid: 30 }.into());
+        assert!(UbiToken::invoice(0).is_none());
+        assert_noop!(
+            UbiToken::pay_invoice(RuntimeOrigin::none(), ALICE, BOB, 0, 50, 1),
+            Error::<Test>::UnknownInvoice
+        );
+    });
+}
+
+#[test]
+fn invoice_payments_cannot_be_replayed() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    let pay = |nonce| crate::Call::<Test>::pay_invoice { from: ALICE, payee: BOB, invoice_id: 0, amount: 20, nonce };
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::create_invoice(RuntimeOrigin::signed(BOB), ASSET, 80, 50));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::pay_invoice(RuntimeOrigin::none(), ALICE, BOB, 0, 20, 1));
+
+        // The same payment is stale while the invoice is still open
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &pay(1)),
+            InvalidTransaction::Stale.into()
+        );
+        assert_noop!(
+            UbiToken::pay_invoice(RuntimeOrigin::none(), ALICE, BOB, 0, 20, 1),
+            Error::<Test>::InvalidBurnNonce
+        );
+        assert_eq!(UbiToken::invoice(0).unwrap().paid, 20);
+
+        // Payments are ordered with the sender's other burns
+        let next = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &pay(2)).unwrap();
+        assert!(next.requires.is_empty());
+        let later = crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &pay(3)).unwrap();
+        assert_eq!(later.requires.len(), 1);
+    });
+}
+
+#[test]
+fn payment_intents_are_fulfilled_by_an_exact_burn() {
+    new_test_ext().execute_with(|| {
+        let order = sp_core::H256::repeat_byte(7);
+        assert_noop!(
+            UbiToken::create_payment_intent(RuntimeOrigin::signed(BOB), ASSET, 0, order, 50),
+            Error::<Test>::InvalidPaymentIntent
+        );
+        assert_ok!(UbiToken::create_payment_intent(RuntimeOrigin::signed(BOB), ASSET, 40, order, 50));
+        let (intent_id, intent) = crate::PaymentIntents::<Test>::iter().next().unwrap();
+        System::assert_last_event(
+            Event::<Test>::PaymentIntentCreated {
+                intent_id,
+                asset_id: ASSET,
+                merchant: BOB,
+                amount: 40,
+                metadata_hash: order,
+                expires_at: 50,
+            }
+            .into(),
+        );
+        assert_eq!(intent.fulfilled_by, None);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 30, None, Some(intent_id), 0, None, None),
+            Error::<Test>::PaymentIntentMismatch
+        );
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 40, None, Some(intent_id), 0, None, None),
+            Error::<Test>::PaymentIntentMismatch
+        );
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 40, None, Some(intent_id), 0, None, None));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::<Test>::PaymentIntentFulfilled { intent_id, burn_id: 0, payer: ALICE }.into()));
+        assert_eq!(UbiToken::payment_intent(intent_id).unwrap().fulfilled_by, Some(0));
+
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 40, None, Some(intent_id), 1, None, None),
+            Error::<Test>::PaymentIntentFulfilled
+        );
+
+        // Paid intents are removed at their expiry without an event
+        run_to_block(50);
+        assert!(UbiToken::payment_intent(intent_id).is_none());
+        assert!(!System::events().iter().any(|record| record.event
+            == Event::<Test>::PaymentIntentExpired { intent_id }.into()));
+    });
+}
+
+#[test]
+fn payment_intents_expire_unpaid() {
+    new_test_ext().execute_with(|| {
+        let order = sp_core::H256::repeat_byte(7);
+        assert_noop!(
+            UbiToken::create_payment_intent(RuntimeOrigin::signed(BOB), ASSET, 40, order, 1),
+            Error::<Test>::InvalidPaymentIntent
+        );
+        assert_ok!(UbiToken::create_payment_intent(RuntimeOrigin::signed(BOB), ASSET, 40, order, 50));
+        assert_ok!(UbiToken::create_payment_intent(RuntimeOrigin::signed(BOB), ASSET, 40, order, 50));
+        assert_eq!(crate::PaymentIntents::<Test>::iter().count(), 2);
+        let (intent_id, _) = crate::PaymentIntents::<Test>::iter().next().unwrap();
+
+        run_to_block(50);
+        System::assert_has_event(Event::<Test>::PaymentIntentExpired { intent_id }.into());
+        assert_eq!(crate::PaymentIntents::<Test>::iter().count(), 0);
+
+        // The id of a removed intent is an ordinary reference
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, Some(intent_id), 0, None, None));
+    });
+}
+
+fn ticket_secret(secret: &[u8]) -> frame_support::BoundedVec<u8, frame_support::traits::ConstU32<128>> {
+    secret.to_vec().try_into().unwrap()
+}
+
+#[test]
+fn ticket_burns_are_redeemed_once_with_their_secret() {
+    new_test_ext().execute_with(|| {
+        let secret = ticket_secret(b"concert seat 12");
+        let commitment = sp_core::H256(sp_io::hashing::blake2_256(&secret));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn_ticket(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, commitment, 1));
+        System::assert_last_event(Event::<Test>::TicketIssued { burn_id: 1, commitment }.into());
+        assert_eq!(UbiToken::burn_receipt(1).unwrap().amount, 20);
+
+        assert_noop!(UbiToken::redeem(RuntimeOrigin::signed(BOB), 0, secret.clone()), Error::<Test>::NoTicket);
+        assert_noop!(
+            UbiToken::redeem(RuntimeOrigin::signed(CHARLIE), 1, secret.clone()),
+            Error::<Test>::NotBurnRecipient
+        );
+        assert_noop!(
+            UbiToken::redeem(RuntimeOrigin::signed(BOB), 1, ticket_secret(b"wrong")),
+            Error::<Test>::InvalidTicketSecret
+        );
+        assert_ok!(UbiToken::redeem(RuntimeOrigin::signed(BOB), 1, secret.clone()));
+        System::assert_last_event(Event::<Test>::TicketRedeemed { burn_id: 1, recipient: BOB }.into());
+        assert_eq!(UbiToken::ticket(1).unwrap().redeemed_at, Some(1));
+        assert_noop!(
+            UbiToken::redeem(RuntimeOrigin::signed(BOB), 1, secret),
+            Error::<Test>::TicketAlreadyRedeemed
+        );
+    });
+}
+
+#[test]
+fn refunded_ticket_burns_cannot_be_redeemed() {
+    new_test_ext().execute_with(|| {
+        let secret = ticket_secret(b"voucher");
+        let commitment = sp_core::H256(sp_io::hashing::blake2_256(&secret));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn_ticket(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, commitment, 0));
+        assert_ok!(UbiToken::issue_refund(RuntimeOrigin::signed(BOB), 0));
+
+        assert_noop!(UbiToken::redeem(RuntimeOrigin::signed(BOB), 0, secret), Error::<Test>::BurnAlreadyRefunded);
+    });
+}
+
+#[test]
+fn service_registration_is_validated_and_can_be_removed() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::register_service(RuntimeOrigin::signed(BOB), 42, service_name(b"haircut"), 30),
+            Error::<Test>::UnknownAsset
+        );
+        assert_noop!(
+            UbiToken::register_service(RuntimeOrigin::signed(BOB), ASSET, service_name(b""), 30),
+            Error::<Test>::InvalidService
+        );
+        assert_noop!(
+            UbiToken::register_service(RuntimeOrigin::signed(BOB), ASSET, service_name(b"haircut"), 0),
+            Error::<Test>::InvalidService
+        );
+
+        assert_ok!(UbiToken::register_service(RuntimeOrigin::signed(BOB), ASSET, service_name(b"haircut"), 30));
+        assert_noop!(
+            UbiToken::unregister_service(RuntimeOrigin::signed(ALICE), 0),
+            Error::<Test>::NotServiceProvider
+        );
+        assert_ok!(UbiToken::unregister_service(RuntimeOrigin::signed(BOB), 0));
+        assert!(Services::<Test>::get(0).is_none());
+        System::assert_last_event(Event::<Test>::ServiceUnregistered { service_id: 0 }.into());
+
+        assert_noop!(
+            UbiToken::unregister_service(RuntimeOrigin::signed(BOB), 0),
+            Error::<Test>::UnknownService
+        );
+    });
+}
+
+// ============================================================================
+// EXPIRATION TESTS
+// ============================================================================
+
+#[test]
+fn tokens_expire_after_expiration_period() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+
+        // Advance past expiration (700 blocks)
+        run_to_block(702);
+
+        // Balance should now show 0 (expired)
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 0);
+    });
+}
+
+#[test]
+fn spendable_balance_at_takes_the_given_block_as_now() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        assert_eq!(UbiToken::spendable_balance_at(&ASSET, &ALICE, 101), 200);
+        // First batch expires at 701, the second one at 801
+        assert_eq!(UbiToken::spendable_balance_at(&ASSET, &ALICE, 701), 100);
+        assert_eq!(UbiToken::spendable_balance_at(&ASSET, &ALICE, 801), 0);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 200);
+    });
+}
+
+#[test]
+fn token_batches_lists_unexpired_batches_by_expiry() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+        assert_ok!(UbiToken::claim_short_expiry(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // The short-expiry batch expires first and is listed first
+        assert_eq!(
+            UbiToken::token_batches(&ASSET, &ALICE),
+            vec![
+                TokenBatch { amount: 110, expires_at: 101 + 280 },
+                TokenBatch { amount: 100, expires_at: 701 },
+            ]
+        );
+
+        run_to_block(381);
+        assert_eq!(UbiToken::token_batches(&ASSET, &ALICE), vec![TokenBatch { amount: 100, expires_at: 701 }]);
+    });
+}
+
+#[test]
+fn unique_recipients_are_paginated() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        for (nonce, to) in [(0, BOB), (1, CHARLIE), (2, 4), (3, BOB)] {
+            assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, to, 10, None, None, nonce, None, None));
+        }
+
+        let mut all = UbiToken::unique_recipients(&ASSET, &ALICE, None, 10);
+        assert_eq!(all.len(), 3);
+
+        // Pages continue after the last recipient of the previous one
+        let first = UbiToken::unique_recipients(&ASSET, &ALICE, None, 2);
+        let second = UbiToken::unique_recipients(&ASSET, &ALICE, first.last().copied(), 2);
+        assert_eq!(first.len(), 2);
+        assert_eq!([first, second].concat(), all);
+
+        all.sort();
+        assert_eq!(all, vec![BOB, CHARLIE, 4]);
+        assert!(UbiToken::unique_recipients(&ASSET, &BOB, None, 10).is_empty());
+    });
+}
+
+#[test]
+fn expired_tokens_cleaned_up_on_claim() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Advance past expiration (700 blocks)
+        run_to_block(702);
+
+        // Claim again - this should clean up expired tokens and claim backlog
+        // After 702 blocks (7 periods), can claim max backlog of 3 periods = 300 tokens
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Should have 300 (3 periods backlog, max)
+        // Original 100 expired, new 300 from backlog claim
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 300);
+
+        // Check expired event was emitted
+        let events = System::events();
+        let expired_event = events.iter().find(|e| {
+            matches!(
+                e.event,
+                RuntimeEvent::UbiToken(Event::Expired { asset_id: ASSET, who: ALICE, amount: 100, .. })
+            )
+        });
+        assert!(expired_event.is_some());
+    });
+}
+
+#[test]
+fn expired_tokens_cleaned_up_on_burn() {
+    new_test_ext().execute_with(|| {
+        // Alice claims twice
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Advance so first batch expires (700 blocks from block 1 = 701)
+        // but second batch hasn't (700 blocks from block 101 = 801)
+        run_to_block(702);
+
+        // Alice tries to burn - should clean up expired batch first
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+
+        // Should have 50 left from second batch
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 50);
+    });
+}
+
+#[test]
+fn cannot_burn_expired_tokens() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Advance past expiration
+        run_to_block(702);
+
+        // Try to burn - should fail (tokens expired)
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None),
+            Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+/// Pre-expiry warnings emitted so far for an account
+fn expiry_warnings(who: u64) -> Vec<(u128, u64)> {
+    System::events()
+        .into_iter()
+        .filter_map(|e| match e.event {
+            RuntimeEvent::UbiToken(Event::ExpiringSoon { who: w, amount, expires_at, .. }) if w == who => {
+                Some((amount, expires_at))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn expiring_soon_emitted_one_period_before_expiry() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Batch expires at 701, the warning is due one period (100 blocks) earlier
+        run_to_block(600);
+        assert!(expiry_warnings(ALICE).is_empty());
+
+        run_to_block(601);
+        assert_eq!(expiry_warnings(ALICE), vec![(100, 701)]);
+        assert!(ExpiryWarnings::<Test>::get(601).is_empty());
+    });
+}
+
+#[test]
+fn expiring_soon_reports_only_unspent_tokens() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 30, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, BOB, CHARLIE, 100, None, None, 0, None, None));
+
+        run_to_block(601);
+
+        assert_eq!(expiry_warnings(ALICE), vec![(70, 701)]);
+        // Fully spent batches are not worth a warning
+        assert!(expiry_warnings(BOB).is_empty());
+    });
+}
+
+#[test]
+fn tidy_purges_expired_batches() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(701);
+
+        assert_eq!(UbiToken::total_balance(&ASSET, &ALICE), 100);
+        assert_ok!(UbiToken::tidy(RuntimeOrigin::none(), ASSET, ALICE));
+
+        assert_eq!(UbiToken::total_balance(&ASSET, &ALICE), 0);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 0);
+        System::assert_last_event(
+            Event::<Test>::Expired { asset_id: ASSET, who: ALICE, amount: 100, period: 7, remaining: 0 }.into(),
+        );
+    });
+}
+
+// ============================================================================
+// COMMUNITY POOL TESTS
+// ============================================================================
+
+fn pool_grants(
+    grants: Vec<(u64, PoolGrant<u128>)>,
+) -> frame_support::BoundedVec<(u64, PoolGrant<u128>), frame_support::traits::ConstU32<4>> {
+    grants.try_into().unwrap()
+}
+
+#[test]
+fn expired_tokens_feed_the_community_pool() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, BOB, CHARLIE, 40, None, None, 0, None, None));
+        run_to_block(701);
+
+        // Burned tokens are gone for good, only expired ones enter the pool
+        assert_ok!(UbiToken::tidy(RuntimeOrigin::none(), ASSET, ALICE));
+        assert_ok!(UbiToken::tidy(RuntimeOrigin::none(), ASSET, BOB));
+        assert_eq!(CommunityPool::<Test>::get(ASSET), 160);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 0);
+    });
+}
+
+#[test]
+fn allocate_community_pool_pays_matching_bonuses() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        run_to_block(701);
+        assert_ok!(UbiToken::tidy(RuntimeOrigin::none(), ASSET, BOB));
+        assert_eq!(UbiToken::community_pool(ASSET), 100);
+
+        let campaign = sp_core::H256::repeat_byte(7);
+        assert_ok!(UbiToken::allocate_community_pool(
+            RuntimeOrigin::root(),
+            ASSET,
+            campaign,
+            pool_grants(vec![(ALICE, PoolGrant::Tokens(50)), (CHARLIE, PoolGrant::Reputation(30))]),
+        ));
+
+        // ALICE's own batch expired when the grant was credited; the pool got it too
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 50);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 50);
+        assert_eq!(CommunityPool::<Test>::get(ASSET), 120);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, CHARLIE).weighted_received, 30);
+        assert_eq!(UbiToken::reputation_score(&ASSET, &CHARLIE), 60);
+        System::assert_last_event(
+            Event::<Test>::CommunityPoolGranted {
+                asset_id: ASSET,
+                campaign,
+                who: CHARLIE,
+                grant: PoolGrant::Reputation(30),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn allocate_community_pool_is_limited_to_the_pool() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(701);
+        assert_ok!(UbiToken::tidy(RuntimeOrigin::none(), ASSET, ALICE));
+        let campaign = sp_core::H256::zero();
+
+        assert_noop!(
+            UbiToken::allocate_community_pool(
+                RuntimeOrigin::signed(ALICE),
+                ASSET,
+                campaign,
+                pool_grants(vec![(ALICE, PoolGrant::Tokens(10))]),
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            UbiToken::allocate_community_pool(
+                RuntimeOrigin::root(),
+                ASSET,
+                campaign,
+                pool_grants(vec![(ALICE, PoolGrant::Tokens(60)), (BOB, PoolGrant::Reputation(41))]),
+            ),
+            Error::<Test>::InsufficientCommunityPool
+        );
+        assert_noop!(
+            UbiToken::allocate_community_pool(
+                RuntimeOrigin::root(),
+                ASSET,
+                campaign,
+                pool_grants(vec![(ALICE, PoolGrant::Reputation(0))]),
+            ),
+            Error::<Test>::AmountMustBePositive
+        );
+        assert_noop!(
+            UbiToken::allocate_community_pool(
+                RuntimeOrigin::root(),
+                ASSET,
+                campaign,
+                pool_grants(vec![(BOB, PoolGrant::Tokens(10))]),
+            ),
+            Error::<Test>::NotAClaimant
+        );
+    });
+}
+
+#[test]
+fn tidy_is_a_no_op_without_expired_tokens() {
+    use frame_support::pallet_prelude::{TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let events = System::events().len();
+
+        assert_ok!(UbiToken::tidy(RuntimeOrigin::none(), ASSET, ALICE));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(System::events().len(), events);
+
+        // ...so it is not accepted as a free transaction
+        assert!(crate::Pallet::<Test>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test>::tidy { asset_id: ASSET, account: ALICE },
+        )
+        .is_err());
+        run_to_block(701);
+        assert!(crate::Pallet::<Test>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test>::tidy { asset_id: ASSET, account: ALICE },
+        )
+        .is_ok());
+
+        assert_noop!(UbiToken::tidy(RuntimeOrigin::none(), 42, ALICE), Error::<Test>::UnknownAsset);
+    });
+}
+
+// ============================================================================
+// COMMITMENT TESTS
+// ============================================================================
+
+#[test]
+fn committed_tokens_are_locked_and_expire_later() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), ASSET, 60, 3));
+
+        // Locked for 3 periods, expiry moved from 701 to 1001
+        assert_eq!(
+            Commitments::<Test>::get(ASSET, ALICE).to_vec(),
+            vec![Commitment { amount: 60, unlocks_at: 301, expires_at: 1001 }]
+        );
+        System::assert_last_event(
+            Event::<Test>::TokensCommitted {
+                asset_id: ASSET,
+                who: ALICE,
+                amount: 60,
+                unlocks_at: 301,
+                expires_at: 1001,
+            }
+            .into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 40);
+        assert_eq!(UbiToken::locked_balance(&ASSET, &ALICE), 60);
+        assert_eq!(UbiToken::total_balance(&ASSET, &ALICE), 100);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 100);
+
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 41, None, None, 0, None, None),
+            Error::<Test>::InsufficientBalance
+        );
+
+        // Unlocked, and still spendable after the original expiry
+        run_to_block(800);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 60);
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+        assert!(Commitments::<Test>::get(ASSET, ALICE).is_empty());
+        System::assert_has_event(
+            Event::<Test>::CommitmentReleased { asset_id: ASSET, who: ALICE, amount: 60, expires_at: 1001 }
+                .into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 10);
+
+        run_to_block(1001);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 0);
+    });
+}
+
+#[test]
+fn commitment_costs_reputation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let before = ReputationStore::<Test>::get(ASSET, ALICE).score;
+
+        assert_ok!(UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), ASSET, 10, 2));
+
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.commitment_cost, 10);
+        assert_eq!(rep.score, before - 10);
+    });
+}
+
+#[test]
+fn extra_backlog_is_paid_with_reputation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        ReputationStore::<Test>::mutate(ASSET, ALICE, |rep| rep.unique_recipients_count = 4);
+
+        // Nine missed periods: three backlog plus four extra at 20 points each
+        run_to_block(901);
+        assert_ok!(UbiToken::claim_extra_backlog(RuntimeOrigin::signed(ALICE), ASSET, 4));
+        System::assert_has_event(
+            Event::<Test>::ExtraBacklogClaimed { asset_id: ASSET, who: ALICE, periods: 4, cost: 80 }.into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 700);
+
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.backlog_cost, 80);
+        // Four unique recipients and a restarted streak, minus the cost
+        assert_eq!(rep.score, 200 + 10 - 80);
+
+        // Only the periods actually missed beyond the backlog are paid
+        run_to_block(1401);
+        assert_ok!(UbiToken::claim_extra_backlog(RuntimeOrigin::signed(ALICE), ASSET, 4));
+        System::assert_has_event(
+            Event::<Test>::ExtraBacklogClaimed { asset_id: ASSET, who: ALICE, periods: 2, cost: 40 }.into(),
+        );
+    });
+}
+
+#[test]
+fn extra_backlog_needs_enough_reputation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        run_to_block(901);
+
+        assert_noop!(
+            UbiToken::claim_extra_backlog(RuntimeOrigin::signed(BOB), ASSET, 0),
+            Error::<Test>::InvalidExtraBacklog
+        );
+        assert_noop!(
+            UbiToken::claim_extra_backlog(RuntimeOrigin::signed(BOB), ASSET, 5),
+            Error::<Test>::InvalidExtraBacklog
+        );
+        assert_noop!(
+            UbiToken::claim_extra_backlog(RuntimeOrigin::signed(BOB), ASSET, 1),
+            Error::<Test>::InsufficientReputation
+        );
+
+        // The regular backlog stays free
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 300);
+    });
+}
+
+#[test]
+fn commit_tokens_validates_input() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        assert_noop!(
+            UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), ASSET, 0, 2),
+            Error::<Test>::InvalidCommitment
+        );
+        assert_noop!(
+            UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), ASSET, 10, 0),
+            Error::<Test>::InvalidCommitment
+        );
+        assert_noop!(
+            UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), ASSET, 10, 8),
+            Error::<Test>::InvalidCommitment
+        );
+        assert_noop!(
+            UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), ASSET, 101, 2),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(
+            UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), 42, 10, 2),
+            Error::<Test>::UnknownAsset
+        );
+    });
+}
+
+// ============================================================================
+// CLEANUP BOUNTY TESTS
+// ============================================================================
+
+fn sweep_list(accounts: Vec<u64>) -> frame_support::BoundedVec<u64, frame_support::traits::ConstU32<4>> {
+    accounts.try_into().unwrap()
+}
+
+#[test]
+fn sweep_expired_purges_batches_and_credits_sweeper() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 200);
+
+        run_to_block(702);
+        assert_ok!(UbiToken::sweep_expired(
+            RuntimeOrigin::signed(CHARLIE),
+            ASSET,
+            sweep_list(vec![ALICE, BOB, CHARLIE])
+        ));
+
+        assert!(Balances::<Test>::get(ASSET, ALICE).is_empty());
+        assert!(Balances::<Test>::get(ASSET, BOB).is_empty());
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 0);
+
+        // Only the two accounts with expired tokens count
+        let rep = ReputationStore::<Test>::get(ASSET, CHARLIE);
+        assert_eq!(rep.maintenance_credit, 2);
+        assert_eq!(rep.score, 2);
+        System::assert_last_event(
+            Event::<Test>::ExpiredSwept { asset_id: ASSET, sweeper: CHARLIE, accounts: 2, amount: 200 }.into(),
+        );
+    });
+}
+
+#[test]
+fn expirations_are_summarized_once_per_block() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+        run_to_block(702);
+        assert_ok!(UbiToken::sweep_expired(RuntimeOrigin::signed(CHARLIE), ASSET, sweep_list(vec![ALICE])));
+        assert_ok!(UbiToken::sweep_expired(RuntimeOrigin::signed(CHARLIE), ASSET, sweep_list(vec![BOB])));
+        assert_eq!(BlockExpirations::<Test>::get(ASSET), (2, 200));
+
+        UbiToken::on_finalize(702);
+        System::assert_last_event(Event::<Test>::ExpiredBatch { asset_id: ASSET, count: 2, total: 200 }.into());
+        assert_eq!(BlockExpirations::<Test>::get(ASSET), (0, 0));
+    });
+}
+
+#[test]
+fn sweep_expired_requires_expired_tokens() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Alice's batch is still valid
+        assert_noop!(
+            UbiToken::sweep_expired(RuntimeOrigin::signed(CHARLIE), ASSET, sweep_list(vec![ALICE, BOB])),
+            Error::<Test>::NothingToSweep
+        );
+        assert_noop!(
+            UbiToken::sweep_expired(RuntimeOrigin::signed(CHARLIE), 7, sweep_list(vec![ALICE])),
+            Error::<Test>::UnknownAsset
+        );
+    });
+}
+
+#[test]
+fn sweep_credit_is_capped() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        ReputationStore::<Test>::mutate(ASSET, CHARLIE, |rep| rep.maintenance_credit = 99);
+
+        run_to_block(702);
+        assert_ok!(UbiToken::sweep_expired(
+            RuntimeOrigin::signed(CHARLIE),
+            ASSET,
+            sweep_list(vec![ALICE, BOB])
+        ));
+
+        assert_eq!(ReputationStore::<Test>::get(ASSET, CHARLIE).maintenance_credit, 100);
+    });
+}
+
+#[test]
+fn expiry_index_sweeps_untouched_accounts() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    let sweep = |accounts| crate::Call::<Test>::sweep_expiry_index {
+        asset_id: ASSET,
+        period: 7,
+        accounts: sweep_list(accounts),
+    };
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+        // Batches claimed at block 1 expire at 701, in period 7
+        assert!(ExpiryIndex::<Test>::contains_key((ASSET, 7, ALICE)));
+        assert!(ExpiryIndex::<Test>::contains_key((ASSET, 7, BOB)));
+
+        // The period has not passed yet
+        run_to_block(702);
+        assert_noop!(
+            UbiToken::sweep_expiry_index(RuntimeOrigin::none(), ASSET, 7, sweep_list(vec![ALICE])),
+            Error::<Test>::InvalidExpirySweep
+        );
+
+        run_to_block(800);
+        // Accounts not indexed for the period cannot be swept
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::Local, &sweep(vec![ALICE, CHARLIE])),
+            InvalidTransaction::Custom(14).into(),
+        );
+        assert_ok!(crate::Pallet::<Test>::validate_unsigned(TransactionSource::Local, &sweep(vec![ALICE, BOB])));
+
+        assert_ok!(UbiToken::sweep_expiry_index(RuntimeOrigin::none(), ASSET, 7, sweep_list(vec![ALICE, BOB])));
+        assert!(Balances::<Test>::get(ASSET, ALICE).is_empty());
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 0);
+        assert!(!ExpiryIndex::<Test>::contains_key((ASSET, 7, ALICE)));
+
+        // A swept period cannot be submitted again
+        assert_noop!(
+            UbiToken::sweep_expiry_index(RuntimeOrigin::none(), ASSET, 7, sweep_list(vec![BOB])),
+            Error::<Test>::InvalidExpirySweep
+        );
+    });
+}
+
+#[test]
+fn on_poll_expires_indexed_batches_within_its_weight() {
+    use frame_support::weights::WeightMeter;
+
+    new_test_ext().execute_with(|| {
+        for who in [ALICE, BOB, CHARLIE] {
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, who, 0));
+        }
+
+        // Period 7 has not passed yet
+        run_to_block(702);
+        let mut meter = WeightMeter::new();
+        UbiToken::on_poll(702, &mut meter);
+        assert_eq!(ExpiryIndex::<Test>::iter_prefix((ASSET, 7)).count(), 3);
+        assert_eq!(ExpiryPollCursor::<Test>::get(ASSET), Some(7));
+
+        // `ExpiryPollWeight` covers two accounts per block
+        run_to_block(800);
+        UbiToken::on_poll(800, &mut meter);
+        assert!(meter.consumed().all_lte(ExpiryPollWeight::get()));
+        assert_eq!(ExpiryIndex::<Test>::iter_prefix((ASSET, 7)).count(), 1);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 100);
+        assert_eq!(ExpiryPollCursor::<Test>::get(ASSET), Some(7));
+
+        run_to_block(801);
+        UbiToken::on_poll(801, &mut WeightMeter::new());
+        assert_eq!(ExpiryIndex::<Test>::iter_prefix((ASSET, 7)).count(), 0);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 0);
+        assert_eq!(ExpiryPollCursor::<Test>::get(ASSET), Some(8));
+        assert!(Balances::<Test>::get(ASSET, CHARLIE).is_empty());
+    });
+}
+
+#[test]
+fn on_poll_takes_the_assets_in_turns() {
+    use frame_support::weights::WeightMeter;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::create_asset(RuntimeOrigin::signed(BOB), NEIGHBORHOOD, UbiToken::default_parameters()));
+        for asset_id in [ASSET, NEIGHBORHOOD] {
+            for who in [ALICE, BOB, CHARLIE] {
+                assert_ok!(UbiToken::claim(RuntimeOrigin::none(), asset_id, who, 0));
+            }
+        }
+        let order: Vec<u32> = crate::Assets::<Test>::iter_keys().collect();
+        let left = |asset_id: u32| ExpiryIndex::<Test>::iter_prefix((asset_id, 7)).count();
+
+        // `ExpiryPollWeight` covers two accounts per block, all spent on the first asset
+        run_to_block(800);
+        UbiToken::on_poll(800, &mut WeightMeter::new());
+        assert_eq!((left(order[0]), left(order[1])), (1, 3));
+
+        // The next block starts with the other asset instead of finishing the first
+        run_to_block(801);
+        UbiToken::on_poll(801, &mut WeightMeter::new());
+        assert_eq!((left(order[0]), left(order[1])), (1, 1));
+
+        run_to_block(802);
+        UbiToken::on_poll(802, &mut WeightMeter::new());
+        assert_eq!((left(order[0]), left(order[1])), (0, 0));
+    });
+}
+
+// ============================================================================
+// DORMANT PRUNING TESTS
+// ============================================================================
+
+#[test]
+fn prune_dormant_removes_inactive_low_score_accounts() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+
+        // Five idle periods later, Charlie's tokens have expired as well
+        run_to_block(702);
+        assert_ok!(UbiToken::prune_dormant(
+            RuntimeOrigin::signed(BOB),
+            ASSET,
+            sweep_list(vec![ALICE, CHARLIE])
+        ));
+
+        assert!(!ReputationStore::<Test>::contains_key(ASSET, CHARLIE));
+        // Alice's score is far above the threshold
+        assert!(ReputationStore::<Test>::contains_key(ASSET, ALICE));
+        System::assert_last_event(Event::<Test>::DormantPruned { asset_id: ASSET, who: CHARLIE }.into());
+    });
+}
+
+#[test]
+fn prune_dormant_clears_unique_recipients() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 1, None, None, 0, None, None));
+        // Strip the bonuses, leaving only the decaying burn volume
+        ReputationStore::<Test>::mutate(ASSET, ALICE, |rep| {
+            rep.unique_recipients_count = 0;
+            rep.claim_streak = 0;
+        });
+
+        run_to_block(702);
+        assert_ok!(UbiToken::prune_dormant(RuntimeOrigin::signed(BOB), ASSET, sweep_list(vec![ALICE])));
+
+        assert!(!UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+    });
+}
+
+#[test]
+fn claims_keep_accounts_without_native_balance_alive() {
+    new_test_ext().execute_with(|| {
+        assert!(!System::account_exists(&CHARLIE));
+
+        // The first claim creates the account, later ones do not add references
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        assert_eq!(System::providers(&CHARLIE), 1);
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        assert_eq!(System::providers(&CHARLIE), 1);
+
+        // Each community holds its own reference
+        assert_ok!(CityToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        assert_eq!(System::providers(&CHARLIE), 2);
+
+        // Pruning releases the reference together with the claim anchor
+        run_to_block(801);
+        assert_ok!(UbiToken::prune_dormant(RuntimeOrigin::signed(BOB), ASSET, sweep_list(vec![CHARLIE])));
+        assert_eq!(UbiToken::last_claim(ASSET, CHARLIE), None);
+        assert_eq!(System::providers(&CHARLIE), 1);
+    });
+}
+
+#[test]
+fn reaped_accounts_lose_their_tokens_and_claim_state() {
+    new_test_ext().execute_with(|| {
+        // Charlie also holds a native balance, modelled as a second provider
+        System::inc_providers(&CHARLIE);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        assert_ok!(UbiToken::commit_tokens(RuntimeOrigin::signed(CHARLIE), ASSET, 30, 2));
+        assert_ok!(UbiToken::approve(RuntimeOrigin::signed(CHARLIE), ASSET, BOB, 20, 50));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, CHARLIE, BOB, 10, None, None, 0, None, None));
+        assert_eq!(UbiToken::total_supply(ASSET), 90);
+
+        // The UBI reference goes (e.g. through pruning), then the native one
+        assert_ok!(System::dec_providers(&CHARLIE));
+        assert_ok!(System::dec_providers(&CHARLIE));
+        assert!(!System::account_exists(&CHARLIE));
+
+        System::assert_has_event(
+            Event::<Test>::AccountReaped { asset_id: ASSET, who: CHARLIE, amount: 90 }.into(),
+        );
+        assert_eq!(UbiToken::total_supply(ASSET), 0);
+        assert!(UbiToken::token_batches(&ASSET, &CHARLIE).is_empty());
+        assert!(Commitments::<Test>::get(ASSET, CHARLIE).is_empty());
+        assert_eq!(UbiToken::last_claim(ASSET, CHARLIE), None);
+        assert!(Approvals::<Test>::get((ASSET, CHARLIE, BOB)).is_none());
+        // Burn history stays, and so does the nonce
+        assert_eq!(ReputationStore::<Test>::get(ASSET, CHARLIE).burns_sent_count, 1);
+        assert_eq!(UbiToken::burn_nonce(ASSET, CHARLIE), 1);
+    });
+}
+
+#[test]
+fn active_or_funded_accounts_are_not_dormant() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+
+        // Idle long enough, but the claimed tokens are still spendable
+        run_to_block(600);
+        assert_noop!(
+            UbiToken::prune_dormant(RuntimeOrigin::signed(BOB), ASSET, sweep_list(vec![CHARLIE])),
+            Error::<Test>::NothingToPrune
+        );
+
+        // Receiving a burn counts as activity
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 1, None, None, 0, None, None));
+        ReputationStore::<Test>::mutate(ASSET, CHARLIE, |rep| rep.claim_streak = 0);
+        run_to_block(702);
+        assert_noop!(
+            UbiToken::prune_dormant(RuntimeOrigin::signed(BOB), ASSET, sweep_list(vec![CHARLIE])),
+            Error::<Test>::NothingToPrune
+        );
+    });
+}
+
+#[test]
+fn on_idle_prunes_dormant_accounts() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        run_to_block(702);
+
+        UbiToken::on_idle(702, Weight::MAX);
+        assert!(!ReputationStore::<Test>::contains_key(ASSET, BOB));
+        assert!(!ReputationStore::<Test>::contains_key(ASSET, CHARLIE));
+        assert_eq!(PruneCursor::<Test>::get(), None);
+    });
+}
+
+// ============================================================================
+// BONUS PERIOD TESTS
+// ============================================================================
+
+fn bonus(start: u64, end: u64, claim_multiplier: u32, double_streak: bool) -> BonusPeriod<u64> {
+    BonusPeriod { start, end, claim_multiplier, double_streak }
+}
+
+#[test]
+fn bonus_period_multiplies_claims() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::declare_bonus_period(
+            RuntimeOrigin::root(),
+            ASSET,
+            bonus(10, 110, 200, false)
+        ));
+        System::assert_last_event(
+            Event::<Test>::BonusPeriodDeclared { asset_id: ASSET, period: bonus(10, 110, 200, false) }
+                .into(),
+        );
+
+        // Not active yet
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 100);
+
+        run_to_block(10);
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 200);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 200);
+
+        // The bonus ended before the next period
+        run_to_block(110);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 300);
+    });
+}
+
+#[test]
+fn bonus_period_can_double_streak() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::declare_bonus_period(
+            RuntimeOrigin::root(),
+            ASSET,
+            bonus(2, 102, 100, true)
+        ));
+        run_to_block(2);
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).claim_streak, 2);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+    });
+}
+
+#[test]
+fn declare_bonus_period_validates_input() {
+    new_test_ext().execute_with(|| {
+        run_to_block(5);
+
+        assert_noop!(
+            UbiToken::declare_bonus_period(RuntimeOrigin::signed(ALICE), ASSET, bonus(10, 20, 200, false)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        // Starts in the past
+        assert_noop!(
+            UbiToken::declare_bonus_period(RuntimeOrigin::root(), ASSET, bonus(5, 20, 200, false)),
+            Error::<Test>::InvalidBonusPeriod
+        );
+        // Empty range
+        assert_noop!(
+            UbiToken::declare_bonus_period(RuntimeOrigin::root(), ASSET, bonus(20, 20, 200, false)),
+            Error::<Test>::InvalidBonusPeriod
+        );
+        // Multiplier out of range
+        assert_noop!(
+            UbiToken::declare_bonus_period(RuntimeOrigin::root(), ASSET, bonus(10, 20, 99, false)),
+            Error::<Test>::InvalidBonusPeriod
+        );
+        assert_noop!(
+            UbiToken::declare_bonus_period(RuntimeOrigin::root(), ASSET, bonus(10, 20, 501, false)),
+            Error::<Test>::InvalidBonusPeriod
+        );
+        assert_noop!(
+            UbiToken::declare_bonus_period(RuntimeOrigin::root(), 42, bonus(10, 20, 200, false)),
+            Error::<Test>::UnknownAsset
+        );
+
+        assert_ok!(UbiToken::declare_bonus_period(
+            RuntimeOrigin::root(),
+            ASSET,
+            bonus(30, 40, 200, false)
+        ));
+        assert_noop!(
+            UbiToken::declare_bonus_period(RuntimeOrigin::root(), ASSET, bonus(35, 50, 200, false)),
+            Error::<Test>::OverlappingBonusPeriod
+        );
+
+        // Periods stay sorted by start
+        assert_ok!(UbiToken::declare_bonus_period(
+            RuntimeOrigin::root(),
+            ASSET,
+            bonus(10, 30, 150, false)
+        ));
+        let starts: Vec<u64> = BonusPeriods::<Test>::get(ASSET).iter().map(|p| p.start).collect();
+        assert_eq!(starts, vec![10, 30]);
+    });
+}
+
+#[test]
+fn cancel_bonus_period_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::declare_bonus_period(
+            RuntimeOrigin::root(),
+            ASSET,
+            bonus(10, 20, 200, false)
+        ));
+        assert_noop!(
+            UbiToken::cancel_bonus_period(RuntimeOrigin::root(), ASSET, 11),
+            Error::<Test>::UnknownBonusPeriod
+        );
+
+        assert_ok!(UbiToken::cancel_bonus_period(RuntimeOrigin::root(), ASSET, 10));
+        assert!(BonusPeriods::<Test>::get(ASSET).is_empty());
+        System::assert_last_event(
+            Event::<Test>::BonusPeriodCancelled { asset_id: ASSET, start: 10 }.into(),
+        );
+
+        run_to_block(10);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+    });
+}
+
+// ============================================================================
+// RING DETECTION TESTS
+// ============================================================================
+
+fn ring(burns: &[u64]) -> RingBurns {
+    burns.to_vec().try_into().unwrap()
+}
+
+/// Alice and Bob burn to each other (0, 1), Bob, Charlie and 4 burn in a circle (2, 3, 4)
+fn burn_in_rings() {
+    for who in [ALICE, BOB, CHARLIE, 4] {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, who, 0));
+    }
+    assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+    assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, BOB, ALICE, 10, None, None, 0, None, None));
+    assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, BOB, CHARLIE, 10, None, None, 1, None, None));
+    assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, CHARLIE, 4, 10, None, None, 0, None, None));
+    assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, 4, BOB, 10, None, None, 0, None, None));
+}
+
+#[test]
+fn find_rings_detects_reciprocal_and_cycle_burns() {
+    new_test_ext().execute_with(|| {
+        burn_in_rings();
+
+        // Each ring is found once
+        assert_eq!(UbiToken::find_rings(0), vec![ring(&[0, 1]), ring(&[2, 3, 4])]);
+        // Only rings with a burn made since the last scan
+        assert_eq!(UbiToken::find_rings(3), vec![ring(&[3, 4, 2])]);
+        assert!(UbiToken::find_rings(5).is_empty());
+
+        // Queued rings are not reported again
+        assert_ok!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[0, 1])));
+        assert_eq!(UbiToken::find_rings(0), vec![ring(&[2, 3, 4])]);
+    });
+}
+
+#[test]
+fn report_suspicion_queues_verified_rings() {
+    new_test_ext().execute_with(|| {
+        burn_in_rings();
+
+        assert_ok!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[2, 3, 4])));
+        assert_eq!(
+            SuspicionReports::<Test>::get().into_inner(),
+            vec![SuspicionReport {
+                asset_id: ASSET,
+                kind: SuspicionKind::Cycle,
+                accounts: vec![BOB, CHARLIE, 4].try_into().unwrap(),
+                burns: ring(&[2, 3, 4]),
+                reported_at: 1,
+            }]
+        );
+        System::assert_last_event(
+            Event::<Test>::SuspicionReported {
+                asset_id: ASSET,
+                kind: SuspicionKind::Cycle,
+                accounts: vec![BOB, CHARLIE, 4].try_into().unwrap(),
+            }
+            .into(),
+        );
+
+        // The same accounts cannot be queued twice
+        assert_noop!(
+            UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[3, 4, 2])),
+            Error::<Test>::RingAlreadyReported
+        );
+        // Burns that do not close a ring are refused
+        assert_noop!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[0, 2])), Error::<Test>::InvalidRing);
+        assert_noop!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[2, 3])), Error::<Test>::InvalidRing);
+        assert_noop!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[0])), Error::<Test>::InvalidRing);
+        assert_noop!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[0, 9])), Error::<Test>::InvalidRing);
+    });
+}
+
+#[test]
+fn report_suspicion_is_validated_in_the_pool() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        burn_in_rings();
+        let report = |burns: &[u64]| {
+            crate::Pallet::<Test>::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::<Test>::report_suspicion { burns: ring(burns) },
+            )
+        };
+
+        assert!(report(&[0, 1]).is_ok());
+        assert_eq!(report(&[0, 2]), InvalidTransaction::Custom(10).into());
+        assert_ok!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[0, 1])));
+        assert_eq!(report(&[1, 0]), InvalidTransaction::Custom(11).into());
+    });
+}
+
+#[test]
+fn rejected_burns_do_not_form_rings() {
+    new_test_ext().execute_with(|| {
+        burn_in_rings();
+        assert_ok!(UbiToken::reject_burn(RuntimeOrigin::signed(BOB), 0));
+
+        assert_eq!(UbiToken::find_rings(0), vec![ring(&[2, 3, 4])]);
+        assert_noop!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[0, 1])), Error::<Test>::InvalidRing);
+    });
+}
+
+#[test]
+fn dismiss_suspicion_requires_review_origin() {
+    new_test_ext().execute_with(|| {
+        burn_in_rings();
+        assert_ok!(UbiToken::report_suspicion(RuntimeOrigin::none(), ring(&[0, 1])));
+
+        assert_noop!(
+            UbiToken::dismiss_suspicion(RuntimeOrigin::signed(ALICE), 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(UbiToken::dismiss_suspicion(RuntimeOrigin::root(), 1), Error::<Test>::UnknownReport);
+
+        assert_ok!(UbiToken::dismiss_suspicion(RuntimeOrigin::root(), 0));
+        assert!(SuspicionReports::<Test>::get().is_empty());
+        let accounts = vec![ALICE, BOB].try_into().unwrap();
+        System::assert_last_event(Event::<Test>::SuspicionDismissed { asset_id: ASSET, accounts }.into());
+    });
+}
+
+// ============================================================================
+// PREFERENCE TESTS
+// ============================================================================
+
+#[test]
+fn preferences_are_stored_only_when_changed() {
+    new_test_ext().execute_with(|| {
+        let preferences = AccountPreferences {
+            auto_claim: true,
+            webhook: Some(b"https://example.org/hook".to_vec().try_into().unwrap()),
+            ..Default::default()
+        };
+        assert_ok!(UbiToken::set_preferences(RuntimeOrigin::signed(ALICE), ASSET, preferences.clone()));
+        System::assert_last_event(Event::<Test>::PreferencesSet { asset_id: ASSET, who: ALICE }.into());
+        assert_eq!(UbiToken::preferences(ASSET, ALICE), preferences);
+
+        // Back to the defaults, the entry is removed
+        assert_ok!(UbiToken::set_preferences(RuntimeOrigin::signed(ALICE), ASSET, Default::default()));
+        assert!(!Preferences::<Test>::contains_key(ASSET, ALICE));
+        assert!(UbiToken::preferences(ASSET, ALICE).allow_third_party_claims);
+
+        assert_noop!(
+            UbiToken::set_preferences(RuntimeOrigin::signed(ALICE), NEIGHBORHOOD, Default::default()),
+            Error::<Test>::UnknownAsset
+        );
+    });
+}
+
+#[test]
+fn preferences_restrict_batch_claims_and_small_burns() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        let preferences =
+            AccountPreferences { allow_third_party_claims: false, min_incoming_burn: 20, ..Default::default() };
+        assert_ok!(UbiToken::set_preferences(RuntimeOrigin::signed(BOB), ASSET, preferences));
+
+        // Relayer batches skip Bob, his own claim still works
+        assert_ok!(UbiToken::claim_for_many(RuntimeOrigin::none(), ASSET, claim_batch(&[ALICE, BOB])));
+        System::assert_last_event(Event::<Test>::BatchClaimed { asset_id: ASSET, claimed: 1, skipped: 1 }.into());
+        assert_noop!(
+            UbiToken::claim_for_many(RuntimeOrigin::none(), ASSET, claim_batch(&[BOB])),
+            Error::<Test>::NothingToClaim
+        );
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+        // Burns to Bob below his minimum are refused
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None),
+            Error::<Test>::BelowRecipientMinimum
+        );
+        let call = crate::Call::<Test>::burn {
+            asset_id: ASSET,
+            from: ALICE,
+            to: BOB,
+            amount: 10,
+            category: None,
+            reference: None,
+            nonce: 0,
+            valid_until: None,
+            idempotency_key: None,
+        };
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &call),
+            Err(InvalidTransaction::Custom(13).into())
+        );
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 0, None, None));
+    });
+}
+
+#[test]
+fn profile_needs_reputation_and_a_valid_name() {
+    new_test_ext().execute_with(|| {
+        let name = |bytes: &[u8]| bytes.to_vec().try_into().unwrap();
+
+        assert_noop!(
+            UbiToken::set_profile(RuntimeOrigin::signed(ALICE), ASSET, name(b"Bakery"), None),
+            Error::<Test>::ReputationTooLow
+        );
+
+        // A claim streak of one is worth the required 10 points
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_noop!(
+            UbiToken::set_profile(RuntimeOrigin::signed(ALICE), ASSET, name(b""), None),
+            Error::<Test>::InvalidDisplayName
+        );
+        assert_noop!(
+            UbiToken::set_profile(RuntimeOrigin::signed(ALICE), ASSET, name(&[0xff, 0xfe]), None),
+            Error::<Test>::InvalidDisplayName
+        );
+
+        let metadata = Some(sp_core::H256::repeat_byte(1));
+        assert_ok!(UbiToken::set_profile(RuntimeOrigin::signed(ALICE), ASSET, name(b"Bakery"), metadata));
+        System::assert_last_event(Event::<Test>::ProfileSet { asset_id: ASSET, who: ALICE }.into());
+        let profile = UbiToken::profile(ASSET, ALICE).unwrap();
+        assert_eq!(profile.display_name.into_inner(), b"Bakery".to_vec());
+        assert_eq!((profile.metadata_hash, profile.updated_at), (metadata, 1));
+
+        assert_ok!(UbiToken::clear_profile(RuntimeOrigin::signed(ALICE), ASSET));
+        assert!(UbiToken::profile(ASSET, ALICE).is_none());
+        assert_noop!(UbiToken::clear_profile(RuntimeOrigin::signed(ALICE), ASSET), Error::<Test>::NoProfile);
+    });
+}
+
+// ============================================================================
+// TUNABLE PARAMETER TESTS
+// ============================================================================
+
+#[test]
+fn set_parameter_changes_reputation_math() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::reputation(&ASSET, &ALICE).score, 10);
+
+        assert_ok!(UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::PointsPerStreakDay(25)));
+        let parameter = UbiParameter::PointsPerStreakDay(25);
+        System::assert_last_event(Event::<Test>::ParameterSet { parameter }.into());
+        assert_eq!(UbiToken::reputation(&ASSET, &ALICE).score, 25);
+        assert_eq!(UbiToken::ubi_parameters(&ASSET).unwrap().reputation.points_per_streak_day, 25);
+
+        assert_ok!(UbiToken::reset_parameter(RuntimeOrigin::root(), UbiParameterKey::PointsPerStreakDay));
+        System::assert_last_event(
+            Event::<Test>::ParameterReset { parameter: UbiParameter::PointsPerStreakDay(10) }.into(),
+        );
+        assert_eq!(UbiToken::reputation(&ASSET, &ALICE).score, 10);
+
+        // Instances are tuned separately
+        assert_ok!(UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::DecayFactor(900)));
+        assert_eq!(CityToken::economics().reputation.decay_factor, 950);
+    });
+}
+
+#[test]
+fn set_parameter_changes_short_expiry_terms() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::set_parameter(
+            RuntimeOrigin::root(),
+            UbiParameter::ShortExpiryBonus(sp_runtime::Perbill::from_percent(50))
+        ));
+        assert_ok!(UbiToken::set_parameter(
+            RuntimeOrigin::root(),
+            UbiParameter::ShortExpiryRatio(sp_runtime::Perbill::from_percent(10))
+        ));
+
+        assert_ok!(UbiToken::claim_short_expiry(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::token_batches(&ASSET, &ALICE), vec![TokenBatch { amount: 150, expires_at: 1 + 70 }]);
+    });
+}
+
+#[test]
+fn set_parameter_rejects_inconsistent_values() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::set_parameter(RuntimeOrigin::signed(ALICE), UbiParameter::DecayFactor(900)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::DecayFactor(1001)),
+            Error::<Test>::InvalidParameter
+        );
+        // The default maximum sender weight is 2000
+        assert_noop!(
+            UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::MinSenderWeight(2500)),
+            Error::<Test>::InvalidParameter
+        );
+
+        // Resetting the maximum below a tuned minimum is refused as well
+        assert_ok!(UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::MaxSenderWeight(3000)));
+        assert_ok!(UbiToken::set_parameter(RuntimeOrigin::root(), UbiParameter::MinSenderWeight(2500)));
+        assert_noop!(
+            UbiToken::reset_parameter(RuntimeOrigin::root(), UbiParameterKey::MaxSenderWeight),
+            Error::<Test>::InvalidParameter
+        );
+    });
+}
+
+// ============================================================================
+// HELPER FUNCTION TESTS
+// ============================================================================
+
+#[test]
+fn can_claim_helper_works() {
+    new_test_ext().execute_with(|| {
+        // New account can claim
+        assert!(UbiToken::can_claim(&ASSET, &ALICE));
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Just claimed - cannot claim again
+        assert!(!UbiToken::can_claim(&ASSET, &ALICE));
+
+        // After one period - can claim
+        run_to_block(101);
+        assert!(UbiToken::can_claim(&ASSET, &ALICE));
+    });
+}
+
+#[test]
+fn claimable_amount_helper_works() {
+    new_test_ext().execute_with(|| {
+        // New account - 1 period = 100
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 100);
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Just claimed - 0
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 0);
+
+        // After 2 periods - 200
+        run_to_block(201);
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 200);
+
+        // After 5 periods - capped at 3 = 300
+        run_to_block(501);
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 300);
+    });
+}
+
+#[test]
+fn balances_are_readable_through_fungible_traits() {
+    use frame_support::traits::{
+        fungible,
+        fungibles::Inspect,
+        tokens::{DepositConsequence, Fortitude, Preservation, Provenance, WithdrawConsequence},
+    };
+
+    frame_support::parameter_types! {
+        pub const Asset: u32 = ASSET;
+    }
+    type Ubi = fungible::ItemOf<UbiToken, Asset, u64>;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::commit_tokens(RuntimeOrigin::signed(ALICE), ASSET, 30, 2));
+
+        assert!(UbiToken::asset_exists(ASSET));
+        assert_eq!(UbiToken::total_issuance(ASSET), 100);
+        assert_eq!(UbiToken::minimum_balance(ASSET), 0);
+        assert_eq!(UbiToken::balance(ASSET, &ALICE), 70);
+        assert_eq!(<UbiToken as Inspect<u64>>::total_balance(ASSET, &ALICE), 100);
+        assert_eq!(UbiToken::reducible_balance(ASSET, &ALICE, Preservation::Expendable, Fortitude::Polite), 70);
+
+        // Tokens only come from claims and leave through burns
+        assert_eq!(UbiToken::can_deposit(ASSET, &BOB, 10, Provenance::Extant), DepositConsequence::Blocked);
+        assert_eq!(UbiToken::can_withdraw(ASSET, &ALICE, 70), WithdrawConsequence::Success);
+        assert_eq!(UbiToken::can_withdraw(ASSET, &ALICE, 71), WithdrawConsequence::BalanceLow);
+        assert_eq!(UbiToken::can_withdraw(42, &ALICE, 1), WithdrawConsequence::UnknownAsset);
+
+        // A single asset as `fungible`
+        assert_eq!(<Ubi as fungible::Inspect<u64>>::balance(&ALICE), 70);
+        assert_eq!(<Ubi as fungible::Inspect<u64>>::total_issuance(), 100);
+
+        // Expired tokens no longer count, the committed ones expire later
+        run_to_block(701);
+        assert_eq!(UbiToken::balance(ASSET, &ALICE), 30);
+        run_to_block(901);
+        assert_eq!(<UbiToken as Inspect<u64>>::total_balance(ASSET, &ALICE), 0);
+    });
+}
+
+#[test]
+fn public_types_round_trip_through_json() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(
+            RuntimeOrigin::none(),
+            ASSET,
+            ALICE,
+            BOB,
+            10,
+            Some(BurnCategory::Food),
+            Some(sp_core::H256::repeat_byte(7)),
+            0,
+            None,
+            None
+        ));
+
+        let receipt = BurnReceipts::<Test>::get(0).unwrap();
+        let json = serde_json::to_string(&receipt).unwrap();
+        assert_eq!(serde_json::from_str::<crate::BurnReceiptOf<Test>>(&json).unwrap(), receipt);
+
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        let json = serde_json::to_value(&rep).unwrap();
+        assert_eq!(json["burns_sent_count"], 1);
+
+        let batches = Balances::<Test>::get(ASSET, ALICE);
+        let json = serde_json::to_string(&batches[0]).unwrap();
+        assert_eq!(json, r#"{"amount":90,"expires_at":701}"#);
+    });
+}
+
+// ============================================================================
+// INTEGRATION TESTS
+// ============================================================================
+
+#[test]
+fn full_lifecycle_pizza_purchase() {
     new_test_ext().execute_with(|| {
         // Day 1: Alice and Bob both claim UBI
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), BOB));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+
+        // Alice burns 50 tokens to Bob for pizza
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+
+        // Check balances
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 50);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 100); // Bob's own UBI, not Alice's burn
+
+        // Check reputation
+        let alice_rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(alice_rep.burns_sent_count, 1);
+        assert_eq!(alice_rep.burns_sent_volume, 50);
+
+        let bob_rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!(bob_rep.burns_received_count, 1);
+        assert_eq!(bob_rep.burns_received_volume, 50);
+
+        // Day 2: Bob burns to Charlie for flour
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, BOB, CHARLIE, 30, None, None, 0, None, None));
+
+        let charlie_rep = ReputationStore::<Test>::get(ASSET, CHARLIE);
+        assert_eq!(charlie_rep.burns_received_count, 1);
+        assert_eq!(charlie_rep.burns_received_volume, 30);
+
+        // Total supply check
+        // Alice: 100 - 50 = 50
+        // Bob: 100 + 100 - 30 = 170
+        // Charlie: 0
+        // Total: 50 + 170 = 220
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 220);
+    });
+}
+
+#[test]
+fn sybil_attack_is_pointless() {
+    new_test_ext().execute_with(|| {
+        // Attacker creates many accounts and claims
+        for i in 100..110 {
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, i, 0));
+        }
+
+        // Total supply is 1000 (10 accounts * 100)
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 1000);
+
+        // But after expiration, all tokens disappear
+        run_to_block(702);
+
+        // All balances are now 0
+        for i in 100..110 {
+            assert_eq!(UbiToken::spendable_balance(&ASSET, &i), 0);
+        }
+
+        // Attacker gained nothing - tokens expired
+        // They can only burn tokens, not trade them
+    });
+}
+
+#[test]
+fn exchange_cannot_operate() {
+    new_test_ext().execute_with(|| {
+        // User claims tokens
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // User "deposits" to exchange by burning to exchange address
+        let exchange: u64 = 999;
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, exchange, 100, None, None, 0, None, None));
+
+        // Exchange received NO TOKENS - just a burn event
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &exchange), 0);
+
+        // Exchange has nothing to sell!
+        // The burn event is proof Alice paid, but exchange cannot transfer anything
+
+        // Exchange can claim its own UBI
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, exchange, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &exchange), 100);
+
+        // But those are the exchange's own tokens, not "user deposits"
+        // Exchange business model is broken
+    });
+}
+
+// ============================================================================
+// ENHANCED REPUTATION SYSTEM TESTS
+// ============================================================================
+
+#[test]
+fn claim_streak_increments_on_consecutive_claims() {
+    new_test_ext().execute_with(|| {
+        // First claim
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.claim_streak, 1);
+
+        // Claim next period
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.claim_streak, 2);
+
+        // Claim next period
+        run_to_block(201);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.claim_streak, 3);
+    });
+}
+
+#[test]
+fn claim_streak_respects_grace_period() {
+    new_test_ext().execute_with(|| {
+        // First claim
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        // Skip 2 periods (within grace period of 2)
+        run_to_block(301);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        // Streak should still increment (3 periods passed, grace is 2+1=3 allowed)
+        assert_eq!(rep.claim_streak, 2);
+    });
+}
+
+#[test]
+fn claim_streak_resets_after_grace_period() {
+    new_test_ext().execute_with(|| {
+        // Build up streak
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(201);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.claim_streak, 3);
+
+        // Skip more than grace period (4 periods = 400 blocks)
+        run_to_block(601);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        // Streak should reset to 1
+        assert_eq!(rep.claim_streak, 1);
+    });
+}
+
+#[test]
+fn streak_freeze_covers_missed_periods() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        assert_ok!(UbiToken::freeze_streak(RuntimeOrigin::signed(ALICE), ASSET));
+        System::assert_last_event(
+            Event::<Test>::StreakFrozen { asset_id: ASSET, who: ALICE, cost: 50, streak_freeze: 3 }.into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 150);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 150);
+
+        // Five periods missed: two within the grace period, three frozen
+        run_to_block(701);
+        assert_eq!(UbiToken::reputation(&ASSET, &ALICE).claim_streak, 2);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.claim_streak, 3);
+        assert_eq!(rep.streak_freeze, 0);
+
+        // Used up, the next long absence resets the streak
+        run_to_block(1101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).claim_streak, 1);
+    });
+}
+
+#[test]
+fn vacation_pauses_streak_and_claims() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        assert_ok!(UbiToken::set_vacation(RuntimeOrigin::signed(ALICE), ASSET, 8));
+        System::assert_last_event(Event::<Test>::VacationSet { asset_id: ASSET, who: ALICE, start: 1, end: 8 }.into());
+
+        // Nobody can claim for Alice while she is away
+        run_to_block(301);
+        assert_noop!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0), Error::<Test>::OnVacation);
+        let call = crate::Call::<Test>::claim { asset_id: ASSET, account: ALICE, nonce: 0 };
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &call),
+            Err(InvalidTransaction::Custom(12).into())
+        );
+
+        // Back in period 8, the vacation periods do not break the streak
+        run_to_block(801);
+        assert_eq!(UbiToken::reputation(&ASSET, &ALICE).claim_streak, 2);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).claim_streak, 3);
+
+        assert_noop!(
+            UbiToken::set_vacation(RuntimeOrigin::signed(ALICE), ASSET, 12),
+            Error::<Test>::VacationCooldown
+        );
+    });
+}
+
+#[test]
+fn vacation_is_bounded_and_can_end_early() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(UbiToken::set_vacation(RuntimeOrigin::signed(BOB), ASSET, 11), Error::<Test>::InvalidVacation);
+        assert_noop!(UbiToken::set_vacation(RuntimeOrigin::signed(BOB), ASSET, 0), Error::<Test>::InvalidVacation);
+
+        assert_ok!(UbiToken::set_vacation(RuntimeOrigin::signed(BOB), ASSET, 10));
+        assert_noop!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0), Error::<Test>::OnVacation);
+
+        // Coming back early ends the vacation in the current period
+        run_to_block(201);
+        assert_ok!(UbiToken::set_vacation(RuntimeOrigin::signed(BOB), ASSET, 0));
+        System::assert_last_event(Event::<Test>::VacationSet { asset_id: ASSET, who: BOB, start: 0, end: 2 }.into());
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+    });
+}
+
+#[test]
+fn streak_freeze_is_bounded_and_paid() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::freeze_streak(RuntimeOrigin::signed(ALICE), ASSET),
+            Error::<Test>::InsufficientBalance
+        );
+
+        for block in [1, 101, 201] {
+            run_to_block(block);
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        }
+        for _ in 0..4 {
+            assert_ok!(UbiToken::freeze_streak(RuntimeOrigin::signed(ALICE), ASSET));
+        }
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).streak_freeze, 12);
+        assert_noop!(
+            UbiToken::freeze_streak(RuntimeOrigin::signed(ALICE), ASSET),
+            Error::<Test>::StreakFreezeLimit
+        );
+    });
+}
+
+#[test]
+fn claim_applies_reputation_decay() {
+    new_test_ext().execute_with(|| {
+        // Alice claims and burns to build up score
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+        
+        let rep_before = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep_before.score, 50 + 50 + 10);
+        
+        // Claim again - should apply 5% decay
+        run_to_block(101);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        let rep_after = ReputationStore::<Test>::get(ASSET, ALICE);
+        
+        // Sent volume decayed (50 * 0.95 = 47), streak grew to 2
+        assert_eq!(rep_after.burns_sent_volume, 47);
+        assert_eq!(rep_after.score, 50 + 47 + 20);
+    });
+}
+
+#[test]
+fn reputation_decays_without_claiming() {
+    new_test_ext().execute_with(|| {
+        // Bob never claims, only receives
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 80, None, None, 0, None, None));
+        // Alice's score of 10 gives her burns a 0.75x weight
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).weighted_received, 60);
+        assert_eq!(UbiToken::reputation_score(&ASSET, &BOB), 120);
+
+        // Three periods later the read already includes the decay (60 * 0.95^3 = 51)
+        run_to_block(301);
+        assert_eq!(UbiToken::reputation_score(&ASSET, &BOB), 102);
+        // ...while storage is only updated on the next write
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).score, 120);
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, CHARLIE, BOB, 10, None, None, 0, None, None));
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!(rep.weighted_received, 51 + 7);
+        assert_eq!(rep.last_decay_period, 3);
+    });
+}
+
+#[test]
+fn reputation_percentile_ranks_against_score_histogram() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        // Both share the bucket of a score of 10
+        assert_eq!(UbiToken::reputation_percentile(&ASSET, &ALICE), sp_runtime::Perbill::zero());
+
+        // Alice ends up at 140 and Bob, who never claimed, at 120, in separate buckets
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 80, None, None, 0, None, None));
+        assert_eq!(UbiToken::score_histogram(ASSET).iter().sum::<u32>(), 3);
+        assert_eq!(UbiToken::reputation_percentile(&ASSET, &CHARLIE), sp_runtime::Perbill::zero());
+        assert_eq!(UbiToken::reputation_percentile(&ASSET, &BOB), sp_runtime::Perbill::from_rational(1u32, 3u32));
+        assert_eq!(UbiToken::reputation_percentile(&ASSET, &ALICE), sp_runtime::Perbill::from_rational(2u32, 3u32));
+
+        // Accounts without a record are not ranked
+        assert_eq!(UbiToken::reputation_percentile(&ASSET, &4), sp_runtime::Perbill::zero());
+    });
+}
+
+#[test]
+fn lapsed_streak_is_dropped_on_read_and_for_sender_weight() {
+    new_test_ext().execute_with(|| {
+        // Alice builds a streak of 3, worth a 0.75x sender weight
+        for block in [1, 101, 201] {
+            run_to_block(block);
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        }
+        assert_eq!(UbiToken::reputation(&ASSET, &ALICE).claim_streak, 3);
+        assert_eq!(UbiToken::reputation_score(&ASSET, &ALICE), 30);
+
+        // Four periods without a claim break the streak
+        run_to_block(601);
+        let rep = UbiToken::reputation(&ASSET, &ALICE);
+        assert_eq!(rep.claim_streak, 0);
+        assert_eq!(rep.score, 0);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, ALICE).claim_streak, 3);
+
+        // The refreshed score is what weights the burn
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 0, None, None));
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).weighted_received, 10);
+    });
+}
+
+#[test]
+fn unique_recipients_tracked_correctly() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        // Burn to Bob - first unique recipient
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 0, None, None));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.unique_recipients_count, 1);
+        assert!(UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+        
+        // Burn to Bob again - should NOT increment unique count
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 20, None, None, 1, None, None));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.unique_recipients_count, 1);
+        
+        // Burn to Charlie - second unique recipient
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 20, None, None, 2, None, None));
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.unique_recipients_count, 2);
+        assert!(UbiToken::has_burned_to(&ASSET, &ALICE, &CHARLIE));
+    });
+}
+
+#[test]
+fn weighted_received_uses_sender_weight() {
+    new_test_ext().execute_with(|| {
+        // Alice (new user) claims - gets streak bonus of 10, score = 10
+        // Score of 10 puts her in 10-99 tier = 0.75x weight (750/1000)
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        let alice_rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(alice_rep.score, 10); // streak bonus only
+        
+        // Alice burns 100 to Bob
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 100, None, None, 0, None, None));
+        
+        let bob_rep = ReputationStore::<Test>::get(ASSET, BOB);
+        // Alice has score 10, so weight is 0.75x = 750/1000
+        // weighted_received = 100 * 750 / 1000 = 75
+        assert_eq!(bob_rep.weighted_received, 75);
+        
+        // Now test with a zero-score sender (burns before any claim/activity)
+        // This is not possible in normal flow since you need to claim first to get tokens
+        // So the minimum practical sender weight is 0.75x (score 10 from first claim streak)
+    });
+}
 
-        // Alice burns 50 tokens to Bob for pizza
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
+#[test]
+fn reputation_score_formula_correct() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        // Burn to 2 unique recipients
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 30, None, None, 0, None, None));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, CHARLIE, 20, None, None, 1, None, None));
+        
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        
+        // Expected score components:
+        // - unique_recipients: 2 * 50 = 100
+        // - burns_sent_volume: 50 * 1 = 50
+        // - weighted_received: 0 * 2 = 0
+        // - streak_bonus: 1 * 10 = 10
+        // Total: 100 + 50 + 0 + 10 = 160
+        
+        assert_eq!(rep.unique_recipients_count, 2);
+        assert_eq!(rep.burns_sent_volume, 50);
+        assert_eq!(rep.weighted_received, 0);
+        assert_eq!(rep.claim_streak, 1);
+        assert_eq!(rep.score, 160);
+    });
+}
 
-        // Check balances
-        assert_eq!(UbiToken::spendable_balance(&ALICE), 50);
-        assert_eq!(UbiToken::spendable_balance(&BOB), 100); // Bob's own UBI, not Alice's burn
+#[test]
+fn streak_bonus_caps_at_max() {
+    new_test_ext().execute_with(|| {
+        // Claim 60 times (more than the 50-day cap)
+        for i in 0..60 {
+            run_to_block(i * 100 + 1);
+            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        }
+        
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(rep.claim_streak, 60);
+        
+        // Streak bonus should be capped at MAX_STREAK_BONUS (500)
+        // Score = unique(0) + sent(0) + received(0) + streak_bonus(500)
+        // But decay has been applied each time, so score will be different
+        // Just verify streak is tracked correctly
+        assert!(rep.claim_streak >= 50);
+    });
+}
 
-        // Check reputation
-        let alice_rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(alice_rep.burns_sent_count, 1);
-        assert_eq!(alice_rep.burns_sent_volume, 50);
+#[test]
+fn bot_farming_yields_low_reputation() {
+    new_test_ext().execute_with(|| {
+        // Simulate bot ring: A -> B -> A
+        let bot_a: u64 = 100;
+        let bot_b: u64 = 101;
+        
+        // Both bots claim - each gets score of 10 (streak bonus)
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, bot_a, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, bot_b, 0));
+        
+        let bot_a_score_before = ReputationStore::<Test>::get(ASSET, bot_a).score;
+        let bot_b_score_before = ReputationStore::<Test>::get(ASSET, bot_b).score;
+        assert_eq!(bot_a_score_before, 10); // Just streak bonus
+        assert_eq!(bot_b_score_before, 10);
+        
+        // Bot A burns to Bot B
+        // Bot A has score 10 -> weight 0.75x (750/1000)
+        // weighted_received for B = 100 * 750 / 1000 = 75
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, bot_a, bot_b, 100, None, None, 0, None, None));
+        
+        // Bot B burns to Bot A
+        // Bot B's score after receiving = 10 (sent) + 75*2 (weighted_received) + 10 (streak) = 170
+        // Actually let me check the actual formula
+        
+        // Bot B burns - their score determines weight
+        // If B's score is in 100-999 range, weight is 1.0x
+        let _bot_b_rep_after_receive = ReputationStore::<Test>::get(ASSET, bot_b);
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, bot_b, bot_a, 100, None, None, 0, None, None));
+        
+        let bot_a_rep = ReputationStore::<Test>::get(ASSET, bot_a);
+        let bot_b_rep = ReputationStore::<Test>::get(ASSET, bot_b);
+        
+        // Both should have limited weighted_received
+        // Bot A received from Bot B when B had score ~170 (100-999 tier = 1.0x)
+        // Bot B received from Bot A when A had score 10 (10-99 tier = 0.75x)
+        assert_eq!(bot_b_rep.weighted_received, 75);  // From A at 0.75x weight
+        assert_eq!(bot_a_rep.weighted_received, 100); // From B at 1.0x weight
+        
+        // Each has only 1 unique recipient
+        assert_eq!(bot_a_rep.unique_recipients_count, 1);
+        assert_eq!(bot_b_rep.unique_recipients_count, 1);
+        
+        // Key insight: bot farming is limited because:
+        // 1. New bots start with low reputation = low weight
+        // 2. Each bot can only have 1 unique recipient from the other
+        // 3. A legitimate user receiving burns from many different high-rep users
+        //    would accumulate much higher weighted_received
+    });
+}
 
-        let bob_rep = ReputationStore::<Test>::get(BOB);
-        assert_eq!(bob_rep.burns_received_count, 1);
-        assert_eq!(bob_rep.burns_received_volume, 50);
+#[test]
+fn reputation_score_public_api() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+        
+        // Public API should return the score
+        let score = UbiToken::reputation_score(&ASSET, &ALICE);
+        assert!(score > 0);
+        
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!(score, rep.score);
+    });
+}
 
-        // Day 2: Bob burns to Charlie for flour
+#[test]
+fn exported_reputation_is_queued_for_bridges() {
+    use crate::{ReputationOracle, ReputationSnapshot};
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+        let score = UbiToken::reputation_score(&ASSET, &ALICE);
+        assert_eq!(<UbiToken as ReputationOracle<u64>>::score(&ASSET, &ALICE), score);
+
+        assert_ok!(UbiToken::export_reputation(RuntimeOrigin::signed(ALICE), ASSET));
+        System::assert_last_event(
+            Event::<Test>::ReputationExported { sequence: 0, asset_id: ASSET, who: ALICE, score }.into(),
+        );
+        assert_noop!(
+            UbiToken::export_reputation(RuntimeOrigin::signed(ALICE), ASSET),
+            Error::<Test>::AlreadyExported
+        );
+        assert_noop!(UbiToken::export_reputation(RuntimeOrigin::signed(ALICE), 42), Error::<Test>::UnknownAsset);
+        assert_ok!(UbiToken::export_reputation(RuntimeOrigin::signed(BOB), ASSET));
+        assert_noop!(
+            UbiToken::export_reputation(RuntimeOrigin::signed(CHARLIE), ASSET),
+            Error::<Test>::OutboundQueueFull
+        );
+        assert_eq!(UbiToken::queued_snapshots(), 2);
+
+        assert_eq!(
+            UbiToken::take_snapshots(1),
+            vec![ReputationSnapshot { sequence: 0, asset_id: ASSET, who: ALICE, score, block: 1 }]
+        );
+        assert_ok!(UbiToken::export_reputation(RuntimeOrigin::signed(CHARLIE), ASSET));
+        let taken: Vec<_> =
+            UbiToken::take_snapshots(10).iter().map(|snapshot| (snapshot.sequence, snapshot.who)).collect();
+        assert_eq!(taken, [(1, BOB), (2, CHARLIE)]);
+        assert_eq!(UbiToken::queued_snapshots(), 0);
+        assert!(UbiToken::take_snapshots(10).is_empty());
+
+        // One export per claim period
         run_to_block(101);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), BOB));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), BOB, CHARLIE, 30));
+        assert_ok!(UbiToken::export_reputation(RuntimeOrigin::signed(ALICE), ASSET));
+        assert_eq!(UbiToken::take_snapshots(10)[0].sequence, 3);
+    });
+}
 
-        let charlie_rep = ReputationStore::<Test>::get(CHARLIE);
-        assert_eq!(charlie_rep.burns_received_count, 1);
-        assert_eq!(charlie_rep.burns_received_volume, 30);
+#[test]
+fn has_burned_to_helper_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        
+        // Before burning
+        assert!(!UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+        
+        // After burning
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+        assert!(UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+        
+        // Different recipient still false
+        assert!(!UbiToken::has_burned_to(&ASSET, &ALICE, &CHARLIE));
+    });
+}
 
-        // Total supply check
-        // Alice: 100 - 50 = 50
-        // Bob: 100 + 100 - 30 = 170
-        // Charlie: 0
-        // Total: 50 + 170 = 220
-        assert_eq!(TotalSupply::<Test>::get(), 220);
+/// Step a multi-block migration to its end, without a weight limit
+fn run_migration<M: frame_support::migrations::SteppedMigration>() {
+    let mut cursor = None;
+    loop {
+        cursor = M::step(cursor, &mut frame_support::weights::WeightMeter::new()).unwrap();
+        if cursor.is_none() {
+            break;
+        }
+    }
+}
+
+#[test]
+fn migration_to_v1_moves_unique_recipients_into_sets() {
+    use crate::migrations::v1;
+    use frame_support::traits::{ConstU32, ConstU64, GetStorageVersion, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<UbiToken>();
+        v1::UniqueRecipients::<Test, ()>::insert(ALICE, BOB, true);
+        v1::UniqueRecipients::<Test, ()>::insert(ALICE, CHARLIE, true);
+        v1::UniqueRecipients::<Test, ()>::insert(BOB, ALICE, true);
+
+        run_migration::<v1::LazyMigrationV0ToV1<Test, ConstU32<ASSET>, ConstU64<ALICE>>>();
+
+        assert_eq!(UbiToken::unique_recipients(&ASSET, &ALICE, None, 10), vec![BOB, CHARLIE]);
+        assert!(UbiToken::has_burned_to(&ASSET, &BOB, &ALICE));
+        assert_eq!(v1::UniqueRecipients::<Test, ()>::iter().count(), 0);
+        assert_eq!(UbiToken::on_chain_storage_version(), 1);
+        System::assert_last_event(Event::<Test>::UniqueRecipientsDrained { moved: 3, dropped: 0 }.into());
+    });
+}
+
+#[test]
+fn migration_to_v1_drops_recipients_beyond_the_bound() {
+    use crate::migrations::v1;
+    use frame_support::traits::{ConstU32, ConstU64, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<UbiToken>();
+        let recipients = 100..(100 + MAX_UNIQUE_RECIPIENTS as u64 + 2);
+        recipients.for_each(|recipient| v1::UniqueRecipients::<Test, ()>::insert(ALICE, recipient, true));
+
+        run_migration::<v1::LazyMigrationV0ToV1<Test, ConstU32<ASSET>, ConstU64<ALICE>>>();
+
+        assert_eq!(UniqueRecipientSets::<Test>::get(ASSET, ALICE).len(), MAX_UNIQUE_RECIPIENTS as usize);
+        assert_eq!(v1::UniqueRecipients::<Test, ()>::iter().count(), 0);
+        let drained = Event::<Test>::UniqueRecipientsDrained { moved: MAX_UNIQUE_RECIPIENTS, dropped: 2 };
+        System::assert_last_event(drained.into());
+    });
+}
+
+#[test]
+fn migration_to_v1_moves_single_asset_state_under_the_legacy_asset() {
+    use crate::migrations::{v1, v2, v3, v4, v5};
+    use frame_support::storage::{unhashed, StoragePrefixedMap};
+    use frame_support::traits::{ConstU32, ConstU64, GetStorageVersion, StorageVersion};
+    use frame_support::{Blake2_128Concat, StorageHasher};
+    use parity_scale_codec::Encode;
+
+    const LEGACY: u32 = 7;
+    let legacy_key = |prefix: [u8; 32], who: u64| [&prefix[..], &Blake2_128Concat::hash(&who.encode())].concat();
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<UbiToken>();
+        for who in [ALICE, BOB] {
+            let batches = vec![v3::OldTokenBatch::<u128, u64> { amount: 40 * who as u128, expires_at: 500 }];
+            unhashed::put(&legacy_key(Balances::<Test>::final_prefix(), who), &batches);
+        }
+        unhashed::put(&legacy_key(LastClaim::<Test>::final_prefix(), ALICE), &1u64);
+        let reputation = v1::LegacyReputation::<u64> {
+            burns_sent_count: 1,
+            burns_sent_volume: 10,
+            burns_received_count: 0,
+            burns_received_volume: 0,
+            first_activity: 1,
+            weighted_received: 0,
+            unique_recipients_count: 1,
+            claim_streak: 1,
+            last_claim_period: 0,
+            score: 70,
+        };
+        unhashed::put(&legacy_key(ReputationStore::<Test>::final_prefix(), ALICE), &reputation);
+        unhashed::put(&TotalSupply::<Test>::final_prefix(), &120u128);
+        let providers = System::providers(&ALICE);
+
+        run_migration::<v1::LazyMigrationV0ToV1<Test, ConstU32<LEGACY>, ConstU64<CHARLIE>>>();
+        run_migration::<v2::LazyMigrationV1ToV2<Test>>();
+        run_migration::<v3::LazyMigrationV2ToV3<Test>>();
+        run_migration::<v4::LazyMigrationV3ToV4<Test>>();
+        run_migration::<v5::LazyMigrationV4ToV5<Test>>();
+
+        assert_eq!(UbiToken::on_chain_storage_version(), 5);
+        assert_eq!(crate::Assets::<Test>::get(LEGACY).map(|details| details.owner), Some(CHARLIE));
+        assert_eq!(crate::AssetCount::<Test>::get(), 2);
+        assert_eq!(TotalSupply::<Test>::get(LEGACY), 120);
+        assert_eq!(Balances::<Test>::get(LEGACY, ALICE).into_inner(), vec![TokenBatch { amount: 40, expires_at: 500 }]);
+        assert_eq!(Balances::<Test>::get(LEGACY, BOB).into_inner(), vec![TokenBatch { amount: 80, expires_at: 500 }]);
+        assert_eq!(LastClaim::<Test>::get(LEGACY, ALICE), Some(1));
+        assert_eq!(System::providers(&ALICE), providers + 1);
+        let rep = ReputationStore::<Test>::get(LEGACY, ALICE);
+        assert_eq!((rep.burns_sent_volume, rep.unique_recipients_count, rep.claim_streak, rep.score), (10, 1, 1, 70));
+        assert_eq!(crate::ScoreHistogram::<Test>::get(LEGACY)[UbiToken::score_bucket(70)], 1);
+
+        // Nothing is left at the single-asset keys
+        for prefix in
+            [Balances::<Test>::final_prefix(), LastClaim::<Test>::final_prefix(), ReputationStore::<Test>::final_prefix()]
+        {
+            assert!(!unhashed::exists(&legacy_key(prefix, ALICE)));
+        }
+        assert!(!unhashed::exists(&TotalSupply::<Test>::final_prefix()));
+    });
+}
+
+#[test]
+fn migration_to_v2_encodes_reputations_compactly() {
+    use crate::migrations::v2;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    use parity_scale_codec::Encode;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<UbiToken>();
+        let old = v2::OldReputationOf::<Test, ()> {
+            burns_sent_count: 3,
+            burns_sent_volume: 120,
+            burns_received_count: 0,
+            burns_received_volume: 0,
+            first_activity: 1,
+            weighted_received: 0,
+            unique_recipients_count: 2,
+            claim_streak: 4,
+            last_claim_period: 3,
+            score: 260,
+            maintenance_credit: 0,
+            last_activity: 301,
+            last_decay_period: 3,
+            commitment_cost: 0,
+            backlog_cost: 0,
+            streak_freeze: 0,
+            vacation_start: 0,
+            vacation_end: 0,
+            boost_stake: 0,
+            slashed_stake: 0,
+            vouch_credit: 0,
+            vouch_cost: 0,
+        };
+        let key = ReputationStore::<Test>::hashed_key_for(ASSET, ALICE);
+        frame_support::storage::unhashed::put_raw(&key, &old.encode());
+
+        run_migration::<v2::LazyMigrationV1ToV2<Test>>();
+
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!((rep.burns_sent_count, rep.burns_sent_volume, rep.score), (3, 120, 260));
+        assert_eq!((rep.claim_streak, rep.last_activity), (4, 301));
+        assert!(rep.encode().len() < old.encode().len());
+        assert_eq!(UbiToken::on_chain_storage_version(), 2);
+    });
+}
+
+#[test]
+fn migration_to_v3_encodes_batches_compactly() {
+    use crate::migrations::v3;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    use parity_scale_codec::Encode;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(2).put::<UbiToken>();
+        let old = vec![
+            v3::OldTokenBatchOf::<Test, ()> { amount: 100, expires_at: 701 },
+            v3::OldTokenBatchOf::<Test, ()> { amount: 40, expires_at: 801 },
+        ];
+        let key = Balances::<Test>::hashed_key_for(ASSET, ALICE);
+        frame_support::storage::unhashed::put_raw(&key, &old.encode());
+
+        run_migration::<v3::LazyMigrationV2ToV3<Test>>();
+
+        assert_eq!(
+            Balances::<Test>::get(ASSET, ALICE).to_vec(),
+            vec![TokenBatch { amount: 100, expires_at: 701 }, TokenBatch { amount: 40, expires_at: 801 }]
+        );
+        assert!(Balances::<Test>::get(ASSET, ALICE).encode().len() < old.encode().len());
+        assert_eq!(UbiToken::on_chain_storage_version(), 3);
+    });
+}
+
+#[test]
+fn migration_to_v4_adds_bonus_credit() {
+    use crate::migrations::v4;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    use parity_scale_codec::Encode;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(3).put::<UbiToken>();
+        let old = v4::OldReputationOf::<Test, ()> {
+            burns_sent_count: 3,
+            burns_sent_volume: 120,
+            burns_received_count: 0,
+            burns_received_volume: 0,
+            first_activity: 1,
+            weighted_received: 0,
+            unique_recipients_count: 2,
+            claim_streak: 4,
+            last_claim_period: 3,
+            score: 260,
+            maintenance_credit: 0,
+            last_activity: 301,
+            last_decay_period: 3,
+            commitment_cost: 0,
+            backlog_cost: 0,
+            streak_freeze: 0,
+            vacation_start: 0,
+            vacation_end: 0,
+            boost_stake: 0,
+            slashed_stake: 0,
+            vouch_credit: 0,
+            vouch_cost: 0,
+        };
+        let key = ReputationStore::<Test>::hashed_key_for(ASSET, ALICE);
+        frame_support::storage::unhashed::put_raw(&key, &old.encode());
+        // The old layout is one field short of the new one
+        assert!(ReputationStore::<Test>::try_get(ASSET, ALICE).is_err());
+
+        run_migration::<v4::LazyMigrationV3ToV4<Test>>();
+
+        let rep = ReputationStore::<Test>::get(ASSET, ALICE);
+        assert_eq!((rep.burns_sent_count, rep.burns_sent_volume, rep.score), (3, 120, 260));
+        assert_eq!((rep.claim_streak, rep.last_activity, rep.bonus_credit), (4, 301, 0));
+        assert_eq!(UbiToken::on_chain_storage_version(), 4);
+    });
+}
+
+#[test]
+fn migration_to_v5_moves_open_boosts_into_boost_received() {
+    use crate::migrations::v5;
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    use parity_scale_codec::Encode;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::boosted_burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 20, 10));
+        // Lay out Bob's record and the receipt as version 4 credited the boost
+        let bob = ReputationStore::<Test>::get(ASSET, BOB);
+        let old = v5::OldReputationOf::<Test, ()> {
+            burns_sent_count: bob.burns_sent_count,
+            burns_sent_volume: bob.burns_sent_volume,
+            burns_received_count: bob.burns_received_count,
+            burns_received_volume: bob.burns_received_volume,
+            first_activity: bob.first_activity,
+            weighted_received: 15 + 10,
+            unique_recipients_count: bob.unique_recipients_count,
+            claim_streak: bob.claim_streak,
+            last_claim_period: bob.last_claim_period,
+            score: (15 + 10) * 2,
+            maintenance_credit: 0,
+            last_activity: bob.last_activity,
+            last_decay_period: bob.last_decay_period,
+            commitment_cost: 0,
+            backlog_cost: 0,
+            streak_freeze: 0,
+            vacation_start: 0,
+            vacation_end: 0,
+            boost_stake: 0,
+            slashed_stake: 0,
+            vouch_credit: 0,
+            vouch_cost: 0,
+            bonus_credit: 0,
+        };
+        let key = ReputationStore::<Test>::hashed_key_for(ASSET, BOB);
+        frame_support::storage::unhashed::put_raw(&key, &old.encode());
+        BurnReceipts::<Test>::mutate(0, |receipt| receipt.as_mut().unwrap().weighted_amount = 25);
+        StorageVersion::new(4).put::<UbiToken>();
+
+        run_to_block(101);
+        run_migration::<v5::LazyMigrationV4ToV5<Test>>();
+
+        // The boost decayed with the burn, 25 to 23 of which 14 is the plain burn
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!((rep.weighted_received, rep.boost_received), (14, 10));
+        assert_eq!(UbiToken::burn_receipt(0).unwrap().weighted_amount, 15);
+        assert_eq!(UbiToken::on_chain_storage_version(), 5);
+
+        assert_ok!(UbiToken::release_boost(RuntimeOrigin::signed(CHARLIE), 0));
+        let rep = ReputationStore::<Test>::get(ASSET, BOB);
+        assert_eq!((rep.weighted_received, rep.boost_received), (14, 0));
+    });
+}
+
+// ============================================================================
+// COMMUNITY ASSET TESTS
+// ============================================================================
+
+const NEIGHBORHOOD: u32 = 7;
+
+fn neighborhood_params() -> AssetParameters<u128, u64> {
+    AssetParameters {
+        ubi_amount: 25,
+        claim_period_blocks: 10,
+        expiration_blocks: 30,
+        max_backlog_periods: 2,
+    }
+}
+
+#[test]
+fn genesis_imports_balances_and_reputations() {
+    use sp_runtime::BuildStorage;
+
+    let reputation = crate::Reputation { burns_sent_count: 3, claim_streak: 2, score: 120, ..Default::default() };
+    let t = RuntimeGenesisConfig {
+        system: Default::default(),
+        native_balances: Default::default(),
+        ubi_token: crate::GenesisConfig {
+            assets: vec![(ASSET, ALICE)],
+            balances: vec![(
+                ASSET,
+                BOB,
+                vec![TokenBatch { amount: 40, expires_at: 300 }, TokenBatch { amount: 60, expires_at: 500 }],
+            )],
+            reputations: vec![(ASSET, BOB, reputation.clone())],
+            ..Default::default()
+        },
+        city_token: Default::default(),
+    }
+    .build_storage()
+    .unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 100);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 100);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB), reputation);
+
+        // Imported batches expire on their own schedule
+        run_to_block(301);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 60);
+    });
+}
+
+#[test]
+fn genesis_overrides_asset_parameters() {
+    use sp_runtime::BuildStorage;
+
+    let t = RuntimeGenesisConfig {
+        system: Default::default(),
+        native_balances: Default::default(),
+        ubi_token: crate::GenesisConfig {
+            assets: vec![(ASSET, ALICE)],
+            asset_parameters: vec![(ASSET, neighborhood_params())],
+            ..Default::default()
+        },
+        city_token: Default::default(),
+    }
+    .build_storage()
+    .unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(UbiToken::asset(ASSET).unwrap().params, neighborhood_params());
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), neighborhood_params().ubi_amount);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Invalid genesis asset parameters")]
+fn genesis_rejects_invalid_asset_parameters() {
+    use sp_runtime::BuildStorage;
+
+    let _ = RuntimeGenesisConfig {
+        system: Default::default(),
+        native_balances: Default::default(),
+        ubi_token: crate::GenesisConfig {
+            assets: vec![(ASSET, ALICE)],
+            asset_parameters: vec![(ASSET, AssetParameters { claim_period_blocks: 0, ..neighborhood_params() })],
+            ..Default::default()
+        },
+        city_token: Default::default(),
+    }
+    .build_storage();
+}
+
+#[test]
+fn create_asset_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(UbiToken::create_asset(RuntimeOrigin::signed(BOB), NEIGHBORHOOD, neighborhood_params()));
+
+        let details = UbiToken::asset(NEIGHBORHOOD).unwrap();
+        assert_eq!(details.owner, BOB);
+        assert_eq!(details.params, neighborhood_params());
+        System::assert_last_event(Event::<Test>::AssetCreated { asset_id: NEIGHBORHOOD, owner: BOB }.into());
+
+        // Claims use the asset's own parameters
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), NEIGHBORHOOD, ALICE, 0));
+        System::assert_last_event(
+            Event::<Test>::Claimed {
+                asset_id: NEIGHBORHOOD,
+                who: ALICE,
+                amount: 25,
+                periods: 1,
+                expires_at: 1 + 30,
+                mode: ClaimMode::Standard,
+                period: 0,
+                remaining: 25,
+            }
+            .into(),
+        );
+
+        // Backlog is capped at the asset's 2 periods
+        run_to_block(51);
+        assert_eq!(UbiToken::claimable_amount(&NEIGHBORHOOD, &ALICE), 50);
+    });
+}
+
+#[test]
+fn create_asset_requires_signed_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::create_asset(RuntimeOrigin::none(), NEIGHBORHOOD, neighborhood_params()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn create_asset_rejects_duplicates_and_invalid_parameters() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            UbiToken::create_asset(RuntimeOrigin::signed(BOB), ASSET, neighborhood_params()),
+            Error::<Test>::AssetAlreadyExists
+        );
+
+        let mut params = neighborhood_params();
+        params.claim_period_blocks = 0;
+        assert_noop!(
+            UbiToken::create_asset(RuntimeOrigin::signed(BOB), NEIGHBORHOOD, params),
+            Error::<Test>::InvalidAssetParameters
+        );
     });
 }
 
 #[test]
-fn sybil_attack_is_pointless() {
+fn create_asset_reserves_a_deposit_up_to_max_assets() {
     new_test_ext().execute_with(|| {
-        // Attacker creates many accounts and claims
-        for i in 100..110 {
-            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), i));
-        }
-
-        // Total supply is 1000 (10 accounts * 100)
-        assert_eq!(TotalSupply::<Test>::get(), 1000);
-
-        // But after expiration, all tokens disappear
-        run_to_block(702);
+        // Charlie has no native balance for the deposit
+        assert!(UbiToken::create_asset(RuntimeOrigin::signed(CHARLIE), NEIGHBORHOOD, neighborhood_params()).is_err());
 
-        // All balances are now 0
-        for i in 100..110 {
-            assert_eq!(UbiToken::spendable_balance(&i), 0);
+        for asset_id in [NEIGHBORHOOD, NEIGHBORHOOD + 1, NEIGHBORHOOD + 2] {
+            assert_ok!(UbiToken::create_asset(RuntimeOrigin::signed(BOB), asset_id, neighborhood_params()));
         }
-
-        // Attacker gained nothing - tokens expired
-        // They can only burn tokens, not trade them
+        assert_eq!(NativeBalances::reserved_balance(BOB), 300);
+        assert_eq!(crate::AssetCount::<Test>::get(), 4);
+        assert_noop!(
+            UbiToken::create_asset(RuntimeOrigin::signed(BOB), NEIGHBORHOOD + 3, neighborhood_params()),
+            Error::<Test>::TooManyAssets
+        );
     });
 }
 
 #[test]
-fn exchange_cannot_operate() {
+fn only_owner_can_update_asset_parameters() {
     new_test_ext().execute_with(|| {
-        // User claims tokens
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
+        let mut params = neighborhood_params();
+        params.ubi_amount = 500;
 
-        // User "deposits" to exchange by burning to exchange address
-        let exchange: u64 = 999;
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, exchange, 100));
+        assert_noop!(
+            UbiToken::set_asset_parameters(RuntimeOrigin::signed(BOB), ASSET, params.clone()),
+            Error::<Test>::NotAssetOwner
+        );
+        assert_noop!(
+            UbiToken::set_asset_parameters(RuntimeOrigin::signed(ALICE), NEIGHBORHOOD, params.clone()),
+            Error::<Test>::UnknownAsset
+        );
 
-        // Exchange received NO TOKENS - just a burn event
-        assert_eq!(UbiToken::spendable_balance(&exchange), 0);
+        assert_ok!(UbiToken::set_asset_parameters(RuntimeOrigin::signed(ALICE), ASSET, params));
+        System::assert_last_event(Event::<Test>::AssetParametersUpdated { asset_id: ASSET }.into());
 
-        // Exchange has nothing to sell!
-        // The burn event is proof Alice paid, but exchange cannot transfer anything
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &BOB), 500);
+    });
+}
 
-        // Exchange can claim its own UBI
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), exchange));
-        assert_eq!(UbiToken::spendable_balance(&exchange), 100);
+#[test]
+fn ubi_parameters_follow_governance_updates() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(UbiToken::ubi_parameters(&NEIGHBORHOOD), None);
 
-        // But those are the exchange's own tokens, not "user deposits"
-        // Exchange business model is broken
+        let parameters = UbiToken::ubi_parameters(&ASSET).unwrap();
+        assert_eq!(parameters.asset.ubi_amount, 100);
+        assert_eq!(parameters.asset.claim_period_blocks, 100);
+        assert_eq!(parameters.asset.expiration_blocks, 700);
+        assert_eq!(parameters.reputation.decay_factor, 950);
+
+        let mut params = neighborhood_params();
+        params.ubi_amount = 500;
+        assert_ok!(UbiToken::set_asset_parameters(RuntimeOrigin::signed(ALICE), ASSET, params.clone()));
+        assert_eq!(UbiToken::ubi_parameters(&ASSET).unwrap().asset, params);
     });
 }
 
-// ============================================================================
-// ENHANCED REPUTATION SYSTEM TESTS
-// ============================================================================
-
 #[test]
-fn claim_streak_increments_on_consecutive_claims() {
+fn emission_schedule_changes_claim_amount_at_milestones() {
     new_test_ext().execute_with(|| {
-        // First claim
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.claim_streak, 1);
+        let schedule = vec![
+            EmissionStep { start_period: 2, ubi_amount: 90 },
+            EmissionStep { start_period: 4, ubi_amount: 81 },
+        ];
+        assert_ok!(UbiToken::set_emission_schedule(
+            RuntimeOrigin::signed(ALICE),
+            ASSET,
+            schedule.try_into().unwrap()
+        ));
+        System::assert_last_event(Event::<Test>::EmissionScheduleSet { asset_id: ASSET, steps: 2 }.into());
 
-        // Claim next period
-        run_to_block(101);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.claim_streak, 2);
+        // Before the first step the asset's amount applies
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
 
-        // Claim next period
         run_to_block(201);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.claim_streak, 3);
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 180);
+        assert_eq!(UbiToken::ubi_parameters(&ASSET).unwrap().current_ubi_amount, 90);
+        // Backlog periods are paid at the current amount
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100 + 180);
+
+        run_to_block(401);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100 + 180 + 162);
+
+        // Clearing the schedule goes back to the asset's amount
+        assert_ok!(UbiToken::set_emission_schedule(RuntimeOrigin::signed(ALICE), ASSET, Default::default()));
+        assert_eq!(UbiToken::ubi_parameters(&ASSET).unwrap().current_ubi_amount, 100);
     });
 }
 
 #[test]
-fn claim_streak_respects_grace_period() {
+fn emission_schedule_is_validated() {
     new_test_ext().execute_with(|| {
-        // First claim
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        // Skip 2 periods (within grace period of 2)
-        run_to_block(301);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        let rep = ReputationStore::<Test>::get(ALICE);
-        // Streak should still increment (3 periods passed, grace is 2+1=3 allowed)
-        assert_eq!(rep.claim_streak, 2);
+        let step = |start_period, ubi_amount| EmissionStep { start_period, ubi_amount };
+
+        assert_noop!(
+            UbiToken::set_emission_schedule(RuntimeOrigin::signed(BOB), ASSET, vec![step(1, 50)].try_into().unwrap()),
+            Error::<Test>::NotAssetOwner
+        );
+        assert_noop!(
+            UbiToken::set_emission_schedule(
+                RuntimeOrigin::signed(ALICE),
+                NEIGHBORHOOD,
+                vec![step(1, 50)].try_into().unwrap()
+            ),
+            Error::<Test>::UnknownAsset
+        );
+        assert_noop!(
+            UbiToken::set_emission_schedule(
+                RuntimeOrigin::signed(ALICE),
+                ASSET,
+                vec![step(2, 50), step(2, 40)].try_into().unwrap()
+            ),
+            Error::<Test>::InvalidEmissionSchedule
+        );
+        assert_noop!(
+            UbiToken::set_emission_schedule(RuntimeOrigin::signed(ALICE), ASSET, vec![step(1, 0)].try_into().unwrap()),
+            Error::<Test>::InvalidEmissionSchedule
+        );
     });
 }
 
+fn velocity_policy() -> VelocityPolicy {
+    VelocityPolicy { target_velocity: 500, step: 100, min_adjustment: 800, max_adjustment: 1200 }
+}
+
 #[test]
-fn claim_streak_resets_after_grace_period() {
+fn velocity_is_tracked_without_policy() {
     new_test_ext().execute_with(|| {
-        // Build up streak
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        run_to_block(101);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        run_to_block(201);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.claim_streak, 3);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 25, None, None, 0, None, None));
 
-        // Skip more than grace period (4 periods = 400 blocks)
-        run_to_block(601);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        let rep = ReputationStore::<Test>::get(ALICE);
-        // Streak should reset to 1
-        assert_eq!(rep.claim_streak, 1);
+        let state = UbiToken::velocity(ASSET);
+        assert_eq!((state.period, state.claimed, state.burned), (0, 100, 25));
+        assert_eq!(state.last_velocity, None);
+
+        run_to_block(101);
+        assert_eq!(UbiToken::velocity_at(&ASSET, 1).last_velocity, Some(250));
+        // Without a policy the amount is unchanged
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 100);
     });
 }
 
 #[test]
-fn claim_applies_reputation_decay() {
+fn velocity_policy_adjusts_next_period_amount() {
     new_test_ext().execute_with(|| {
-        // Alice claims and burns to build up score
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
-        
-        let rep_before = ReputationStore::<Test>::get(ALICE);
-        let _score_before = rep_before.score;
-        
-        // Claim again - should apply 5% decay
+        assert_ok!(UbiToken::set_velocity_policy(RuntimeOrigin::root(), ASSET, Some(velocity_policy())));
+
+        // Period 0: 10% of the claims are burned, below the 50% target
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None));
+
         run_to_block(101);
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        let rep_after = ReputationStore::<Test>::get(ALICE);
-        
-        // Score should have decayed (0.95x) then been recalculated
-        // The exact value depends on recalculation, but decay was applied
-        assert!(rep_after.score > 0);
+        assert_eq!(UbiToken::claimable_amount(&ASSET, &ALICE), 90);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        System::assert_has_event(
+            Event::<Test>::VelocityAdjusted { asset_id: ASSET, period: 1, velocity: 100, adjustment: 900 }.into(),
+        );
+
+        // Period 1: the adjustment is bounded by the policy
+        run_to_block(201);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(301);
+        assert_eq!(UbiToken::velocity_at(&ASSET, 3).adjustment, 800);
+        assert_eq!(UbiToken::ubi_parameters(&ASSET).unwrap().current_ubi_amount, 80);
+
+        // Spending above the target raises it again
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        let balance = UbiToken::spendable_balance(&ASSET, &ALICE);
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, balance, None, None, 1, None, None));
+        run_to_block(401);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(UbiToken::velocity(ASSET).adjustment, 900);
+
+        // Removing the policy restores the full amount
+        assert_ok!(UbiToken::set_velocity_policy(RuntimeOrigin::root(), ASSET, None));
+        assert_eq!(UbiToken::velocity(ASSET).adjustment, 1000);
+        assert_eq!(UbiToken::ubi_parameters(&ASSET).unwrap().current_ubi_amount, 100);
     });
 }
 
 #[test]
-fn unique_recipients_tracked_correctly() {
+fn velocity_policy_is_validated() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        // Burn to Bob - first unique recipient
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 20));
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.unique_recipients_count, 1);
-        assert!(UniqueRecipients::<Test>::get(ALICE, BOB));
-        
-        // Burn to Bob again - should NOT increment unique count
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 20));
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.unique_recipients_count, 1);
-        
-        // Burn to Charlie - second unique recipient
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, CHARLIE, 20));
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.unique_recipients_count, 2);
-        assert!(UniqueRecipients::<Test>::get(ALICE, CHARLIE));
+        assert_noop!(
+            UbiToken::set_velocity_policy(RuntimeOrigin::signed(ALICE), ASSET, Some(velocity_policy())),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            UbiToken::set_velocity_policy(RuntimeOrigin::root(), NEIGHBORHOOD, Some(velocity_policy())),
+            Error::<Test>::UnknownAsset
+        );
+        for policy in [
+            VelocityPolicy { step: 0, ..velocity_policy() },
+            VelocityPolicy { min_adjustment: 0, ..velocity_policy() },
+            VelocityPolicy { min_adjustment: 1300, ..velocity_policy() },
+            VelocityPolicy { max_adjustment: MAX_VELOCITY_ADJUSTMENT + 1, ..velocity_policy() },
+        ] {
+            assert_noop!(
+                UbiToken::set_velocity_policy(RuntimeOrigin::root(), ASSET, Some(policy)),
+                Error::<Test>::InvalidVelocityPolicy
+            );
+        }
     });
 }
 
 #[test]
-fn weighted_received_uses_sender_weight() {
+fn claims_are_throttled_near_the_supply_cap() {
+    use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned};
+
     new_test_ext().execute_with(|| {
-        // Alice (new user) claims - gets streak bonus of 10, score = 10
-        // Score of 10 puts her in 10-99 tier = 0.75x weight (750/1000)
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        let alice_rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(alice_rep.score, 10); // streak bonus only
-        
-        // Alice burns 100 to Bob
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 100));
-        
-        let bob_rep = ReputationStore::<Test>::get(BOB);
-        // Alice has score 10, so weight is 0.75x = 750/1000
-        // weighted_received = 100 * 750 / 1000 = 75
-        assert_eq!(bob_rep.weighted_received, 75);
-        
-        // Now test with a zero-score sender (burns before any claim/activity)
-        // This is not possible in normal flow since you need to claim first to get tokens
-        // So the minimum practical sender weight is 0.75x (score 10 from first claim streak)
+        let cap = SupplyCap { ceiling: 250, throttle_from: sp_runtime::Perbill::from_percent(60) };
+        assert_noop!(
+            UbiToken::set_supply_cap(RuntimeOrigin::root(), ASSET, Some(SupplyCap { ceiling: 0, ..cap.clone() })),
+            Error::<Test>::InvalidSupplyCap
+        );
+        assert_ok!(UbiToken::set_supply_cap(RuntimeOrigin::root(), ASSET, Some(cap.clone())));
+        System::assert_last_event(Event::<Test>::SupplyCapSet { asset_id: ASSET, cap: Some(cap) }.into());
+
+        // Up to 60% of the ceiling claims are paid in full
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 200);
+
+        // Half of the throttling band is left, so half of the claim is paid
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, CHARLIE, 0));
+        System::assert_has_event(
+            Event::<Test>::ClaimThrottled { asset_id: ASSET, who: CHARLIE, requested: 100, granted: 50 }.into(),
+        );
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &CHARLIE), 50);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 250);
+
+        // At the ceiling nothing can be claimed until tokens are burned
+        run_to_block(101);
+        assert!(UbiToken::supply_cap_reached(&ASSET));
+        assert_noop!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0), Error::<Test>::SupplyCapReached);
+        let call = crate::Call::<Test>::claim { asset_id: ASSET, account: ALICE, nonce: 0 };
+        assert_eq!(
+            crate::Pallet::<Test>::validate_unsigned(TransactionSource::External, &call),
+            Err(InvalidTransaction::Custom(17).into())
+        );
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 100, None, None, 0, None, None));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 250);
+
+        assert_ok!(UbiToken::set_supply_cap(RuntimeOrigin::root(), ASSET, None));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 350);
     });
 }
 
 #[test]
-fn reputation_score_formula_correct() {
+fn unknown_asset_cannot_be_claimed_or_burned() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        // Burn to 2 unique recipients
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 30));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, CHARLIE, 20));
-        
-        let rep = ReputationStore::<Test>::get(ALICE);
-        
-        // Expected score components:
-        // - unique_recipients: 2 * 50 = 100
-        // - burns_sent_volume: 50 * 1 = 50
-        // - weighted_received: 0 * 2 = 0
-        // - streak_bonus: 1 * 10 = 10
-        // Total: 100 + 50 + 0 + 10 = 160
-        
-        assert_eq!(rep.unique_recipients_count, 2);
-        assert_eq!(rep.burns_sent_volume, 50);
-        assert_eq!(rep.weighted_received, 0);
-        assert_eq!(rep.claim_streak, 1);
-        assert_eq!(rep.score, 160);
+        assert!(!UbiToken::can_claim(&NEIGHBORHOOD, &ALICE));
+        assert_eq!(UbiToken::claimable_amount(&NEIGHBORHOOD, &ALICE), 0);
+
+        assert_noop!(
+            UbiToken::claim(RuntimeOrigin::none(), NEIGHBORHOOD, ALICE, 0),
+            Error::<Test>::UnknownAsset
+        );
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), NEIGHBORHOOD, ALICE, BOB, 10, None, None, 0, None, None),
+            Error::<Test>::UnknownAsset
+        );
     });
 }
 
 #[test]
-fn streak_bonus_caps_at_max() {
+fn assets_have_isolated_balances_and_reputation() {
     new_test_ext().execute_with(|| {
-        // Claim 60 times (more than the 50-day cap)
-        for i in 0..60 {
-            run_to_block(i * 100 + 1);
-            assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        }
-        
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(rep.claim_streak, 60);
-        
-        // Streak bonus should be capped at MAX_STREAK_BONUS (500)
-        // Score = unique(0) + sent(0) + received(0) + streak_bonus(500)
-        // But decay has been applied each time, so score will be different
-        // Just verify streak is tracked correctly
-        assert!(rep.claim_streak >= 50);
+        assert_ok!(UbiToken::create_asset(RuntimeOrigin::signed(BOB), NEIGHBORHOOD, neighborhood_params()));
+
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), NEIGHBORHOOD, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), NEIGHBORHOOD, ALICE, BOB, 20, None, None, 0, None, None));
+
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(UbiToken::spendable_balance(&NEIGHBORHOOD, &ALICE), 5);
+        assert_eq!(TotalSupply::<Test>::get(ASSET), 100);
+        assert_eq!(TotalSupply::<Test>::get(NEIGHBORHOOD), 5);
+
+        // Reputation is tracked per asset
+        assert_eq!(ReputationStore::<Test>::get(NEIGHBORHOOD, BOB).burns_received_volume, 20);
+        assert_eq!(ReputationStore::<Test>::get(ASSET, BOB).burns_received_volume, 0);
+        assert!(UbiToken::has_burned_to(&NEIGHBORHOOD, &ALICE, &BOB));
+        assert!(!UbiToken::has_burned_to(&ASSET, &ALICE, &BOB));
+
+        // Neighborhood balance cannot pay in the main asset
+        assert_noop!(
+            UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 101, None, None, 0, None, None),
+            Error::<Test>::InsufficientBalance
+        );
     });
 }
 
+// ============================================================================
+// MULTI-INSTANCE TESTS
+// ============================================================================
+
 #[test]
-fn bot_farming_yields_low_reputation() {
+fn instances_use_their_own_parameters() {
     new_test_ext().execute_with(|| {
-        // Simulate bot ring: A -> B -> A
-        let bot_a: u64 = 100;
-        let bot_b: u64 = 101;
-        
-        // Both bots claim - each gets score of 10 (streak bonus)
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), bot_a));
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), bot_b));
-        
-        let bot_a_score_before = ReputationStore::<Test>::get(bot_a).score;
-        let bot_b_score_before = ReputationStore::<Test>::get(bot_b).score;
-        assert_eq!(bot_a_score_before, 10); // Just streak bonus
-        assert_eq!(bot_b_score_before, 10);
-        
-        // Bot A burns to Bot B
-        // Bot A has score 10 -> weight 0.75x (750/1000)
-        // weighted_received for B = 100 * 750 / 1000 = 75
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), bot_a, bot_b, 100));
-        
-        // Bot B burns to Bot A
-        // Bot B's score after receiving = 10 (sent) + 75*2 (weighted_received) + 10 (streak) = 170
-        // Actually let me check the actual formula
-        let bot_b_rep_after_receive = ReputationStore::<Test>::get(bot_b);
-        
-        // Bot B burns - their score determines weight
-        // If B's score is in 100-999 range, weight is 1.0x
-        let _bot_b_rep_after_receive = ReputationStore::<Test>::get(bot_b);
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), bot_b, bot_a, 100));
-        
-        let bot_a_rep = ReputationStore::<Test>::get(bot_a);
-        let bot_b_rep = ReputationStore::<Test>::get(bot_b);
-        
-        // Both should have limited weighted_received
-        // Bot A received from Bot B when B had score ~170 (100-999 tier = 1.0x)
-        // Bot B received from Bot A when A had score 10 (10-99 tier = 0.75x)
-        assert_eq!(bot_b_rep.weighted_received, 75);  // From A at 0.75x weight
-        assert_eq!(bot_a_rep.weighted_received, 100); // From B at 1.0x weight
-        
-        // Each has only 1 unique recipient
-        assert_eq!(bot_a_rep.unique_recipients_count, 1);
-        assert_eq!(bot_b_rep.unique_recipients_count, 1);
-        
-        // Key insight: bot farming is limited because:
-        // 1. New bots start with low reputation = low weight
-        // 2. Each bot can only have 1 unique recipient from the other
-        // 3. A legitimate user receiving burns from many different high-rep users
-        //    would accumulate much higher weighted_received
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(CityToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+
+        // Default instance pays 100, city instance pays 10
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(CityToken::spendable_balance(&ASSET, &ALICE), 10);
+
+        // City claim period is 50 blocks, default is 100
+        run_to_block(51);
+        assert!(!UbiToken::can_claim(&ASSET, &ALICE));
+        assert!(CityToken::can_claim(&ASSET, &ALICE));
+
+        // City tokens expire after 200 blocks
+        run_to_block(202);
+        assert_eq!(UbiToken::spendable_balance(&ASSET, &ALICE), 100);
+        assert_eq!(CityToken::spendable_balance(&ASSET, &ALICE), 0);
     });
 }
 
 #[test]
-fn reputation_score_public_api() {
+fn instances_have_isolated_state() {
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
-        
-        // Public API should return the score
-        let score = UbiToken::reputation_score(&ALICE);
-        assert!(score > 0);
-        
-        let rep = ReputationStore::<Test>::get(ALICE);
-        assert_eq!(score, rep.score);
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 50, None, None, 0, None, None));
+
+        // City instance sees none of the default instance's activity
+        assert_eq!(TotalSupply::<Test, Instance1>::get(ASSET), 0);
+        assert_eq!(ReputationStore::<Test, Instance1>::get(ASSET, BOB).burns_received_count, 0);
+        assert!(!CityToken::has_burned_to(&ASSET, &ALICE, &BOB));
+
+        // And cannot spend tokens claimed elsewhere
+        assert_noop!(
+            CityToken::burn(RuntimeOrigin::none(), ASSET, ALICE, BOB, 10, None, None, 0, None, None),
+            Error::<Test, Instance1>::InsufficientBalance
+        );
+
+        // Events carry the instance they came from
+        assert_ok!(CityToken::claim(RuntimeOrigin::none(), ASSET, BOB, 0));
+        System::assert_last_event(
+            Event::<Test, Instance1>::Claimed {
+                asset_id: ASSET,
+                who: BOB,
+                amount: 10,
+                periods: 1,
+                expires_at: 1 + 200,
+                mode: ClaimMode::Standard,
+                period: 0,
+                remaining: 10,
+            }
+            .into(),
+        );
     });
 }
 
 #[test]
-fn has_burned_to_helper_works() {
+fn instances_provide_distinct_pool_tags() {
+    use frame_support::pallet_prelude::{TransactionSource, ValidateUnsigned};
+
     new_test_ext().execute_with(|| {
-        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ALICE));
-        
-        // Before burning
-        assert!(!UbiToken::has_burned_to(&ALICE, &BOB));
-        
-        // After burning
-        assert_ok!(UbiToken::burn(RuntimeOrigin::none(), ALICE, BOB, 50));
-        assert!(UbiToken::has_burned_to(&ALICE, &BOB));
-        
-        // Different recipient still false
-        assert!(!UbiToken::has_burned_to(&ALICE, &CHARLIE));
+        assert_ok!(UbiToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        assert_ok!(CityToken::claim(RuntimeOrigin::none(), ASSET, ALICE, 0));
+        run_to_block(101);
+
+        // Same account claiming on both instances in the same period must not conflict
+        let national = crate::Pallet::<Test>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test>::claim { asset_id: ASSET, account: ALICE, nonce: 0 },
+        )
+        .unwrap();
+        let city = crate::Pallet::<Test, Instance1>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test, Instance1>::claim { asset_id: ASSET, account: ALICE, nonce: 0 },
+        )
+        .unwrap();
+        assert_ne!(national.provides, city.provides);
+
+        // Same for burns from the same sender in the same block
+        let national = crate::Pallet::<Test>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test>::burn {
+                asset_id: ASSET,
+                from: ALICE,
+                to: BOB,
+                amount: 10,
+                category: None,
+                reference: None,
+                nonce: 0,
+                valid_until: None,
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+        let city = crate::Pallet::<Test, Instance1>::validate_unsigned(
+            TransactionSource::External,
+            &crate::Call::<Test, Instance1>::burn {
+                asset_id: ASSET,
+                from: ALICE,
+                to: BOB,
+                amount: 10,
+                category: None,
+                reference: None,
+                nonce: 0,
+                valid_until: None,
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+        assert_ne!(national.provides, city.provides);
     });
 }
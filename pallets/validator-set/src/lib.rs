@@ -0,0 +1,488 @@
+//! # Validator Set Pallet
+//!
+//! Governance-managed set of block authors for the NST chain.
+//!
+//! Without this pallet the Aura and Grandpa authorities are frozen at genesis.
+//! Here, `T::AddRemoveOrigin` (sudo or a governance body) can add and remove
+//! validators at runtime.
+//!
+//! ## How Changes Are Applied
+//!
+//! The pallet is the `SessionManager` of `pallet_session`. Adding or removing
+//! a validator only updates storage and marks the set as changed. When the
+//! session pallet plans the next session it asks for the new validator set,
+//! looks up the session keys each validator registered with `set_keys`, and
+//! hands them to Aura and Grandpa one session later.
+//!
+//! ```text
+//! add_validator / remove_validator → PendingChange = true
+//!                                          ↓
+//!              SessionManager::new_session → pallet_session → Aura / Grandpa
+//! ```
+//!
+//! Only accounts with registered session keys (`T::ValidatorRegistration`)
+//! are handed to the session pallet.
+//!
+//! ## Reputation Seats
+//!
+//! Besides the governance-managed validators, up to `T::ReputationSeats`
+//! authorities are chosen from accounts that registered as candidates. At
+//! every new session the candidates are ranked by their UBI reputation
+//! (`T::Reputation`) and the highest ones with at least
+//! `T::MinCandidateReputation` take the seats. Block authoring thus becomes a
+//! reward for sustained honest activity: a candidate whose reputation decays
+//! below the others loses its seat at the next rotation.
+//!
+//! ```text
+//! authorities = governance validators ++ top candidates by reputation
+//! ```
+//!
+//! ## Offences
+//!
+//! The pallet handles offences reported through `pallet_offences` (e.g. a
+//! Grandpa equivocation proven with a historical session proof). Offenders
+//! are removed from the governance validators and from the candidates, and
+//! leave the authority set at the next session. The last `T::MinValidators`
+//! governance validators are never removed, so the chain keeps its authors.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use frame_support::pallet_prelude::*;
+use frame_support::traits::ValidatorRegistration;
+use frame_system::pallet_prelude::*;
+use sp_runtime::Perbill;
+use sp_staking::{
+    offence::{OffenceDetails, OnOffenceHandler},
+    SessionIndex,
+};
+
+/// Source of the reputation used to rank authority candidates
+pub trait ReputationProvider<AccountId> {
+    /// Current reputation score of an account
+    fn reputation(who: &AccountId) -> u128;
+}
+
+impl<AccountId> ReputationProvider<AccountId> for () {
+    fn reputation(_who: &AccountId) -> u128 {
+        0
+    }
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Configuration trait for the validator set pallet
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// Origin allowed to add and remove validators (e.g. root or governance)
+        type AddRemoveOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Tells whether an account has registered its session keys
+        type ValidatorRegistration: ValidatorRegistration<Self::AccountId>;
+
+        /// Maximum number of validators
+        #[pallet::constant]
+        type MaxValidators: Get<u32>;
+
+        /// Minimum number of validators that must remain in the set
+        #[pallet::constant]
+        type MinValidators: Get<u32>;
+
+        /// Reputation used to rank authority candidates
+        type Reputation: ReputationProvider<Self::AccountId>;
+
+        /// Maximum number of registered authority candidates
+        #[pallet::constant]
+        type MaxCandidates: Get<u32>;
+
+        /// Number of authority seats given to the highest-reputation candidates
+        #[pallet::constant]
+        type ReputationSeats: Get<u32>;
+
+        /// Minimum reputation required to register and to be elected
+        #[pallet::constant]
+        type MinCandidateReputation: Get<u128>;
+    }
+
+    /// Governance-managed validators, in the order they are passed to the session pallet
+    #[pallet::storage]
+    #[pallet::getter(fn validators)]
+    pub type Validators<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxValidators>, ValueQuery>;
+
+    /// Accounts competing for the reputation seats, in registration order
+    #[pallet::storage]
+    #[pallet::getter(fn candidates)]
+    pub type Candidates<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxCandidates>, ValueQuery>;
+
+    /// Candidates currently holding a reputation seat, highest reputation first
+    #[pallet::storage]
+    #[pallet::getter(fn elected_candidates)]
+    pub type ElectedCandidates<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxCandidates>, ValueQuery>;
+
+    /// Whether the validator set changed since it was last handed to the session pallet
+    #[pallet::storage]
+    pub type PendingChange<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// Genesis configuration: validators that exist from block zero
+    ///
+    /// The session pallet is expected to hold the session keys of the same
+    /// accounts in its own genesis config.
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Initial validators
+        pub validators: Vec<T::AccountId>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            let mut validators = BoundedVec::<T::AccountId, T::MaxValidators>::default();
+            for who in &self.validators {
+                assert!(!validators.contains(who), "Duplicate validator in genesis");
+                validators
+                    .try_push(who.clone())
+                    .expect("Too many genesis validators");
+            }
+            Validators::<T>::put(validators);
+        }
+    }
+
+    /// Events emitted by this pallet
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A validator was added to the set
+        ValidatorAdded { who: T::AccountId },
+        /// A validator was removed from the set
+        ValidatorRemoved { who: T::AccountId },
+        /// The new validator set was handed to the session pallet
+        ValidatorSetApplied { count: u32 },
+        /// An account registered as authority candidate
+        CandidateRegistered { who: T::AccountId },
+        /// An account withdrew its authority candidacy
+        CandidateDeregistered { who: T::AccountId },
+        /// The reputation seats were re-elected and changed
+        ReputationSeatsElected { elected: Vec<T::AccountId> },
+        /// A reported offender was removed from the validators and candidates
+        OffenderRemoved { who: T::AccountId },
+    }
+
+    /// Errors that can occur in this pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account is already a validator
+        AlreadyValidator,
+        /// The account is not a validator
+        NotValidator,
+        /// The validator set is full
+        TooManyValidators,
+        /// Removing the validator would leave too few validators
+        TooFewValidators,
+        /// The account is already an authority candidate
+        AlreadyCandidate,
+        /// The account is not an authority candidate
+        NotCandidate,
+        /// The candidate list is full
+        TooManyCandidates,
+        /// The account's reputation is below `MinCandidateReputation`
+        InsufficientReputation,
+        /// The account has not registered its session keys
+        NoSessionKeys,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Add a new validator
+        ///
+        /// The validator starts authoring once the session pallet rotates it
+        /// in, provided it has registered its session keys by then.
+        ///
+        /// # Errors
+        /// - `AlreadyValidator` if the account is already in the set
+        /// - `TooManyValidators` if the set is full
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+        pub fn add_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::AddRemoveOrigin::ensure_origin(origin)?;
+
+            ensure!(!Self::is_candidate(&who), Error::<T>::AlreadyCandidate);
+            Validators::<T>::try_mutate(|validators| {
+                ensure!(!validators.contains(&who), Error::<T>::AlreadyValidator);
+                validators
+                    .try_push(who.clone())
+                    .map_err(|_| Error::<T>::TooManyValidators)
+            })?;
+            PendingChange::<T>::put(true);
+
+            Self::deposit_event(Event::ValidatorAdded { who });
+            Ok(())
+        }
+
+        /// Remove a validator from the set
+        ///
+        /// The validator stops authoring once the session pallet rotates it out.
+        ///
+        /// # Errors
+        /// - `NotValidator` if the account is not in the set
+        /// - `TooFewValidators` if the set would drop below `MinValidators`
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 2))]
+        pub fn remove_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::AddRemoveOrigin::ensure_origin(origin)?;
+
+            Validators::<T>::try_mutate(|validators| -> DispatchResult {
+                let index = validators
+                    .iter()
+                    .position(|v| v == &who)
+                    .ok_or(Error::<T>::NotValidator)?;
+                ensure!(
+                    validators.len() as u32 > T::MinValidators::get(),
+                    Error::<T>::TooFewValidators
+                );
+                validators.remove(index);
+                Ok(())
+            })?;
+            PendingChange::<T>::put(true);
+
+            Self::deposit_event(Event::ValidatorRemoved { who });
+            Ok(())
+        }
+
+        /// Register as candidate for a reputation seat
+        ///
+        /// The candidate takes part in the next election if its reputation is
+        /// still at least `MinCandidateReputation` by then.
+        ///
+        /// # Errors
+        /// - `AlreadyValidator` / `AlreadyCandidate` if the account already takes part
+        /// - `NoSessionKeys` if the account has not called `session.set_keys`
+        /// - `InsufficientReputation` if the reputation is below the minimum
+        /// - `TooManyCandidates` if the candidate list is full
+        #[pallet::call_index(2)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 1))]
+        pub fn register_candidate(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(!Self::is_validator(&who), Error::<T>::AlreadyValidator);
+            ensure!(T::ValidatorRegistration::is_registered(&who), Error::<T>::NoSessionKeys);
+            ensure!(
+                T::Reputation::reputation(&who) >= T::MinCandidateReputation::get(),
+                Error::<T>::InsufficientReputation
+            );
+            Candidates::<T>::try_mutate(|candidates| {
+                ensure!(!candidates.contains(&who), Error::<T>::AlreadyCandidate);
+                candidates
+                    .try_push(who.clone())
+                    .map_err(|_| Error::<T>::TooManyCandidates)
+            })?;
+
+            Self::deposit_event(Event::CandidateRegistered { who });
+            Ok(())
+        }
+
+        /// Withdraw an authority candidacy
+        ///
+        /// A candidate holding a seat leaves the authority set at the next session.
+        ///
+        /// # Errors
+        /// - `NotCandidate` if the account is not a candidate
+        #[pallet::call_index(3)]
+        #[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 3))]
+        pub fn deregister_candidate(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Candidates::<T>::try_mutate(|candidates| -> DispatchResult {
+                let index = candidates
+                    .iter()
+                    .position(|c| c == &who)
+                    .ok_or(Error::<T>::NotCandidate)?;
+                candidates.remove(index);
+                Ok(())
+            })?;
+
+            let mut elected = ElectedCandidates::<T>::get();
+            if let Some(index) = elected.iter().position(|c| c == &who) {
+                elected.remove(index);
+                ElectedCandidates::<T>::put(elected);
+                PendingChange::<T>::put(true);
+            }
+
+            Self::deposit_event(Event::CandidateDeregistered { who });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Current authorities: governance validators first, then the elected
+        /// candidates, skipping accounts without registered session keys
+        pub fn authorities() -> Vec<T::AccountId> {
+            Validators::<T>::get()
+                .into_iter()
+                .chain(ElectedCandidates::<T>::get())
+                .filter(T::ValidatorRegistration::is_registered)
+                .collect()
+        }
+
+        /// Re-elect the reputation seats
+        ///
+        /// Candidates are ranked by reputation; ties are won by the earlier
+        /// registration. Candidates without session keys are not eligible.
+        pub(crate) fn elect_reputation_seats() {
+            let candidates = Candidates::<T>::get();
+            let min_reputation = T::MinCandidateReputation::get();
+
+            let mut ranked: Vec<(u128, T::AccountId)> = candidates
+                .iter()
+                .filter(|who| T::ValidatorRegistration::is_registered(who))
+                .filter_map(|who| {
+                    let reputation = T::Reputation::reputation(who);
+                    (reputation >= min_reputation).then(|| (reputation, who.clone()))
+                })
+                .collect();
+            // Stable sort keeps registration order among equal reputations
+            ranked.sort_by_key(|(reputation, _)| core::cmp::Reverse(*reputation));
+
+            let elected: BoundedVec<T::AccountId, T::MaxCandidates> = BoundedVec::truncate_from(
+                ranked
+                    .into_iter()
+                    .take(T::ReputationSeats::get() as usize)
+                    .map(|(_, who)| who)
+                    .collect(),
+            );
+
+            if elected != ElectedCandidates::<T>::get() {
+                ElectedCandidates::<T>::put(&elected);
+                PendingChange::<T>::put(true);
+                Self::deposit_event(Event::ReputationSeatsElected { elected: elected.into_inner() });
+            }
+        }
+
+        /// Check if an account is a governance-managed validator
+        pub fn is_validator(who: &T::AccountId) -> bool {
+            Validators::<T>::get().contains(who)
+        }
+
+        /// Remove a reported offender from every role it holds
+        ///
+        /// Returns whether anything was removed.
+        pub(crate) fn remove_offender(who: &T::AccountId) -> bool {
+            let mut removed = false;
+            Validators::<T>::mutate(|validators| {
+                if let Some(index) = validators.iter().position(|v| v == who) {
+                    if validators.len() as u32 > T::MinValidators::get() {
+                        validators.remove(index);
+                        removed = true;
+                    }
+                }
+            });
+            Candidates::<T>::mutate(|candidates| {
+                if let Some(index) = candidates.iter().position(|c| c == who) {
+                    candidates.remove(index);
+                    removed = true;
+                }
+            });
+            ElectedCandidates::<T>::mutate(|elected| {
+                if let Some(index) = elected.iter().position(|c| c == who) {
+                    elected.remove(index);
+                    removed = true;
+                }
+            });
+
+            if removed {
+                PendingChange::<T>::put(true);
+                Self::deposit_event(Event::OffenderRemoved { who: who.clone() });
+            }
+            removed
+        }
+
+        /// Check if an account is an authority candidate
+        pub fn is_candidate(who: &T::AccountId) -> bool {
+            Candidates::<T>::get().contains(who)
+        }
+    }
+}
+
+impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
+    /// Elect the reputation seats and hand over the authorities if they changed
+    fn new_session(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+        Self::elect_reputation_seats();
+        if !PendingChange::<T>::take() {
+            return None;
+        }
+
+        let authorities = Self::authorities();
+        if authorities.is_empty() {
+            // Never leave the chain without authors; keep the current set
+            return None;
+        }
+        Self::deposit_event(Event::ValidatorSetApplied { count: authorities.len() as u32 });
+        Some(authorities)
+    }
+
+    fn new_session_genesis(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+        let authorities = Self::authorities();
+        (!authorities.is_empty()).then_some(authorities)
+    }
+
+    fn end_session(_end_index: SessionIndex) {}
+
+    fn start_session(_start_index: SessionIndex) {}
+}
+
+/// Historical variant used with `pallet_session::historical::NoteHistoricalRoot`
+///
+/// The full identification of a validator is its account id.
+impl<T: Config> pallet_session::historical::SessionManager<T::AccountId, T::AccountId> for Pallet<T> {
+    fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, T::AccountId)>> {
+        <Self as pallet_session::SessionManager<_>>::new_session(new_index)
+            .map(|validators| validators.into_iter().map(|v| (v.clone(), v)).collect())
+    }
+
+    fn new_session_genesis(new_index: SessionIndex) -> Option<Vec<(T::AccountId, T::AccountId)>> {
+        <Self as pallet_session::SessionManager<_>>::new_session_genesis(new_index)
+            .map(|validators| validators.into_iter().map(|v| (v.clone(), v)).collect())
+    }
+
+    fn start_session(_start_index: SessionIndex) {}
+
+    fn end_session(_end_index: SessionIndex) {}
+}
+
+/// Removes offenders reported through `pallet_offences`
+///
+/// Offenders are identified as `(validator id, full identification)`, as
+/// produced by `pallet_session::historical`. Slash fractions are ignored: any
+/// proven offence costs the offender its place in the authority set.
+impl<T: Config> OnOffenceHandler<T::AccountId, (T::AccountId, T::AccountId), Weight> for Pallet<T> {
+    fn on_offence(
+        offenders: &[OffenceDetails<T::AccountId, (T::AccountId, T::AccountId)>],
+        _slash_fraction: &[Perbill],
+        _session: SessionIndex,
+    ) -> Weight {
+        let removed = offenders
+            .iter()
+            .filter(|details| Self::remove_offender(&details.offender.0))
+            .count() as u64;
+
+        T::DbWeight::get().reads_writes(3 * offenders.len() as u64, 4 * removed)
+    }
+}
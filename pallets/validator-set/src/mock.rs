@@ -0,0 +1,121 @@
+use crate as pallet_validator_set;
+use frame_support::{
+    derive_impl,
+    parameter_types,
+    traits::{ConstU128, ConstU16, ConstU32, ConstU64},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        ValidatorSet: pallet_validator_set,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    /// Accounts that have not registered session keys
+    pub storage Unregistered: Vec<u64> = Vec::new();
+}
+
+parameter_types! {
+    /// Reputation of each account, as reported by the (fake) UBI pallet
+    pub storage Reputations: Vec<(u64, u128)> = Vec::new();
+}
+
+/// Set the reputation returned for an account
+pub fn set_reputation(who: u64, reputation: u128) {
+    let mut reputations = Reputations::get();
+    reputations.retain(|(account, _)| *account != who);
+    reputations.push((who, reputation));
+    Reputations::set(&reputations);
+}
+
+/// Reads reputation from the `Reputations` test storage
+pub struct TestReputation;
+impl crate::ReputationProvider<u64> for TestReputation {
+    fn reputation(who: &u64) -> u128 {
+        Reputations::get()
+            .into_iter()
+            .find(|(account, _)| account == who)
+            .map(|(_, reputation)| reputation)
+            .unwrap_or(0)
+    }
+}
+
+/// Treats every account as registered unless listed in `Unregistered`
+pub struct TestRegistration;
+impl frame_support::traits::ValidatorRegistration<u64> for TestRegistration {
+    fn is_registered(who: &u64) -> bool {
+        !Unregistered::get().contains(who)
+    }
+}
+
+impl pallet_validator_set::Config for Test {
+    type AddRemoveOrigin = EnsureRoot<u64>;
+    type ValidatorRegistration = TestRegistration;
+    type MaxValidators = ConstU32<3>;
+    type MinValidators = ConstU32<1>;
+    type Reputation = TestReputation;
+    type MaxCandidates = ConstU32<4>;
+    type ReputationSeats = ConstU32<2>;
+    type MinCandidateReputation = ConstU128<100>;
+}
+
+// Test accounts
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+pub const EVE: u64 = 5;
+pub const FERDIE: u64 = 6;
+
+/// Build genesis storage with ALICE as the only validator
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = RuntimeGenesisConfig {
+        system: Default::default(),
+        validator_set: pallet_validator_set::GenesisConfig {
+            validators: vec![ALICE],
+        },
+    }
+    .build_storage()
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
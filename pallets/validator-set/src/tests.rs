@@ -0,0 +1,324 @@
+use crate::{mock::*, Error, Event, PendingChange};
+use frame_support::{assert_noop, assert_ok};
+use pallet_session::SessionManager;
+use sp_runtime::{DispatchError, Perbill};
+use sp_staking::offence::{OffenceDetails, OnOffenceHandler};
+
+#[test]
+fn genesis_validators_are_set() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ValidatorSet::validators().to_vec(), vec![ALICE]);
+        assert!(ValidatorSet::is_validator(&ALICE));
+        assert!(!PendingChange::<Test>::get());
+        assert_eq!(ValidatorSet::new_session_genesis(0), Some(vec![ALICE]));
+    });
+}
+
+#[test]
+fn add_validator_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), BOB));
+
+        assert_eq!(ValidatorSet::validators().to_vec(), vec![ALICE, BOB]);
+        assert_eq!(ValidatorSet::authorities(), vec![ALICE, BOB]);
+        assert!(PendingChange::<Test>::get());
+        System::assert_last_event(Event::ValidatorAdded { who: BOB }.into());
+    });
+}
+
+#[test]
+fn only_add_remove_origin_can_change_set() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ValidatorSet::add_validator(RuntimeOrigin::signed(ALICE), BOB),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ValidatorSet::remove_validator(RuntimeOrigin::signed(ALICE), ALICE),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn add_validator_rejects_duplicates_and_full_set() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ValidatorSet::add_validator(RuntimeOrigin::root(), ALICE),
+            Error::<Test>::AlreadyValidator
+        );
+
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), BOB));
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), CHARLIE));
+        assert_noop!(
+            ValidatorSet::add_validator(RuntimeOrigin::root(), DAVE),
+            Error::<Test>::TooManyValidators
+        );
+    });
+}
+
+#[test]
+fn remove_validator_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), BOB));
+        assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), ALICE));
+
+        assert_eq!(ValidatorSet::validators().to_vec(), vec![BOB]);
+        assert!(!ValidatorSet::is_validator(&ALICE));
+        System::assert_last_event(Event::ValidatorRemoved { who: ALICE }.into());
+    });
+}
+
+#[test]
+fn remove_validator_respects_minimum() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ValidatorSet::remove_validator(RuntimeOrigin::root(), ALICE),
+            Error::<Test>::TooFewValidators
+        );
+        assert_noop!(
+            ValidatorSet::remove_validator(RuntimeOrigin::root(), BOB),
+            Error::<Test>::NotValidator
+        );
+    });
+}
+
+#[test]
+fn change_is_handed_to_session_pallet() {
+    new_test_ext().execute_with(|| {
+        // Nothing changed, the session pallet keeps the current set
+        assert_eq!(ValidatorSet::new_session(1), None);
+
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), BOB));
+        assert_eq!(ValidatorSet::new_session(2), Some(vec![ALICE, BOB]));
+        assert!(!PendingChange::<Test>::get());
+        System::assert_last_event(Event::ValidatorSetApplied { count: 2 }.into());
+
+        assert_eq!(ValidatorSet::new_session(3), None);
+    });
+}
+
+#[test]
+fn validators_without_session_keys_are_skipped() {
+    new_test_ext().execute_with(|| {
+        Unregistered::set(&vec![BOB]);
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), BOB));
+        assert_eq!(ValidatorSet::new_session(1), Some(vec![ALICE]));
+
+        // Nobody with keys left: the current set is kept
+        Unregistered::set(&vec![ALICE, BOB]);
+        assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), ALICE));
+        assert_eq!(ValidatorSet::new_session(2), None);
+    });
+}
+
+// ============================================================================
+// REPUTATION SEAT TESTS
+// ============================================================================
+
+#[test]
+fn register_candidate_requires_reputation() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 99);
+        assert_noop!(
+            ValidatorSet::register_candidate(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::InsufficientReputation
+        );
+
+        set_reputation(BOB, 100);
+        assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(BOB)));
+        assert_eq!(ValidatorSet::candidates().to_vec(), vec![BOB]);
+        System::assert_last_event(Event::CandidateRegistered { who: BOB }.into());
+    });
+}
+
+#[test]
+fn register_candidate_requires_session_keys() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 500);
+        Unregistered::set(&vec![BOB]);
+        assert_noop!(
+            ValidatorSet::register_candidate(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::NoSessionKeys
+        );
+    });
+}
+
+#[test]
+fn register_candidate_rejects_existing_participants() {
+    new_test_ext().execute_with(|| {
+        set_reputation(ALICE, 500);
+        set_reputation(BOB, 500);
+
+        assert_noop!(
+            ValidatorSet::register_candidate(RuntimeOrigin::signed(ALICE)),
+            Error::<Test>::AlreadyValidator
+        );
+
+        assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(BOB)));
+        assert_noop!(
+            ValidatorSet::register_candidate(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::AlreadyCandidate
+        );
+        assert_noop!(
+            ValidatorSet::add_validator(RuntimeOrigin::root(), BOB),
+            Error::<Test>::AlreadyCandidate
+        );
+    });
+}
+
+#[test]
+fn candidate_list_is_bounded() {
+    new_test_ext().execute_with(|| {
+        for who in [BOB, CHARLIE, DAVE, EVE, FERDIE] {
+            set_reputation(who, 200);
+        }
+        for who in [BOB, CHARLIE, DAVE, EVE] {
+            assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(who)));
+        }
+        assert_noop!(
+            ValidatorSet::register_candidate(RuntimeOrigin::signed(FERDIE)),
+            Error::<Test>::TooManyCandidates
+        );
+    });
+}
+
+#[test]
+fn highest_reputation_candidates_take_the_seats() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 300);
+        set_reputation(CHARLIE, 1000);
+        set_reputation(DAVE, 500);
+        for who in [BOB, CHARLIE, DAVE] {
+            assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(who)));
+        }
+
+        // Governance validators come first, then the elected candidates
+        assert_eq!(ValidatorSet::new_session(1), Some(vec![ALICE, CHARLIE, DAVE]));
+        assert_eq!(ValidatorSet::elected_candidates().to_vec(), vec![CHARLIE, DAVE]);
+        System::assert_has_event(
+            Event::ReputationSeatsElected { elected: vec![CHARLIE, DAVE] }.into(),
+        );
+    });
+}
+
+#[test]
+fn candidates_without_session_keys_are_not_elected() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 300);
+        set_reputation(CHARLIE, 200);
+        for who in [BOB, CHARLIE] {
+            assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(who)));
+        }
+
+        // Bob purges the session keys after registering
+        Unregistered::set(&vec![BOB]);
+        assert_eq!(ValidatorSet::new_session(1), Some(vec![ALICE, CHARLIE]));
+    });
+}
+
+#[test]
+fn decayed_candidates_lose_their_seat() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 300);
+        set_reputation(CHARLIE, 200);
+        for who in [BOB, CHARLIE] {
+            assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(who)));
+        }
+        assert_eq!(ValidatorSet::new_session(1), Some(vec![ALICE, BOB, CHARLIE]));
+
+        // Bob's reputation decays below the minimum
+        set_reputation(BOB, 50);
+        assert_eq!(ValidatorSet::new_session(2), Some(vec![ALICE, CHARLIE]));
+        assert_eq!(ValidatorSet::elected_candidates().to_vec(), vec![CHARLIE]);
+
+        // Unchanged result does not trigger another authority change
+        assert_eq!(ValidatorSet::new_session(3), None);
+    });
+}
+
+#[test]
+fn deregister_candidate_frees_the_seat() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 300);
+        assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(BOB)));
+        assert_eq!(ValidatorSet::new_session(1), Some(vec![ALICE, BOB]));
+
+        assert_ok!(ValidatorSet::deregister_candidate(RuntimeOrigin::signed(BOB)));
+        assert!(ValidatorSet::candidates().is_empty());
+        assert!(ValidatorSet::elected_candidates().is_empty());
+        System::assert_last_event(Event::CandidateDeregistered { who: BOB }.into());
+
+        assert_eq!(ValidatorSet::new_session(2), Some(vec![ALICE]));
+
+        assert_noop!(
+            ValidatorSet::deregister_candidate(RuntimeOrigin::signed(BOB)),
+            Error::<Test>::NotCandidate
+        );
+    });
+}
+
+// ============================================================================
+// OFFENCE TESTS
+// ============================================================================
+
+/// Report an offence by the given accounts
+fn report(offenders: &[u64]) {
+    let details: Vec<_> = offenders
+        .iter()
+        .map(|who| OffenceDetails { offender: (*who, *who), reporters: vec![] })
+        .collect();
+    let fractions = vec![Perbill::zero(); details.len()];
+    <ValidatorSet as OnOffenceHandler<_, _, _>>::on_offence(&details, &fractions, 0);
+}
+
+#[test]
+fn offending_validator_is_removed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), BOB));
+        assert_eq!(ValidatorSet::new_session(1), Some(vec![ALICE, BOB]));
+
+        report(&[BOB]);
+
+        assert_eq!(ValidatorSet::validators().to_vec(), vec![ALICE]);
+        System::assert_last_event(Event::OffenderRemoved { who: BOB }.into());
+        assert_eq!(ValidatorSet::new_session(2), Some(vec![ALICE]));
+    });
+}
+
+#[test]
+fn offending_candidate_loses_seat_and_candidacy() {
+    new_test_ext().execute_with(|| {
+        set_reputation(BOB, 300);
+        assert_ok!(ValidatorSet::register_candidate(RuntimeOrigin::signed(BOB)));
+        assert_eq!(ValidatorSet::new_session(1), Some(vec![ALICE, BOB]));
+
+        report(&[BOB]);
+
+        assert!(ValidatorSet::candidates().is_empty());
+        assert!(ValidatorSet::elected_candidates().is_empty());
+        assert_eq!(ValidatorSet::new_session(2), Some(vec![ALICE]));
+    });
+}
+
+#[test]
+fn offences_never_drop_below_minimum_validators() {
+    new_test_ext().execute_with(|| {
+        report(&[ALICE]);
+
+        assert_eq!(ValidatorSet::validators().to_vec(), vec![ALICE]);
+        assert!(!PendingChange::<Test>::get());
+
+        // Repeated reports of an already removed offender are ignored
+        assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), BOB));
+        report(&[BOB]);
+        report(&[BOB]);
+        assert_eq!(
+            System::events()
+                .iter()
+                .filter(|r| r.event == Event::OffenderRemoved { who: BOB }.into())
+                .count(),
+            1
+        );
+    });
+}
@@ -3,6 +3,10 @@
 //! This runtime implements a blockchain with a burn-only UBI token.
 //! Tokens are distributed daily to any wallet, can only be burned (not transferred),
 //! and expire after 7 days. This design prevents speculation and trading.
+//!
+//! Building with the `parachain` feature produces a Cumulus flavor of the runtime
+//! (parachain-system, parachain-info and aura-ext) that can be registered as a
+//! Polkadot/Kusama parachain. The default build remains a standalone solochain.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -14,20 +18,20 @@ pub const WASM_BINARY: Option<&[u8]> = None;
 
 extern crate alloc;
 
-use alloc::{vec, vec::Vec};
+use alloc::{borrow::Cow, vec, vec::Vec};
 use frame_support::{
     construct_runtime, derive_impl, parameter_types,
-    traits::{ConstU16, ConstU32, ConstU8},
+    traits::{ConstU128, ConstU32, ConstU8, Get},
     weights::constants::RocksDbWeight,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
-    create_runtime_str, generic, impl_opaque_keys,
+    generic, impl_opaque_keys,
     traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, NumberFor},
     transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, ExtrinsicInclusionMode,
+    ApplyExtrinsicResult, ExtrinsicInclusionMode, Perbill,
 };
 
 #[cfg(feature = "std")]
@@ -63,14 +67,18 @@ pub type BlockNumber = u32;
 pub type UncheckedExtrinsic =
     generic::UncheckedExtrinsic<sp_runtime::MultiAddress<AccountId, ()>, RuntimeCall, Signature, SignedExtra>;
 
+/// Storage migrations run across blocks after the next runtime upgrade
+pub type Migrations = (
+    pallet_ubi_token::migrations::v1::LazyMigrationV0ToV1<Runtime, NstAssetId, NstAssetOwner>,
+    pallet_ubi_token::migrations::v2::LazyMigrationV1ToV2<Runtime>,
+    pallet_ubi_token::migrations::v3::LazyMigrationV2ToV3<Runtime>,
+    pallet_ubi_token::migrations::v4::LazyMigrationV3ToV4<Runtime>,
+    pallet_ubi_token::migrations::v5::LazyMigrationV4ToV5<Runtime>,
+);
+
 /// Executive type for dispatching transactions
-pub type Executive = frame_executive::Executive<
-    Runtime,
-    Block,
-    frame_system::ChainContext<Runtime>,
-    Runtime,
-    AllPalletsWithSystem,
->;
+pub type Executive =
+    frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPalletsWithSystem>;
 
 /// Signed extra data attached to transactions
 pub type SignedExtra = (
@@ -87,10 +95,10 @@ pub type SignedExtra = (
 /// Runtime version
 #[sp_version::runtime_version]
 pub const VERSION: RuntimeVersion = RuntimeVersion {
-    spec_name: create_runtime_str!("nst"),
-    impl_name: create_runtime_str!("nst"),
+    spec_name: Cow::Borrowed("nst"),
+    impl_name: Cow::Borrowed("nst"),
     authoring_version: 1,
-    spec_version: 100,
+    spec_version: 101,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 1,
@@ -106,14 +114,28 @@ pub fn native_version() -> NativeVersion {
     }
 }
 
+/// Address format of NST accounts.
+///
+/// 42 is the generic Substrate entry of the SS58 registry. A dedicated prefix
+/// must be registered there before this is changed, or wallets will not
+/// recognise the addresses.
+pub const SS58_PREFIX: u16 = 42;
+
+/// Symbol of the main NST asset, advertised to wallets in the chain spec
+pub const TOKEN_SYMBOL: &str = "NST";
+
+/// Decimals of the main NST asset (one NST is `10^9` of the smallest unit)
+pub const TOKEN_DECIMALS: u8 = 9;
+
 parameter_types! {
     pub const BlockHashCount: BlockNumber = 2400;
     pub const Version: RuntimeVersion = VERSION;
+    pub const SS58Prefix: u16 = SS58_PREFIX;
 }
 
 #[derive_impl(frame_system::config_preludes::SolochainDefaultConfig)]
 impl frame_system::Config for Runtime {
-    type BaseCallFilter = frame_support::traits::Everything;
+    type BaseCallFilter = CallPause;
     type BlockWeights = ();
     type BlockLength = ();
     type DbWeight = RocksDbWeight;
@@ -131,11 +153,15 @@ impl frame_system::Config for Runtime {
     type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<Balance>;
     type OnNewAccount = ();
-    type OnKilledAccount = ();
+    type OnKilledAccount = UbiToken;
     type SystemWeightInfo = ();
-    type SS58Prefix = ConstU16<42>;
+    type SS58Prefix = SS58Prefix;
+    #[cfg(not(feature = "parachain"))]
     type OnSetCode = ();
+    #[cfg(feature = "parachain")]
+    type OnSetCode = cumulus_pallet_parachain_system::ParachainSetCode<Self>;
     type MaxConsumers = ConstU32<16>;
+    type MultiBlockMigrator = MultiBlockMigrations;
 }
 
 parameter_types! {
@@ -166,7 +192,7 @@ impl pallet_balances::Config for Runtime {
     type WeightInfo = ();
     type FreezeIdentifier = ();
     type MaxFreezes = ();
-    type RuntimeHoldReason = ();
+    type RuntimeHoldReason = RuntimeHoldReason;
     type RuntimeFreezeReason = ();
     type DoneSlashHandler = ();
 }
@@ -187,6 +213,216 @@ impl pallet_sudo::Config for Runtime {
     type WeightInfo = ();
 }
 
+// ============================================================================
+// REFERENDA
+// ============================================================================
+
+parameter_types! {
+    /// Votes are counted seven claim periods after submission
+    pub const ReferendumVotingPeriod: BlockNumber = 7 * ClaimPeriodBlocks::get();
+    /// Reputation needed to put a call to a referendum
+    pub const MinProposerReputation: u128 = 1_000;
+    /// Reputation needed to vote, far beyond what a fresh account gets from its first claims
+    pub const MinVoterReputation: u128 = 300;
+    /// Approved proposals are dispatched in `on_initialize`; sixteen at one deadline fit in 80% of a block
+    pub MaxProposalWeight: frame_support::weights::Weight = Perbill::from_percent(5)
+        * <<Runtime as frame_system::Config>::BlockWeights as Get<frame_system::limits::BlockWeights>>::get().max_block;
+    /// Reputation that must vote for a referendum to pass
+    pub const MinReferendumTurnout: u128 = 10_000;
+}
+
+impl pallet_reputation_referenda::Config for Runtime {
+    type RuntimeCall = RuntimeCall;
+    type Reputation = UbiReputation;
+    type CancelOrigin = frame_system::EnsureRoot<AccountId>;
+    type VotingPeriod = ReferendumVotingPeriod;
+    type MinProposerReputation = MinProposerReputation;
+    type MinVoterReputation = MinVoterReputation;
+    type MinTurnout = MinReferendumTurnout;
+    type MaxOngoing = ConstU32<16>;
+    type MaxVoters = ConstU32<1_000>;
+    type MaxProposalLen = ConstU32<4096>;
+    type MaxProposalWeight = MaxProposalWeight;
+}
+
+// ============================================================================
+// TECHNICAL COMMITTEE
+// ============================================================================
+
+/// Emergency actions the technical committee can take without a referendum
+///
+/// Pausing and unpausing single calls, and rolling a UBI parameter back to
+/// its default.
+pub struct EmergencyCalls;
+impl frame_support::traits::Contains<RuntimeCall> for EmergencyCalls {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(
+            call,
+            RuntimeCall::CallPause(_) | RuntimeCall::UbiToken(pallet_ubi_token::Call::reset_parameter { .. })
+        )
+    }
+}
+
+parameter_types! {
+    /// Motions stay open for one claim period
+    pub const MotionDuration: BlockNumber = ClaimPeriodBlocks::get();
+    /// Emergency calls are light; a tenth of a second is plenty
+    pub MaxMotionWeight: frame_support::weights::Weight =
+        frame_support::weights::Weight::from_parts(100_000_000_000, 64 * 1024);
+}
+
+impl pallet_technical_committee::Config for Runtime {
+    type RuntimeCall = RuntimeCall;
+    type MembershipOrigin = frame_system::EnsureRoot<AccountId>;
+    type AllowedCalls = EmergencyCalls;
+    type MinApprovals = ConstU32<3>;
+    type MotionDuration = MotionDuration;
+    type MaxMembers = ConstU32<7>;
+    type MaxMotions = ConstU32<16>;
+    type MaxCallLen = ConstU32<512>;
+    type MaxCallWeight = MaxMotionWeight;
+}
+
+// ============================================================================
+// COMMITTEE ELECTIONS
+// ============================================================================
+
+parameter_types! {
+    /// The technical committee is re-elected every week
+    pub const CommitteeTermDuration: BlockNumber = 7 * ClaimPeriodBlocks::get();
+}
+
+impl pallet_committee_elections::Config for Runtime {
+    type Reputation = UbiReputation;
+    type ChangeMembers = TechnicalCommittee;
+    type TermDuration = CommitteeTermDuration;
+    type DesiredMembers = ConstU32<5>;
+    type MinCandidacyReputation = ConstU128<5_000>;
+    type MinVoterReputation = MinVoterReputation;
+    type MaxCandidates = ConstU32<64>;
+    type MaxVoters = ConstU32<1_000>;
+    type MaxVotesPerVoter = ConstU32<5>;
+}
+
+// ============================================================================
+// RANKED COUNCIL
+// ============================================================================
+
+/// Calls the ranked council can dispatch without a referendum
+///
+/// Tuning and resetting UBI parameters within their governed bounds.
+pub struct CouncilCalls;
+impl frame_support::traits::Contains<RuntimeCall> for CouncilCalls {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(
+            call,
+            RuntimeCall::UbiToken(
+                pallet_ubi_token::Call::set_parameter { .. } | pallet_ubi_token::Call::reset_parameter { .. }
+            )
+        )
+    }
+}
+
+parameter_types! {
+    /// Reputation needed to vote, propose and veto
+    pub const CouncilRankThresholds: [u128; pallet_ranked_council::RANKS] = [1_000, 5_000, 20_000];
+    /// Ranks follow reputation once per claim period
+    pub const CouncilSyncPeriod: BlockNumber = ClaimPeriodBlocks::get();
+    /// Motions stay open for three claim periods
+    pub const CouncilVotingPeriod: BlockNumber = 3 * ClaimPeriodBlocks::get();
+}
+
+impl pallet_ranked_council::Config for Runtime {
+    type RuntimeCall = RuntimeCall;
+    type Reputation = UbiReputation;
+    type AllowedCalls = CouncilCalls;
+    type RankThresholds = CouncilRankThresholds;
+    type SyncPeriod = CouncilSyncPeriod;
+    type VotingPeriod = CouncilVotingPeriod;
+    type MinApprovalWeight = ConstU32<6>;
+    type MaxMembers = ConstU32<64>;
+    type MaxMotions = ConstU32<16>;
+    type MaxCallLen = ConstU32<512>;
+    type MaxCallWeight = MaxMotionWeight;
+}
+
+// ============================================================================
+// CALL PAUSE
+// ============================================================================
+
+/// Calls that stay available while others are paused
+///
+/// Inherents keep the chain producing blocks, and sudo and the pause pallet
+/// itself are needed to lift a pause.
+pub struct UnpausableCalls;
+impl frame_support::traits::Contains<pallet_call_pause::RuntimeCallNameOf<Runtime>> for UnpausableCalls {
+    fn contains((pallet_name, _): &pallet_call_pause::RuntimeCallNameOf<Runtime>) -> bool {
+        matches!(&pallet_name[..], b"System" | b"Timestamp" | b"ParachainSystem" | b"Sudo" | b"CallPause")
+    }
+}
+
+impl pallet_call_pause::Config for Runtime {
+    type RuntimeCall = RuntimeCall;
+    type PauseOrigin = frame_system::EnsureRoot<AccountId>;
+    type UnpauseOrigin = frame_system::EnsureRoot<AccountId>;
+    type WhitelistedCalls = UnpausableCalls;
+    type MaxNameLen = ConstU32<64>;
+}
+
+// ============================================================================
+// MULTI-BLOCK MIGRATIONS
+// ============================================================================
+
+parameter_types! {
+    /// Leaves a fifth of every block to inherents while migrations run
+    pub MigrationServiceWeight: frame_support::weights::Weight = Perbill::from_percent(80)
+        * <<Runtime as frame_system::Config>::BlockWeights as Get<frame_system::limits::BlockWeights>>::get().max_block;
+}
+
+/// Resume transactions after a failed migration
+///
+/// A stuck chain only applies inherents, so not even sudo could repair it.
+/// Calls relying on the failed migration can be paused with `CallPause`
+/// until governance fixes storage.
+pub struct ResumeOnFailedMigration;
+impl frame_support::migrations::FailedMigrationHandler for ResumeOnFailedMigration {
+    fn failed(_migration: Option<u32>) -> frame_support::migrations::FailedMigrationHandling {
+        frame_support::migrations::FailedMigrationHandling::ForceUnstuck
+    }
+}
+
+impl pallet_stepped_migrations::Config for Runtime {
+    type Migrations = Migrations;
+    type ControlOrigin = frame_system::EnsureRoot<AccountId>;
+    type FailedMigrationHandler = ResumeOnFailedMigration;
+    type CursorMaxLen = ConstU32<256>;
+    type MaxServiceWeight = MigrationServiceWeight;
+}
+
+// ============================================================================
+// MULTISIG
+// ============================================================================
+
+parameter_types! {
+    /// Reserved for creating a multisig operation (covers the storage entry)
+    pub const MultisigDepositBase: Balance = 1_000_000_000;
+    /// Reserved additionally per signatory
+    pub const MultisigDepositFactor: Balance = 100_000_000;
+    /// Organizations (co-ops, clubs) may have up to 20 signatories
+    pub const MaxSignatories: u32 = 20;
+}
+
+impl pallet_multisig::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Currency = Balances;
+    type DepositBase = MultisigDepositBase;
+    type DepositFactor = MultisigDepositFactor;
+    type MaxSignatories = MaxSignatories;
+    type WeightInfo = ();
+    type BlockNumberProvider = System;
+}
+
 // ============================================================================
 // CONSENSUS (AURA + GRANDPA)
 // ============================================================================
@@ -215,11 +451,195 @@ impl pallet_grandpa::Config for Runtime {
     type WeightInfo = ();
     type MaxAuthorities = MaxAuthorities;
     type MaxNominators = ConstU32<0>;
-    type MaxSetIdSessionEntries = ();
-    type KeyOwnerProof = sp_core::Void;
-    type EquivocationReportSystem = ();
+    type MaxSetIdSessionEntries = MaxSetIdSessionEntries;
+    type KeyOwnerProof = sp_session::MembershipProof;
+    type EquivocationReportSystem =
+        pallet_grandpa::EquivocationReportSystem<Self, Offences, Historical, ReportLongevity>;
+}
+
+// ============================================================================
+// VALIDATOR SET
+// ============================================================================
+
+/// Reputation in the main NST asset, ranking authority candidates and council members and weighing votes
+pub struct UbiReputation;
+
+impl pallet_validator_set::ReputationProvider<AccountId> for UbiReputation {
+    fn reputation(who: &AccountId) -> u128 {
+        UbiToken::reputation_score(&NST_ASSET_ID, who)
+    }
+}
+
+impl pallet_reputation_referenda::ReputationProvider<AccountId> for UbiReputation {
+    fn reputation(who: &AccountId) -> u128 {
+        UbiToken::reputation_score(&NST_ASSET_ID, who)
+    }
+}
+
+impl pallet_committee_elections::ReputationProvider<AccountId> for UbiReputation {
+    fn reputation(who: &AccountId) -> u128 {
+        UbiToken::reputation_score(&NST_ASSET_ID, who)
+    }
+}
+
+impl pallet_ranked_council::ReputationProvider<AccountId> for UbiReputation {
+    fn reputation(who: &AccountId) -> u128 {
+        UbiToken::reputation_score(&NST_ASSET_ID, who)
+    }
+}
+
+parameter_types! {
+    /// Governance validators and reputation seats together fit in `MaxAuthorities`
+    pub const MaxValidators: u32 = 16;
+    pub const ReputationSeats: u32 = 16;
+    pub const MaxCandidates: u32 = 100;
+    /// Reputation needed to compete for a seat
+    pub const MinCandidateReputation: u128 = 1_000;
+}
+
+impl pallet_validator_set::Config for Runtime {
+    type AddRemoveOrigin = frame_system::EnsureRoot<AccountId>;
+    type ValidatorRegistration = Session;
+    type MaxValidators = MaxValidators;
+    type MinValidators = ConstU32<1>;
+    type Reputation = UbiReputation;
+    type MaxCandidates = MaxCandidates;
+    type ReputationSeats = ReputationSeats;
+    type MinCandidateReputation = MinCandidateReputation;
+}
+
+// ============================================================================
+// SESSION
+// ============================================================================
+
+parameter_types! {
+    /// Sessions (and with them the reputation seats) rotate once per UBI claim period
+    pub const SessionPeriod: BlockNumber = ClaimPeriodBlocks::get();
+    pub const SessionOffset: BlockNumber = 0;
+}
+
+/// Session pallet: rotates the Aura and Grandpa authorities to the set chosen
+/// by the validator set pallet, using the keys registered with `set_keys`
+impl pallet_session::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type ValidatorId = AccountId;
+    type ValidatorIdOf = sp_runtime::traits::ConvertInto;
+    type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+    type SessionHandler = <SessionKeys as sp_runtime::traits::OpaqueKeys>::KeyTypeIdProviders;
+    type Keys = SessionKeys;
+    type DisablingStrategy = ();
+    type WeightInfo = ();
+    type Currency = Balances;
+    type KeyDeposit = ConstU128<0>;
+}
+
+/// Keeps a trie root of every session's validators, so equivocation reports
+/// can prove which account owned a key at the time of the offence
+impl pallet_session::historical::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type FullIdentification = AccountId;
+    type FullIdentificationOf = sp_runtime::traits::ConvertInto;
 }
 
+// ============================================================================
+// OFFENCES
+// ============================================================================
+
+parameter_types! {
+    /// Number of past sessions whose Grandpa set ids are kept for equivocation proofs
+    pub const MaxSetIdSessionEntries: u64 = 168;
+    /// Equivocation reports stay valid as long as the session is still provable
+    pub const ReportLongevity: u64 = MaxSetIdSessionEntries::get() * SessionPeriod::get() as u64;
+}
+
+/// Block author lookup, used to credit the reporter of an unsigned equivocation report
+impl pallet_authorship::Config for Runtime {
+    type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+    type EventHandler = ();
+}
+
+/// Proven offences remove the offender from the validator set
+impl pallet_offences::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+    type OnOffenceHandler = ValidatorSet;
+}
+
+impl<C> frame_system::offchain::CreateTransactionBase<C> for Runtime
+where
+    RuntimeCall: From<C>,
+{
+    type Extrinsic = UncheckedExtrinsic;
+    type RuntimeCall = RuntimeCall;
+}
+
+/// Lets Grandpa submit equivocation reports and the UBI ring-detection worker
+/// its suspicion reports as unsigned transactions
+impl<C> frame_system::offchain::CreateBare<C> for Runtime
+where
+    RuntimeCall: From<C>,
+{
+    fn create_bare(call: RuntimeCall) -> UncheckedExtrinsic {
+        UncheckedExtrinsic::new_bare(call)
+    }
+}
+
+// ============================================================================
+// PARACHAIN (CUMULUS)
+// ============================================================================
+
+/// Relay chain slot duration, in milliseconds
+#[cfg(feature = "parachain")]
+pub const RELAY_CHAIN_SLOT_DURATION_MILLIS: u32 = 6000;
+
+/// Number of parachain blocks that may be authored per relay chain slot
+#[cfg(feature = "parachain")]
+pub const BLOCK_PROCESSING_VELOCITY: u32 = 1;
+
+/// Maximum number of blocks allowed in the unincluded segment
+#[cfg(feature = "parachain")]
+pub const UNINCLUDED_SEGMENT_CAPACITY: u32 = 3;
+
+#[cfg(feature = "parachain")]
+type ConsensusHook = cumulus_pallet_aura_ext::FixedVelocityConsensusHook<
+    Runtime,
+    RELAY_CHAIN_SLOT_DURATION_MILLIS,
+    BLOCK_PROCESSING_VELOCITY,
+    UNINCLUDED_SEGMENT_CAPACITY,
+>;
+
+#[cfg(feature = "parachain")]
+parameter_types! {
+    pub const RelayOrigin: cumulus_primitives_core::AggregateMessageOrigin =
+        cumulus_primitives_core::AggregateMessageOrigin::Parent;
+}
+
+// NST does not use XCM: downward and XCMP messages are dropped and no
+// outbound messages are ever produced.
+#[cfg(feature = "parachain")]
+impl cumulus_pallet_parachain_system::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type OnSystemEvent = ();
+    type SelfParaId = staging_parachain_info::Pallet<Runtime>;
+    type OutboundXcmpMessageSource = ();
+    type DmpQueue = frame_support::traits::EnqueueWithOrigin<(), RelayOrigin>;
+    type ReservedDmpWeight = ();
+    type XcmpMessageHandler = ();
+    type ReservedXcmpWeight = ();
+    type CheckAssociatedRelayNumber = cumulus_pallet_parachain_system::RelayNumberMonotonicallyIncreases;
+    type WeightInfo = ();
+    type ConsensusHook = ConsensusHook;
+    type RelayParentOffset = ConstU32<0>;
+}
+
+#[cfg(feature = "parachain")]
+impl staging_parachain_info::Config for Runtime {}
+
+#[cfg(feature = "parachain")]
+impl cumulus_pallet_aura_ext::Config for Runtime {}
+
 // ============================================================================
 // UBI TOKEN CONFIGURATION
 // ============================================================================
@@ -228,29 +648,182 @@ impl pallet_grandpa::Config for Runtime {
 // - 6 second blocks (standard for Substrate)
 // - 1 day = 14,400 blocks
 // - 7 days = 100,800 blocks
+//
+// The `fast-runtime` feature turns days into minutes for dev and staging
+// networks, which otherwise run the same code as mainnet.
 
 parameter_types! {
     /// Amount of tokens distributed per claim period (100 NST per day)
     pub const UbiAmount: u128 = 100_000_000_000; // 100 tokens with 9 decimals
     
     /// Number of blocks in one claim period (~1 day with 6s blocks)
-    /// For dev/testing: 10 blocks
-    pub const ClaimPeriodBlocks: BlockNumber = 10;
+    /// With `fast-runtime`: 10 blocks (1 minute)
+    pub const ClaimPeriodBlocks: BlockNumber = if cfg!(feature = "fast-runtime") { 10 } else { 14_400 };
     
     /// Number of blocks until tokens expire (~7 days)
-    /// For dev/testing: 70 blocks
-    pub const ExpirationBlocks: BlockNumber = 70;
+    /// With `fast-runtime`: 70 blocks (7 minutes)
+    pub const ExpirationBlocks: BlockNumber = if cfg!(feature = "fast-runtime") { 70 } else { 100_800 };
     
     /// Maximum number of claim periods that can be claimed as backlog
     pub const MaxBacklogPeriods: u32 = 3;
+
+    /// Maximum number of accounts handled by one `sweep_expired` or `prune_dormant` call
+    pub const MaxSweepAccounts: u32 = 64;
+
+    /// Accounts idle for 30 claim periods with a negligible score can be pruned
+    pub const DormantPeriods: u32 = 30;
+    pub const DormantScoreThreshold: Balance = 10;
+
+    /// Recipients can reject a burn for one claim period after receiving it
+    pub const RejectionWindow: BlockNumber = ClaimPeriodBlocks::get();
+
+    /// Burns to verified charities count 25% extra for their senders
+    pub const CharityBonus: Perbill = Perbill::from_percent(25);
+
+    /// Accounts a household's claimer can claim for besides itself
+    pub const MaxHouseholdMembers: u32 = 8;
+
+    /// Members an organization can split received recognition across
+    pub const MaxOrganizationMembers: u32 = 16;
+
+    /// Role tags such as "baker" or "bike-repair", attested by established accounts
+    pub const MaxRoleLen: u32 = 32;
+    pub const MinAttesterReputation: Balance = 500;
+
+    /// Each account can vouch for up to 3 others per claim period
+    pub const MaxVouchesPerPeriod: u32 = 3;
+
+    /// Boosted burns can be slashed for a week after they happened
+    pub const BoostLockBlocks: BlockNumber = 7 * ClaimPeriodBlocks::get();
+
+    /// New accounts get no extra tokens with their first claim, and 20 reputation
+    /// points that decay like earned reputation
+    pub const WelcomeBonus: Balance = 0;
+    pub const WelcomeReputation: Balance = 20;
+
+    /// Referrals grant both sides up to 50 reputation points, 5 referrals per day
+    pub const ReferralBonus: Balance = 50;
+    pub const MaxReferralsPerPeriod: u32 = 5;
+
+    /// Idempotency keys of burns block retries for a day, one per possible unsigned burn
+    pub const IdempotencyWindow: BlockNumber = ClaimPeriodBlocks::get();
+    pub const MaxIdempotencyKeys: u32 = MaxUnsignedBurnsPerPeriod::get();
+
+    /// Creating a community asset reserves 1000 native tokens
+    pub const AssetDeposit: Balance = 1_000 * 10u128.pow(TOKEN_DECIMALS as u32);
+    /// Community assets, every one of which is visited when an account is reaped
+    pub const MaxAssets: u32 = 100;
+
+    /// Reputation snapshots a bridge has not taken yet
+    pub const MaxOutboundSnapshots: u32 = 1024;
+
+    /// Expired batches are purged with up to 5% of every block
+    pub ExpiryPollWeight: frame_support::weights::Weight = Perbill::from_percent(5)
+        * <<Runtime as frame_system::Config>::BlockWeights as Get<frame_system::limits::BlockWeights>>::get().max_block;
+
+    /// Service names such as "haircut" or "bike repair"
+    pub const MaxServiceNameLen: u32 = 64;
+
+    /// Profile names such as "Maria's Bakery", settable from 100 reputation points
+    pub const MaxDisplayNameLen: u32 = 64;
+    pub const MinProfileReputation: u128 = 100;
+
+    /// Accounts a claim bot can process in one `claim_for_many`
+    pub const MaxClaimBatch: u32 = 100;
+
+    /// Short-expiry claims expire after 3 of the 7 days and grant 10% extra
+    pub ShortExpiryRatio: Perbill = Perbill::from_rational(3u32, 7u32);
+    pub const ShortExpiryBonus: Perbill = Perbill::from_percent(10);
+
+    /// Tokens can be committed for up to two extra weeks
+    pub const MaxCommitPeriods: u32 = 14;
+
+    /// A week offline can be fully claimed: 3 backlog days plus 4 paid with reputation
+    pub const MaxExtraBacklogPeriods: u32 = 4;
+
+    /// A streak freeze burns half a day's UBI and covers three more missed days
+    pub const StreakFreezeCost: u128 = 50_000_000_000; // 50 tokens with 9 decimals
+    pub const StreakFreezePeriods: u32 = 3;
+
+    /// Vacations last up to 30 days, with 90 days between two of them
+    pub const MaxVacationPeriods: u64 = 30;
+    pub const VacationCooldownPeriods: u64 = 90;
+
+    /// Leading zero bits of the claim proof-of-work
+    /// For dev/testing: disabled, a public network would use e.g. 16
+    pub const ClaimPowDifficulty: u32 = 0;
+
+    /// Maximum number of unsigned burns per account and claim period
+    pub const MaxUnsignedBurnsPerPeriod: u32 = 50;
+
+    /// Claims of the next period can be submitted 5 blocks (~30s) before it starts
+    pub const ClaimLookaheadBlocks: BlockNumber = 5;
+}
+
+/// The main NST community asset, created at genesis with the parameters above
+pub const NST_ASSET_ID: u32 = 0;
+
+parameter_types! {
+    pub const NstAssetId: u32 = NST_ASSET_ID;
+    /// Owner of the NST asset when it is created from the single-asset state: the root key, like at genesis
+    pub NstAssetOwner: AccountId = pallet_sudo::Key::<Runtime>::get().unwrap_or_else(|| AccountId::from([0u8; 32]));
 }
 
+/// Read-only `fungible::Inspect` view of the main NST asset for generic tooling
+pub type NstToken = frame_support::traits::fungible::ItemOf<UbiToken, NstAssetId, AccountId>;
+
 impl pallet_ubi_token::Config for Runtime {
-    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type AssetId = u32;
+    type CreateOrigin = frame_system::EnsureSigned<AccountId>;
+    type Currency = Balances;
+    type AssetDeposit = AssetDeposit;
+    type MaxAssets = MaxAssets;
+    type BonusOrigin = frame_system::EnsureRoot<AccountId>;
+    type ReviewOrigin = frame_system::EnsureRoot<AccountId>;
+    type CharityOrigin = frame_system::EnsureRoot<AccountId>;
+    type ParameterOrigin = frame_system::EnsureRoot<AccountId>;
     type UbiAmount = UbiAmount;
     type ClaimPeriodBlocks = ClaimPeriodBlocks;
     type ExpirationBlocks = ExpirationBlocks;
     type MaxBacklogPeriods = MaxBacklogPeriods;
+    type MaxSweepAccounts = MaxSweepAccounts;
+    type DormantPeriods = DormantPeriods;
+    type DormantScoreThreshold = DormantScoreThreshold;
+    type RejectionWindow = RejectionWindow;
+    type MaxServiceNameLen = MaxServiceNameLen;
+    type MaxDisplayNameLen = MaxDisplayNameLen;
+    type MinProfileReputation = MinProfileReputation;
+    type MaxClaimBatch = MaxClaimBatch;
+    type ShortExpiryRatio = ShortExpiryRatio;
+    type ShortExpiryBonus = ShortExpiryBonus;
+    type MaxCommitPeriods = MaxCommitPeriods;
+    type MaxExtraBacklogPeriods = MaxExtraBacklogPeriods;
+    type StreakFreezeCost = StreakFreezeCost;
+    type StreakFreezePeriods = StreakFreezePeriods;
+    type MaxVacationPeriods = MaxVacationPeriods;
+    type VacationCooldownPeriods = VacationCooldownPeriods;
+    type ClaimPowDifficulty = ClaimPowDifficulty;
+    type MaxUnsignedBurnsPerPeriod = MaxUnsignedBurnsPerPeriod;
+    type ClaimLookaheadBlocks = ClaimLookaheadBlocks;
+    type MaxPoolGrants = ConstU32<100>;
+    type CharityBonus = CharityBonus;
+    type MaxHouseholdMembers = MaxHouseholdMembers;
+    type MaxOrganizationMembers = MaxOrganizationMembers;
+    type MaxRoleLen = MaxRoleLen;
+    type MinAttesterReputation = MinAttesterReputation;
+    type MaxVouchesPerPeriod = MaxVouchesPerPeriod;
+    type BoostLockBlocks = BoostLockBlocks;
+    type WelcomeBonus = WelcomeBonus;
+    type WelcomeReputation = WelcomeReputation;
+    type ReferralBonus = ReferralBonus;
+    type MaxReferralsPerPeriod = MaxReferralsPerPeriod;
+    type IdempotencyWindow = IdempotencyWindow;
+    type MaxIdempotencyKeys = MaxIdempotencyKeys;
+    type MaxOutboundSnapshots = MaxOutboundSnapshots;
+    type ExpiryPollWeight = ExpiryPollWeight;
+    type OffchainSignature = Signature;
+    type OffchainPublic = <Signature as sp_runtime::traits::Verify>::Signer;
 }
 
 // ============================================================================
@@ -261,11 +834,25 @@ construct_runtime!(
     pub enum Runtime {
         // System pallets
         System: frame_system,
+        #[cfg(feature = "parachain")]
+        ParachainSystem: cumulus_pallet_parachain_system::{
+            Pallet, Call, Config<T>, Storage, Inherent, Event<T>,
+        },
         Timestamp: pallet_timestamp,
+        #[cfg(feature = "parachain")]
+        ParachainInfo: staging_parachain_info::{Pallet, Storage, Config<T>},
         
         // Consensus
         Aura: pallet_aura,
+        #[cfg(feature = "parachain")]
+        AuraExt: cumulus_pallet_aura_ext::{Pallet, Storage, Config<T>},
         Grandpa: pallet_grandpa,
+        // Must come before `Session`, whose genesis asks it for the initial set
+        ValidatorSet: pallet_validator_set,
+        Session: pallet_session,
+        Historical: pallet_session::historical,
+        Authorship: pallet_authorship,
+        Offences: pallet_offences,
         
         // Monetary pallets (for transaction fees only)
         Balances: pallet_balances,
@@ -273,12 +860,25 @@ construct_runtime!(
         
         // Governance
         Sudo: pallet_sudo,
+        Multisig: pallet_multisig,
+        Referenda: pallet_reputation_referenda,
+        TechnicalCommittee: pallet_technical_committee,
+        CommitteeElections: pallet_committee_elections,
+        RankedCouncil: pallet_ranked_council,
+        CallPause: pallet_call_pause,
+        MultiBlockMigrations: pallet_stepped_migrations,
         
         // NST UBI Token
         UbiToken: pallet_ubi_token,
     }
 );
 
+#[cfg(feature = "parachain")]
+cumulus_pallet_parachain_system::register_validate_block! {
+    Runtime = Runtime,
+    BlockExecutor = cumulus_pallet_aura_ext::BlockExecutor::<Runtime, Executive>,
+}
+
 /// Opaque types for the runtime. Used for the node.
 pub mod opaque {
     use super::*;
@@ -304,7 +904,7 @@ sp_api::impl_runtime_apis! {
         }
 
         fn execute_block(block: <Block as BlockT>::LazyBlock) {
-            Executive::execute_block(block.into());
+            Executive::execute_block(block);
         }
 
         fn initialize_block(header: &<Block as BlockT>::Header) -> ExtrinsicInclusionMode {
@@ -373,6 +973,25 @@ sp_api::impl_runtime_apis! {
         }
     }
 
+    #[cfg(feature = "parachain")]
+    impl cumulus_primitives_aura::AuraUnincludedSegmentApi<Block> for Runtime {
+        fn can_build_upon(
+            included_hash: <Block as BlockT>::Hash,
+            slot: cumulus_primitives_aura::Slot,
+        ) -> bool {
+            ConsensusHook::can_build_upon(included_hash, slot)
+        }
+    }
+
+    #[cfg(feature = "parachain")]
+    impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
+        fn collect_collation_info(
+            header: &<Block as BlockT>::Header,
+        ) -> cumulus_primitives_core::CollationInfo {
+            ParachainSystem::collect_collation_info(header)
+        }
+    }
+
     impl sp_consensus_grandpa::GrandpaApi<Block> for Runtime {
         fn grandpa_authorities() -> sp_consensus_grandpa::AuthorityList {
             Grandpa::grandpa_authorities()
@@ -383,20 +1002,27 @@ sp_api::impl_runtime_apis! {
         }
 
         fn submit_report_equivocation_unsigned_extrinsic(
-            _equivocation_proof: sp_consensus_grandpa::EquivocationProof<
+            equivocation_proof: sp_consensus_grandpa::EquivocationProof<
                 <Block as BlockT>::Hash,
                 NumberFor<Block>,
             >,
-            _key_owner_proof: sp_consensus_grandpa::OpaqueKeyOwnershipProof,
+            key_owner_proof: sp_consensus_grandpa::OpaqueKeyOwnershipProof,
         ) -> Option<()> {
-            None
+            let key_owner_proof = key_owner_proof.decode()?;
+
+            Grandpa::submit_unsigned_equivocation_report(equivocation_proof, key_owner_proof)
         }
 
         fn generate_key_ownership_proof(
             _set_id: sp_consensus_grandpa::SetId,
-            _authority_id: GrandpaId,
+            authority_id: GrandpaId,
         ) -> Option<sp_consensus_grandpa::OpaqueKeyOwnershipProof> {
-            None
+            use frame_support::traits::KeyOwnerProofSystem;
+            use parity_scale_codec::Encode;
+
+            Historical::prove((sp_consensus_grandpa::KEY_TYPE, authority_id))
+                .map(|proof| proof.encode())
+                .map(sp_consensus_grandpa::OpaqueKeyOwnershipProof::new)
         }
     }
 
@@ -442,6 +1068,110 @@ sp_api::impl_runtime_apis! {
         }
     }
 
+    impl pallet_ubi_token_runtime_api::UbiTokenApi<Block, u32, AccountId, Balance, BlockNumber> for Runtime {
+        fn ubi_parameters(
+            asset_id: u32,
+        ) -> Option<pallet_ubi_token_runtime_api::UbiParameters<Balance, BlockNumber>> {
+            UbiToken::ubi_parameters(&asset_id)
+        }
+
+        fn reputation(
+            asset_id: u32,
+            who: AccountId,
+        ) -> pallet_ubi_token_runtime_api::Reputation<Balance, BlockNumber> {
+            UbiToken::reputation(&asset_id, &who)
+        }
+
+        fn claim_anchor(asset_id: u32, who: AccountId) -> Option<u64> {
+            UbiToken::claim_anchor(&asset_id, &who)
+        }
+
+        fn next_claim_block(asset_id: u32, who: AccountId) -> Option<BlockNumber> {
+            UbiToken::next_claim_block(&asset_id, &who)
+        }
+
+        fn attested_accounts(asset_id: u32, role: Vec<u8>) -> Vec<(AccountId, u32)> {
+            match role.try_into() {
+                Ok(role) => UbiToken::attested_accounts(asset_id, &role),
+                Err(_) => Vec::new(),
+            }
+        }
+
+        fn profile(asset_id: u32, who: AccountId) -> Option<(Vec<u8>, Option<Hash>)> {
+            UbiToken::profile(asset_id, who).map(|profile| (profile.display_name.into_inner(), profile.metadata_hash))
+        }
+
+        fn preferences(asset_id: u32, who: AccountId) -> pallet_ubi_token_runtime_api::AccountPreferences<Balance> {
+            UbiToken::preferences(asset_id, who)
+        }
+
+        fn claimable_amount(asset_id: u32, who: AccountId) -> Balance {
+            UbiToken::claimable_amount(&asset_id, &who)
+        }
+
+        fn spendable_balance_at(asset_id: u32, who: AccountId, block: BlockNumber) -> Balance {
+            UbiToken::spendable_balance_at(&asset_id, &who, block)
+        }
+
+        fn balances_of(asset_id: u32, accounts: Vec<AccountId>) -> Vec<Balance> {
+            accounts.iter().map(|who| UbiToken::spendable_balance(&asset_id, who)).collect()
+        }
+
+        fn reputation_percentile(asset_id: u32, who: AccountId) -> Perbill {
+            UbiToken::reputation_percentile(&asset_id, &who)
+        }
+
+        fn reputations_of(
+            asset_id: u32,
+            accounts: Vec<AccountId>,
+        ) -> Vec<pallet_ubi_token_runtime_api::Reputation<Balance, BlockNumber>> {
+            accounts.iter().map(|who| UbiToken::reputation(&asset_id, who)).collect()
+        }
+
+        fn token_batches(
+            asset_id: u32,
+            who: AccountId,
+        ) -> Vec<pallet_ubi_token_runtime_api::TokenBatch<Balance, BlockNumber>> {
+            UbiToken::token_batches(&asset_id, &who)
+        }
+
+        fn top_supporters(
+            asset_id: u32,
+            who: AccountId,
+        ) -> Vec<pallet_ubi_token_runtime_api::TopSupporter<AccountId, Balance>> {
+            UbiToken::top_supporters(asset_id, who).into_inner()
+        }
+
+        fn burn_nonce(asset_id: u32, who: AccountId) -> u64 {
+            UbiToken::burn_nonce(asset_id, who)
+        }
+
+        fn burn_authorization(
+            asset_id: u32,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> pallet_ubi_token::BurnAuthorization<u32, AccountId, Balance, Hash> {
+            let nonce = UbiToken::burn_nonce(asset_id, &from);
+            UbiToken::burn_authorization_payload(asset_id, from, to, amount, None, None, nonce)
+        }
+
+        fn unique_recipients(
+            asset_id: u32,
+            sender: AccountId,
+            start_key: Option<AccountId>,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            UbiToken::unique_recipients(&asset_id, &sender, start_key, limit)
+        }
+
+        fn burn_receipt(
+            burn_id: u64,
+        ) -> Option<pallet_ubi_token_runtime_api::BurnReceiptWithKey<u32, AccountId, Balance, BlockNumber>> {
+            UbiToken::burn_receipt_with_key(burn_id)
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn benchmark_metadata(_extra: bool) -> (